@@ -0,0 +1,20 @@
+//! Packages a theme directory into a single bundle file, which may then be embedded with `include_bytes!`
+//! and registered with `ContextBuilder::register_theme_bundle`.
+//!
+//! Usage: cargo run --example bundle_theme -- <theme directory> <output file>
+
+use std::path::Path;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() != 3 {
+        eprintln!("Usage: {} <theme directory> <output file>", args[0]);
+        std::process::exit(1);
+    }
+
+    let bundle = thyme::ThemeBundle::from_dir(Path::new(&args[1]))?;
+    bundle.write(Path::new(&args[2]))?;
+    println!("Wrote {} files to '{}'", bundle.len(), args[2]);
+
+    Ok(())
+}
@@ -89,7 +89,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let context = context_builder.build(&mut renderer, &mut io)?;
     let party = demo::Party::default();
 
-    let mut app = AppRunner { io, renderer, context, window, surface, display_context, party, frames: 0 };
+    let mut app = AppRunner {
+        io, renderer, context, window, surface, display_context, party, frames: 0,
+        #[cfg(feature = "recording")]
+        recorder: None,
+    };
 
     let start = std::time::Instant::now();
     event_loop.run_app(&mut app)?;
@@ -109,6 +113,33 @@ struct AppRunner {
     display_context: PossiblyCurrentContext,
     party: demo::Party,
     frames: u32,
+
+    // toggled with F9, and written to recording.png when stopped
+    #[cfg(feature = "recording")]
+    recorder: Option<thyme::Recorder>,
+}
+
+impl AppRunner {
+    #[cfg(feature = "recording")]
+    fn toggle_recording(&mut self) {
+        match self.recorder.take() {
+            None => self.recorder = Some(thyme::Recorder::new(20)),
+            Some(recorder) => match recorder.write_apng(std::path::Path::new("recording.png")) {
+                Ok(()) => log::warn!("Wrote {} frames to recording.png", recorder.frame_count()),
+                Err(e) => log::error!("Unable to write recording: {}", e),
+            }
+        }
+    }
+
+    #[cfg(feature = "recording")]
+    fn capture_frame(&mut self) {
+        if let Some(recorder) = self.recorder.as_mut() {
+            if recorder.wants_frame() {
+                let (width, height): (u32, u32) = self.window.inner_size().into();
+                recorder.capture(width, height, &self.renderer.read_pixels(width, height));
+            }
+        }
+    }
 }
 
 impl ApplicationHandler for AppRunner {
@@ -144,10 +175,20 @@ impl ApplicationHandler for AppRunner {
                     });
                 });
 
+                #[cfg(feature = "recording")]
+                self.capture_frame();
+
                 self.surface.swap_buffers(&self.display_context).unwrap();
                 self.frames += 1;
             }
             WindowEvent::CloseRequested => event_loop.exit(),
+            #[cfg(feature = "recording")]
+            WindowEvent::KeyboardInput { event: winit::event::KeyEvent {
+                physical_key: winit::keyboard::PhysicalKey::Code(winit::keyboard::KeyCode::F9),
+                state: winit::event::ElementState::Pressed,
+                repeat: false,
+                ..
+            }, .. } => self.toggle_recording(),
             event => {
                 self.io.handle_event(&mut self.context, &event);
             }
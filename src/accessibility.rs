@@ -0,0 +1,168 @@
+use crate::font::{Font, FontDrawParams};
+use crate::{Color, DrawQuad, Point, Rect};
+
+/**
+A widget with a screen reader `aria_label` or `description`, set in the widget's theme or via
+[`WidgetBuilder.aria_label`](struct.WidgetBuilder.html#method.aria_label) and
+[`WidgetBuilder.description`](struct.WidgetBuilder.html#method.description).  The labelled widgets in the
+most recently drawn frame are obtained with [`Context.accessible_widgets`](struct.Context.html#method.accessible_widgets),
+allowing an application to expose them to platform accessibility APIs.  Labels are passed through the
+[`text resolver`](struct.Context.html#method.set_text_resolver), if any.
+**/
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccessibleWidget {
+    /// The unique ID of the widget
+    pub id: String,
+
+    /// The ID of the theme used by the widget
+    pub theme_id: String,
+
+    /// The position and size of the widget, in logical pixels
+    pub rect: Rect,
+
+    /// A short, human readable name for the widget, such as "Close" for an icon only close button
+    pub label: Option<String>,
+
+    /// A longer description of the widget's purpose
+    pub description: Option<String>,
+}
+
+/// A type of color vision deficiency, used with a [`ColorTransform`](enum.ColorTransform.html).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ColorBlindness {
+    /// Reduced sensitivity to red light
+    Protanopia,
+
+    /// Reduced sensitivity to green light
+    Deuteranopia,
+
+    /// Reduced sensitivity to blue light
+    Tritanopia,
+}
+
+/**
+A transform applied to the colors of all drawn UI pixels, including text and images, by the renderer.
+This is set with [`Context.set_color_transform`](struct.Context.html#method.set_color_transform), and allows
+games to offer color accessibility options without authoring a separate theme for each.
+**/
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub enum ColorTransform {
+    /// Colors are drawn unchanged
+    #[default]
+    None,
+
+    /// Simulates how colors appear to a user with the specified color vision deficiency.  This is useful
+    /// for checking that a theme remains usable.
+    Simulate(ColorBlindness),
+
+    /// Shifts colors that would be difficult to distinguish for a user with the specified color vision
+    /// deficiency towards colors that are easier to distinguish.
+    Compensate(ColorBlindness),
+
+    /// A custom matrix, in row major order, which is multiplied with the RGB color of each pixel
+    Matrix([[f32; 3]; 3]),
+}
+
+const IDENTITY: [[f32; 3]; 3] = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+
+impl ColorTransform {
+    /// Returns the matrix for this transform, in row major order, which is multiplied with the RGB
+    /// color of each pixel.
+    pub fn matrix(self) -> [[f32; 3]; 3] {
+        match self {
+            ColorTransform::None => IDENTITY,
+            ColorTransform::Simulate(kind) => simulation(kind),
+            ColorTransform::Compensate(kind) => {
+                // shift the color information lost by the simulation into channels the user can distinguish
+                let shift = match kind {
+                    ColorBlindness::Protanopia | ColorBlindness::Deuteranopia =>
+                        [[0.0, 0.0, 0.0], [0.7, 1.0, 0.0], [0.7, 0.0, 1.0]],
+                    ColorBlindness::Tritanopia =>
+                        [[1.0, 0.0, 0.7], [0.0, 1.0, 0.7], [0.0, 0.0, 0.0]],
+                };
+
+                let sim = simulation(kind);
+                let mut out = IDENTITY;
+                for (row, out_row) in out.iter_mut().enumerate() {
+                    for (col, value) in out_row.iter_mut().enumerate() {
+                        *value += (0..3).map(|i| shift[row][i] * (IDENTITY[i][col] - sim[i][col])).sum::<f32>();
+                    }
+                }
+                out
+            },
+            ColorTransform::Matrix(matrix) => matrix,
+        }
+    }
+
+    /// Returns the matrix for this transform in column major order, as used by shader uniforms
+    #[cfg(any(feature="glium_backend", feature="gl_backend"))]
+    pub(crate) fn uniform(self) -> [[f32; 3]; 3] {
+        let m = self.matrix();
+        [0, 1, 2].map(|col| [m[0][col], m[1][col], m[2][col]])
+    }
+}
+
+// simulation matrices from Machado, Oliveira, and Fernandes (2009), at full severity
+fn simulation(kind: ColorBlindness) -> [[f32; 3]; 3] {
+    match kind {
+        ColorBlindness::Protanopia => [
+            [0.152286, 1.052583, -0.204868],
+            [0.114503, 0.786281, 0.099216],
+            [-0.003882, -0.048116, 1.051998],
+        ],
+        ColorBlindness::Deuteranopia => [
+            [0.367322, 0.860646, -0.227968],
+            [0.280085, 0.672501, 0.047413],
+            [-0.011820, 0.042940, 0.968881],
+        ],
+        ColorBlindness::Tritanopia => [
+            [1.255528, -0.076749, -0.178779],
+            [-0.078411, 0.930809, 0.147602],
+            [0.004733, 0.691367, 0.303900],
+        ],
+    }
+}
+
+/// The padding around text backplates in high contrast mode, in logical pixels
+const BACKPLATE_PADDING: f32 = 2.0;
+
+/// How to draw a piece of text when [`high contrast`](struct.Context.html#method.set_high_contrast) is enabled
+pub(crate) struct HighContrastText {
+    pub text_color: Color,
+
+    /// the backplate area in logical pixels, or `None` if no text is drawn
+    pub plate: Option<Rect>,
+    pub plate_color: Color,
+}
+
+impl HighContrastText {
+    /// Replaces the text color with black or white, whichever is closer, and places
+    /// a backplate of the opposite color behind the text
+    pub(crate) fn new(font: &Font, params: FontDrawParams, text: &str, clip: Rect) -> HighContrastText {
+        let color = params.color;
+        let luminance = 0.2126 * color.r as f32 + 0.7152 * color.g as f32 + 0.0722 * color.b as f32;
+        let (text_color, plate_color) = if luminance >= 127.5 {
+            (Color::white(), Color::black())
+        } else {
+            (Color::black(), Color::white())
+        };
+
+        let text_color = Color { a: color.a, ..text_color };
+        let plate_color = Color { a: (color.a as f32 * 0.8).round() as u8, ..plate_color };
+
+        let mut quads: Vec<DrawQuad> = Vec::new();
+        font.draw(&mut quads, params, text, clip);
+
+        let plate = quads.iter().map(|quad| Rect::new(quad.pos.into(), quad.size.into())).reduce(|a, b| {
+            let min = Point::new(a.pos.x.min(b.pos.x), a.pos.y.min(b.pos.y));
+            let max = Point::new(a.right().max(b.right()), a.bot().max(b.bot()));
+            Rect::new(min, max - min)
+        }).map(|bounds| {
+            let scale = params.scale_factor;
+            let padding = Point::new(BACKPLATE_PADDING, BACKPLATE_PADDING);
+            Rect::new(bounds.pos / scale - padding, bounds.size / scale + padding * 2.0)
+        });
+
+        HighContrastText { text_color, plate, plate_color }
+    }
+}
@@ -0,0 +1,42 @@
+/*!
+A single call entry point for simple Thyme apps.
+
+[`run`](fn.run.html) takes a configured [`AppBuilder`](../struct.AppBuilder.html), creates the window, renderer,
+and [`Context`](../struct.Context.html) for the enabled backend, and then runs the Winit event loop, calling the
+passed in closure to build the UI each frame.  Window resizing and scale factor changes are handled automatically.
+When both backends are enabled, the [`GliumRenderer`](../struct.GliumRenderer.html) is used.  For more control,
+use [`AppBuilder::build_glium`](../struct.AppBuilder.html#method.build_glium) or
+[`AppBuilder::build_gl`](../struct.AppBuilder.html#method.build_gl) directly.
+
+# Example
+```no_run
+fn main() -> Result<(), thyme::Error> {
+    let app = thyme::AppBuilder::new()
+        .with_title("My App")
+        .with_theme_file("theme.yml")
+        .with_font_dir("fonts")
+        .with_image_dir("images");
+
+    thyme::app::run(app, |ui| {
+        ui.window("window", |ui| {
+            ui.button("label", "Hello, World!");
+        });
+    })
+}
+```
+!*/
+
+use crate::{AppBuilder, Error, Frame};
+use crate::winit_io::WinitError;
+
+/// Builds the app specified by `builder` and runs it until the window is closed, calling `f` to build the UI
+/// each frame.  Returns an error if the app could not be created, or if the event loop fails.
+pub fn run<F: Fn(&mut Frame) + 'static>(builder: AppBuilder, f: F) -> Result<(), Error> {
+    #[cfg(feature="glium_backend")]
+    let app = builder.build_glium()?;
+
+    #[cfg(all(feature="gl_backend", not(feature="glium_backend")))]
+    let app = builder.build_gl()?;
+
+    app.main_loop(f).map_err(|e| Error::Winit(WinitError::EventLoop(e)))
+}
@@ -18,6 +18,7 @@ pub struct AppBuilder {
     images: Option<AssetSource>,
     base_dir: PathBuf,
     logger: bool,
+    scale_factor: Option<f32>,
     options: BuildOptions,
 }
 
@@ -51,6 +52,7 @@ impl AppBuilder {
             fonts: None,
             images: None,
             logger: false,
+            scale_factor: None,
             options: BuildOptions::default(),
         }
     }
@@ -62,6 +64,13 @@ impl AppBuilder {
         self
     }
 
+    /// Set the time in milliseconds after a tooltip is shown during which new tooltips
+    /// show immediately.  See [`BuildOptions`](struct.BuildOptions.html)
+    pub fn with_tooltip_instant_switch_time(mut self, time_millis: u32) -> AppBuilder {
+        self.options.tooltip_instant_switch_time = time_millis;
+        self
+    }
+
     /// Set the number of lines that scrollbars will scroll per mouse scroll.
     /// See [`BuildOptions`](struct.BuildOptions.html)
     pub fn with_line_scroll(mut self, line_scroll: f32) -> AppBuilder {
@@ -69,6 +78,20 @@ impl AppBuilder {
         self
     }
 
+    /// Set the time in milliseconds the mouse must be held down for a long press.
+    /// See [`BuildOptions`](struct.BuildOptions.html)
+    pub fn with_long_press_time(mut self, time_millis: u32) -> AppBuilder {
+        self.options.long_press_time = time_millis;
+        self
+    }
+
+    /// Set whether widget interactions are recorded in the event queue.
+    /// See [`BuildOptions`](struct.BuildOptions.html)
+    pub fn with_event_queue(mut self, event_queue: bool) -> AppBuilder {
+        self.options.event_queue = event_queue;
+        self
+    }
+
     /// If called, this App Builder will setup a default Thyme logger
     /// at the warn level.  See [`SimpleLogger`](struct.SimpleLogger.html).
     pub fn with_logger(mut self) -> AppBuilder {
@@ -76,6 +99,13 @@ impl AppBuilder {
         self
     }
 
+    /// Specifies an explicit scale factor for this app, rather than using the scale factor of
+    /// the monitor.  See [`WinitIo::with_scale_factor`](struct.WinitIo.html#method.with_scale_factor).
+    pub fn with_scale_factor(mut self, scale_factor: f32) -> AppBuilder {
+        self.scale_factor = Some(scale_factor);
+        self
+    }
+
     /// Specifies the window title for this app.
     pub fn with_title<T: Into<String>>(mut self, title: T) -> AppBuilder {
         self.title = title.into();
@@ -265,8 +295,7 @@ impl AppBuilder {
 
         }
 
-        let mut io = crate::WinitIo::new(&window, self.window_size)
-            .map_err(Error::Winit)?;
+        let mut io = self.winit_io(&window)?;
         let mut renderer = crate::GLRenderer::new();
         let mut context_builder = crate::ContextBuilder::new(self.options.clone());
 
@@ -277,6 +306,14 @@ impl AppBuilder {
         Ok(GlApp { io, renderer, context, event_loop, window, surface, display_context })
     }
     
+    #[cfg(any(feature="gl_backend", feature="glium_backend"))]
+    fn winit_io(&self, window: &winit::window::Window) -> Result<crate::WinitIo, Error> {
+        match self.scale_factor {
+            Some(scale) => Ok(crate::WinitIo::with_scale_factor(self.window_size, scale)),
+            None => crate::WinitIo::new(window, self.window_size).map_err(Error::Winit),
+        }
+    }
+
     /// Creates a [`GliumApp`](struct.GliumApp.html) object, setting up Thyme as specified
     /// in this Builder and using the [`GliumRenderer`](struct.GliumRenderer.html).
     #[cfg(feature="glium_backend")]
@@ -300,8 +337,7 @@ impl AppBuilder {
             .set_window_builder(attrs)
             .build(&event_loop);
 
-        let mut io = crate::WinitIo::new(&window, self.window_size)
-            .map_err(Error::Winit)?;
+        let mut io = self.winit_io(&window)?;
         let mut renderer = crate::GliumRenderer::new(&display)
             .map_err(Error::Glium)?;
         let mut context_builder = crate::ContextBuilder::new(self.options.clone());
@@ -346,6 +382,16 @@ impl AppBuilder {
     }
 }
 
+/// The theme used to show theme reload errors in the app main loops, if it is defined
+#[cfg(any(feature="gl_backend", feature="glium_backend"))]
+const RELOAD_ERROR_THEME: &str = "reload_error";
+
+/// Returns whether there is a theme reload error to show, and the theme can show it
+#[cfg(any(feature="gl_backend", feature="glium_backend"))]
+fn show_reload_error(context: &Context) -> bool {
+    context.reload_error().is_some() && context.widget_theme(RELOAD_ERROR_THEME).is_some()
+}
+
 /// The GlApp object, containing the Thyme [`Context`](struct.Context.html), [`Renderer`](struct.GlRenderer.html), and
 /// [`IO`](struct.WinitIo.html).  YOu can manually use the public members of this struct, or use [`main_loop`](#method.main_loop)
 /// for basic use cases.
@@ -392,6 +438,15 @@ impl<F: Fn(&mut Frame)> ApplicationHandler for GlAppRunner<F> {
         self.window.request_redraw();
     }
 
+    fn device_event(
+        &mut self,
+        _event_loop: &winit::event_loop::ActiveEventLoop,
+        _device_id: winit::event::DeviceId,
+        event: winit::event::DeviceEvent,
+    ) {
+        self.io.handle_device_event(&mut self.context, &event);
+    }
+
     fn window_event(
         &mut self,
         event_loop: &winit::event_loop::ActiveEventLoop,
@@ -404,15 +459,33 @@ impl<F: Fn(&mut Frame)> ApplicationHandler for GlAppRunner<F> {
             WindowEvent::RedrawRequested => {
                 self.renderer.clear_color(0.0, 0.0, 0.0, 0.0);
 
+                if let Err(e) = self.context.check_live_reload(&mut self.renderer) {
+                    log::error!("Unable to live reload theme: {}", e);
+                }
+
+                let show_error = show_reload_error(&self.context);
                 let mut ui = self.context.create_frame();
     
                 (self.f)(&mut ui);
+
+                if show_error {
+                    ui.reload_error(RELOAD_ERROR_THEME);
+                }
     
                 self.renderer.draw_frame(ui);
+                self.io.update_cursor(&self.context, &self.window);
 
                 self.surface.swap_buffers(&self.display_context).unwrap();
             }
             WindowEvent::CloseRequested => event_loop.exit(),
+            WindowEvent::Resized(size) => {
+                use std::num::NonZeroU32;
+                if let (Some(width), Some(height)) = (NonZeroU32::new(size.width), NonZeroU32::new(size.height)) {
+                    self.surface.resize(&self.display_context, width, height);
+                    unsafe { gl::Viewport(0, 0, size.width as i32, size.height as i32); }
+                }
+                self.io.handle_event(&mut self.context, &WindowEvent::Resized(size));
+            }
             event => {
                 self.io.handle_event(&mut self.context, &event);
             }
@@ -497,6 +570,15 @@ impl<F: Fn(&mut Frame)> ApplicationHandler for GliumAppRunner<F> {
         self.window.request_redraw();
     }
 
+    fn device_event(
+        &mut self,
+        _event_loop: &winit::event_loop::ActiveEventLoop,
+        _device_id: winit::event::DeviceId,
+        event: winit::event::DeviceEvent,
+    ) {
+        self.io.handle_device_event(&mut self.context, &event);
+    }
+
     fn window_event(
         &mut self,
         event_loop: &winit::event_loop::ActiveEventLoop,
@@ -510,15 +592,29 @@ impl<F: Fn(&mut Frame)> ApplicationHandler for GliumAppRunner<F> {
                 let mut target = self.display.draw();
                 target.clear_color(0.0, 0.0, 0.0, 0.0);
     
+                if let Err(e) = self.context.check_live_reload(&mut self.renderer) {
+                    log::error!("Unable to live reload theme: {}", e);
+                }
+
+                let show_error = show_reload_error(&self.context);
                 let mut ui = self.context.create_frame();
     
                 (self.f)(&mut ui);
+
+                if show_error {
+                    ui.reload_error(RELOAD_ERROR_THEME);
+                }
     
                 self.renderer.draw_frame(&mut target, ui).unwrap();
+                self.io.update_cursor(&self.context, &self.window);
     
                 target.finish().unwrap();
             }
             WindowEvent::CloseRequested => event_loop.exit(),
+            WindowEvent::Resized(size) => {
+                self.display.resize(size.into());
+                self.io.handle_event(&mut self.context, &WindowEvent::Resized(size));
+            }
             event => {
                 self.io.handle_event(&mut self.context, &event);
             }
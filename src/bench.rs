@@ -6,15 +6,33 @@
 //! [`start`](fn.start.html) and end the timing with [`end`](struct.Handle.html#method.end).
 //! Use [`stats`](fn.stats.html) to get a [`Stats`](struct.Stats.html), which is the
 //! primary interface for reporting on the timings.
+//!
+//! Separately, Thyme can profile the building of each frame, recording the time spent
+//! on each widget theme.  Enable this with [`set_frame_profiling`](fn.set_frame_profiling.html)
+//! and query the most recently completed frame with [`frame_profile`](fn.frame_profile.html).
+//!
+//! Counters record a single value for a tag, such as the number of quads drawn in the last frame.
+//! Use [`counter`](fn.counter.html) to read them.
 
+use std::cmp::Reverse;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant};
 
 use parking_lot::{const_mutex, Mutex};
+use rustc_hash::FxHashMap;
 
 const MOVING_AVG_LEN: usize = 30;
 
 static BENCH: Mutex<BenchSet> = const_mutex(BenchSet::new());
 
+static COUNTERS: Mutex<Vec<(String, u64)>> = const_mutex(Vec::new());
+
+/// The counter holding the number of quads drawn in the most recent frame.  See [`counter`](fn.counter.html).
+pub const QUADS_COUNTER: &str = "quads";
+
+static PROFILER_ENABLED: AtomicBool = AtomicBool::new(false);
+static PROFILER: Mutex<Option<FrameProfiler>> = const_mutex(None);
+
 /// Configuration values to pass to the benchmark [`report`](fn.report.html) function.
 #[derive(Copy, Clone)]
 pub struct ReportConfig {
@@ -191,6 +209,21 @@ pub fn report(tag: &str, config: ReportConfig) -> String {
     }
 }
 
+/// Sets the counter with the given `tag` to `value`.
+pub fn set_counter(tag: &str, value: u64) {
+    let mut counters = COUNTERS.lock();
+    match counters.iter_mut().find(|(counter_tag, _)| counter_tag == tag) {
+        Some((_, counter)) => *counter = value,
+        None => counters.push((tag.to_string(), value)),
+    }
+}
+
+/// Returns the value of the counter with the given `tag`, or zero if it has not been set.
+pub fn counter(tag: &str) -> u64 {
+    let counters = COUNTERS.lock();
+    counters.iter().find(|(counter_tag, _)| counter_tag == tag).map_or(0, |(_, value)| *value)
+}
+
 fn end(handle: Handle) {
     let mut bench = BENCH.lock();
     bench.end(handle);
@@ -481,3 +514,250 @@ impl Bench {
         }
     }
 }
+
+
+/// Enables or disables per frame profiling.  When enabled, Thyme records the time spent
+/// building, laying out, and measuring text for each widget, grouped by theme ID.  The
+/// results for the most recently completed frame are available via
+/// [`frame_profile`](fn.frame_profile.html).  Profiling is disabled by default, and has
+/// close to zero cost when disabled.
+pub fn set_frame_profiling(enabled: bool) {
+    PROFILER_ENABLED.store(enabled, Ordering::Relaxed);
+
+    if !enabled {
+        PROFILER.lock().take();
+    }
+}
+
+/// Returns whether per frame profiling is currently enabled.  See
+/// [`set_frame_profiling`](fn.set_frame_profiling.html).
+pub fn frame_profiling() -> bool {
+    PROFILER_ENABLED.load(Ordering::Relaxed)
+}
+
+/// Returns the [`FrameProfile`](struct.FrameProfile.html) for the most recently completed
+/// frame.  The profile will be empty if [`frame profiling`](fn.set_frame_profiling.html) is
+/// disabled or no frame has been completed since it was enabled.  The profile entries are
+/// sorted according to the current [`frame_profile_sort`](fn.frame_profile_sort.html).
+pub fn frame_profile() -> FrameProfile {
+    let profiler = PROFILER.lock();
+    match profiler.as_ref() {
+        None => FrameProfile::default(),
+        Some(profiler) => {
+            let mut profile = profiler.last.clone();
+            profile.sort(profiler.sort);
+            profile
+        }
+    }
+}
+
+/// Sets the sort order that will be used for subsequent calls to [`frame_profile`](fn.frame_profile.html).
+/// This is also used by the [`frame_profiler`](../struct.Frame.html#method.frame_profiler) window.
+pub fn set_frame_profile_sort(sort: ProfileSort) {
+    let mut profiler = PROFILER.lock();
+    profiler.get_or_insert_with(FrameProfiler::new).sort = sort;
+}
+
+/// Returns the current sort order used by [`frame_profile`](fn.frame_profile.html).
+pub fn frame_profile_sort() -> ProfileSort {
+    PROFILER.lock().as_ref().map_or(ProfileSort::default(), |profiler| profiler.sort)
+}
+
+pub(crate) fn begin_profile_frame() {
+    if !frame_profiling() { return; }
+
+    let mut profiler = PROFILER.lock();
+    let profiler = profiler.get_or_insert_with(FrameProfiler::new);
+    profiler.current.clear();
+    profiler.frame_start = Some(Instant::now());
+}
+
+pub(crate) fn end_profile_frame() {
+    if !frame_profiling() { return; }
+
+    let mut profiler = PROFILER.lock();
+    let profiler = match profiler.as_mut() {
+        None => return,
+        Some(profiler) => profiler,
+    };
+
+    let frame_start = match profiler.frame_start.take() {
+        None => return,
+        Some(start) => start,
+    };
+
+    profiler.last = FrameProfile {
+        frame_time: Instant::now() - frame_start,
+        entries: profiler.current.drain().map(|(_, entry)| entry).collect(),
+    };
+}
+
+/// Starts a profiling span, returning `None` if profiling is disabled.
+pub(crate) fn profile_start() -> Option<Instant> {
+    if frame_profiling() {
+        Some(Instant::now())
+    } else {
+        None
+    }
+}
+
+/// Ends a profiling span started with `profile_start`, adding the elapsed time
+/// to the entry for the specified `theme_id`.
+pub(crate) fn profile_end(start: Option<Instant>, theme_id: &str, kind: ProfileKind) {
+    let start = match start {
+        None => return,
+        Some(start) => start,
+    };
+    let duration = Instant::now() - start;
+
+    let mut profiler = PROFILER.lock();
+    let profiler = match profiler.as_mut() {
+        None => return,
+        Some(profiler) => profiler,
+    };
+
+    if profiler.frame_start.is_none() { return; }
+
+    let entry = match profiler.current.get_mut(theme_id) {
+        Some(entry) => entry,
+        None => profiler.current.entry(theme_id.to_string()).or_insert_with(|| ProfileEntry::new(theme_id)),
+    };
+
+    match kind {
+        ProfileKind::Build => {
+            entry.count += 1;
+            entry.build += duration;
+        },
+        ProfileKind::Layout => entry.layout += duration,
+        ProfileKind::Text => entry.text += duration,
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum ProfileKind {
+    Build,
+    Layout,
+    Text,
+}
+
+/// The field used to sort the entries in a [`FrameProfile`](struct.FrameProfile.html).
+/// Theme IDs are sorted alphabetically, while all other fields are sorted from largest
+/// to smallest.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum ProfileSort {
+    /// Sort by the widget theme ID
+    ThemeId,
+
+    /// Sort by the number of widgets created with the theme
+    Count,
+
+    /// Sort by the total build time
+    #[default]
+    Build,
+
+    /// Sort by the time spent on layout
+    Layout,
+
+    /// Sort by the time spent on text measurement
+    Text,
+}
+
+/// The profiling data for a single frame, obtained with [`frame_profile`](fn.frame_profile.html).
+#[derive(Clone, Debug, Default)]
+pub struct FrameProfile {
+    frame_time: Duration,
+    entries: Vec<ProfileEntry>,
+}
+
+impl FrameProfile {
+    /// Returns the total time between the creation of the frame and when it was finished,
+    /// generally when it was passed to the renderer.
+    pub fn frame_time(&self) -> Duration { self.frame_time }
+
+    /// Returns the profiling entries for this frame, one per widget theme ID
+    pub fn entries(&self) -> &[ProfileEntry] { &self.entries }
+
+    /// Sorts the entries in this profile based on the specified field.
+    pub fn sort(&mut self, sort: ProfileSort) {
+        use ProfileSort::*;
+        match sort {
+            ThemeId => self.entries.sort_by(|a, b| a.theme_id.cmp(&b.theme_id)),
+            Count => self.entries.sort_by_key(|entry| Reverse(entry.count)),
+            Build => self.entries.sort_by_key(|entry| Reverse(entry.build)),
+            Layout => self.entries.sort_by_key(|entry| Reverse(entry.layout)),
+            Text => self.entries.sort_by_key(|entry| Reverse(entry.text)),
+        }
+    }
+
+    /// Generates a report string for each entry in this profile, in the current order.
+    pub fn report(&self) -> Vec<String> {
+        self.entries.iter().map(|entry| {
+            format!(
+                "{} ({}): build {:.2} µs, layout {:.2} µs, text {:.2} µs",
+                entry.theme_id, entry.count, micros(entry.build), micros(entry.layout), micros(entry.text),
+            )
+        }).collect()
+    }
+}
+
+/// The profiling data for all widgets sharing a single theme ID over the course of one frame.
+/// Build times include the time spent building any children, so the times for parent
+/// themes will include the times for their child themes.  Similarly, layout time includes
+/// any text measurement needed for layout.
+#[derive(Clone, Debug)]
+pub struct ProfileEntry {
+    theme_id: String,
+    count: u32,
+    build: Duration,
+    layout: Duration,
+    text: Duration,
+}
+
+impl ProfileEntry {
+    fn new(theme_id: &str) -> ProfileEntry {
+        ProfileEntry {
+            theme_id: theme_id.to_string(),
+            count: 0,
+            build: Duration::ZERO,
+            layout: Duration::ZERO,
+            text: Duration::ZERO,
+        }
+    }
+
+    /// The full widget theme ID associated with this entry
+    pub fn theme_id(&self) -> &str { &self.theme_id }
+
+    /// The number of widgets using this theme that were built during the frame
+    pub fn count(&self) -> u32 { self.count }
+
+    /// The total time spent building widgets with this theme, including their children
+    pub fn build(&self) -> Duration { self.build }
+
+    /// The total time spent computing the size and position of widgets with this theme
+    pub fn layout(&self) -> Duration { self.layout }
+
+    /// The total time spent measuring text for widgets with this theme
+    pub fn text(&self) -> Duration { self.text }
+}
+
+pub(crate) fn micros(duration: Duration) -> f32 {
+    duration.as_secs_f32() * Unit::Micros.multiplier()
+}
+
+struct FrameProfiler {
+    current: FxHashMap<String, ProfileEntry>,
+    last: FrameProfile,
+    frame_start: Option<Instant>,
+    sort: ProfileSort,
+}
+
+impl FrameProfiler {
+    fn new() -> FrameProfiler {
+        FrameProfiler {
+            current: FxHashMap::default(),
+            last: FrameProfile::default(),
+            frame_start: None,
+            sort: ProfileSort::default(),
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use std::ops::RangeInclusive;
+
+use crate::Frame;
+
+// Helpers which read and write application values directly
+impl Frame {
+    /**
+    A [`toggle_button`](#method.toggle_button) which is bound to `value`.  The button is drawn as active
+    when `value` is `true`, and clicking it toggles `value`.  Returns whether `value` was changed.
+
+    # Example
+    ```
+    fn options(ui: &mut Frame, options: &mut Options) {
+        ui.bind_checkbox("checkbox", "Show FPS", &mut options.show_fps);
+        ui.bind_checkbox("checkbox", "VSync", &mut options.vsync);
+    }
+    ```
+    **/
+    pub fn bind_checkbox(&mut self, theme: &str, label: &str, value: &mut bool) -> bool {
+        if self.toggle_button(theme, label, *value).clicked {
+            *value = !*value;
+            true
+        } else {
+            false
+        }
+    }
+
+    /**
+    A [`horizontal_slider`](#method.horizontal_slider) which is bound to `value`, within the specified `range`.
+    Moving the slider updates `value`.  Returns whether `value` was changed.
+
+    # Example
+    ```
+    fn options(ui: &mut Frame, options: &mut Options) {
+        ui.bind_slider("slider", &mut options.volume, 0.0..=1.0);
+    }
+    ```
+    **/
+    pub fn bind_slider(&mut self, theme: &str, value: &mut f32, range: RangeInclusive<f32>) -> bool {
+        match self.horizontal_slider(theme, *range.start(), *range.end(), *value) {
+            Some(new_value) if new_value != *value => {
+                *value = new_value;
+                true
+            },
+            _ => false,
+        }
+    }
+
+    /**
+    An [`input_field`](#method.input_field) which is bound to `value`.  The field always shows the current
+    contents of `value`, even if it is changed elsewhere by the application, and text entered by the user is written
+    back to `value`.  The `id` must be unique.  Returns whether `value` was changed.
+
+    # Example
+    ```
+    fn character(ui: &mut Frame, character: &mut Character) {
+        ui.bind_text("input_field", "character_name", &mut character.name);
+    }
+    ```
+    **/
+    pub fn bind_text(&mut self, theme: &str, id: &str, value: &mut String) -> bool {
+        self.modify(id, |state| {
+            if state.text.as_deref() != Some(value.as_str()) {
+                state.text = Some(value.clone());
+            }
+        });
+
+        self.input_field(theme, id, None);
+
+        self.modify(id, |state| {
+            match &state.text {
+                Some(text) if text != value => {
+                    value.clone_from(text);
+                    true
+                },
+                _ => false,
+            }
+        })
+    }
+}
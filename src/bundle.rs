@@ -0,0 +1,233 @@
+use std::path::{Path, PathBuf};
+
+use crate::Error;
+
+const MAGIC: &[u8; 8] = b"THYMEBND";
+const VERSION: u32 = 1;
+
+/// The kind of asset file in a theme directory or bundle, determined by the file extension
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum AssetKind {
+    Theme,
+    Image,
+    CompressedImage,
+    Font,
+}
+
+impl AssetKind {
+    fn from_path(path: &Path) -> Option<AssetKind> {
+        let ext = path.extension()?.to_string_lossy().to_lowercase();
+        match ext.as_str() {
+            "yml" | "yaml" => Some(AssetKind::Theme),
+            "png" | "jpg" | "jpeg" => Some(AssetKind::Image),
+            "ktx2" => Some(AssetKind::CompressedImage),
+            "ttf" | "otf" => Some(AssetKind::Font),
+            _ => None,
+        }
+    }
+
+    fn tag(self) -> u8 {
+        match self {
+            AssetKind::Theme => 0,
+            AssetKind::Image => 1,
+            AssetKind::CompressedImage => 2,
+            AssetKind::Font => 3,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Option<AssetKind> {
+        match tag {
+            0 => Some(AssetKind::Theme),
+            1 => Some(AssetKind::Image),
+            2 => Some(AssetKind::CompressedImage),
+            3 => Some(AssetKind::Font),
+            _ => None,
+        }
+    }
+}
+
+/// An asset file found in a theme directory
+pub(crate) struct ThemeDirFile {
+    pub kind: AssetKind,
+    pub id: String,
+    pub path: PathBuf,
+}
+
+/**
+Finds the theme, image, and font files in `dir` and its subdirectories, sorted by path.  Images and fonts
+use their file name, without the extension, as their ID.  Files of other types are ignored.
+**/
+pub(crate) fn scan_theme_dir(dir: &Path) -> Result<Vec<ThemeDirFile>, Error> {
+    let mut paths = Vec::new();
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in dir.read_dir().map_err(Error::IO)? {
+            let path = entry.map_err(Error::IO)?.path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else {
+                paths.push(path);
+            }
+        }
+    }
+    paths.sort();
+
+    let mut files = Vec::new();
+    for path in paths {
+        let kind = match AssetKind::from_path(&path) {
+            None => continue,
+            Some(kind) => kind,
+        };
+
+        let id = match kind {
+            AssetKind::Theme => path.strip_prefix(dir).unwrap_or(&path).to_string_lossy().to_string(),
+            _ => match path.file_stem() {
+                None => continue,
+                Some(stem) => stem.to_string_lossy().to_string(),
+            },
+        };
+
+        files.push(ThemeDirFile { kind, id, path });
+    }
+
+    Ok(files)
+}
+
+/**
+A theme directory, consisting of YAML theme files, images, and fonts, packaged into a single binary bundle.  The
+bundle may be written to a file and then embedded in an application with `include_bytes!`, and registered with
+[`ContextBuilder::register_theme_bundle`](struct.ContextBuilder.html#method.register_theme_bundle).
+
+Files are found in the directory and all of its subdirectories by extension.  Theme files (`yml` or `yaml`) are
+merged in path order.  Images (`png`, `jpg`, `jpeg`, or compressed `ktx2`) and fonts (`ttf` or `otf`) are
+registered using their file name, without the extension, as their ID.  Image sets listing image `files` or a
+`directory` are not supported in bundles.
+
+During development, register the same directory with
+[`ContextBuilder::register_theme_dir`](struct.ContextBuilder.html#method.register_theme_dir) instead to keep
+live reload.  The `bundle_theme` example creates a bundle file from a directory.
+
+# Example
+```no_run
+let bundle = thyme::ThemeBundle::from_dir(std::path::Path::new("assets/theme"))?;
+bundle.write(std::path::Path::new("assets/theme.bundle"))?;
+
+// in the application
+let mut context_builder = thyme::ContextBuilder::with_defaults();
+context_builder.register_theme_bundle(include_bytes!("../assets/theme.bundle"))?;
+```
+**/
+pub struct ThemeBundle {
+    entries: Vec<BundleEntry>,
+}
+
+struct BundleEntry {
+    kind: AssetKind,
+    id: String,
+    data: Vec<u8>,
+}
+
+impl ThemeBundle {
+    /// Reads all of the theme, image, and font files in the directory at `dir` into a bundle
+    pub fn from_dir(dir: &Path) -> Result<ThemeBundle, Error> {
+        let mut entries = Vec::new();
+        for file in scan_theme_dir(dir)? {
+            let data = std::fs::read(&file.path).map_err(Error::IO)?;
+            log::debug!("Read {} bytes from '{:?}' for theme bundle", data.len(), file.path);
+            entries.push(BundleEntry { kind: file.kind, id: file.id, data });
+        }
+
+        Ok(ThemeBundle { entries })
+    }
+
+    /// Reads a bundle previously created with [`to_bytes`](#method.to_bytes).  Returns `Err` if `bytes`
+    /// is not a valid bundle.
+    pub fn from_bytes(bytes: &[u8]) -> Result<ThemeBundle, Error> {
+        let invalid = |msg: &str| Error::Theme(format!("Invalid theme bundle: {}", msg));
+
+        let mut reader = BundleReader { bytes };
+        if reader.take(MAGIC.len()).ok_or_else(|| invalid("missing header"))? != MAGIC {
+            return Err(invalid("missing header"));
+        }
+
+        let version = reader.u32().ok_or_else(|| invalid("missing version"))?;
+        if version != VERSION {
+            return Err(invalid(&format!("unsupported version {}", version)));
+        }
+
+        let count = reader.u32().ok_or_else(|| invalid("missing entry count"))?;
+        let mut entries = Vec::new();
+        for _ in 0..count {
+            let tag = reader.take(1).ok_or_else(|| invalid("truncated entry"))?[0];
+            let kind = AssetKind::from_tag(tag).ok_or_else(|| invalid(&format!("unknown entry kind {}", tag)))?;
+            let id = reader.block().ok_or_else(|| invalid("truncated entry"))?;
+            let id = String::from_utf8(id.to_vec()).map_err(|_| invalid("entry id is not valid UTF-8"))?;
+            let data = reader.block().ok_or_else(|| invalid("truncated entry"))?.to_vec();
+            entries.push(BundleEntry { kind, id, data });
+        }
+
+        Ok(ThemeBundle { entries })
+    }
+
+    /// Serializes this bundle into bytes, which may be read with [`from_bytes`](#method.from_bytes)
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&VERSION.to_le_bytes());
+        out.extend_from_slice(&(self.entries.len() as u32).to_le_bytes());
+
+        for entry in &self.entries {
+            out.push(entry.kind.tag());
+            for block in [entry.id.as_bytes(), &entry.data] {
+                out.extend_from_slice(&(block.len() as u32).to_le_bytes());
+                out.extend_from_slice(block);
+            }
+        }
+
+        out
+    }
+
+    /// Writes this bundle to the file at `path`.  See [`to_bytes`](#method.to_bytes).
+    pub fn write(&self, path: &Path) -> Result<(), Error> {
+        std::fs::write(path, self.to_bytes()).map_err(Error::IO)
+    }
+
+    /// Returns the number of theme, image, and font files in this bundle
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether this bundle contains no files
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub(crate) fn entries(&self) -> impl Iterator<Item = (AssetKind, &str, &[u8])> {
+        self.entries.iter().map(|entry| (entry.kind, entry.id.as_str(), entry.data.as_slice()))
+    }
+}
+
+struct BundleReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> BundleReader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if len > self.bytes.len() { return None; }
+
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Some(taken)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        let bytes = self.take(4)?;
+        Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// Reads a length prefixed block of bytes
+    fn block(&mut self) -> Option<&'a [u8]> {
+        let len = self.u32()? as usize;
+        self.take(len)
+    }
+}
@@ -0,0 +1,76 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/**
+A source of time for a [`Context`](struct.Context.html), used for all UI animations, tooltips, and other timing.
+By default, the context uses a [`SystemClock`](struct.SystemClock.html).  A custom clock may be set with
+[`Context::set_clock`](struct.Context.html#method.set_clock), so that UI animations follow simulation time,
+for example in deterministic replays, while paused, in slow motion, or in fixed timestep tests.  See also
+[`ManualClock`](struct.ManualClock.html).
+**/
+pub trait Clock {
+    /// Returns the time elapsed since an arbitrary fixed starting point, such as when the clock was created.
+    /// This should never decrease.
+    fn elapsed(&self) -> Duration;
+}
+
+/// A [`Clock`](trait.Clock.html) following the system time, measured from when the clock was created
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    /// Creates a new clock, starting at the current time
+    pub fn new() -> SystemClock {
+        SystemClock { start: Instant::now() }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self { SystemClock::new() }
+}
+
+impl Clock for SystemClock {
+    fn elapsed(&self) -> Duration { self.start.elapsed() }
+}
+
+/**
+A [`Clock`](trait.Clock.html) which only advances when told to.  Clones of a `ManualClock` share the same
+time, so one clone may be set on the [`Context`](struct.Context.html) while the application keeps another
+to drive it.
+
+# Example
+```
+let clock = ManualClock::new();
+context.set_clock(clock.clone());
+
+// each simulation step
+clock.advance(Duration::from_millis(16) * time_scale);
+```
+**/
+#[derive(Clone, Default)]
+pub struct ManualClock {
+    time: Rc<Cell<Duration>>,
+}
+
+impl ManualClock {
+    /// Creates a new clock, starting at zero
+    pub fn new() -> ManualClock {
+        ManualClock::default()
+    }
+
+    /// Sets the current `time` of this clock
+    pub fn set(&self, time: Duration) {
+        self.time.set(time);
+    }
+
+    /// Advances the current time of this clock by `delta`
+    pub fn advance(&self, delta: Duration) {
+        self.time.set(self.time.get() + delta);
+    }
+}
+
+impl Clock for ManualClock {
+    fn elapsed(&self) -> Duration { self.time.get() }
+}
@@ -0,0 +1,103 @@
+/**
+A GPU compressed texture format, which may be used for textures registered with
+[`ContextBuilder::register_compressed_texture`](struct.ContextBuilder.html#method.register_compressed_texture).
+Compressed textures are uploaded to the GPU as is, reducing GPU memory use for large UI atlases.  Support
+for each format depends on the [`Renderer`](trait.Renderer.html) and the graphics device; the
+[`GLRenderer`](struct.GLRenderer.html) supports all formats supported by the device, while the
+[`GliumRenderer`](struct.GliumRenderer.html) supports the BCn formats.
+**/
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CompressedFormat {
+    /// BC1 (DXT1) with 1 bit alpha, 8 bytes per 4x4 block
+    Bc1,
+
+    /// BC3 (DXT5), 16 bytes per 4x4 block
+    Bc3,
+
+    /// BC7, 16 bytes per 4x4 block
+    Bc7,
+
+    /// ASTC with 4x4 blocks, 16 bytes per block
+    Astc4x4,
+}
+
+impl CompressedFormat {
+    /// Returns the size of the data for a texture of this format with the specified `dimensions`, in bytes
+    pub fn data_len(self, dimensions: (u32, u32)) -> usize {
+        let block_bytes = match self {
+            CompressedFormat::Bc1 => 8,
+            CompressedFormat::Bc3 | CompressedFormat::Bc7 | CompressedFormat::Astc4x4 => 16,
+        };
+
+        let blocks_x = (dimensions.0 as usize).div_ceil(4);
+        let blocks_y = (dimensions.1 as usize).div_ceil(4);
+        blocks_x * blocks_y * block_bytes
+    }
+
+    // Vulkan format identifiers used by KTX2, in both UNORM and SRGB variants
+    fn from_vk_format(format: u32) -> Option<CompressedFormat> {
+        match format {
+            133 | 134 => Some(CompressedFormat::Bc1),
+            137 | 138 => Some(CompressedFormat::Bc3),
+            145 | 146 => Some(CompressedFormat::Bc7),
+            157 | 158 => Some(CompressedFormat::Astc4x4),
+            _ => None,
+        }
+    }
+}
+
+/// The top level mip map of a compressed texture read from a KTX2 container
+pub(crate) struct CompressedTexture {
+    pub format: CompressedFormat,
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+const KTX2_IDENTIFIER: [u8; 12] = [0xAB, b'K', b'T', b'X', b' ', b'2', b'0', 0xBB, b'\r', b'\n', 0x1A, b'\n'];
+
+/// Reads the top level mip map of the 2D texture in the KTX2 container `data`.  Supercompressed
+/// textures, such as those using Basis Universal, are not supported.
+pub(crate) fn parse_ktx2(data: &[u8]) -> Result<CompressedTexture, String> {
+    // the header, followed by the index entry for at least one level
+    if data.len() < 104 || data[0..12] != KTX2_IDENTIFIER {
+        return Err("Not a valid KTX2 file".to_string());
+    }
+
+    let u32_at = |offset: usize| u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]]);
+    let u64_at = |offset: usize| u64::from(u32_at(offset)) | (u64::from(u32_at(offset + 4)) << 32);
+
+    let vk_format = u32_at(12);
+    let width = u32_at(20);
+    let height = u32_at(24);
+    let depth = u32_at(28);
+    let layers = u32_at(32);
+    let faces = u32_at(36);
+    let supercompression = u32_at(44);
+
+    let format = CompressedFormat::from_vk_format(vk_format).ok_or_else(||
+        format!("Unsupported KTX2 texture format {}", vk_format)
+    )?;
+
+    if depth > 1 || layers > 1 || faces > 1 {
+        return Err("Only 2D KTX2 textures are supported".to_string());
+    }
+
+    if supercompression != 0 {
+        return Err("Supercompressed KTX2 textures are not supported".to_string());
+    }
+
+    // the level index immediately follows the header, starting with the top level
+    let offset = u64_at(80) as usize;
+    let len = u64_at(88) as usize;
+    match offset.checked_add(len) {
+        Some(end) if end <= data.len() => (),
+        _ => return Err("The KTX2 level data is out of bounds".to_string()),
+    }
+
+    if len != format.data_len((width, height)) {
+        return Err(format!("The KTX2 level data has an invalid length for a {}x{} {:?} texture", width, height, format));
+    }
+
+    Ok(CompressedTexture { format, width, height, data: data[offset..offset + len].to_vec() })
+}
@@ -0,0 +1,418 @@
+use crate::{Color, ContextBuilder, BuildOptions, DrawData, DrawDataRenderer, Error, Frame, Point, Rect, Renderer, WidgetBuilder, IO};
+use crate::theme_definition::{Align, TextWrap};
+
+const DISPLAY_SIZE: Point = Point { x: 256.0, y: 160.0 };
+
+const THEME: &str = r##"
+fonts:
+  text:
+    source: conformance
+    size: 14
+image_sets:
+  conformance:
+    source: conformance
+    scale: 1.0
+    images:
+      solid:
+        solid: true
+        color: "#48C"
+      composed:
+        position: [4, 0]
+        grid_size: [4, 4]
+      composed_horizontal:
+        position: [16, 0]
+        grid_size_horiz: [4, 8]
+      checker:
+        position: [0, 12]
+        size: [4, 4]
+        fill: Repeat
+      gradient:
+        position: [0, 16]
+        size: [16, 16]
+        fill: Stretch
+      normal:
+        position: [16, 16]
+        size: [8, 8]
+        fill: Stretch
+      hover:
+        position: [24, 16]
+        size: [8, 8]
+        fill: Stretch
+      pressed:
+        position: [16, 24]
+        size: [8, 8]
+        fill: Stretch
+      active:
+        position: [24, 24]
+        size: [8, 8]
+        fill: Stretch
+      disabled:
+        position: [16, 16]
+        size: [8, 8]
+        fill: Stretch
+        color: "#8888"
+      button:
+        states:
+          Normal: normal
+          Hover: hover
+          Pressed: pressed
+          Active: active
+          Active + Hover: hover
+          Disabled: disabled
+widgets:
+  box:
+    size: [0, 0]
+  label:
+    font: text
+    text_color: "#FFF"
+    border: { all: 2 }
+  button:
+    from: label
+    background: conformance/button
+    wants_mouse: true
+"##;
+
+const PALETTE: [[u8; 4]; 9] = [
+    [255, 0, 0, 255], [0, 255, 0, 255], [0, 0, 255, 255],
+    [255, 255, 0, 255], [0, 255, 255, 255], [255, 0, 255, 255],
+    [255, 128, 0, 255], [128, 0, 255, 255], [0, 128, 64, 255],
+];
+
+/// Creates the 32 by 32 pixel RGBA test texture used by the theme
+fn texture() -> Vec<u8> {
+    let mut data = vec![0; 32 * 32 * 4];
+    let mut set = |x: usize, y: usize, color: [u8; 4]| {
+        let index = (x + y * 32) * 4;
+        data[index..index + 4].copy_from_slice(&color);
+    };
+
+    for y in 0..32 {
+        for x in 0..32 {
+            let color = match (x, y) {
+                // white, used by solid images
+                (0..=3, 0..=11) => [255, 255, 255, 255],
+                // composed, a 3 by 3 grid of 4 pixel cells
+                (4..=15, 0..=11) => PALETTE[(x - 4) / 4 + 3 * (y / 4)],
+                // composed horizontal, a 3 by 1 grid of 4 by 8 pixel cells
+                (16..=27, 0..=7) => PALETTE[(x - 16) / 4 + 6],
+                // checker with transparent cells, for repeat fill
+                (0..=3, 12..=15) if (x / 2 + y / 2) % 2 == 0 => [255, 255, 0, 255],
+                (0..=3, 12..=15) => [0, 0, 0, 0],
+                // gradient, for linear filtering
+                (0..=15, 16..=31) => [(x * 16) as u8, ((y - 16) * 16) as u8, 128, 255],
+                // anim state images, with a one pixel dark edge
+                (16..=31, 16..=31) => {
+                    let edge = x % 8 == 0 || x % 8 == 7 || y % 8 == 0 || y % 8 == 7;
+                    let base = match (x / 8, y / 8) {
+                        (2, 2) => [64, 96, 160, 255],
+                        (3, 2) => [128, 176, 255, 255],
+                        (2, 3) => [32, 48, 96, 255],
+                        _ => [255, 160, 0, 128],
+                    };
+                    if edge { [base[0] / 2, base[1] / 2, base[2] / 2, base[3]] } else { base }
+                },
+                _ => [0, 0, 0, 0],
+            };
+            set(x, y, color);
+        }
+    }
+
+    data
+}
+
+struct Scene {
+    name: &'static str,
+    build: fn(&mut Frame),
+}
+
+const SCENES: [Scene; 5] = [
+    Scene { name: "images", build: images_scene },
+    Scene { name: "clipping", build: clipping_scene },
+    Scene { name: "text", build: text_scene },
+    Scene { name: "anim_states", build: anim_states_scene },
+    Scene { name: "image_color", build: image_color_scene },
+];
+
+fn image(ui: &mut Frame, image: &str, pos: [f32; 2], size: [f32; 2]) {
+    ui.start("box").background(image).pos(pos[0], pos[1]).size(size[0], size[1]).finish();
+}
+
+fn images_scene(ui: &mut Frame) {
+    image(ui, "conformance/solid", [8.0, 8.0], [40.0, 40.0]);
+    image(ui, "conformance/composed", [56.0, 8.0], [60.0, 40.0]);
+    image(ui, "conformance/composed_horizontal", [124.0, 8.0], [60.0, 32.0]);
+    image(ui, "conformance/gradient", [8.0, 56.0], [64.0, 48.0]);
+    image(ui, "conformance/checker", [80.0, 56.0], [50.0, 30.0]);
+    image(ui, "conformance/composed", [192.5, 8.25], [51.5, 30.75]);
+    image(ui, "conformance/gradient", [140.0, 56.0], [7.0, 90.0]);
+}
+
+fn clipping_scene(ui: &mut Frame) {
+    ui.start("box")
+    .background("conformance/composed")
+    .pos(8.0, 8.0)
+    .size(100.0, 60.0)
+    .clip(Rect::new(Point::new(8.0, 8.0), Point::new(100.0, 60.0)))
+    .children(|ui| {
+        image(ui, "conformance/gradient", [60.0, 30.0], [80.0, 80.0]);
+        image(ui, "conformance/checker", [-20.0, -10.0], [40.0, 30.0]);
+        ui.start("label").text("Clipped text which is too long").pos(10.0, 20.0).size(150.0, 20.0).finish();
+
+        ui.start("box")
+        .background("conformance/solid")
+        .pos(80.0, 40.0)
+        .size(60.0, 30.0)
+        .unclip()
+        .finish();
+    });
+
+    ui.start("box")
+    .background("conformance/composed_horizontal")
+    .pos(130.0, 40.0)
+    .size(40.0, 40.0)
+    .clip(Rect::new(Point::new(130.0, 40.0), Point::new(40.0, 40.0)))
+    .children(|ui| {
+        // nested clips are intersected
+        ui.start("box")
+        .background("conformance/composed")
+        .pos(10.0, 10.0)
+        .size(60.0, 60.0)
+        .clip(Rect::new(Point::new(150.0, 45.0), Point::new(60.0, 60.0)))
+        .children(|ui| {
+            image(ui, "conformance/gradient", [-20.0, 5.0], [100.0, 10.0]);
+        });
+    });
+}
+
+fn text_scene(ui: &mut Frame) {
+    let label = |ui: &mut Frame, text: &str, pos: [f32; 2], size: [f32; 2], align: Align| {
+        ui.start("label")
+        .background("conformance/normal")
+        .text(text)
+        .text_align(align)
+        .pos(pos[0], pos[1])
+        .size(size[0], size[1])
+        .finish();
+    };
+
+    label(ui, "Left aligned", [8.0, 8.0], [112.0, 24.0], Align::Left);
+    label(ui, "Centered", [128.0, 8.0], [112.0, 24.0], Align::Center);
+    label(ui, "Right", [8.0, 40.0], [112.0, 24.0], Align::Right);
+    label(ui, "Bottom", [128.0, 40.0], [112.0, 40.0], Align::BotRight);
+
+    ui.start("label")
+    .background("conformance/normal")
+    .text("This text is wrapped onto multiple lines within its widget.")
+    .text_wrap(TextWrap::Word)
+    .text_color(Color { r: 255, g: 224, b: 96, a: 255 })
+    .pos(8.0, 72.0)
+    .size(112.0, 80.0)
+    .finish();
+
+    ui.start("label")
+    .text("Symbols: {}[]()<>!?@#$%^&*_+=|~")
+    .text_color(Color { r: 128, g: 255, b: 160, a: 255 })
+    .pos(128.0, 88.0)
+    .size(120.0, 64.0)
+    .text_wrap(TextWrap::Character)
+    .finish();
+}
+
+fn anim_states_scene(ui: &mut Frame) {
+    fn button(ui: &mut Frame, x: f32, y: f32) -> WidgetBuilder<'_> {
+        ui.start("button").pos(x, y).size(72.0, 32.0)
+    }
+
+    button(ui, 8.0, 8.0).text("Normal").finish();
+    button(ui, 88.0, 8.0).text("Hover").force_hover(true).finish();
+    button(ui, 168.0, 8.0).text("Pressed").force_pressed(true).finish();
+    button(ui, 8.0, 48.0).text("Active").active(true).finish();
+    button(ui, 88.0, 48.0).text("Both").active(true).force_hover(true).finish();
+    button(ui, 168.0, 48.0).text("Disabled").enabled(false).finish();
+
+    // an active image over other content, to exercise alpha blending
+    image(ui, "conformance/gradient", [8.0, 96.0], [120.0, 56.0]);
+    button(ui, 40.0, 104.0).size(120.0, 40.0).active(true).finish();
+}
+
+fn image_color_scene(ui: &mut Frame) {
+    let colors = [
+        Color { r: 255, g: 255, b: 255, a: 255 },
+        Color { r: 255, g: 0, b: 0, a: 255 },
+        Color { r: 0, g: 255, b: 128, a: 255 },
+        Color { r: 255, g: 255, b: 255, a: 128 },
+        Color { r: 64, g: 64, b: 255, a: 64 },
+    ];
+
+    image(ui, "conformance/composed", [0.0, 0.0], [256.0, 160.0]);
+    for (index, color) in colors.iter().enumerate() {
+        let x = 8.0 + index as f32 * 48.0;
+        for (row, name) in ["gradient", "solid", "composed"].iter().enumerate() {
+            ui.start("box")
+            .background(&format!("conformance/{}", name))
+            .image_color(*color)
+            .pos(x, 8.0 + row as f32 * 48.0)
+            .size(40.0, 40.0)
+            .finish();
+        }
+    }
+}
+
+struct FixedIO {
+    scale: f32,
+}
+
+impl IO for FixedIO {
+    fn scale_factor(&self) -> f32 { self.scale }
+
+    fn display_size(&self) -> Point { DISPLAY_SIZE * self.scale }
+}
+
+/// The result of rendering one scene of the [`ConformanceSuite`](struct.ConformanceSuite.html).
+pub struct ConformanceResult {
+    /// The name of the scene
+    pub scene: &'static str,
+
+    /// The scale factor the scene was drawn with
+    pub scale: f32,
+
+    /// The width and height of the images, in physical pixels
+    pub size: (u32, u32),
+
+    /// The reference image, as produced by [`DrawData::rasterize`](struct.DrawData.html#method.rasterize)
+    pub expected: Vec<u8>,
+
+    /// The image produced by the renderer being tested
+    pub actual: Vec<u8>,
+
+    /// The reference draw data the expected image was created from
+    pub draw_data: DrawData,
+}
+
+impl ConformanceResult {
+    /// Returns the number of pixels where any channel of the actual image differs from the
+    /// expected image by more than `tolerance`.  If the actual image has the wrong size, every
+    /// pixel is counted as differing.
+    pub fn differing_pixels(&self, tolerance: u8) -> usize {
+        if self.actual.len() != self.expected.len() {
+            return (self.size.0 * self.size.1) as usize;
+        }
+
+        differing_pixels(&self.expected, &self.actual, tolerance)
+    }
+
+    /// Returns whether at most `max_pixels` pixels differ by more than `tolerance`.
+    /// See [`differing_pixels`](#method.differing_pixels).
+    pub fn passed(&self, tolerance: u8, max_pixels: usize) -> bool {
+        self.differing_pixels(tolerance) <= max_pixels
+    }
+}
+
+/// Returns the number of RGBA pixels where any channel differs by more than `tolerance` between two images
+/// of the same size
+pub(crate) fn differing_pixels(expected: &[u8], actual: &[u8], tolerance: u8) -> usize {
+    expected.chunks(4).zip(actual.chunks(4)).filter(|(expected, actual)| {
+        expected.iter().zip(actual.iter()).any(|(e, a)| e.abs_diff(*a) > tolerance)
+    }).count()
+}
+
+/**
+A set of reference scenes for verifying a [`Renderer`](trait.Renderer.html) implementation, such as a
+third party backend.  The scenes exercise plain, solid, composed, and repeating images, clipping, fonts and
+text layout, anim states, image colors, alpha blending, and fractional positions and scale factors.
+
+Each scene is drawn with the renderer being tested, and compared against a reference image produced in
+software by [`DrawData::rasterize`](struct.DrawData.html#method.rasterize).  The images are generated
+internally, but font data must be supplied, as Thyme does not embed any fonts.  Renderers should not be
+expected to match the reference exactly, due to differences in rasterization rules, so results are
+normally checked with a small tolerance.
+
+# Example
+```
+let font = std::fs::read("examples/data/fonts/Roboto-Medium.ttf")?;
+let suite = thyme::ConformanceSuite::new(font);
+
+let results = suite.run(&mut renderer, |renderer, frame, (width, height)| {
+    // draw the frame to an offscreen target of the given size, cleared to opaque black,
+    // and read back the RGBA pixels, with the top row first
+    renderer.draw_to_image(frame, width, height)
+})?;
+
+for result in results {
+    assert!(result.passed(8, 64), "{} at scale {} failed", result.scene, result.scale);
+}
+```
+**/
+pub struct ConformanceSuite {
+    font: Vec<u8>,
+    scales: Vec<f32>,
+}
+
+impl ConformanceSuite {
+    /// Creates a new suite, using the specified TTF or OTF `font` data for all text.
+    pub fn new(font: Vec<u8>) -> ConformanceSuite {
+        ConformanceSuite { font, scales: vec![1.0, 1.5, 2.0] }
+    }
+
+    /// Sets the scale factors that each scene is drawn at.  Defaults to `1.0`, `1.5`, and `2.0`.
+    #[must_use]
+    pub fn with_scales(mut self, scales: &[f32]) -> ConformanceSuite {
+        self.scales = scales.to_vec();
+        self
+    }
+
+    /// Returns the names of all scenes in the suite, in the order they are drawn.
+    pub fn scenes(&self) -> Vec<&'static str> {
+        SCENES.iter().map(|scene| scene.name).collect()
+    }
+
+    /// Returns the size of the display for the scenes, in logical pixels.
+    pub fn display_size(&self) -> Point {
+        DISPLAY_SIZE
+    }
+
+    fn context_builder(&self) -> Result<ContextBuilder, Error> {
+        let mut builder = ContextBuilder::new(BuildOptions { enable_live_reload: false, ..Default::default() });
+        builder.register_theme(serde_yaml::Deserializer::from_str(THEME)).map_err(|e| Error::Serde(e.to_string()))?;
+        builder.register_font("conformance", self.font.clone());
+        builder.register_texture("conformance", texture(), (32, 32));
+        Ok(builder)
+    }
+
+    /**
+    Draws each scene at each scale factor with `renderer`, and compares the results against the reference.
+    For each scene, a new [`Context`](struct.Context.html) is built with the `renderer`, so fonts and textures
+    will be registered multiple times.  The `draw` closure must draw the [`Frame`](struct.Frame.html) to an
+    image of the specified physical size, cleared to opaque black, and return its RGBA pixels with 8 bits per
+    channel, with the top row first.
+    **/
+    pub fn run<R, F>(&self, renderer: &mut R, mut draw: F) -> Result<Vec<ConformanceResult>, Error>
+    where
+        R: Renderer,
+        F: FnMut(&mut R, Frame, (u32, u32)) -> Vec<u8>,
+    {
+        let mut results = Vec::new();
+        for &scale in &self.scales {
+            let mut io = FixedIO { scale };
+
+            for scene in SCENES.iter() {
+                let mut reference = DrawDataRenderer::new();
+                let mut context = self.context_builder()?.build(&mut reference, &mut io)?;
+                let mut frame = context.create_frame();
+                (scene.build)(&mut frame);
+                let draw_data = reference.draw_frame(frame).clone();
+                let expected = draw_data.rasterize(&reference);
+                let size = draw_data.pixel_size();
+
+                let mut context = self.context_builder()?.build(renderer, &mut io)?;
+                let mut frame = context.create_frame();
+                (scene.build)(&mut frame);
+                let actual = draw(renderer, frame, size);
+
+                results.push(ConformanceResult { scene: scene.name, scale, size, expected, actual, draw_data });
+            }
+        }
+
+        Ok(results)
+    }
+}
@@ -1,16 +1,19 @@
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 use std::path::{Path, PathBuf};
-use std::time::Instant;
+use std::time::Duration;
 
 use serde::{Serialize, Deserialize};
 
-use crate::KeyEvent;
-use crate::{BuildOptions, Error, Point, Frame, MouseButton, Rect, frame::{RendGroup, RendGroupDef}};
-use crate::{font::FontSummary, widget::Widget, image::ImageHandle, theme::ThemeSet, resource::ResourceSet};
-use crate::theme_definition::{AnimState, AnimStateKey};
-use crate::render::Renderer;
+use crate::{bench, Clock, KeyEvent, ManualClock, SystemClock};
+use crate::{AccessibleWidget, BuildOptions, Effect, BuildProgress, Transition, ColorTransform, Border, Error, Point, Frame, HapticEvent, Interaction, MouseButton, UiEvent, Rect, WidgetThemeInfo, frame::{RendGroup, RendGroupDef}};
+use crate::{font::{inline_image_char, Font, FontDrawParams, FontSummary, TextLayout, TextLayoutCache}, widget::Widget, image::ImageHandle, theme::ThemeSet, resource::{AssetBuild, ResourceReport, ResourceSet}};
+use crate::selection::Selection;
+use crate::theme_definition::{AnimState, AnimStateKey, CustomData, SystemCursor};
+use crate::theme_overrides::ThemeEnvironment;
+use crate::render::{DisplayClass, DisplayRotation, Renderer};
 
 #[derive(Copy, Clone)]
 pub(crate) struct PersistentStateData {
@@ -18,6 +21,7 @@ pub(crate) struct PersistentStateData {
     pub resize: Point,
     pub moved: Point,
     pub scroll: Point,
+    pub effect: Option<(Effect, u32)>,
 }
 
 /**
@@ -76,6 +80,71 @@ pub struct PersistentState {
     /// A timer in milliseconds, allowing the widget to easily store a particular relevant
     /// time - for delayed actions, for example
     pub timer: u32,
+
+    /// Whether a toggle, such as the shift key of a [`virtual_keyboard`](struct.Frame.html#method.virtual_keyboard),
+    /// is on.  Defaults to false.
+    #[serde(default)]
+    pub toggled: bool,
+
+    /// Whether a window has been positioned by its [`placement`](struct.WindowBuilder.html#method.placement)
+    /// policy, which is only applied the first time it is opened.  Defaults to false.
+    #[serde(default)]
+    pub placed: bool,
+
+    /// Whether a window has been shaded, collapsing it to just its titlebar.  See
+    /// [`WindowBuilder::shadeable`](struct.WindowBuilder.html#method.shadeable).  Defaults to false.
+    #[serde(default)]
+    pub shaded: bool,
+
+    /// The selected child items, for lists and similar containers.  Defaults to empty.
+    #[serde(default)]
+    pub selection: Selection,
+
+    /// The size of this widget's content, as measured during the previous frame, for widgets
+    /// such as [`collapsing_header`](struct.Frame.html#method.collapsing_header) or
+    /// [`form`](struct.Frame.html#method.form) which are sized based on their content, and for scrollpane content.
+    /// This is not saved.
+    #[serde(skip)]
+    pub content_size: Point,
+
+    /// The size of the visible area of a scrollpane's content, as measured during the previous frame.
+    /// This is not saved.
+    #[serde(skip)]
+    pub viewport_size: Point,
+
+    /// The position of a scrollpane's [`anchor`](enum.ScrollAnchor.html) child within the content, ignoring
+    /// the scroll, as measured during the previous frame.  This is not saved.
+    #[serde(skip)]
+    pub anchor_pos: Option<Point>,
+
+    /// The offset of the mouse from a scrollbar thumb being dragged, if any.  This is not saved.
+    #[serde(skip)]
+    pub grab_offset: Option<Point>,
+
+    /// Whether a scrollpane was scrolled to within its [`near end`](struct.ScrollpaneBuilder.html#method.on_near_end)
+    /// threshold during the previous frame.  This is not saved.
+    #[serde(skip)]
+    pub near_end: bool,
+
+    /// The attention [`Effect`](enum.Effect.html) most recently played on this widget, if any, along with
+    /// the time it was started.  This is not saved.
+    #[serde(skip)]
+    pub effect: Option<(Effect, u32)>,
+
+    /// The most recent values shown by a [`sample_graph`](struct.Frame.html#method.sample_graph), from
+    /// oldest to newest.  This is not saved.
+    #[serde(skip)]
+    pub samples: Vec<f32>,
+
+    /// The time of the most recent click on this widget, for detecting double clicks, or zero if the
+    /// click completed a double click.  This is not saved.
+    #[serde(skip)]
+    pub last_click_millis: u32,
+
+    /// The widget receiving the characters typed on a [`virtual_keyboard`](struct.Frame.html#method.virtual_keyboard),
+    /// which was focused when the keyboard was shown.  This is not saved.
+    #[serde(skip)]
+    pub focus_target: Option<String>,
 }
 
 impl PersistentState {
@@ -85,6 +154,7 @@ impl PersistentState {
             resize: self.resize,
             moved: self.moved,
             scroll: self.scroll,
+            effect: self.effect,
         }
     }
 }
@@ -102,6 +172,19 @@ impl Default for PersistentState {
             key_events: Vec::default(),
             text: None,
             timer: 0,
+            toggled: false,
+            placed: false,
+            shaded: false,
+            selection: Selection::default(),
+            content_size: Point::default(),
+            viewport_size: Point::default(),
+            anchor_pos: None,
+            grab_offset: None,
+            near_end: false,
+            effect: None,
+            samples: Vec::new(),
+            last_click_millis: 0,
+            focus_target: None,
         }
     }
 }
@@ -120,6 +203,41 @@ pub struct InputModifiers {
     pub alt: bool,
 }
 
+/// Controls how mouse wheel and touchpad scrolling is converted into scroll distance.  This may be changed
+/// at any time, for example from a settings menu, with [`Context::set_scroll_config`](struct.Context.html#method.set_scroll_config).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ScrollConfig {
+    /// The distance scrolled for each line of mouse wheel movement, in logical pixels.  Defaults to the
+    /// [`line_scroll`](struct.BuildOptions.html#structfield.line_scroll) build option.
+    pub line_height: f32,
+
+    /// A factor multiplied with scrolling reported in pixels, such as from a touchpad.  Defaults to 1.0.
+    pub pixel_scale: f32,
+
+    /// Whether to reverse the scroll direction, for natural or inverted scrolling.  Defaults to false.
+    pub invert: bool,
+
+    /// Whether purely vertical scrolling is converted to horizontal scrolling while Shift is held.
+    /// Defaults to false.
+    pub shift_horizontal: bool,
+}
+
+impl ScrollConfig {
+    fn new(line_height: f32) -> ScrollConfig {
+        ScrollConfig {
+            line_height,
+            pixel_scale: 1.0,
+            invert: false,
+            shift_horizontal: false,
+        }
+    }
+}
+
+type TextResolver = Box<dyn Fn(&str) -> Cow<str>>;
+type SoundCallback = Box<dyn FnMut(&str)>;
+type HapticCallback = Box<dyn FnMut(HapticEvent)>;
+type InteractionCallback = Box<dyn FnMut(&str, Interaction, u32)>;
+
 pub struct ContextInternal {
     resources: ResourceSet,
     options: BuildOptions,
@@ -128,12 +246,16 @@ pub struct ContextInternal {
 
     mouse_taken_switch_time: u32,
     mouse_taken_switch_position: Option<Point>,
+    tooltip_shown_time: Option<u32>,
     mouse_taken_last_frame: Option<(String, RendGroup)>,
     mouse_in_rend_group_last_frame: Option<RendGroup>,
     top_rend_group: RendGroup,
     check_set_top_rend_group: Option<String>,
+    system_cursor: SystemCursor,
+    cursor_locked: bool,
+    raw_mouse_drag: bool,
 
-    mouse_pressed_outside: [bool; 3],
+    mouse_pressed_outside: Vec<bool>,
     keyboard_focus_widget: Option<String>,
     empty_persistent_state: PersistentState,
 
@@ -141,22 +263,158 @@ pub struct ContextInternal {
     persistent_state: HashMap<String, PersistentState>,
 
     input_modifiers: InputModifiers,
+    scroll_config: ScrollConfig,
     last_mouse_pos: Point,
     mouse_pos: Point,
-    mouse_pressed: [bool; 3],
-    mouse_clicked: [bool; 3],
+    mouse_pressed: Vec<bool>,
+    mouse_clicked: Vec<bool>,
     mouse_wheel: Point,
+    raw_mouse_delta: Option<Point>,
 
     display_size: Point,
     scale_factor: f32,
+    safe_area_insets: Border,
+    display_rotation: DisplayRotation,
+    display_class: Option<DisplayClass>,
+    display_class_changed: bool,
+
+    redraw_frames: u32,
+    animating: bool,
 
-    start_instant: Instant,
+    events: Vec<UiEvent>,
+
+    clock: Box<dyn Clock>,
+    stepped_clock: Option<ManualClock>,
     time_millis: u32,
+    last_time_millis: u32,
+    mouse_press_time: Vec<u32>,
+    mouse_press_new: Vec<bool>,
 
     errors: HashSet<String>,
+    text_layouts: TextLayoutCache,
+    text_resolver: Option<TextResolver>,
+    pseudo_localization: bool,
+    drag_source: Option<(String, usize)>,
+    drag_source_used: bool,
+
+    sound_callback: Option<SoundCallback>,
+    sounds: Vec<String>,
+    sound_hover_widget: Option<String>,
+    sound_hover_next: Option<String>,
+
+    haptic_callback: Option<HapticCallback>,
+    haptics: Vec<HapticEvent>,
+    gamepad_active: bool,
+
+    interaction_callback: Option<InteractionCallback>,
+    interactions: Vec<(String, Interaction, u32)>,
+
+    accessible_widgets: Vec<AccessibleWidget>,
+    custom_widget_data: HashMap<String, Vec<(String, CustomData)>>,
+    focus_order: Vec<(String, String)>,
+    present_widgets: HashSet<String>,
+    present_widgets_next: HashSet<String>,
+    rebuild: Option<AssetBuild>,
+    reload_error: Option<String>,
+    reload_retry: bool,
+    color_transform: ColorTransform,
+    high_contrast: bool,
+    quad_budget_exceeded: Cell<bool>,
+    widget_limit_exceeded: bool,
+    last_cascade_pos: Option<Point>,
+
+    // the parent window of each child window, and whether the child is docked to it
+    window_parents: HashMap<String, (String, bool)>,
+
+    // the window parents set by the child windows built in the current frame, which replace `window_parents` at
+    // the end of the frame, so windows which are no longer built are removed
+    window_parents_built: HashMap<String, (String, bool)>,
+
+    #[cfg(feature="native_dialog")]
+    native_dialogs: HashMap<String, std::sync::mpsc::Receiver<Option<PathBuf>>>,
 }
 
 impl ContextInternal {
+    /// The error from the most recent failed theme rebuild, if the theme has not since been rebuilt successfully
+    pub(crate) fn reload_error(&self) -> Option<&str> {
+        self.reload_error.as_deref()
+    }
+
+    /// Requests that the next live reload check rebuilds the theme, even if no files have changed
+    pub(crate) fn retry_reload(&mut self) {
+        self.reload_retry = true;
+        self.input_changed();
+    }
+
+    /// Resolves the specified `text` with the user specified text resolver, if any,
+    /// then applies pseudo-localization if it is enabled.
+    pub(crate) fn resolve_text<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let text = match &self.text_resolver {
+            None => Cow::Borrowed(text),
+            Some(resolver) => resolver(text),
+        };
+
+        if self.pseudo_localization {
+            Cow::Owned(pseudo_localize(&text))
+        } else {
+            text
+        }
+    }
+
+    /// Replaces each inline image of the form `[img:{image_set_id}/{image_id}]` in `text` with a placeholder character
+    /// for layout, returning the new text and the handle of each image in order.  Markup naming an image which does not
+    /// exist is left as is.  Returns `None` if `text` contains no inline images.
+    pub(crate) fn inline_images(&self, text: &str) -> Option<(String, Vec<ImageHandle>)> {
+        const START: &str = "[img:";
+
+        if !text.contains(START) { return None; }
+
+        let mut out = String::with_capacity(text.len());
+        let mut handles = Vec::new();
+        let mut rest = text;
+        while let Some(start) = rest.find(START) {
+            let (before, markup) = rest.split_at(start);
+            out.push_str(before);
+
+            let image = markup.find(']').and_then(|end| {
+                let handle = self.themes.find_image(Some(&markup[START.len()..end]))?;
+                Some((handle, end))
+            });
+
+            match image {
+                None => {
+                    out.push_str(START);
+                    rest = &markup[START.len()..];
+                },
+                Some((handle, end)) => {
+                    out.push(inline_image_char(self.themes.image(handle).base_size()));
+                    handles.push(handle);
+                    rest = &markup[end + 1..];
+                }
+            }
+        }
+        out.push_str(rest);
+
+        if handles.is_empty() { None } else { Some((out, handles)) }
+    }
+
+    /// Lays out the specified `text` and returns the final cursor position and text height, using
+    /// the result from a previous frame if the same layout was already performed.
+    pub(crate) fn text_layout(&mut self, font: &FontSummary, params: FontDrawParams, text: &str) -> TextLayout {
+        let font: &Font = self.themes.font(font.handle);
+        self.text_layouts.layout(font, params, text)
+    }
+
+    pub(crate) fn text_carets(&self, font: &FontSummary, params: FontDrawParams, text: &str) -> Vec<Rect> {
+        let font: &Font = self.themes.font(font.handle);
+        font.carets(params, text)
+    }
+
+    pub(crate) fn text_pages(&self, font: &FontSummary, params: FontDrawParams, text: &str) -> Vec<usize> {
+        let font: &Font = self.themes.font(font.handle);
+        font.pages(params, text)
+    }
+
     pub(crate) fn log(&mut self, level: log::Level, error: String) {
         if self.errors.contains(&error) { return; }
 
@@ -180,12 +438,74 @@ impl ContextInternal {
 
     pub(crate) fn clear_modal_if_match(&mut self, id: &str) {
         if self.modal_id() == Some(id) {
-            self.modal.take();
+            self.end_modal();
         }
     }
 
+    /// Removes the current modal, restoring the keyboard focus held before it was opened
+    fn end_modal(&mut self) -> Option<Modal> {
+        let modal = self.modal.take()?;
+        self.set_focus(modal.restore_focus.clone());
+        Some(modal)
+    }
+
     pub(crate) fn set_modal(&mut self, id: String) {
-        self.modal = Some(Modal::new(id));
+        let mut modal = Modal::new(id, self.time_millis);
+
+        // reopening the current modal keeps the focus that it will restore, and its transition
+        match self.modal.take() {
+            Some(old) if old.id == modal.id => {
+                modal.transition = old.transition;
+                modal.opened = old.opened;
+
+                // a closing modal reverses its transition from the current point
+                if let (Some(transition), Some(_)) = (old.transition, old.closing) {
+                    let shown = self.modal_shown(&old, transition);
+                    let duration = transition.duration_millis() as f32;
+                    modal.opened = self.time_millis.saturating_sub((shown * duration) as u32);
+                }
+
+                modal.restore_focus = old.restore_focus;
+                modal.focus_pending = old.focus_pending;
+            },
+            _ => {
+                modal.restore_focus = self.keyboard_focus_widget.clone();
+                modal.focus_pending = true;
+            }
+        }
+        self.modal = Some(modal);
+    }
+
+    /// Closes the widget `id`, ending the current modal if it matches.  If the modal has a transition, it
+    /// instead starts closing, and is closed once the transition completes.
+    pub(crate) fn close_widget(&mut self, id: String) {
+        if let Some(modal) = self.modal.as_mut().filter(|modal| modal.id == id && modal.transition.is_some()) {
+            if modal.closing.is_none() {
+                modal.closing = Some(self.time_millis);
+                self.animating = true;
+            }
+            return;
+        }
+
+        self.clear_modal_if_match(&id);
+        self.set_open(id, false);
+    }
+
+    /// How far the `modal` is shown with its `transition`, from zero when hidden to one when fully shown
+    fn modal_shown(&self, modal: &Modal, transition: Transition) -> f32 {
+        let duration = transition.duration_millis().max(1) as f32;
+        let shown = match modal.closing {
+            None => self.time_millis.saturating_sub(modal.opened) as f32 / duration,
+            Some(start) => 1.0 - self.time_millis.saturating_sub(start) as f32 / duration,
+        };
+        shown.clamp(0.0, 1.0)
+    }
+
+    /// The transition of the current modal and how far it is shown, if the modal is `id` and has a transition
+    pub(crate) fn modal_transition(&self, id: &str) -> Option<(Transition, f32)> {
+        let modal = self.modal.as_ref().filter(|modal| modal.id == id)?;
+        let transition = modal.transition?;
+        Some((transition, self.modal_shown(modal, transition)))
     }
 
     pub(crate) fn mouse_in_rend_group_last_frame(&self) -> Option<RendGroup> {
@@ -222,32 +542,138 @@ impl ContextInternal {
     }
 
     pub(crate) fn time_millis(&self) -> u32 { self.time_millis }
+
+    /// Returns how long the specified mouse `button` has been held down, in milliseconds, as of the
+    /// previous frame and the current frame.  The previous time is `None` if the button was pressed
+    /// since the previous frame.
+    pub(crate) fn mouse_held_millis(&self, button: MouseButton) -> (Option<u32>, u32) {
+        let index = button.index();
+        let press_time = self.mouse_press_time[index];
+        let prev = if self.mouse_press_new[index] {
+            None
+        } else {
+            Some(self.last_time_millis.saturating_sub(press_time))
+        };
+        (prev, self.time_millis.saturating_sub(press_time))
+    }
     pub(crate) fn mouse_pos(&self) -> Point { self.mouse_pos }
     pub(crate) fn last_mouse_pos(&self) -> Point { self.last_mouse_pos }
     pub(crate) fn mouse_pressed(&self, index: usize) -> bool { self.mouse_pressed[index] }
 
     pub(crate) fn mouse_pressed_button(&self) -> Option<MouseButton> {
-        if self.mouse_pressed[0] { Some(MouseButton::Left) }
-        else if self.mouse_pressed[1] { Some(MouseButton::Right) }
-        else if self.mouse_pressed[2] { Some(MouseButton::Middle) }
-        else { None }
+        self.mouse_pressed.iter().position(|pressed| *pressed).map(MouseButton::from_index)
     }
 
     pub(crate) fn mouse_clicked_button(&self) -> Option<MouseButton> {
-        if self.mouse_clicked[0] { Some(MouseButton::Left) }
-        else if self.mouse_clicked[1] { Some(MouseButton::Right) }
-        else if self.mouse_clicked[2] { Some(MouseButton::Middle) }
-        else { None }
+        self.mouse_clicked.iter().position(|clicked| *clicked).map(MouseButton::from_index)
+    }
+
+    pub(crate) fn mouse_clicked(&self, button: MouseButton) -> bool {
+        self.mouse_clicked.get(button.index()).copied().unwrap_or_default()
     }
 
     pub (crate) fn set_focus_keyboard(&mut self, id: String) {
-        self.keyboard_focus_widget = Some(id);
+        self.set_focus(Some(id));
+    }
+
+    fn set_focus(&mut self, id: Option<String>) {
+        if self.keyboard_focus_widget != id {
+            if id.is_some() {
+                self.haptic(HapticEvent::FocusMoved);
+            }
+            if let Some(old) = self.keyboard_focus_widget.clone() {
+                self.record_interaction(&old, Interaction::FocusLost);
+            }
+            if let Some(new) = id.as_deref() {
+                self.record_interaction(new, Interaction::FocusGained);
+            }
+            self.push_event(UiEvent::FocusChanged { id: id.clone() });
+        }
+        self.keyboard_focus_widget = id;
+    }
+
+    pub(crate) fn push_event(&mut self, event: UiEvent) {
+        if self.options.event_queue {
+            self.events.push(event);
+        }
+    }
+
+    pub(crate) fn drag_source(&mut self, id: &str) -> Option<usize> {
+        match &self.drag_source {
+            Some((drag_id, index)) if drag_id == id => {
+                self.drag_source_used = true;
+                Some(*index)
+            },
+            _ => None,
+        }
+    }
+
+    pub(crate) fn set_drag_source(&mut self, source: Option<(String, usize)>) {
+        if let Some((id, index)) = &source {
+            self.push_event(UiEvent::DragStarted { id: id.clone(), index: *index });
+        }
+        self.drag_source = source;
+        self.drag_source_used = true;
+    }
+
+    #[cfg(feature="native_dialog")]
+    pub(crate) fn add_native_dialog(&mut self, id: String, receiver: std::sync::mpsc::Receiver<Option<PathBuf>>) {
+        self.native_dialogs.insert(id, receiver);
+    }
+
+    #[cfg(feature="native_dialog")]
+    pub(crate) fn is_native_dialog_open(&self, id: &str) -> bool {
+        self.native_dialogs.contains_key(id)
+    }
+
+    /// Checks whether the native dialog with the specified `id` has finished.  Returns `None`
+    /// if it is still open or does not exist, otherwise the user's selection, if any.
+    #[cfg(feature="native_dialog")]
+    pub(crate) fn poll_native_dialog(&mut self, id: &str) -> Option<Option<PathBuf>> {
+        use std::sync::mpsc::TryRecvError;
+
+        let result = match self.native_dialogs.get(id)?.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return None,
+            Err(TryRecvError::Disconnected) => None,
+        };
+
+        self.native_dialogs.remove(id);
+        Some(result)
+    }
+
+    pub (crate) fn clear_focus_keyboard(&mut self) {
+        self.set_focus(None);
+    }
+
+    pub (crate) fn focus_keyboard_widget(&self) -> Option<&str> {
+        self.keyboard_focus_widget.as_deref()
     }
 
     pub (crate) fn is_focus_keyboard(&self, id: &str) -> bool {
         self.keyboard_focus_widget.as_deref() == Some(id)
     }
 
+    /// Sends the character `c` to the widget with keyboard focus, if any
+    pub(crate) fn push_focus_character(&mut self, c: char) {
+        let id = match &self.keyboard_focus_widget {
+            Some(id) => id.to_string(),
+            None => return,
+        };
+
+        self.state_mut(id).characters.push(c);
+    }
+
+    /// Sends the key `event` to the widget with keyboard focus, if any
+    pub(crate) fn push_focus_key_event(&mut self, event: KeyEvent) {
+        let id = match &self.keyboard_focus_widget {
+            Some(id) => id.to_string(),
+            None => return,
+        };
+
+        self.state_mut(id).key_events.push(event);
+    }
+
     pub(crate) fn take_mouse_wheel(&mut self) -> Point {
         let result = self.mouse_wheel;
         self.mouse_wheel = Point::default();
@@ -259,7 +685,11 @@ impl ContextInternal {
     }
 
     pub(crate) fn scale_factor(&self) -> f32 { self.scale_factor }
-    pub(crate) fn display_size(&self) -> Point { self.display_size }
+    pub(crate) fn display_size(&self) -> Point { self.display_rotation.rotate_size(self.display_size) }
+    pub(crate) fn display_rotation(&self) -> DisplayRotation { self.display_rotation }
+    pub(crate) fn safe_area_insets(&self) -> Border { self.safe_area_insets }
+    pub(crate) fn display_class(&self) -> Option<DisplayClass> { self.display_class }
+    pub(crate) fn display_class_changed(&self) -> bool { self.display_class_changed }
 
     pub(crate) fn themes(&self) -> &ThemeSet { &self.themes }
 
@@ -306,41 +736,377 @@ impl ContextInternal {
         }
     }
 
-    pub(crate) fn tooltip_ready(&mut self, mouse_rect: Rect) -> Option<Point> {
+    /// Checks whether a tooltip should be shown, after the mouse has hovered the current widget for
+    /// `delay` milliseconds, or the default tooltip time if not specified.  If `duration` is specified,
+    /// the tooltip is hidden again after being shown for that many milliseconds.
+    pub(crate) fn tooltip_ready(&mut self, mouse_rect: Rect, delay: Option<u32>, duration: Option<u32>) -> Option<Point> {
         // do not allow tooltip to show while a mouse button is pressed
         if self.mouse_pressed.iter().any(|p| *p) {
             return None;
         }
 
-        let ready = self.time_millis - self.mouse_taken_switch_time > self.options.tooltip_time;
+        let hover_time = self.time_millis - self.mouse_taken_switch_time;
+
+        // show immediately if another tooltip was recently shown
+        let switch_time = self.options.tooltip_instant_switch_time;
+        let recently_shown = match self.tooltip_shown_time {
+            Some(shown) if switch_time > 0 => self.mouse_taken_switch_time.saturating_sub(shown) <= switch_time,
+            _ => false,
+        };
+
+        let delay = if recently_shown { 0 } else { delay.unwrap_or(self.options.tooltip_time) };
+        let ready = recently_shown || hover_time > delay;
+        if !ready || duration.is_some() {
+            self.animating = true;
+        }
         if ready && self.mouse_taken_switch_position.is_none() {
             self.mouse_taken_switch_position = Some(Point::new(mouse_rect.left(), mouse_rect.bot()));
         }
 
+        if let Some(duration) = duration {
+            if hover_time > delay.saturating_add(duration) {
+                return None;
+            }
+        }
+
+        if self.mouse_taken_switch_position.is_some() {
+            self.tooltip_shown_time = Some(self.time_millis);
+        }
+
         self.mouse_taken_switch_position
     }
 
+    /// Queues the `sound` for the sound callback, which is invoked once the frame is finished
+    pub(crate) fn play_sound(&mut self, sound: &str) {
+        if self.sound_callback.is_some() {
+            self.sounds.push(sound.to_string());
+        }
+    }
+
+    /// Plays the hover `sound` if the widget was not already hovered in the previous frame
+    pub(crate) fn play_hover_sound(&mut self, widget_id: &str, sound: &str) {
+        if self.sound_hover_widget.as_deref() != Some(widget_id) {
+            self.play_sound(sound);
+        }
+        self.sound_hover_next = Some(widget_id.to_string());
+    }
+
+    pub(crate) fn take_sounds(&mut self) -> (Vec<String>, Option<SoundCallback>) {
+        if self.sounds.is_empty() {
+            (Vec::new(), None)
+        } else {
+            (std::mem::take(&mut self.sounds), self.sound_callback.take())
+        }
+    }
+
+    pub(crate) fn restore_sound_callback(&mut self, callback: SoundCallback) {
+        // the callback may have been replaced while it was running
+        if self.sound_callback.is_none() {
+            self.sound_callback = Some(callback);
+        }
+    }
+
+    /// Queues the haptic `event` for the haptic callback, if gamepad input is active
+    pub(crate) fn haptic(&mut self, event: HapticEvent) {
+        if self.gamepad_active && self.haptic_callback.is_some() {
+            self.haptics.push(event);
+        }
+    }
+
+    pub(crate) fn take_haptics(&mut self) -> (Vec<HapticEvent>, Option<HapticCallback>) {
+        if self.haptics.is_empty() {
+            (Vec::new(), None)
+        } else {
+            (std::mem::take(&mut self.haptics), self.haptic_callback.take())
+        }
+    }
+
+    pub(crate) fn restore_haptic_callback(&mut self, callback: HapticCallback) {
+        if self.haptic_callback.is_none() {
+            self.haptic_callback = Some(callback);
+        }
+    }
+
+    /// Queues the `interaction` with the widget `id` for the interaction callback, which is invoked once
+    /// the frame is finished
+    pub(crate) fn record_interaction(&mut self, id: &str, interaction: Interaction) {
+        if self.interaction_callback.is_some() {
+            self.interactions.push((id.to_string(), interaction, self.time_millis));
+        }
+    }
+
+    pub(crate) fn take_interactions(&mut self) -> (Vec<(String, Interaction, u32)>, Option<InteractionCallback>) {
+        if self.interactions.is_empty() {
+            (Vec::new(), None)
+        } else {
+            (std::mem::take(&mut self.interactions), self.interaction_callback.take())
+        }
+    }
+
+    pub(crate) fn restore_interaction_callback(&mut self, callback: InteractionCallback) {
+        if self.interaction_callback.is_none() {
+            self.interaction_callback = Some(callback);
+        }
+    }
+
+    /// Sets whether the widget with the specified `id` is open in its persistent state, recording
+    /// an interaction if this changes it
+    pub(crate) fn set_open<T: Into<String>>(&mut self, id: T, open: bool) {
+        let id = id.into();
+        let state = self.state_mut(id.as_str());
+        if state.is_open == open { return; }
+
+        state.is_open = open;
+        self.record_interaction(&id, if open { Interaction::Opened } else { Interaction::Closed });
+
+        // closing a window closes its child windows
+        if !open {
+            for child in self.child_windows(&id, false) {
+                self.clear_modal_if_match(&child);
+                self.set_open(child, false);
+            }
+        }
+    }
+
+    pub(crate) fn set_accessible_widgets(&mut self, widgets: Vec<AccessibleWidget>) {
+        self.accessible_widgets = widgets;
+    }
+
+    pub(crate) fn set_custom_widget_data(&mut self, data: HashMap<String, Vec<(String, CustomData)>>) {
+        self.custom_widget_data = data;
+    }
+
+    /// Records that the widget with the specified `id` was drawn this frame.  Returns true
+    /// if it was not drawn on the previous frame.
+    pub(crate) fn mark_present(&mut self, id: &str) -> bool {
+        if !self.present_widgets_next.insert(id.to_string()) || self.present_widgets.contains(id) {
+            return false;
+        }
+
+        self.push_event(UiEvent::Appeared { id: id.to_string() });
+        true
+    }
+
+    /// Sets the `order` of focusable widgets built this frame, each with the ID of its focus scope.  If a
+    /// modal was just opened, the keyboard focus moves to its first focusable widget.
+    pub(crate) fn set_focus_order(&mut self, order: Vec<(String, String)>) {
+        self.focus_order = order;
+
+        let modal_id = match self.modal.as_mut() {
+            Some(modal) if modal.focus_pending => {
+                modal.focus_pending = false;
+                modal.id.clone()
+            },
+            _ => return,
+        };
+
+        let first = self.focus_order.iter().find(|(_, scope)| *scope == modal_id).map(|(id, _)| id.clone());
+        if let Some(id) = first {
+            self.set_focus(Some(id));
+        }
+    }
+
+    /// Moves the keyboard focus to the next, or if not `forward` the previous, focusable widget within the focus
+    /// scope of the currently focused widget, wrapping around at either end.  If a modal is open, focus is kept
+    /// within the modal.
+    fn move_focus(&mut self, forward: bool) {
+        let current = self.keyboard_focus_widget.as_ref().and_then(|id| {
+            self.focus_order.iter().position(|(focus_id, _)| focus_id == id)
+        });
+
+        let scope = match (&self.modal, current) {
+            (Some(modal), _) => Some(modal.id.as_str()),
+            (None, Some(index)) => Some(self.focus_order[index].1.as_str()),
+            // leave the focus alone if it is held by a widget which handles Tab itself
+            (None, None) if self.keyboard_focus_widget.is_some() => return,
+            (None, None) => None,
+        };
+
+        let candidates: Vec<usize> = (0..self.focus_order.len()).filter(|index| {
+            scope.is_none() || scope == Some(self.focus_order[*index].1.as_str())
+        }).collect();
+        if candidates.is_empty() { return; }
+
+        let next = match candidates.iter().position(|index| Some(*index) == current) {
+            None if forward => candidates[0],
+            None => candidates[candidates.len() - 1],
+            Some(pos) if forward => candidates[(pos + 1) % candidates.len()],
+            Some(pos) => candidates[(pos + candidates.len() - 1) % candidates.len()],
+        };
+
+        let id = self.focus_order[next].0.clone();
+        self.set_focus(Some(id));
+    }
+
+    pub(crate) fn color_transform(&self) -> ColorTransform {
+        self.color_transform
+    }
+
+    pub(crate) fn high_contrast(&self) -> bool {
+        self.high_contrast
+    }
+
+    /// Records the number of `quads` drawn by the renderer for the current frame in the bench
+    /// quads counter, warning when the quad budget is first exceeded.
+    pub(crate) fn record_quads(&self, quads: usize) {
+        crate::bench::set_counter(crate::bench::QUADS_COUNTER, quads as u64);
+
+        let budget = self.options.quad_budget;
+        let exceeded = quads > budget;
+        if exceeded && !self.quad_budget_exceeded.get() {
+            log::warn!("Drew {} quads in one frame, exceeding the budget of {}.  Rendering may be slow.", quads, budget);
+        }
+        self.quad_budget_exceeded.set(exceeded);
+    }
+
+    /// The position of the most recent window placed with a cascade, to offset the next cascaded window from
+    pub(crate) fn last_cascade_pos(&self) -> Option<Point> { self.last_cascade_pos }
+
+    pub(crate) fn set_last_cascade_pos(&mut self, pos: Point) {
+        self.last_cascade_pos = Some(pos);
+    }
+
+    /// Records that the window `child` belongs to the window `parent`, moving with it if `docked`
+    pub(crate) fn set_window_parent(&mut self, child: &str, parent: &str, docked: bool) {
+        let entry = (parent.to_string(), docked);
+        self.window_parents.insert(child.to_string(), entry.clone());
+        self.window_parents_built.insert(child.to_string(), entry);
+    }
+
+    /// The parent window of each child window
+    pub(crate) fn window_parents(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.window_parents.iter().map(|(child, (parent, _))| (child.as_str(), parent.as_str()))
+    }
+
+    /// The child windows of the window `id`, including their children, only including docked children if `docked`
+    pub(crate) fn child_windows(&self, id: &str, docked: bool) -> Vec<String> {
+        let mut children = Vec::new();
+        let mut parents = vec![id.to_string()];
+        while let Some(parent) = parents.pop() {
+            for (child, (child_parent, child_docked)) in &self.window_parents {
+                if *child_parent != parent || (docked && !child_docked) || children.contains(child) || child == id {
+                    continue;
+                }
+
+                children.push(child.clone());
+                parents.push(child.clone());
+            }
+        }
+        children
+    }
+
+    /// The maximum number of widgets, child depth, and number of render groups in each frame
+    pub(crate) fn widget_limits(&self) -> (usize, usize, usize) {
+        (self.options.max_widgets, self.options.max_depth, self.options.max_render_groups)
+    }
+
+    /// Logs the `diagnostic` for a widget limit exceeded in the current frame, unless a limit was also
+    /// exceeded in the previous frame.
+    pub(crate) fn report_widget_limit(&mut self, diagnostic: Option<String>) {
+        if let (Some(diagnostic), false) = (diagnostic.as_ref(), self.widget_limit_exceeded) {
+            log::error!("{}", diagnostic);
+        }
+        self.widget_limit_exceeded = diagnostic.is_some();
+    }
+
+    /// Returns the raw mouse movement since the previous frame, converted to logical pixels, or `None`
+    /// if the [`IO`](trait.IO.html) backend does not provide raw mouse movement
+    pub(crate) fn raw_mouse_delta(&self) -> Option<Point> {
+        self.raw_mouse_delta.map(|delta| delta / self.scale_factor)
+    }
+
+    pub(crate) fn set_cursor_locked(&mut self, locked: bool) {
+        self.cursor_locked = locked;
+    }
+
+    pub(crate) fn set_raw_mouse_drag(&mut self, raw: bool) {
+        self.raw_mouse_drag = raw;
+    }
+
+    pub(crate) fn set_system_cursor(&mut self, cursor: SystemCursor) {
+        self.system_cursor = cursor;
+    }
+
+    /// Requests another frame after the current one, for time based changes such as animations
+    pub(crate) fn request_redraw(&mut self) {
+        self.animating = true;
+    }
+
+    // some state, such as hover, is based on the previous frame, so input requires two frames
+    fn input_changed(&mut self) {
+        self.redraw_frames = self.redraw_frames.max(2);
+    }
+
     pub(crate) fn next_frame(&mut self, mouse_taken: Option<(String, RendGroup)>, mouse_in_rend_group: Option<RendGroup>) {
         let mut clear_modal = false;
+        let mut close_modal = None;
         if let Some(modal) = self.modal.as_mut() {
             if modal.prevent_close {
                 modal.prevent_close = false;
             } else if modal.close_on_click_outside && self.mouse_clicked[0] && !modal.bounds.is_inside(self.mouse_pos) {
-                clear_modal = true;
+                close_modal = Some(modal.id.clone());
+            }
+
+            // a closing modal is removed once its transition completes
+            if let (Some(transition), Some(start)) = (modal.transition, modal.closing) {
+                if self.time_millis.saturating_sub(start) >= transition.duration_millis() {
+                    clear_modal = true;
+                } else {
+                    self.animating = true;
+                }
             }
         }
 
         if clear_modal {
-            let modal = self.modal.take().unwrap();
-            self.state_mut(modal.id).is_open = false;
+            let modal = self.end_modal().unwrap();
+            self.set_open(modal.id, false);
+        } else if let Some(id) = close_modal {
+            self.close_widget(id);
+        }
+
+        let mut present = std::mem::take(&mut self.present_widgets_next);
+        std::mem::swap(&mut present, &mut self.present_widgets);
+        for id in present {
+            if !self.present_widgets.contains(&id) {
+                self.push_event(UiEvent::Disappeared { id });
+            }
+        }
+
+        // a drag is cancelled if its source was not built this frame
+        if !self.drag_source_used {
+            self.drag_source = None;
         }
+        self.drag_source_used = false;
+
+        self.sound_hover_widget = self.sound_hover_next.take();
+        self.window_parents = std::mem::take(&mut self.window_parents_built);
 
         self.mouse_wheel = Point::default();
-        self.mouse_clicked = [false; 3];
+        if let Some(delta) = self.raw_mouse_delta.as_mut() {
+            *delta = Point::default();
+        }
+        self.mouse_clicked.fill(false);
+        self.mouse_press_new.fill(false);
         self.mouse_taken_last_frame = mouse_taken;
         self.last_mouse_pos = self.mouse_pos;
         self.mouse_in_rend_group_last_frame = mouse_in_rend_group;
         self.frame_active = false;
+
+        // long press and repeat on hold are time based
+        if self.mouse_pressed.iter().any(|p| *p) {
+            self.animating = true;
+        }
+
+        #[cfg(feature="native_dialog")]
+        if !self.native_dialogs.is_empty() {
+            self.animating = true;
+        }
+
+        self.redraw_frames = self.redraw_frames.saturating_sub(1);
+        if self.animating {
+            self.redraw_frames = self.redraw_frames.max(1);
+        }
+        self.animating = false;
     }
 }
 
@@ -363,32 +1129,82 @@ impl Context {
         display_size: Point,
         scale_factor: f32
     ) -> Context {
+        let mouse_buttons = options.mouse_buttons.max(5);
+        let scroll_config = ScrollConfig::new(options.line_scroll);
         let internal = ContextInternal {
             resources,
             options,
             display_size,
             scale_factor,
+            safe_area_insets: Border::default(),
+            display_rotation: DisplayRotation::default(),
+            display_class: None,
+            display_class_changed: false,
+            redraw_frames: 1,
+            animating: false,
+            events: Vec::new(),
             themes,
             persistent_state: HashMap::new(),
             empty_persistent_state: PersistentState::default(),
             mouse_pos: Point::default(),
             last_mouse_pos: Point::default(),
             input_modifiers: InputModifiers::default(),
-            mouse_pressed: [false; 3],
-            mouse_clicked: [false; 3],
+            scroll_config,
+            mouse_pressed: vec![false; mouse_buttons],
+            mouse_clicked: vec![false; mouse_buttons],
             mouse_wheel: Point::default(),
+            raw_mouse_delta: None,
             mouse_taken_switch_time: 0,
             mouse_taken_switch_position: None,
+            tooltip_shown_time: None,
             mouse_taken_last_frame: None,
             mouse_in_rend_group_last_frame: None,
             top_rend_group: RendGroup::default(),
             check_set_top_rend_group: None,
-            mouse_pressed_outside: [false; 3],
+            system_cursor: SystemCursor::default(),
+            cursor_locked: false,
+            raw_mouse_drag: false,
+            mouse_pressed_outside: vec![false; mouse_buttons],
             modal: None,
             time_millis: 0,
-            start_instant: Instant::now(),
+            last_time_millis: 0,
+            mouse_press_time: vec![0; mouse_buttons],
+            mouse_press_new: vec![false; mouse_buttons],
+            clock: Box::new(SystemClock::new()),
+            stepped_clock: None,
             keyboard_focus_widget: None,
             errors: HashSet::new(),
+            text_layouts: TextLayoutCache::default(),
+            text_resolver: None,
+            pseudo_localization: false,
+            drag_source: None,
+            drag_source_used: false,
+            sound_callback: None,
+            sounds: Vec::new(),
+            sound_hover_widget: None,
+            sound_hover_next: None,
+            haptic_callback: None,
+            interaction_callback: None,
+            interactions: Vec::new(),
+            haptics: Vec::new(),
+            gamepad_active: false,
+            accessible_widgets: Vec::new(),
+            custom_widget_data: HashMap::new(),
+            focus_order: Vec::new(),
+            present_widgets: HashSet::new(),
+            present_widgets_next: HashSet::new(),
+            rebuild: None,
+            reload_error: None,
+            reload_retry: false,
+            color_transform: ColorTransform::None,
+            high_contrast: false,
+            quad_budget_exceeded: Cell::new(false),
+            widget_limit_exceeded: false,
+            last_cascade_pos: None,
+            window_parents: HashMap::new(),
+            window_parents_built: HashMap::new(),
+            #[cfg(feature="native_dialog")]
+            native_dialogs: HashMap::new(),
             frame_active: false,
         };
 
@@ -397,28 +1213,51 @@ impl Context {
         }
     }
 
-    // Finds the specified font and appropriately logs any error in this context.
+    // Finds the specified font and appropriately logs any error in this context.  Returns the fallback font,
+    // if placeholders are enabled, when the font is missing.
     pub(crate) fn find_font(&self, id: &str) -> Option<FontSummary> {
         let mut internal = self.internal.borrow_mut();
         match internal.themes().find_font(Some(id)) {
             None => {
                 internal.log(log::Level::Error, format!("Unable to find font '{}' for widget", id));
-                None
+                internal.themes().fallback_font()
             }, Some(handle) => Some(handle)
         }
     }
 
-    // Finds the specified image and appropriately logs any error in this context.
+    // Finds the specified image and appropriately logs any error in this context.  Returns the placeholder
+    // image, if placeholders are enabled, when the image is missing.
     pub(crate) fn find_image(&self, id: &str) -> Option<ImageHandle> {
         let mut internal = self.internal.borrow_mut();
         match internal.themes().find_image(Some(id)) {
             None => {
                 internal.log(log::Level::Error, format!("Unable to find image '{}' for widget", id));
-                None
+                internal.themes().placeholder_image()
             }, Some(handle) => Some(handle),
         }
     }
 
+    /// Returns a summary of the resources currently held by this context, including texture and font atlas
+    /// sizes, the number of themes and [`PersistentState`](struct.PersistentState.html) entries, and the
+    /// approximate CPU side memory usage.  See [`ResourceReport`](struct.ResourceReport.html).
+    pub fn resource_report(&self) -> ResourceReport {
+        let internal = self.internal.borrow();
+
+        let mut report = ResourceReport {
+            persistent_state_count: internal.persistent_state.len(),
+            ..Default::default()
+        };
+        internal.themes.add_to_report(&mut report);
+
+        report.approximate_heap_bytes += internal.text_layouts.heap_bytes() +
+            internal.persistent_state.iter().map(|(id, state)| {
+                std::mem::size_of::<(String, PersistentState)>() + id.capacity() +
+                    state.text.as_ref().map_or(0, |text| text.capacity())
+            }).sum::<usize>();
+
+        report
+    }
+
     /// Returns true if thyme wants to use the mouse in the current frame, generally
     /// because the mouse is over a Thyme widget.  If this returns true, you probably
     /// want Thyme to handle input this frame, while if it returns false, your application
@@ -428,6 +1267,59 @@ impl Context {
         internal.mouse_taken_last_frame.is_some() || internal.modal.is_some()
     }
 
+    /// Returns the platform mouse cursor requested by the most recently drawn frame.  This is
+    /// either the `cursor` theme attribute of the hovered widget, or the cursor set via
+    /// [`Frame.set_system_cursor`](struct.Frame.html#method.set_system_cursor).  Your application
+    /// should apply this to the window after drawing each frame, unless it hides the OS cursor.
+    /// [`WinitIo.update_cursor`](struct.WinitIo.html#method.update_cursor) will do this for you.
+    pub fn system_cursor(&self) -> SystemCursor {
+        self.internal.borrow().system_cursor
+    }
+
+    /// Returns whether the most recently drawn frame requested that the OS cursor be hidden and held in place,
+    /// because a widget using [`lock_cursor_on_drag`](struct.WidgetBuilder.html#method.lock_cursor_on_drag) is
+    /// being dragged.  Your application should apply this to the window after drawing each frame.
+    /// [`WinitIo.update_cursor`](struct.WinitIo.html#method.update_cursor) will do this for you.
+    pub fn cursor_locked(&self) -> bool {
+        self.internal.borrow().cursor_locked
+    }
+
+    /// Returns the visible widgets in the most recently drawn frame which have a screen reader
+    /// [`aria_label`](struct.WidgetBuilder.html#method.aria_label) or
+    /// [`description`](struct.WidgetBuilder.html#method.description), in the order they were created.
+    pub fn accessible_widgets(&self) -> Vec<AccessibleWidget> {
+        self.internal.borrow().accessible_widgets.clone()
+    }
+
+    /// Returns the custom data attached to the widget with the specified `id` in the most recently drawn frame,
+    /// with [`WidgetBuilder::custom`](struct.WidgetBuilder.html#method.custom).  Returns an empty list if the
+    /// widget has no custom data or was not drawn.
+    pub fn widget_custom_data(&self, id: &str) -> Vec<(String, CustomData)> {
+        self.internal.borrow().custom_widget_data.get(id).cloned().unwrap_or_default()
+    }
+
+    /// Returns the full ids of all widget themes in the currently loaded theme, in the order they
+    /// were defined.  Child themes have ids consisting of their parents' ids and their own id, separated by `/`.
+    pub fn widget_theme_ids(&self) -> Vec<String> {
+        self.internal.borrow().themes.widget_theme_ids().map(|id| id.to_string()).collect()
+    }
+
+    /// Returns the resolved attributes of the widget theme with the specified full `id`, or `None` if there
+    /// is no such theme.  See [`widget_theme_ids`](#method.widget_theme_ids).
+    pub fn widget_theme(&self, id: &str) -> Option<WidgetThemeInfo> {
+        self.internal.borrow().themes.widget_theme_info(id)
+    }
+
+    /// Returns the ids of all images in the currently loaded theme, in the form `image_set/image`.
+    pub fn image_ids(&self) -> Vec<String> {
+        self.internal.borrow().themes.image_ids().map(|id| id.to_string()).collect()
+    }
+
+    /// Returns the ids of all fonts in the currently loaded theme.
+    pub fn font_ids(&self) -> Vec<String> {
+        self.internal.borrow().themes.font_ids().map(|id| id.to_string()).collect()
+    }
+
     /// Returns true if thyme wants to use keyboard input in the current frame, generally
     /// because a widget that accepts text input is keyboard focused.  If this returns true,
     /// you probably don't want to handle keyboard events in your own application code.
@@ -451,17 +1343,247 @@ impl Context {
         self.internal.borrow().options.clone()
     }
 
+    /// Sets a `resolver` which is applied to all widget text, both from the theme `text` field and
+    /// set via [`WidgetBuilder.text`](struct.WidgetBuilder.html#method.text).  This is intended for
+    /// localization; text may be specified as keys which the resolver then converts into
+    /// the appropriate string for the current language.  The resolver should return the
+    /// input unchanged for any text which is not a key.  Text input by the user, such as in
+    /// [`input fields`](struct.Frame.html#method.input_field), is not passed to the resolver.
+    ///
+    /// # Example
+    /// ```
+    /// let strings: HashMap<String, String> = load_strings("de");
+    /// context.set_text_resolver(move |key| match strings.get(key) {
+    ///     Some(text) => Cow::Owned(text.clone()),
+    ///     None => Cow::Borrowed(key),
+    /// });
+    /// ```
+    pub fn set_text_resolver<F: Fn(&str) -> Cow<str> + 'static>(&mut self, resolver: F) {
+        let mut internal = self.internal.borrow_mut();
+        internal.text_resolver = Some(Box::new(resolver));
+        internal.text_layouts.clear();
+    }
+
+    /// Removes any text resolver previously set with [`set_text_resolver`](#method.set_text_resolver).
+    pub fn clear_text_resolver(&mut self) {
+        let mut internal = self.internal.borrow_mut();
+        internal.text_resolver = None;
+        internal.text_layouts.clear();
+    }
+
+    /**
+    Sets a `callback` which is invoked with the sound id whenever a UI sound should be played.  Sounds are
+    specified per widget with the `sound_hover` and `sound_click` theme attributes, which play when the mouse
+    first moves over the widget and when it is clicked, respectively.  Sounds may also be played manually
+    with [`Frame.play_sound`](struct.Frame.html#method.play_sound).  The callback is invoked for each
+    sound once the frame has been drawn.
+
+    # Example
+    ```
+    // in the theme
+    button:
+      sound_hover: ui_hover
+      sound_click: ui_click
+
+    // in your application
+    let audio = my_audio_system.clone();
+    context.set_sound_callback(move |sound| audio.play(sound));
+    ```
+    **/
+    pub fn set_sound_callback<F: FnMut(&str) + 'static>(&mut self, callback: F) {
+        let mut internal = self.internal.borrow_mut();
+        internal.sound_callback = Some(Box::new(callback));
+    }
+
+    /// Removes any sound callback previously set with [`set_sound_callback`](#method.set_sound_callback).
+    pub fn clear_sound_callback(&mut self) {
+        let mut internal = self.internal.borrow_mut();
+        internal.sound_callback = None;
+        internal.sounds.clear();
+    }
+
+    /**
+    Sets a `callback` which is invoked with a [`HapticEvent`](enum.HapticEvent.html) on UI interactions
+    while [`gamepad input`](#method.set_gamepad_active) is active, allowing console style UIs to provide
+    controller rumble feedback.  Events are sent when keyboard focus moves to a widget and when a widget
+    is clicked.  Other events, such as errors, may be sent with [`Frame.haptic`](struct.Frame.html#method.haptic).
+    The callback is invoked for each event once the frame has been drawn.
+
+    # Example
+    ```
+    context.set_haptic_callback(move |event| match event {
+        HapticEvent::FocusMoved => gamepad.rumble(0.1, 20),
+        HapticEvent::Confirm => gamepad.rumble(0.3, 40),
+        HapticEvent::Error => gamepad.rumble(0.8, 120),
+    });
+    ```
+    **/
+    pub fn set_haptic_callback<F: FnMut(HapticEvent) + 'static>(&mut self, callback: F) {
+        let mut internal = self.internal.borrow_mut();
+        internal.haptic_callback = Some(Box::new(callback));
+    }
+
+    /// Removes any haptic callback previously set with [`set_haptic_callback`](#method.set_haptic_callback).
+    pub fn clear_haptic_callback(&mut self) {
+        let mut internal = self.internal.borrow_mut();
+        internal.haptic_callback = None;
+        internal.haptics.clear();
+    }
+
+    /**
+    Sets a `callback` which is invoked with the widget id, the kind of [`Interaction`](enum.Interaction.html), and
+    a timestamp for each user interaction with the UI.  This allows applications to feed UI telemetry or
+    analytics without wrapping each recipe call.  Interactions are recorded when a widget is clicked, when a
+    widget such as a window or modal is opened or closed, and when keyboard focus changes.  The timestamp is the
+    time of the frame in milliseconds since the context was created.  The callback is invoked for each
+    interaction once the frame has been drawn.
+
+    # Example
+    ```
+    context.set_interaction_callback(move |id, interaction, time_millis| {
+        analytics.record(id, format!("{:?}", interaction), time_millis);
+    });
+    ```
+    **/
+    pub fn set_interaction_callback<F: FnMut(&str, Interaction, u32) + 'static>(&mut self, callback: F) {
+        let mut internal = self.internal.borrow_mut();
+        internal.interaction_callback = Some(Box::new(callback));
+    }
+
+    /// Removes any interaction callback previously set with
+    /// [`set_interaction_callback`](#method.set_interaction_callback).
+    pub fn clear_interaction_callback(&mut self) {
+        let mut internal = self.internal.borrow_mut();
+        internal.interaction_callback = None;
+        internal.interactions.clear();
+    }
+
+    /// Sets the [`Clock`](trait.Clock.html) used for all UI timing, including animations and tooltips, in place
+    /// of the default [`SystemClock`](struct.SystemClock.html).  UI timestamps are measured in milliseconds from
+    /// the clock's starting point, so the clock should generally be set before the first frame is created.
+    pub fn set_clock<C: Clock + 'static>(&mut self, clock: C) {
+        let mut internal = self.internal.borrow_mut();
+        internal.clock = Box::new(clock);
+        internal.stepped_clock = None;
+    }
+
+    /// Sets whether the user is currently navigating the UI with a gamepad.  Thyme does not read gamepad
+    /// input itself, so the application should set this when gamepad input is received, and unset it when
+    /// the mouse or keyboard is used.  [`Haptic events`](#method.set_haptic_callback) are only sent while
+    /// this is active.
+    pub fn set_gamepad_active(&mut self, active: bool) {
+        let mut internal = self.internal.borrow_mut();
+        internal.gamepad_active = active;
+        if !active {
+            internal.haptics.clear();
+        }
+    }
+
+    /// Returns whether gamepad input is active.  See [`set_gamepad_active`](#method.set_gamepad_active).
+    pub fn gamepad_active(&self) -> bool {
+        self.internal.borrow().gamepad_active
+    }
+
+    /**
+    Sets a [`ColorTransform`](enum.ColorTransform.html) which is applied by the renderer to the colors of
+    everything drawn, including text and images.  This may be used to offer color blindness modes for the UI
+    without a separate theme.
+
+    # Example
+    ```
+    context.set_color_transform(ColorTransform::Compensate(ColorBlindness::Deuteranopia));
+    ```
+    **/
+    pub fn set_color_transform(&mut self, transform: ColorTransform) {
+        self.internal.borrow_mut().color_transform = transform;
+    }
+
+    /// Returns the current color transform.  See [`set_color_transform`](#method.set_color_transform).
+    pub fn color_transform(&self) -> ColorTransform {
+        self.internal.borrow().color_transform
+    }
+
+    /// Enables or disables high contrast mode.  When enabled, all text is drawn in either black or white,
+    /// whichever is closest to its theme color, over a backplate of the opposite color.  This keeps text
+    /// readable over images regardless of the loaded theme, without authoring a separate high contrast theme.
+    /// See also [`set_color_transform`](#method.set_color_transform).
+    pub fn set_high_contrast(&mut self, enabled: bool) {
+        self.internal.borrow_mut().high_contrast = enabled;
+    }
+
+    /// Returns whether high contrast mode is enabled.  See [`set_high_contrast`](#method.set_high_contrast).
+    pub fn high_contrast(&self) -> bool {
+        self.internal.borrow().high_contrast
+    }
+
+    /// Enables or disables pseudo-localization.  When enabled, all widget text (after being passed
+    /// through any [`text resolver`](#method.set_text_resolver)) has its letters replaced with accented
+    /// equivalents and is padded to be roughly 40% longer, and bracketed.  This makes it easy to spot
+    /// hard coded strings that are not being localized, as well as widgets that will truncate
+    /// or overflow with longer translations.  Variables in the form `{name}` are left unchanged.
+    pub fn set_pseudo_localization(&mut self, enabled: bool) {
+        let mut internal = self.internal.borrow_mut();
+        internal.pseudo_localization = enabled;
+    }
+
+    /// Returns whether pseudo-localization is enabled.  See
+    /// [`set_pseudo_localization`](#method.set_pseudo_localization).
+    pub fn pseudo_localization(&self) -> bool {
+        self.internal.borrow().pseudo_localization
+    }
+
     pub(crate) fn internal(&self) -> &Rc<RefCell<ContextInternal>> {
         &self.internal
     }
 
+    /**
+    Returns whether another frame needs to be drawn.  This is the case after any input is received, while
+    anything is animating, such as timed images, tooltips waiting to show, or collapsing headers, and when
+    a redraw is requested with [`request_redraw`](#method.request_redraw).  Applications which do not need to
+    redraw continuously may use this to sleep until new input arrives, for example by using
+    `ControlFlow::Wait` in winit, rather than drawing at the full frame rate.
+
+    # Example
+    ```
+    // after drawing the frame
+    if context.needs_redraw() {
+        window.request_redraw();
+    } else {
+        event_loop.set_control_flow(ControlFlow::Wait);
+    }
+    ```
+    **/
+    pub fn needs_redraw(&self) -> bool {
+        let internal = self.internal.borrow();
+        internal.redraw_frames > 0
+    }
+
+    /// Requests that another frame be drawn, causing [`needs_redraw`](#method.needs_redraw) to return `true`.
+    /// This is useful when application state shown in the UI changes outside of Thyme input handling.
+    pub fn request_redraw(&mut self) {
+        let mut internal = self.internal.borrow_mut();
+        internal.redraw_frames = internal.redraw_frames.max(1);
+    }
+
+    /// Returns all [`UiEvents`](enum.UiEvent.html) recorded since the last call to this method, clearing
+    /// the queue.  This is normally called after each frame is drawn.  Events are only recorded if the
+    /// [`event_queue`](struct.BuildOptions.html#structfield.event_queue) option is enabled.
+    pub fn take_events(&mut self) -> Vec<UiEvent> {
+        let mut internal = self.internal.borrow_mut();
+        std::mem::take(&mut internal.events)
+    }
+
     /// Sets the scale factor, sometimes referred to as HiDPI factor for the monitor.
     /// This is normally handled by the [`IO`](trait.IO.html) backend, which will set
     /// the scale factor based on a scale factor changed event.  User code should
     /// not need to call this.
     pub fn set_scale_factor(&mut self, scale: f32) {
         let mut internal = self.internal.borrow_mut();
+        if internal.scale_factor != scale {
+            internal.text_layouts.clear();
+        }
         internal.scale_factor = scale;
+        internal.input_changed();
     }
 
     /// Returns the current scale factor being used internally by Thyme.  See
@@ -478,23 +1600,85 @@ impl Context {
     pub fn set_display_size(&mut self, size: Point) {
         let mut internal = self.internal.borrow_mut();
         internal.display_size = size;
+        internal.input_changed();
     }
 
     /// Returns the current display size being used internally by Thyme.  See
-    /// [`set_display_size`](#method.set_display_size)
+    /// [`set_display_size`](#method.set_display_size).  If the
+    /// [`display rotation`](#method.set_display_rotation) is sideways, this is the
+    /// window size with the width and height swapped.
     pub fn display_size(&self) -> Point {
         let internal = self.internal.borrow();
-        internal.display_size
+        internal.display_size()
+    }
+
+    /// Sets the rotation of the entire UI on the display.  The rotation is applied by the
+    /// renderer, and mouse positions are transformed by the [`IO`](trait.IO.html) backend
+    /// accordingly.  Defaults to no rotation.
+    pub fn set_display_rotation(&mut self, rotation: DisplayRotation) {
+        let mut internal = self.internal.borrow_mut();
+        internal.display_rotation = rotation;
+        internal.input_changed();
+    }
+
+    /// Returns the current display rotation.  See
+    /// [`set_display_rotation`](#method.set_display_rotation)
+    pub fn display_rotation(&self) -> DisplayRotation {
+        let internal = self.internal.borrow();
+        internal.display_rotation
+    }
+
+    /// Sets the safe area insets, in logical pixels.  The insets shrink the usable area of the
+    /// root widget, so that top level widgets are aligned and sized within the inset area.  This is
+    /// useful on TVs and consoles to keep HUD elements out of the overscan region, or on devices with
+    /// notches or rounded corners.  Widgets positioned with
+    /// [`screen_pos`](struct.WidgetBuilder.html#method.screen_pos) are not affected.
+    /// The insets default to zero on all sides.
+    pub fn set_safe_area_insets(&mut self, insets: Border) {
+        let mut internal = self.internal.borrow_mut();
+        internal.safe_area_insets = insets;
+        internal.input_changed();
+    }
+
+    /// Returns the current safe area insets.  See
+    /// [`set_safe_area_insets`](#method.set_safe_area_insets)
+    pub fn safe_area_insets(&self) -> Border {
+        let internal = self.internal.borrow();
+        internal.safe_area_insets
     }
 
-    /// Add mouse wheel event, with `delta` being the amount of device-dependant logical scrolling.
+    /// Add mouse wheel event, with `delta` being the amount of device-dependant logical scrolling, in
+    /// lines if `line` is true or otherwise in logical pixels.  The delta is converted according to the
+    /// current [`ScrollConfig`](struct.ScrollConfig.html).
     /// This is normally handled by the [`IO`](trait.IO.html) backend, which will set
     /// this in response to a window resize event.  User code should
     /// not need to call this.
     pub fn add_mouse_wheel(&mut self, delta: Point, line: bool) {
         let mut internal = self.internal.borrow_mut();
+        internal.input_changed();
+
+        let config = internal.scroll_config;
+        let mut delta = delta * if line { config.line_height } else { config.pixel_scale };
+        if config.invert {
+            delta = delta * -1.0;
+        }
 
-        internal.mouse_wheel = internal.mouse_wheel + delta * if line { internal.options.line_scroll } else { 1.0 };
+        if config.shift_horizontal && internal.input_modifiers.shift && delta.x == 0.0 {
+            delta = Point::new(delta.y, 0.0);
+        }
+
+        internal.mouse_wheel = internal.mouse_wheel + delta;
+    }
+
+    /// Returns the current [`ScrollConfig`](struct.ScrollConfig.html)
+    pub fn scroll_config(&self) -> ScrollConfig {
+        self.internal.borrow().scroll_config
+    }
+
+    /// Sets the [`ScrollConfig`](struct.ScrollConfig.html), controlling the scroll distance for mouse
+    /// wheel input, the scroll direction, and conversion to horizontal scrolling.
+    pub fn set_scroll_config(&mut self, config: ScrollConfig) {
+        self.internal.borrow_mut().scroll_config = config;
     }
 
     /// Set the input modifiers - the status of keys such as `ctrl` and `shift`.
@@ -504,18 +1688,22 @@ impl Context {
     pub fn set_input_modifiers(&mut self, input_modifiers: InputModifiers) {
         let mut internal = self.internal.borrow_mut();
         internal.input_modifiers = input_modifiers;
+        internal.input_changed();
     }
 
     /// Set the mouse pressed state for a given mouse button.
     /// # Inputs:
     /// - button `pressed` state
-    /// - index: 0 = LeftClick, 1 = Right Click, 2 = Middle Click
+    /// - index: 0 = LeftClick, 1 = Right Click, 2 = Middle Click, 3 = Back, 4 = Forward, with
+    ///   higher indices for additional buttons.  See [`MouseButton::index`](enum.MouseButton.html#method.index).
+    ///   Indices beyond the [`mouse_buttons`](struct.BuildOptions.html#structfield.mouse_buttons) option are ignored.
     /// 
     /// This is normally handled by the [`IO`](trait.IO.html) backend, which will set
     /// this in response to a window resize event.  User code should
     /// not need to call this.
     pub fn set_mouse_pressed(&mut self, pressed: bool, index: usize) {
         let mut internal = self.internal.borrow_mut();
+        internal.input_changed();
 
         if index >= internal.mouse_pressed.len() {
             return;
@@ -532,7 +1720,12 @@ impl Context {
 
         if internal.mouse_pressed[index] && !pressed {
             internal.mouse_clicked[index] = true;
-            internal.keyboard_focus_widget = None;
+            internal.set_focus(None);
+        }
+
+        if !internal.mouse_pressed[index] && pressed {
+            internal.mouse_press_time[index] = internal.clock.elapsed().as_millis() as u32;
+            internal.mouse_press_new[index] = true;
         }
 
         internal.mouse_pressed[index] = pressed;
@@ -549,30 +1742,25 @@ impl Context {
     /// not need to call this.
     pub fn push_character(&mut self, c: char) {
         let mut internal = self.internal.borrow_mut();
-
-        let id = match &internal.keyboard_focus_widget {
-            Some(id) => id.to_string(),
-            None => return,
-        };
-
-        let state = internal.state_mut(id);
-        state.characters.push(c);
+        internal.input_changed();
+        internal.push_focus_character(c);
     }
 
     /// Pushes a key event (that was received from the keyboard as virtual key code) to thyme,
     /// to be dispatched to the appropriate widget based on keyboard focus in the next frame.
     /// This is normally handled by the [`IO`](trait.IO.html) backend, which should set this
     /// in response to a window event.  User code should not need to call this.
+    /// Tab and Shift + Tab additionally move the keyboard focus between
+    /// [`focusable`](struct.WidgetBuilder.html#method.focusable) widgets.
     pub fn push_key_event(&mut self, event: KeyEvent) {
         let mut internal = self.internal.borrow_mut();
+        internal.input_changed();
+        internal.push_focus_key_event(event);
 
-        let id = match &internal.keyboard_focus_widget {
-            Some(id) => id.to_string(),
-            None => return,
-        };
-
-        let state = internal.state_mut(id);
-        state.key_events.push(event);
+        if matches!(event, KeyEvent::Tab) {
+            let forward = !internal.input_modifiers.shift;
+            internal.move_focus(forward);
+        }
     }
 
     /// Returns the current mouse position, based on mouse cursor movement.  The scale
@@ -592,9 +1780,30 @@ impl Context {
     /// not need to call this.
     pub fn set_mouse_pos(&mut self, pos: Point) {
         let mut internal = self.internal.borrow_mut();
+        if internal.mouse_pos != pos {
+            internal.input_changed();
+        }
         internal.mouse_pos = pos;
     }
 
+    /// Adds raw mouse movement, as reported by the mouse device rather than the OS cursor, in physical
+    /// pixels.  Unlike the cursor position, raw movement continues when the cursor reaches the edge of the
+    /// screen, and is used by widgets with [`raw_mouse_drag`](struct.WidgetBuilder.html#method.raw_mouse_drag).
+    /// This is normally handled by the [`IO`](trait.IO.html) backend, which will set this in response to
+    /// a device motion event.  User code should not need to call this.  The movement is only recorded while a
+    /// widget using raw mouse movement is being dragged, as device motion is reported continuously.
+    pub fn add_raw_mouse_delta(&mut self, delta: Point) {
+        let mut internal = self.internal.borrow_mut();
+        let active = internal.raw_mouse_drag || internal.cursor_locked;
+
+        // the delta is always set, so that widgets know raw movement is available
+        let total = internal.raw_mouse_delta.get_or_insert_with(Point::default);
+        if active {
+            *total = *total + delta;
+            internal.input_changed();
+        }
+    }
+
     /// Adds the specified path as a source file for the resources being used
     /// by the theme for this context.  This will only work if the theme was
     /// set up to read source data from files, i.e. using
@@ -624,32 +1833,132 @@ impl Context {
     pub fn rebuild_all<R: Renderer>(&mut self, renderer: &mut R) -> Result<(), Error> {
         let mut internal = self.internal.borrow_mut();
         internal.resources.clear_data_cache();
-        internal.resources.cache_data()?;
 
         let scale_factor = internal.scale_factor;
-        let themes = internal.resources.build_assets(renderer, scale_factor)?;
+        let display_size = internal.display_size() / scale_factor;
+        let platform = internal.options.platform.clone();
+        let env = ThemeEnvironment { display_size, platform: platform.as_deref() };
+        let themes = internal.resources.build_assets(renderer, scale_factor, &env).inspect_err(|e| {
+            internal.reload_error = Some(e.to_string());
+        })?;
         internal.themes = themes;
+        internal.reload_error = None;
+        internal.text_layouts.clear();
+        internal.input_changed();
         Ok(())
     }
 
+    /// Starts an incremental rebuild of this context, reloading all asset data as in
+    /// [`rebuild_all`](#method.rebuild_all).  The rebuild is performed by calling
+    /// [`rebuild_step`](#method.rebuild_step), typically once per frame, allowing the application to show its
+    /// progress.  The current theme remains in use, unchanged, until the rebuild is complete, as the new textures
+    /// and fonts are registered with handles not used by the current theme.  Any rebuild already in progress is
+    /// restarted.
+    pub fn begin_rebuild(&mut self) {
+        let mut internal = self.internal.borrow_mut();
+        internal.resources.clear_data_cache();
+        internal.rebuild = Some(AssetBuild::new(false));
+    }
+
+    /// Performs the next step of a rebuild started with [`begin_rebuild`](#method.begin_rebuild).  Returns
+    /// the progress of the rebuild while steps remain, or `None` once the rebuild is complete and the new theme
+    /// is in use, or if there is no rebuild in progress.  If any errors are encountered, this will return `Err`
+    /// and the rebuild is cancelled, with no changes made to the current theme.
+    pub fn rebuild_step<R: Renderer>(&mut self, renderer: &mut R) -> Result<Option<BuildProgress>, Error> {
+        let mut internal = self.internal.borrow_mut();
+        let mut build = match internal.rebuild.take() {
+            None => return Ok(None),
+            Some(build) => build,
+        };
+
+        let scale_factor = internal.scale_factor;
+        let display_size = internal.display_size() / scale_factor;
+        let platform = internal.options.platform.clone();
+        let env = ThemeEnvironment { display_size, platform: platform.as_deref() };
+
+        let result = internal.resources.build_step(&mut build, renderer, scale_factor, &env).inspect_err(|e| {
+            internal.reload_error = Some(e.to_string());
+        })?;
+
+        match result {
+            None => {
+                let progress = internal.resources.build_progress(&build);
+                internal.rebuild = Some(build);
+                Ok(Some(progress))
+            },
+            Some(themes) => {
+                internal.themes = themes;
+                internal.reload_error = None;
+                internal.text_layouts.clear();
+                internal.input_changed();
+                Ok(None)
+            }
+        }
+    }
+
+    /**
+    Registers all of the textures and fonts used by this context with the `renderer` again, using the asset data
+    retained by this context and the same handles, so the current theme remains valid without a rebuild.  This is
+    intended for mobile app lifecycles, where GPU resources are lost while the app is suspended.  Call this after
+    the renderer has been recreated, or after [`GLRenderer::resume`](struct.GLRenderer.html#method.resume).
+
+    If several contexts [`share resources`](struct.SharedResources.html), restore each of them, in the order
+    they were built.  Returns `Err` if asset data is no longer available, such as after a failed rebuild.
+    **/
+    pub fn restore_resources<R: Renderer>(&mut self, renderer: &mut R) -> Result<(), Error> {
+        self.internal.borrow_mut().resources.restore(renderer)
+    }
+
     /// Checks the internal live reload thread to see if any file notifications have occurred
     /// since the last check.  If so, will fully rebuild the theme.  If any errors are encountered
     /// in the process of rebuilding the theme, will return the `Err` and no changes are made to
-    /// the current theme.  Note that if you built the context with live reload disabled
-    /// (see [`BuildOptions`](struct.BuildOptions.html)), this function will do nothing.
+    /// the current theme, which remains the last successfully built theme.  The error is also available
+    /// from [`reload_error`](#method.reload_error) until the theme is rebuilt successfully.  The theme is
+    /// also rebuilt if the display size has changed such that a different set of theme `overrides` applies,
+    /// or if a retry was requested with [`retry_reload`](#method.retry_reload).  Note that if you built the
+    /// context with live reload disabled (see [`BuildOptions`](struct.BuildOptions.html)), file changes are
+    /// not detected.
     pub fn check_live_reload<R: Renderer>(&mut self, renderer: &mut R) -> Result<(), Error> {
         let mut internal = self.internal.borrow_mut();
         let scale_factor = internal.scale_factor;
-
-        let themes = internal.resources.check_live_reload(renderer, scale_factor)?;
+        let display_size = internal.display_size() / scale_factor;
+        let platform = internal.options.platform.clone();
+        let env = ThemeEnvironment { display_size, platform: platform.as_deref() };
+
+        let retry = std::mem::take(&mut internal.reload_retry);
+        let themes = match internal.resources.check_live_reload(renderer, scale_factor, &env, retry) {
+            Ok(themes) => themes,
+            Err(e) => {
+                internal.reload_error = Some(e.to_string());
+                internal.input_changed();
+                return Err(e);
+            }
+        };
 
         if let Some(themes) = themes {
             internal.themes = themes;
+            internal.reload_error = None;
+            internal.text_layouts.clear();
+            internal.input_changed();
         }
 
         Ok(())
     }
 
+    /// Returns the error from the most recent failed theme rebuild, such as a YAML parse error during
+    /// [`live reload`](#method.check_live_reload), if the theme has not since been rebuilt successfully.
+    /// The last successfully built theme remains in use while there is an error.  See
+    /// [`Frame::reload_error`](struct.Frame.html#method.reload_error) to show the error in the UI.
+    pub fn reload_error(&self) -> Option<String> {
+        self.internal.borrow().reload_error.clone()
+    }
+
+    /// Requests that the theme is rebuilt, reloading all files, on the next call to
+    /// [`check_live_reload`](#method.check_live_reload), even if no file changes have been detected.
+    pub fn retry_reload(&mut self) {
+        self.internal.borrow_mut().retry_reload();
+    }
+
     /// Generates a [`SavedContext`](struct.SavedContext.html) from the current
     /// context state.  This can be serialized to a file and restored later using
     /// [`load`](struct.Context.html#load) to restore the Context state.
@@ -672,16 +1981,48 @@ impl Context {
         internal.persistent_state = save.persistent_state;
         internal.top_rend_group = save.top_rend_group;
         internal.keyboard_focus_widget = save.keyboard_focus_widget;
+        internal.input_changed();
+    }
+
+    /**
+    Creates a [`Frame`](struct.Frame.html) as in [`create_frame`](#method.create_frame), after advancing the UI
+    time by exactly `delta`.  The first call switches the context to a stepped mode, where UI time, used for
+    animations, tooltips, and other timing, advances only by the deltas passed here, starting from the time of the
+    previous frame.  Frames created with [`create_frame`](#method.create_frame) in this mode do not advance the
+    time.  This is useful for lockstep multiplayer games, where all clients must show identical UI animation
+    frames.  Setting a [`clock`](#method.set_clock) ends the stepped mode.
+
+    # Example
+    ```
+    // each simulation tick
+    let mut ui = context.create_frame_with_delta(Duration::from_millis(16));
+    ```
+    **/
+    pub fn create_frame_with_delta(&mut self, delta: Duration) -> Frame {
+        {
+            let mut internal = self.internal.borrow_mut();
+            let clock = match internal.stepped_clock.as_ref() {
+                Some(clock) => clock.clone(),
+                None => {
+                    let clock = ManualClock::new();
+                    clock.set(Duration::from_millis(internal.time_millis as u64));
+                    internal.clock = Box::new(clock.clone());
+                    internal.stepped_clock = Some(clock.clone());
+                    clock
+                }
+            };
+            clock.advance(delta);
+        }
+
+        self.create_frame()
     }
 
     /// Creates a [`Frame`](struct.Frame.html), the main object that should pass through
     /// your UI building functions and is responsible for constructing the widget tree.
     /// This method should be called each frame you want to draw / interact with the UI.
     pub fn create_frame(&mut self) -> Frame {
-        let now = Instant::now();
-
         let anim_state;
-        let display_size = {
+        let (display_size, safe_area_insets) = {
             let mut context = self.internal.borrow_mut();
 
             if context.frame_active {
@@ -689,22 +2030,34 @@ impl Context {
             }
 
             context.frame_active = true;
+            context.text_layouts.next_frame();
 
-            let elapsed = (now - context.start_instant).as_millis() as u32;
+            let elapsed = context.clock.elapsed().as_millis() as u32;
+            context.last_time_millis = context.time_millis;
             context.time_millis = elapsed;
 
+            let display_size = context.display_size() / context.scale_factor();
+            let class = DisplayClass::from_width(display_size.x, context.options.display_breakpoints);
+            let last_class = context.display_class.replace(class);
+            context.display_class_changed = last_class.is_some() && last_class != Some(class);
+            if context.display_class_changed {
+                context.push_event(UiEvent::DisplayClassChanged { class });
+            }
+
             if context.mouse_pressed[0] {
                 anim_state = AnimState::new(AnimStateKey::Pressed);
             } else {
                 anim_state = AnimState::normal();
             }
 
-            context.display_size() / context.scale_factor()
+            (display_size, context.safe_area_insets)
         };
 
         let context = Context { internal: Rc::clone(&self.internal) };
 
-        let root = Widget::root(display_size);
+        bench::begin_profile_frame();
+
+        let root = Widget::root(display_size, safe_area_insets);
         Frame::new(context, root, anim_state)
     }
 }
@@ -715,15 +2068,36 @@ pub(crate) struct Modal {
     pub(crate) close_on_click_outside: bool,
     pub(crate) bounds: Rect,
     pub(crate) prevent_close: bool,
+
+    // the keyboard focus prior to opening the modal, restored when it closes
+    #[serde(default)]
+    restore_focus: Option<String>,
+
+    // whether the focus should move into the modal once it has been built
+    #[serde(default)]
+    focus_pending: bool,
+
+    // the open and close transition, with the times the modal was opened and started closing
+    #[serde(skip)]
+    pub(crate) transition: Option<Transition>,
+    #[serde(skip)]
+    opened: u32,
+    #[serde(skip)]
+    closing: Option<u32>,
 }
 
 impl Modal {
-    fn new(id: String) -> Modal {
+    fn new(id: String, opened: u32) -> Modal {
         Modal {
             id,
             close_on_click_outside: false,
             bounds: Rect::default(),
             prevent_close: true,
+            restore_focus: None,
+            focus_pending: false,
+            transition: None,
+            opened,
+            closing: None,
         }
     }
 }
@@ -738,4 +2112,40 @@ pub struct SavedContext {
     persistent_state: HashMap<String, PersistentState>,
     top_rend_group: RendGroup,
     keyboard_focus_widget: Option<String>,
-}
\ No newline at end of file
+}
+
+fn pseudo_localize(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() * 2 + 4);
+    out.push('[');
+
+    let mut letters = 0;
+    let mut in_variable = false;
+    for c in text.chars() {
+        match c {
+            '{' => in_variable = true,
+            '}' => in_variable = false,
+            _ => (),
+        }
+
+        if in_variable {
+            out.push(c);
+            continue;
+        }
+
+        if c.is_alphabetic() { letters += 1; }
+
+        out.push(match c {
+            'a' => 'á', 'c' => 'ç', 'e' => 'é', 'i' => 'í', 'n' => 'ñ', 'o' => 'ö', 'u' => 'ü', 'y' => 'ý',
+            'A' => 'Å', 'C' => 'Ç', 'D' => 'Ð', 'E' => 'É', 'I' => 'Î', 'N' => 'Ñ', 'O' => 'Ø', 'U' => 'Ü', 'Y' => 'Ý',
+            _ => c,
+        });
+    }
+
+    // expand the text by roughly 40%, as many languages need more space than English
+    for _ in 0..(letters * 2 + 4) / 5 {
+        out.push('·');
+    }
+
+    out.push(']');
+    out
+}
@@ -1,10 +1,17 @@
 use std::path::Path;
 
 use crate::{Error, Context};
-use crate::resource::ResourceSet;
-use crate::theme_definition::ThemeDefinition;
+#[cfg(feature="image")]
+use crate::bundle::{AssetKind, ThemeBundle, scan_theme_dir};
+use crate::compressed::parse_ktx2;
+use crate::resource::{AssetBuild, ResourceSet, SharedResources};
+use crate::theme_definition::{CharacterRange, CharacterRangePreset};
+use crate::theme_overrides::ThemeEnvironment;
 use crate::render::{Renderer, IO};
 
+#[cfg(feature="default_font")]
+const DEFAULT_THEME: &str = include_str!("default_theme.yml");
+
 /// Global options that may be specified when building the Thyme context with
 /// [`ContextBuilder`](struct.ContextBuilder.html).  These options
 /// cannot be changed afterwards.
@@ -20,8 +27,62 @@ pub struct BuildOptions {
     /// to show up.
     pub tooltip_time: u32,
 
+    /// If a tooltip was showing within this many milliseconds of the mouse moving onto a new widget,
+    /// that widget's tooltip is shown immediately rather than after the `tooltip_time`.  This allows
+    /// quickly browsing the tooltips of neighboring widgets, such as in a toolbar.  Zero disables this.
+    pub tooltip_instant_switch_time: u32,
+
     /// The number of lines that scrollbars will scroll per mouse scroll.
     pub line_scroll: f32,
+
+    /// The amount of time in milliseconds that the mouse must be held down on a widget
+    /// for it to be [`long_pressed`](struct.WidgetState.html#structfield.long_pressed).
+    pub long_press_time: u32,
+
+    /// Whether widget interactions are recorded as [`UiEvents`](enum.UiEvent.html), to be retrieved with
+    /// [`take_events`](struct.Context.html#method.take_events).  The default value is `false`.
+    pub event_queue: bool,
+
+    /// An additional platform name, such as `steam_deck`, matched by the `platform` condition of theme
+    /// `overrides`.  The operating system name and the `desktop` or `mobile` form factor are always matched.
+    /// The default value is `None`.
+    pub platform: Option<String>,
+
+    /// The logical display widths at which the [`DisplayClass`](enum.DisplayClass.html) changes from `Small`
+    /// to `Medium`, and from `Medium` to `Large`.  The default value is `[800.0, 1400.0]`.
+    pub display_breakpoints: [f32; 2],
+
+    /// A soft limit on the number of quads drawn in each frame.  Exceeding it logs a warning, but the frame is
+    /// still drawn in full.  The number drawn in the last frame is recorded in the
+    /// [`QUADS_COUNTER`](bench/constant.QUADS_COUNTER.html).  The default value is 16384.
+    pub quad_budget: usize,
+
+    /// The number of mouse buttons tracked.  The first five buttons are the left, right, middle, back, and forward
+    /// buttons, while any additional buttons are reported as [`MouseButton::Other`](enum.MouseButton.html#variant.Other).
+    /// At least five buttons are always tracked.  The default value is 8.
+    pub mouse_buttons: usize,
+
+    /// Whether a placeholder is drawn in place of a missing image or font, rather than nothing.  Missing images
+    /// are drawn as a magenta and black checkerboard.  Missing fonts are replaced with an embedded default font
+    /// if the `default_font` feature is enabled, and otherwise with the first font in the theme.  Widget themes
+    /// referencing a missing image or font also use the placeholders, rather than failing the theme build.  Each
+    /// missing asset is still logged.  The default value is `false`.
+    pub placeholder_assets: bool,
+
+    /// The maximum number of widgets created in each frame.  Further widgets in the frame are not built, and their
+    /// children closures are not run, with an error logged identifying the parent they were added to.  This guards
+    /// against a bug creating widgets in an unbounded loop.  The default value is 65536.
+    pub max_widgets: usize,
+
+    /// The maximum depth of nested child widgets, with widgets added directly to the frame at depth one.  Deeper
+    /// widgets are not built, with an error logged identifying their parent.  This guards against unbounded
+    /// recursion in the UI.  The default value is 256.
+    pub max_depth: usize,
+
+    /// The maximum number of render groups, such as those created by windows and popups, in each frame.  Further
+    /// widgets starting a new render group are not built, with an error logged identifying their parent.  The
+    /// default value is 4096.
+    pub max_render_groups: usize,
 }
 
 impl Default for BuildOptions {
@@ -29,7 +90,18 @@ impl Default for BuildOptions {
         Self {
             enable_live_reload: true,
             tooltip_time: 0,
+            tooltip_instant_switch_time: 0,
             line_scroll: 20.0,
+            long_press_time: 500,
+            event_queue: false,
+            platform: None,
+            display_breakpoints: [800.0, 1400.0],
+            quad_budget: 16384,
+            mouse_buttons: 8,
+            placeholder_assets: false,
+            max_widgets: 65536,
+            max_depth: 256,
+            max_render_groups: 4096,
         }
     }
 }
@@ -60,22 +132,66 @@ impl ContextBuilder {
 
     /// Creates a new `ContextBuilder`, using the specified [`BuildOptions`](struct.BuildOptions.html)
     pub fn new(options: BuildOptions) -> ContextBuilder {
+        let mut resources = ResourceSet::new(options.enable_live_reload);
+        if options.placeholder_assets {
+            resources.enable_placeholders();
+        }
+
         ContextBuilder {
-            resources: ResourceSet::new(options.enable_live_reload),
+            resources,
             options,
         }
     }
 
+    /// Shares the textures and fonts of this context with any other contexts using the same `shared` registry
+    /// and [`Renderer`](trait.Renderer.html).  See [`SharedResources`](struct.SharedResources.html).
+    pub fn share_resources(&mut self, shared: &SharedResources) {
+        self.resources.share_resources(shared.clone());
+    }
+
+    /**
+    Sets the theme for this context to the built in default theme, and registers the embedded font it uses.  The
+    default theme uses only solid color images, so no other images or fonts need to be registered.  It includes
+    definitions for all of the basic widgets, such as `label`, `button`, `window`, `scrollpane`, and `input_field`,
+    and `medium`, `small`, `heading1`, and `heading2` fonts.  Requires the `default_font` feature.
+    **/
+    #[cfg(feature="default_font")]
+    pub fn register_default_theme(&mut self) {
+        log::debug!("Registering default theme");
+
+        let theme: serde_yaml::Value = serde_yaml::from_str(DEFAULT_THEME)
+            .expect("The default theme is always valid YAML");
+        self.resources.register_theme(theme);
+        self.resources.register_default_font();
+    }
+
+    /**
+    Builds a [`Context`](struct.Context.html) using the default [`BuildOptions`](struct.BuildOptions.html) and
+    the built in default theme, with no external assets.  See
+    [`register_default_theme`](#method.register_default_theme).  Requires the `default_font` feature.
+
+    # Example
+    ```no_run
+    let context = thyme::ContextBuilder::build_with_defaults(&mut renderer, &mut io)?;
+    ```
+    **/
+    #[cfg(feature="default_font")]
+    pub fn build_with_defaults<R: Renderer, I: IO>(renderer: &mut R, io: &mut I) -> Result<Context, Error> {
+        let mut builder = ContextBuilder::with_defaults();
+        builder.register_default_theme();
+        builder.build(renderer, io)
+    }
+
     /// Sets the theme for this context.  The theme for your UI will be deserialized from
     /// `theme`.  For example, `theme` could be a [`serde_json Value`](https://docs.serde.rs/serde_json/value/enum.Value.html) or
-    /// [`serde_yaml Value`](https://docs.serde.rs/serde_yaml/enum.Value.html).  See [`the crate root`](index.html) for a
-    /// discussion of the theme format.  If this method is called multiple times, only the last
-    /// theme is used
+    /// [`serde_yaml Value`](https://docs.serde.rs/serde_yaml/enum.Value.html).  The deserializer must be for a self-describing
+    /// format, supporting `deserialize_any`.  See [`the crate root`](index.html) for a discussion of the theme format.  If this
+    /// method is called multiple times, only the last theme is used
     pub fn register_theme<'a, T: serde::Deserializer<'a>>(&mut self, theme: T) -> Result<(), T::Error> {
         log::debug!("Registering theme");
         
-        let theme_def: ThemeDefinition = serde::Deserialize::deserialize(theme)?;
-        self.resources.register_theme(theme_def);
+        let theme: serde_yaml::Value = serde::Deserialize::deserialize(theme)?;
+        self.resources.register_theme(theme);
         Ok(())
     }
 
@@ -105,6 +221,71 @@ impl ContextBuilder {
         Ok(())
     }
 
+    /**
+    Registers the theme, images, and fonts from the bundle `bytes`, created with
+    [`ThemeBundle::to_bytes`](struct.ThemeBundle.html#method.to_bytes).  The bundle's theme files are merged in
+    order, and images and fonts are registered using their file names as IDs.  This replaces any previously
+    registered theme.  Returns `Err` if the bundle is invalid, or if any theme file or image can't be parsed.
+    Requires the `image` feature.
+    **/
+    #[cfg(feature="image")]
+    pub fn register_theme_bundle(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        let bundle = ThemeBundle::from_bytes(bytes)?;
+        log::debug!("Registering theme bundle with {} files", bundle.len());
+
+        let mut docs = Vec::new();
+        for (kind, id, data) in bundle.entries() {
+            match kind {
+                AssetKind::Theme => {
+                    let doc = serde_yaml::from_slice(data).map_err(|e|
+                        Error::Serde(format!("Error in bundled theme file '{}': {}", id, e))
+                    )?;
+                    docs.push(doc);
+                },
+                AssetKind::Image => {
+                    let image = image::load_from_memory(data).map_err(Error::Image)?.into_rgba8();
+                    let (width, height) = image.dimensions();
+                    self.resources.register_image_from_data(id.to_string(), image.into_raw(), width, height);
+                },
+                AssetKind::CompressedImage => self.register_compressed_texture(id, data)?,
+                AssetKind::Font => self.resources.register_font_from_data(id.to_string(), data.to_vec()),
+            }
+        }
+
+        if docs.is_empty() {
+            return Err(Error::Theme("The theme bundle does not contain any theme files".to_string()));
+        }
+
+        self.resources.register_theme_docs(docs);
+        Ok(())
+    }
+
+    /// Registers the theme, images, and fonts from the directory at `dir` and its subdirectories, in the same
+    /// way as a [`ThemeBundle`](struct.ThemeBundle.html) created from that directory.  The files are read when
+    /// the context is built, and support live reload.  This replaces any previously registered theme.
+    /// Requires the `image` feature.
+    #[cfg(feature="image")]
+    pub fn register_theme_dir(&mut self, dir: &Path) -> Result<(), Error> {
+        log::debug!("Registering theme directory '{:?}'", dir);
+
+        let files = scan_theme_dir(dir)?;
+        let mut themes = Vec::new();
+        for file in &files {
+            match file.kind {
+                AssetKind::Theme => themes.push(file.path.as_path()),
+                AssetKind::Image => self.register_texture_from_file(file.id.as_str(), &file.path),
+                AssetKind::CompressedImage => self.register_compressed_texture_from_file(file.id.as_str(), &file.path),
+                AssetKind::Font => self.register_font_from_file(file.id.as_str(), &file.path),
+            }
+        }
+
+        if themes.is_empty() {
+            return Err(Error::Theme(format!("The directory '{:?}' does not contain any theme files", dir)));
+        }
+
+        self.register_theme_from_files(&themes)
+    }
+
     /// Registers the font data located in the file at the specified `path` with Thyme via the specified `id`.
     /// See [`register_font`](#method.register_font)
     pub fn register_font_from_file<T: Into<String>>(
@@ -130,6 +311,43 @@ impl ContextBuilder {
         self.resources.register_font_from_data(id, data);
     }
 
+    /// Adds the specified `ranges` of characters to every font in the theme, in addition to
+    /// the characters specified for each font in the theme definition.  Characters not present
+    /// in a given font's source data will be skipped.
+    pub fn add_font_characters(&mut self, ranges: &[CharacterRange]) {
+        self.resources.add_font_ranges(ranges);
+    }
+
+    /// Adds the characters in the specified `preset` to every font in the theme.
+    /// See [`add_font_characters`](#method.add_font_characters).
+    pub fn add_font_preset(&mut self, preset: CharacterRangePreset) {
+        log::debug!("Adding font character preset {:?}", preset);
+        self.resources.add_font_ranges(preset.ranges());
+    }
+
+    /// Adds the characters needed to display text in the language of the specified `locale`,
+    /// such as `uk_UA.UTF-8`, to every font in the theme.
+    /// See [`CharacterRangePreset::for_locale`](enum.CharacterRangePreset.html#method.for_locale).
+    pub fn add_font_characters_for_locale(&mut self, locale: &str) {
+        for preset in CharacterRangePreset::for_locale(locale) {
+            self.add_font_preset(preset);
+        }
+    }
+
+    /// Attempts to detect the operating system locale from the `LC_ALL`, `LC_CTYPE`, and `LANG`
+    /// environment variables, in that order, and adds the characters needed for that locale to
+    /// every font in the theme.  Returns the detected locale, if any.
+    /// See [`add_font_characters_for_locale`](#method.add_font_characters_for_locale).
+    pub fn add_font_characters_for_os_locale(&mut self) -> Option<String> {
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"].iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty() && value != "C" && value != "POSIX")?;
+
+        log::info!("Detected OS locale '{}'", locale);
+        self.add_font_characters_for_locale(&locale);
+        Some(locale)
+    }
+
     /// Reads a texture from the specified image file.  See [`register_texture`](#method.register_texture).
     /// Requires you to enable the `image` feature in `Cargo.toml` to enable the dependancy on the
     /// [`image`](https://github.com/image-rs/image) crate.
@@ -141,7 +359,39 @@ impl ContextBuilder {
     ) {
         let id = id.into();
         log::debug!("Reading texture '{}' from file: '{:?}'", id, path);
-        self.resources.register_image_from_file(id, path);
+        self.resources.register_image_from_file(id, path, false);
+    }
+
+    /// Reads a GPU compressed texture from the specified KTX2 file.  See
+    /// [`register_compressed_texture`](#method.register_compressed_texture).
+    pub fn register_compressed_texture_from_file<T: Into<String>>(
+        &mut self,
+        id: T,
+        path: &Path,
+    ) {
+        let id = id.into();
+        log::debug!("Reading compressed texture '{}' from file: '{:?}'", id, path);
+        self.resources.register_image_from_file(id, path, true);
+    }
+
+    /// Registers a GPU compressed texture for use with Thyme via the specified `id`.  The `data` must be a
+    /// KTX2 container holding a 2D texture in one of the [`CompressedFormat`](enum.CompressedFormat.html)s,
+    /// without supercompression.  Only the top level mip map is used.  The texture is uploaded to the GPU
+    /// without decompression, and so the [`Renderer`](trait.Renderer.html) must support its format.  Once
+    /// registered, the texture can be accessed in your theme file via the image `source`, in the same way
+    /// as [`register_texture`](#method.register_texture).  Returns `Err` if the data is not a valid KTX2 texture.
+    pub fn register_compressed_texture<T: Into<String>>(
+        &mut self,
+        id: T,
+        data: &[u8],
+    ) -> Result<(), Error> {
+        let id = id.into();
+        log::debug!("Registering compressed texture '{}'", id);
+        let texture = parse_ktx2(data).map_err(|e|
+            Error::Theme(format!("Unable to read compressed texture '{}': {}", id, e))
+        )?;
+        self.resources.register_compressed_image_from_data(id, texture);
+        Ok(())
     }
 
     /// Registers the image data for use with Thyme via the specified `id`.  The `data` must consist of
@@ -162,13 +412,135 @@ impl ContextBuilder {
 
     /// Consumes this builder and releases the borrows on the [`Renderer`](trait.Renderer.html) and [`IO`](trait.IO.html),
     /// so they can be used further.  Builds a [`Context`](struct.Context.html).
-    pub fn build<R: Renderer, I: IO>(mut self, renderer: &mut R, io: &mut I) -> Result<Context, Error> {
+    pub fn build<R: Renderer, I: IO>(self, renderer: &mut R, io: &mut I) -> Result<Context, Error> {
+        // wait for images to decode rather than polling for them
+        let mut build = self.into_build(true);
+        loop {
+            if let Some(context) = build.step(renderer, io)? {
+                return Ok(context);
+            }
+        }
+    }
+
+    /**
+    Consumes this builder, returning a [`ContextBuild`](struct.ContextBuild.html) which builds the
+    [`Context`](struct.Context.html) incrementally, one step at a time.  This allows an application to
+    show a loading indicator while a large theme is built, rather than blocking in [`build`](#method.build).
+
+    # Example
+    ```
+    let mut build = context_builder.build_incremental();
+    let context = loop {
+        if let Some(context) = build.step(&mut renderer, &mut io)? {
+            break context;
+        }
+
+        let progress = build.progress();
+        draw_loading_screen(progress.fraction());
+    };
+    ```
+    **/
+    pub fn build_incremental(self) -> ContextBuild {
+        self.into_build(false)
+    }
+
+    fn into_build(self, blocking: bool) -> ContextBuild {
         log::info!("Building Thyme Context");
+        ContextBuild {
+            resources: Some(self.resources),
+            options: self.options,
+            build: AssetBuild::new(blocking),
+        }
+    }
+}
+
+/// A stage of building the theme assets.  See [`BuildProgress`](struct.BuildProgress.html).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BuildStage {
+    /// Reading and parsing the theme files
+    ReadingTheme,
+
+    /// Decoding image files, on background threads
+    DecodingImages,
+
+    /// Reading and parsing font files
+    ParsingFonts,
+
+    /// Rendering the glyphs of each theme font to a texture
+    BakingFonts,
+
+    /// Uploading image textures to the renderer
+    UploadingTextures,
+
+    /// Resolving the images and widget themes from the theme definition
+    ResolvingThemes,
+}
+
+/// The progress of an incremental build, from
+/// [`ContextBuild::progress`](struct.ContextBuild.html#method.progress) or
+/// [`Context::rebuild_step`](struct.Context.html#method.rebuild_step).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BuildProgress {
+    /// The stage of the next step
+    pub stage: BuildStage,
+
+    /// The number of completed steps
+    pub completed: usize,
+
+    /// The total number of steps.  The number of images to decode and fonts to bake is not
+    /// known until the theme has been read, so this may change after the first step.
+    pub total: usize,
+}
+
+impl BuildProgress {
+    /// Returns the fraction of steps which are complete, between 0 and 1
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.completed as f32 / self.total as f32
+        }
+    }
+}
+
+/// An incremental build of a [`Context`](struct.Context.html), created with
+/// [`ContextBuilder::build_incremental`](struct.ContextBuilder.html#method.build_incremental).
+pub struct ContextBuild {
+    resources: Option<ResourceSet>,
+    options: BuildOptions,
+    build: AssetBuild,
+}
+
+impl ContextBuild {
+    /// Returns the current progress of this build
+    pub fn progress(&self) -> BuildProgress {
+        match self.resources.as_ref() {
+            None => BuildProgress { stage: BuildStage::ResolvingThemes, completed: 1, total: 1 },
+            Some(resources) => resources.build_progress(&self.build),
+        }
+    }
+
+    /// Performs the next step of this build, such as receiving a single decoded image or baking a single font.
+    /// Images are decoded on background threads, and this returns immediately if none are ready.  Returns
+    /// the [`Context`](struct.Context.html) once the final step is complete, or `None` if there are steps
+    /// remaining.  Returns `Err` if there was a problem building any of the assets, or if the context has
+    /// already been built.
+    pub fn step<R: Renderer, I: IO>(&mut self, renderer: &mut R, io: &mut I) -> Result<Option<Context>, Error> {
         let scale_factor = io.scale_factor();
         let display_size = io.display_size();
 
-        self.resources.cache_data()?;
-        let themes = self.resources.build_assets(renderer, scale_factor)?;
-        Ok(Context::new(self.resources, self.options, themes, display_size, scale_factor))
+        let resources = self.resources.as_mut().ok_or_else(||
+            Error::Theme("The context has already been built".to_string())
+        )?;
+
+        let env = ThemeEnvironment { display_size: display_size / scale_factor, platform: self.options.platform.as_deref() };
+        let themes = match resources.build_step(&mut self.build, renderer, scale_factor, &env)? {
+            None => return Ok(None),
+            Some(themes) => themes,
+        };
+
+        // the resources were checked above
+        let resources = self.resources.take().unwrap();
+        Ok(Some(Context::new(resources, self.options.clone(), themes, display_size, scale_factor)))
     }
 }
@@ -0,0 +1,139 @@
+use crate::Frame;
+
+impl Frame {
+    /**
+    A dialogue or subtitle box, revealing `text` a few characters at a time, as if it were being typed.  The
+    text is revealed at a rate set by the theme `custom` float `chars_per_second`, defaulting to 30, using the
+    [`current time`](#method.cur_time_millis).  The `speaker` name and `portrait` image are shown with the
+    `speaker` and `portrait` children, if specified, while the text is drawn in the `text` child.
+
+    Text which does not fit within the `text` child is split into pages, with the `more` child shown once a page
+    is fully revealed and another page follows.  Clicking the dialogue while text is being revealed completes
+    the current page immediately, while clicking once it is complete moves to the next page.  Returns true
+    when the dialogue is clicked while the final page is complete, at which point the application will
+    typically move on to the next line of dialogue or close the box.
+
+    The reveal restarts from the first page whenever `text` changes.  The current page is stored as the
+    [`selection`](struct.PersistentState.html#structfield.selection) of the dialogue's state.  The `id` must be unique.
+
+    An example theme definition:
+    ```yaml
+    dialogue:
+      background: gui/window_bg
+      wants_mouse: true
+      size: [500, 140]
+      border: { all: 8 }
+      custom:
+        chars_per_second: 40.0
+      children:
+        portrait:
+          size: [96, 96]
+          align: Left
+        speaker:
+          font: medium
+          pos: [104, 0]
+          size_from: [Text, FontLine]
+        text:
+          font: medium
+          pos: [104, 24]
+          size: [-104, -24]
+          width_from: Parent
+          height_from: Parent
+          text_wrap: Word
+        more:
+          foreground: gui/arrow_down
+          align: BotRight
+          size: [12, 12]
+    ```
+
+    # Example
+    ```
+    fn cutscene(ui: &mut Frame, lines: &[(&str, &str)], line: &mut usize) {
+        let (speaker, text) = lines[*line];
+        if ui.dialogue("dialogue", "cutscene", Some(speaker), Some("portraits/guard"), text) {
+            *line += 1;
+        }
+    }
+    ```
+    **/
+    pub fn dialogue(
+        &mut self,
+        theme: &str,
+        id: &str,
+        speaker: Option<&str>,
+        portrait: Option<&str>,
+        text: &str,
+    ) -> bool {
+        let now = self.cur_time_millis();
+        let (mut page, start_time) = self.modify(id, |state| {
+            if state.text.as_deref() != Some(text) {
+                state.text = Some(text.to_string());
+                state.base_time_millis = now;
+                state.selection.clear();
+            }
+
+            (state.selection.first().unwrap_or_default(), state.base_time_millis)
+        });
+
+        let builder = self.start(theme);
+        let chars_per_second = builder.custom_float("chars_per_second", 30.0).max(1.0);
+
+        let mut page_len = 0;
+        let mut last_page = true;
+        let mut complete = false;
+
+        let state = builder.id(id).children(|ui| {
+            if let Some(portrait) = portrait {
+                ui.start("portrait").foreground(portrait).finish();
+            }
+
+            if let Some(speaker) = speaker {
+                ui.label("speaker", speaker);
+            }
+
+            let mut pages = Vec::new();
+            let builder = ui.start("text").text(text).trigger_text_pages(&mut pages);
+            page = page.min(pages.len().saturating_sub(1));
+            let page_start = pages.get(page).copied().unwrap_or_default();
+            let page_end = pages.get(page + 1).copied().unwrap_or_else(|| text.chars().count());
+            page_len = page_end - page_start;
+            last_page = page + 1 >= pages.len();
+
+            let elapsed = now.wrapping_sub(start_time);
+            let revealed = ((elapsed as f32 * chars_per_second / 1000.0) as usize).min(page_len);
+            complete = revealed == page_len;
+
+            let page_text = match text.char_indices().nth(page_start) {
+                None => "",
+                Some((index, _)) => &text[index..],
+            };
+            builder.text(page_text).text_reveal(revealed).finish();
+
+            if complete && !last_page {
+                ui.start("more").finish();
+            }
+        });
+
+        if !complete {
+            self.request_redraw();
+        }
+
+        if !state.clicked { return false; }
+
+        if !complete {
+            // move the start time back so the whole page is revealed
+            let duration = (page_len as f32 * 1000.0 / chars_per_second).ceil() as u32;
+            self.modify(id, |state| state.base_time_millis = now.wrapping_sub(duration));
+            false
+        } else if !last_page {
+            self.modify(id, |state| {
+                state.selection.clear();
+                state.selection.select(page + 1);
+                state.base_time_millis = now;
+            });
+            false
+        } else {
+            true
+        }
+    }
+}
@@ -0,0 +1,696 @@
+use crate::font::{Font, FontDrawParams, FontSource, FontTextureWriter};
+use crate::image::ImageDrawParams;
+use crate::render::{
+    store_handle, triangle_vertices, view_matrix, DrawList, DrawVertex, FontHandle, Renderer, TexCoord, TextureData, TextureHandle,
+    TransformedDrawList,
+};
+use crate::theme_definition::CharacterRange;
+use crate::accessibility::HighContrastText;
+use crate::{AnimState, Color, ColorTransform, Frame, Point, Rect};
+
+/// A single textured rectangle in [`DrawData`](struct.DrawData.html).  All positions and sizes are
+/// in physical pixels.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DrawQuad {
+    /// The position of the top left corner of the rectangle
+    pub pos: [f32; 2],
+
+    /// The size of the rectangle
+    pub size: [f32; 2],
+
+    /// The normalized texture coordinates of the top left and bottom right corners of the rectangle
+    pub tex: [[f32; 2]; 2],
+
+    /// The color to multiply the texture color by, as RGBA
+    pub color: [f32; 4],
+
+    /// The position of the clip rectangle.  Any part of the quad outside the clip rectangle must not be drawn.
+    pub clip_pos: [f32; 2],
+
+    /// The size of the clip rectangle
+    pub clip_size: [f32; 2],
+}
+
+/// A single textured triangle in [`DrawData`](struct.DrawData.html).  All positions are in physical pixels.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DrawTriangle {
+    /// The position of each vertex
+    pub pos: [[f32; 2]; 3],
+
+    /// The normalized texture coordinates of each vertex
+    pub tex: [[f32; 2]; 3],
+
+    /// The color of each vertex, as RGBA, to multiply the texture color by.  Colors are interpolated across the triangle.
+    pub color: [[f32; 4]; 3],
+
+    /// The position of the clip rectangle.  Any part of the triangle outside the clip rectangle must not be drawn.
+    pub clip_pos: [f32; 2],
+
+    /// The size of the clip rectangle
+    pub clip_size: [f32; 2],
+}
+
+/// The kind of primitive drawn by a [`DrawGroup`](struct.DrawGroup.html)
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DrawPrimitive {
+    /// The group is a range of [`quads`](struct.DrawData.html#structfield.quads)
+    Quads,
+
+    /// The group is a range of [`triangles`](struct.DrawData.html#structfield.triangles)
+    Triangles,
+}
+
+/// The texture used by a [`DrawGroup`](struct.DrawGroup.html)
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DrawTexture {
+    /// An RGBA image texture, with the index of the texture in
+    /// [`DrawDataRenderer::textures`](struct.DrawDataRenderer.html#method.textures)
+    Image(usize),
+
+    /// A single channel font texture, containing coverage values, with the index of the texture in
+    /// [`DrawDataRenderer::fonts`](struct.DrawDataRenderer.html#method.fonts).  The texture value
+    /// is used as the alpha, together with the RGB of the quad color.
+    Font(usize),
+}
+
+/// A range of quads or triangles in [`DrawData`](struct.DrawData.html) which are all drawn with the same texture.
+/// A group contains at most [`MAX_QUADS`](#associatedconstant.MAX_QUADS) primitives, so that each may be drawn
+/// using 16 bit indices.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DrawGroup {
+    /// The index of the first quad or triangle in the group
+    pub start: usize,
+
+    /// One past the index of the last quad or triangle in the group
+    pub end: usize,
+
+    /// Whether the group is a range of quads or triangles
+    pub primitive: DrawPrimitive,
+
+    /// The texture used to draw the group
+    pub texture: DrawTexture,
+}
+
+impl DrawGroup {
+    /// The maximum number of quads, with four vertices each, or triangles in a group.  Larger ranges
+    /// using the same texture are split into multiple groups.
+    pub const MAX_QUADS: usize = 16384;
+}
+
+/**
+All of the data needed to draw a [`Frame`](struct.Frame.html), as created by
+[`DrawDataRenderer::draw_frame`](struct.DrawDataRenderer.html#method.draw_frame).  The `groups`
+should be drawn in order, each drawing its range of `quads` or `triangles` with the specified texture,
+using alpha blending.
+**/
+#[derive(Clone, Debug, Default)]
+pub struct DrawData {
+    /// All quads to draw, in order
+    pub quads: Vec<DrawQuad>,
+
+    /// All triangles to draw, in order
+    pub triangles: Vec<DrawTriangle>,
+
+    /// The groups of quads to draw, in order
+    pub groups: Vec<DrawGroup>,
+
+    /// The size of the display in physical pixels
+    pub display_size: [f32; 2],
+
+    /// An orthographic projection matrix, in column major order, converting from physical pixels to normalized
+    /// device coordinates.  This includes any [`display rotation`](struct.Context.html#method.set_display_rotation).
+    pub matrix: [[f32; 4]; 4],
+
+    /// The [`color transform`](struct.Context.html#method.set_color_transform) matrix, in row major order, which
+    /// should be multiplied with the RGB color of each drawn pixel.  `None` if no transform is set.
+    pub color_transform: Option<[[f32; 3]; 3]>,
+}
+
+impl DrawData {
+    /// Returns the size of the display in whole physical pixels, as used by [`rasterize`](#method.rasterize).
+    pub fn pixel_size(&self) -> (u32, u32) {
+        (self.display_size[0].round() as u32, self.display_size[1].round() as u32)
+    }
+
+    /**
+    Draws this data in software, using the textures and fonts stored in the `renderer`.  Returns RGBA pixel
+    data with 8 bits per channel, in rows from the top of the display to the bottom, with the size given by
+    [`pixel_size`](#method.pixel_size).  The image is first cleared to opaque black.  Image textures are sampled
+    with linear filtering, font textures with nearest filtering, and quads are alpha blended, in the same way as
+    the built in backends.  Any `color_transform` is applied to each quad's colors before blending.  The display
+    rotation is not applied.
+
+    This is slow, and is intended for testing, such as with the [`ConformanceSuite`](struct.ConformanceSuite.html).
+    **/
+    pub fn rasterize(&self, renderer: &DrawDataRenderer) -> Vec<u8> {
+        let (width, height) = self.pixel_size();
+        let mut target = Target { width: width as usize, height: height as usize, pixels: Vec::new() };
+        target.pixels = [0.0, 0.0, 0.0, 1.0].repeat(target.width * target.height);
+
+        for group in &self.groups {
+            let (texture, linear) = match group.texture {
+                DrawTexture::Image(index) => (&renderer.textures[index], true),
+                DrawTexture::Font(index) => (&renderer.fonts[index], false),
+            };
+
+            match group.primitive {
+                DrawPrimitive::Quads => for quad in &self.quads[group.start..group.end] {
+                    target.draw(quad, texture, linear, self.color_transform);
+                },
+                DrawPrimitive::Triangles => for triangle in &self.triangles[group.start..group.end] {
+                    target.draw_triangle(triangle, texture, linear, self.color_transform);
+                },
+            }
+        }
+
+        target.pixels.iter().map(|value| (value.clamp(0.0, 1.0) * 255.0).round() as u8).collect()
+    }
+}
+
+struct Target {
+    width: usize,
+    height: usize,
+    pixels: Vec<f32>,
+}
+
+impl Target {
+    fn draw(&mut self, quad: &DrawQuad, texture: &TextureImage, linear: bool, transform: Option<[[f32; 3]; 3]>) {
+        if quad.size[0] <= 0.0 || quad.size[1] <= 0.0 { return; }
+
+        let min_x = quad.pos[0].max(quad.clip_pos[0]);
+        let min_y = quad.pos[1].max(quad.clip_pos[1]);
+        let max_x = (quad.pos[0] + quad.size[0]).min(quad.clip_pos[0] + quad.clip_size[0]);
+        let max_y = (quad.pos[1] + quad.size[1]).min(quad.clip_pos[1] + quad.clip_size[1]);
+
+        // pixels are drawn when their center is within the quad and its clip
+        let start_x = (min_x - 0.5).ceil().max(0.0) as usize;
+        let start_y = (min_y - 0.5).ceil().max(0.0) as usize;
+        let end_x = ((max_x - 0.5).ceil().max(0.0) as usize).min(self.width);
+        let end_y = ((max_y - 0.5).ceil().max(0.0) as usize).min(self.height);
+
+        for y in start_y..end_y {
+            let frac_y = (y as f32 + 0.5 - quad.pos[1]) / quad.size[1];
+            let tex_y = quad.tex[0][1] + frac_y * (quad.tex[1][1] - quad.tex[0][1]);
+
+            for x in start_x..end_x {
+                let frac_x = (x as f32 + 0.5 - quad.pos[0]) / quad.size[0];
+                let tex_x = quad.tex[0][0] + frac_x * (quad.tex[1][0] - quad.tex[0][0]);
+
+                let src = texture.shade(tex_x, tex_y, quad.color, linear);
+                self.blend(x, y, src, transform);
+            }
+        }
+    }
+
+    fn draw_triangle(
+        &mut self,
+        tri: &DrawTriangle,
+        texture: &TextureImage,
+        linear: bool,
+        transform: Option<[[f32; 3]; 3]>,
+    ) {
+        // twice the signed area of the triangle a, b, c
+        let edge = |a: [f32; 2], b: [f32; 2], c: [f32; 2]| (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0]);
+
+        let [p0, p1, p2] = tri.pos;
+        let area = edge(p0, p1, p2);
+        if area == 0.0 { return; }
+
+        let min_x = p0[0].min(p1[0]).min(p2[0]).max(tri.clip_pos[0]);
+        let min_y = p0[1].min(p1[1]).min(p2[1]).max(tri.clip_pos[1]);
+        let max_x = p0[0].max(p1[0]).max(p2[0]).min(tri.clip_pos[0] + tri.clip_size[0]);
+        let max_y = p0[1].max(p1[1]).max(p2[1]).min(tri.clip_pos[1] + tri.clip_size[1]);
+
+        // pixels are drawn when their center is within the triangle and its clip
+        let start_x = (min_x - 0.5).ceil().max(0.0) as usize;
+        let start_y = (min_y - 0.5).ceil().max(0.0) as usize;
+        let end_x = ((max_x - 0.5).ceil().max(0.0) as usize).min(self.width);
+        let end_y = ((max_y - 0.5).ceil().max(0.0) as usize).min(self.height);
+
+        for y in start_y..end_y {
+            for x in start_x..end_x {
+                let p = [x as f32 + 0.5, y as f32 + 0.5];
+                let weights = [edge(p1, p2, p) / area, edge(p2, p0, p) / area, edge(p0, p1, p) / area];
+                if weights.iter().any(|weight| *weight < 0.0) { continue; }
+
+                let interpolate = |values: [f32; 3]| weights[0] * values[0] + weights[1] * values[1] + weights[2] * values[2];
+                let tex_x = interpolate(tri.tex.map(|tex| tex[0]));
+                let tex_y = interpolate(tri.tex.map(|tex| tex[1]));
+                let color = [0, 1, 2, 3].map(|i| interpolate(tri.color.map(|color| color[i])));
+
+                let src = texture.shade(tex_x, tex_y, color, linear);
+                self.blend(x, y, src, transform);
+            }
+        }
+    }
+
+    fn blend(&mut self, x: usize, y: usize, mut src: [f32; 4], transform: Option<[[f32; 3]; 3]>) {
+        if let Some(m) = transform {
+            let [r, g, b, _] = src;
+            for (i, row) in m.iter().enumerate() {
+                src[i] = (row[0] * r + row[1] * g + row[2] * b).clamp(0.0, 1.0);
+            }
+        }
+
+        let index = (x + y * self.width) * 4;
+        let dst = &mut self.pixels[index..index + 4];
+        let alpha = src[3];
+        for i in 0..4 {
+            dst[i] = src[i] * alpha + dst[i] * (1.0 - alpha);
+        }
+    }
+}
+
+impl TextureImage {
+    /// The color of a pixel drawn with the specified `color` at the texture coordinates `x`, `y`
+    fn shade(&self, x: f32, y: f32, color: [f32; 4], linear: bool) -> [f32; 4] {
+        if linear {
+            let texel = self.sample_linear(x, y);
+            [0, 1, 2, 3].map(|i| texel[i] * color[i])
+        } else {
+            let coverage = self.sample_nearest(x, y);
+            [color[0], color[1], color[2], coverage]
+        }
+    }
+
+    fn texel(&self, x: i64, y: i64) -> [f32; 4] {
+        let x = x.clamp(0, self.width as i64 - 1) as usize;
+        let y = y.clamp(0, self.height as i64 - 1) as usize;
+        let index = x + y * self.width as usize;
+
+        if self.data.len() == self.width as usize * self.height as usize * 4 {
+            let texel = &self.data[index * 4..index * 4 + 4];
+            [0, 1, 2, 3].map(|i| texel[i] as f32 / 255.0)
+        } else {
+            let value = self.data[index] as f32 / 255.0;
+            [value, value, value, value]
+        }
+    }
+
+    fn sample_nearest(&self, x: f32, y: f32) -> f32 {
+        let x = (x * self.width as f32).floor() as i64;
+        let y = (y * self.height as f32).floor() as i64;
+        self.texel(x, y)[0]
+    }
+
+    fn sample_linear(&self, x: f32, y: f32) -> [f32; 4] {
+        let x = x * self.width as f32 - 0.5;
+        let y = y * self.height as f32 - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as i64, y0 as i64);
+
+        let (t00, t10) = (self.texel(x0, y0), self.texel(x0 + 1, y0));
+        let (t01, t11) = (self.texel(x0, y0 + 1), self.texel(x0 + 1, y0 + 1));
+
+        [0, 1, 2, 3].map(|i| {
+            let top = t00[i] * (1.0 - fx) + t10[i] * fx;
+            let bot = t01[i] * (1.0 - fx) + t11[i] * fx;
+            top * (1.0 - fy) + bot * fy
+        })
+    }
+}
+
+impl DrawList for Vec<DrawQuad> {
+    fn push_rect(
+        &mut self,
+        pos: [f32; 2],
+        size: [f32; 2],
+        tex: [TexCoord; 2],
+        color: Color,
+        clip: Rect,
+    ) {
+        self.push(DrawQuad {
+            pos,
+            size,
+            tex: [tex[0].into(), tex[1].into()],
+            color: color.into(),
+            clip_pos: clip.pos.into(),
+            clip_size: clip.size.into(),
+        });
+    }
+
+    // only used for measuring text, which consists of rectangles
+    fn push_triangles(&mut self, _vertices: &[DrawVertex], _indices: &[u32], _clip: Rect) {}
+
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    fn back_adjust_positions(&mut self, since_index: usize, amount: Point) {
+        for quad in self.iter_mut().skip(since_index) {
+            quad.pos[0] += amount.x;
+            quad.pos[1] += amount.y;
+        }
+    }
+}
+
+impl DrawList for DrawData {
+    fn push_rect(
+        &mut self,
+        pos: [f32; 2],
+        size: [f32; 2],
+        tex: [TexCoord; 2],
+        color: Color,
+        clip: Rect,
+    ) {
+        self.quads.push_rect(pos, size, tex, color, clip);
+    }
+
+    fn push_triangles(&mut self, vertices: &[DrawVertex], indices: &[u32], clip: Rect) {
+        for [v0, v1, v2] in triangle_vertices(vertices, indices) {
+            self.triangles.push(DrawTriangle {
+                pos: [v0.pos, v1.pos, v2.pos],
+                tex: [v0.tex.into(), v1.tex.into(), v2.tex.into()],
+                color: [v0.color.into(), v1.color.into(), v2.color.into()],
+                clip_pos: clip.pos.into(),
+                clip_size: clip.size.into(),
+            });
+        }
+    }
+
+    // text is only drawn with quads, so positions are adjusted by quad index
+    fn len(&self) -> usize {
+        self.quads.len()
+    }
+
+    fn back_adjust_positions(&mut self, since_index: usize, amount: Point) {
+        self.quads.back_adjust_positions(since_index, amount);
+    }
+}
+
+/// Texture data registered with a [`DrawDataRenderer`](struct.DrawDataRenderer.html)
+#[derive(Clone, Debug)]
+pub struct TextureImage {
+    /// The width of the texture in pixels
+    pub width: u32,
+
+    /// The height of the texture in pixels
+    pub height: u32,
+
+    /// The pixel data, in rows, with the first row at a texture coordinate `y` of zero.  This is RGBA
+    /// with 8 bits per channel for images, and a single 8 bit channel for fonts.
+    pub data: Vec<u8>,
+}
+
+/**
+A Thyme [`Renderer`](trait.Renderer.html) which does not draw anything itself, but instead produces
+[`DrawData`](struct.DrawData.html) for each frame.  This allows integrating Thyme with any graphics API or
+engine, without depending on one of the backend features.
+
+When the [`Context`](struct.Context.html) is [`built`](struct.ContextBuilder.html#method.build), image and font
+data is stored in the renderer, and may be uploaded to the GPU using [`textures`](#method.textures) and
+[`fonts`](#method.fonts).  Fonts and textures may be registered again when the theme is rebuilt, such as
+with live reload; [`textures_changed`](#method.textures_changed) may be used to check for this.
+
+# Example
+```
+let mut renderer = thyme::DrawDataRenderer::new();
+let mut context = context_builder.build(&mut renderer, &mut io)?;
+
+// upload renderer.textures() and renderer.fonts() to the GPU
+
+loop {
+    let mut ui = context.create_frame();
+    build_ui(&mut ui);
+
+    let data = renderer.draw_frame(ui);
+    for group in &data.groups {
+        my_engine.draw(&data.quads[group.start..group.end], group.texture);
+    }
+}
+```
+**/
+#[derive(Default)]
+pub struct DrawDataRenderer {
+    textures: Vec<TextureImage>,
+    fonts: Vec<TextureImage>,
+    textures_changed: bool,
+    data: DrawData,
+}
+
+impl DrawDataRenderer {
+    /// Creates a new, empty renderer
+    pub fn new() -> DrawDataRenderer {
+        DrawDataRenderer::default()
+    }
+
+    /// Returns the image textures, as referenced by [`DrawTexture::Image`](enum.DrawTexture.html)
+    pub fn textures(&self) -> &[TextureImage] {
+        &self.textures
+    }
+
+    /// Returns the font textures, as referenced by [`DrawTexture::Font`](enum.DrawTexture.html)
+    pub fn fonts(&self) -> &[TextureImage] {
+        &self.fonts
+    }
+
+    /// Returns whether any textures or fonts have been registered since the last call to this method.
+    pub fn textures_changed(&mut self) -> bool {
+        std::mem::take(&mut self.textures_changed)
+    }
+
+    /// Creates the [`DrawData`](struct.DrawData.html) for the specified [`Frame`](struct.Frame.html).
+    pub fn draw_frame(&mut self, frame: Frame) -> &DrawData {
+        let mouse_cursor = frame.mouse_cursor();
+        let (context, widgets, render_groups) = frame.finish_frame();
+        let context = context.internal().borrow();
+
+        let time_millis = context.time_millis();
+        let display_size = context.display_size();
+        let scale = context.scale_factor();
+
+        let data = &mut self.data;
+        data.quads.clear();
+        data.triangles.clear();
+        data.groups.clear();
+        data.display_size = display_size.into();
+        data.matrix = view_matrix(Point::default(), display_size, context.display_rotation());
+        data.color_transform = match context.color_transform() {
+            ColorTransform::None => None,
+            transform => Some(transform.matrix()),
+        };
+
+        for render_group in render_groups.into_iter().rev() {
+            // render backgrounds
+            for widget in render_group.iter(&widgets) {
+                if !widget.visible() || widget.clipped_out() { continue; }
+                let image_handle = match widget.background() {
+                    None => continue,
+                    Some(handle) => handle,
+                };
+                let time_millis = time_millis - context.base_time_millis_for(widget.id());
+                let image = context.themes().image(image_handle);
+
+                let mut draw_list = TransformedDrawList::new(data, widget.transform(), scale);
+                image.draw(
+                    &mut draw_list,
+                    ImageDrawParams {
+                        pos: widget.pos().into(),
+                        size: widget.size().into(),
+                        anim_state: widget.anim_state(),
+                        clip: widget.clip(),
+                        time_millis,
+                        scale,
+                        color: widget.image_color(),
+                    }
+                );
+                draw_list.finish();
+                push_group(data, DrawTexture::Image(image.texture().id()));
+            }
+
+            // render foregrounds & text
+            for widget in render_group.iter(&widgets) {
+                if !widget.visible() || widget.clipped_out() { continue; }
+
+                let border = widget.border();
+                let fg_pos = widget.pos() + border.tl();
+                let fg_size = widget.inner_size();
+
+                if let Some(image_handle) = widget.foreground() {
+                    let time_millis = time_millis - context.base_time_millis_for(widget.id());
+                    let image = context.themes().image(image_handle);
+
+                    let mut draw_list = TransformedDrawList::new(data, widget.transform(), scale);
+                    image.draw(
+                        &mut draw_list,
+                        ImageDrawParams {
+                            pos: fg_pos.into(),
+                            size: fg_size.into(),
+                            anim_state: widget.anim_state(),
+                            clip: widget.clip(),
+                            time_millis,
+                            scale,
+                            color: widget.image_color(),
+                        }
+                    );
+                    draw_list.finish();
+                    push_group(data, DrawTexture::Image(image.texture().id()));
+                }
+
+                if let Some(triangles) = widget.triangles() {
+                    let image = context.themes().backplate();
+                    let mut draw_list = TransformedDrawList::new(data, widget.transform(), scale);
+                    triangles.draw(&mut draw_list, widget, scale);
+                    draw_list.finish();
+                    push_group(data, DrawTexture::Image(image.texture().id()));
+                }
+
+                if let (Some(text), Some(font_sum)) = (widget.text(), widget.font()) {
+                    let font = context.themes().font(font_sum.handle);
+
+                    let mut params = FontDrawParams {
+                        area_size: fg_size * scale,
+                        pos: fg_pos * scale,
+                        indent: widget.text_indent(),
+                        align: widget.text_align(),
+                        wrap: widget.text_wrap(),
+                        soft_hyphens: widget.text_soft_hyphens(),
+                        color: widget.text_color(),
+                        scale_factor: scale,
+                        visible_chars: widget.text_reveal(),
+                    };
+
+                    if context.high_contrast() {
+                        let contrast = HighContrastText::new(font, params, text, widget.clip() * scale);
+                        params.color = contrast.text_color;
+
+                        if let Some(plate) = contrast.plate {
+                            let image = context.themes().backplate();
+                            let mut draw_list = TransformedDrawList::new(data, widget.transform(), scale);
+                            image.draw(
+                                &mut draw_list,
+                                ImageDrawParams {
+                                    pos: plate.pos.into(),
+                                    size: plate.size.into(),
+                                    anim_state: AnimState::normal(),
+                                    clip: widget.clip(),
+                                    time_millis,
+                                    scale,
+                                    color: contrast.plate_color,
+                                }
+                            );
+                            draw_list.finish();
+                            push_group(data, DrawTexture::Image(image.texture().id()));
+                        }
+                    }
+
+                    let mut draw_list = TransformedDrawList::new(data, widget.transform(), scale);
+                    let inline_images = font.draw(&mut draw_list, params, text, widget.clip() * scale);
+                    draw_list.finish();
+                    push_group(data, DrawTexture::Font(font_sum.handle.id()));
+
+                    for (index, rect) in inline_images {
+                        let image_handle = match widget.inline_images().get(index) {
+                            None => continue,
+                            Some(handle) => *handle,
+                        };
+                        let image = context.themes().image(image_handle);
+
+                        let mut draw_list = TransformedDrawList::new(data, widget.transform(), scale);
+                        image.draw(
+                            &mut draw_list,
+                            ImageDrawParams {
+                                pos: (rect.pos / scale).into(),
+                                size: (rect.size / scale).into(),
+                                anim_state: widget.anim_state(),
+                                clip: widget.clip(),
+                                time_millis: time_millis - context.base_time_millis_for(widget.id()),
+                                scale,
+                                color: widget.image_color(),
+                            }
+                        );
+                        draw_list.finish();
+                        push_group(data, DrawTexture::Image(image.texture().id()));
+                    }
+                }
+            }
+        }
+
+        if let Some((mouse_cursor, align, anim_state)) = mouse_cursor {
+            let image = context.themes().image(mouse_cursor);
+            let size = image.base_size();
+            let pos = context.mouse_pos() - align.adjust_for(size);
+
+            let params = ImageDrawParams {
+                pos: pos.into(),
+                size: size.into(),
+                anim_state,
+                clip: Rect::new(pos, size),
+                time_millis,
+                scale,
+                color: Color::white(),
+            };
+
+            image.draw(data, params);
+            push_group(data, DrawTexture::Image(image.texture().id()));
+        }
+
+        context.record_quads(data.quads.len() + data.triangles.len());
+        &self.data
+    }
+}
+
+/// Adds any quads and triangles since the last groups to groups with the specified texture, extending the
+/// last group if it uses the same primitive and texture.  Groups are split when they reach `DrawGroup::MAX_QUADS`.
+fn push_group(data: &mut DrawData, texture: DrawTexture) {
+    push_primitive_group(data, DrawPrimitive::Quads, data.quads.len(), texture);
+    push_primitive_group(data, DrawPrimitive::Triangles, data.triangles.len(), texture);
+}
+
+fn push_primitive_group(data: &mut DrawData, primitive: DrawPrimitive, end: usize, texture: DrawTexture) {
+    let mut start = data.groups.iter().rev()
+        .find(|group| group.primitive == primitive)
+        .map_or(0, |group| group.end);
+    if start == end { return; }
+
+    if let Some(group) = data.groups.last_mut() {
+        if group.primitive == primitive && group.texture == texture {
+            group.end = end.min(group.start + DrawGroup::MAX_QUADS);
+            start = group.end;
+        }
+    }
+
+    while start < end {
+        let group_end = end.min(start + DrawGroup::MAX_QUADS);
+        data.groups.push(DrawGroup { start, end: group_end, primitive, texture });
+        start = group_end;
+    }
+}
+
+fn store(images: &mut Vec<TextureImage>, index: usize, image: TextureImage) {
+    store_handle(images, index, image, || TextureImage { width: 0, height: 0, data: Vec::new() });
+}
+
+impl Renderer for DrawDataRenderer {
+    fn register_texture(
+        &mut self,
+        handle: TextureHandle,
+        image_data: &[u8],
+        dimensions: (u32, u32),
+    ) -> Result<TextureData, crate::Error> {
+        let image = TextureImage { width: dimensions.0, height: dimensions.1, data: image_data.to_vec() };
+        store(&mut self.textures, handle.id(), image);
+        self.textures_changed = true;
+
+        Ok(TextureData::new(handle, dimensions.0, dimensions.1))
+    }
+
+    fn register_font(
+        &mut self,
+        handle: FontHandle,
+        source: &FontSource,
+        ranges: &[CharacterRange],
+        size: f32,
+        scale: f32,
+    ) -> Result<Font, crate::Error> {
+        let writer = FontTextureWriter::new(&source.font, ranges, size, scale);
+        let writer_out = writer.write(handle, ranges)?;
+
+        let image = TextureImage { width: writer_out.tex_width, height: writer_out.tex_height, data: writer_out.data };
+        store(&mut self.fonts, handle.id(), image);
+        self.textures_changed = true;
+
+        Ok(writer_out.font)
+    }
+}
@@ -0,0 +1,105 @@
+use std::f32::consts::{PI, TAU};
+
+use crate::{Point, Transform};
+
+/// An attention effect, briefly animating a widget to draw the user's attention to it.  Effects are
+/// started with [`Frame::play_effect`](struct.Frame.html#method.play_effect) or one of its shortcuts,
+/// such as [`Frame::shake`](struct.Frame.html#method.shake).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Effect {
+    /// Shakes the widget from side to side, such as to signal invalid input
+    Shake,
+
+    /// Briefly grows and then shrinks the widget back to its normal size, such as to signal a newly available action
+    Pulse,
+
+    /// Fades the widget out and back in twice
+    Flash,
+}
+
+impl Effect {
+    /// The length of this effect, in milliseconds
+    pub fn duration_millis(self) -> u32 {
+        match self {
+            Effect::Shake => 400,
+            Effect::Pulse => 300,
+            Effect::Flash => 600,
+        }
+    }
+
+    /// The transform and opacity of a widget `elapsed` milliseconds after this effect was started
+    pub(crate) fn at(self, elapsed: u32) -> (Option<Transform>, f32) {
+        let t = (elapsed as f32 / self.duration_millis() as f32).min(1.0);
+
+        match self {
+            Effect::Shake => {
+                // three oscillations, settling back to rest
+                let x = 6.0 * (1.0 - t) * (3.0 * TAU * t).sin();
+                (Some(Transform::offset(x, 0.0)), 1.0)
+            },
+            Effect::Pulse => {
+                let scale = 1.0 + 0.1 * (PI * t).sin();
+                (Some(Transform::scale(scale, scale)), 1.0)
+            },
+            Effect::Flash => (None, 0.5 + 0.5 * (2.0 * TAU * t).cos()),
+        }
+    }
+}
+
+/**
+A transition played when a modal opens and closes, set with
+[`Frame::set_modal_transition`](struct.Frame.html#method.set_modal_transition).  The transition is played forward
+when the modal opens, and in reverse when it is closed, with the modal only removed once the transition completes.
+The transition applies to the modal widget and all of its children.
+**/
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Transition {
+    /// Fades the modal in and out
+    Fade,
+
+    /// Fades the modal in while growing it to its full size, and the reverse on close
+    FadeScale,
+
+    /// Slides the modal in from above its position, by its own height
+    SlideFromTop,
+
+    /// Slides the modal in from below its position, by its own height
+    SlideFromBottom,
+
+    /// Slides the modal in from the left of its position, by its own width
+    SlideFromLeft,
+
+    /// Slides the modal in from the right of its position, by its own width
+    SlideFromRight,
+}
+
+impl Transition {
+    /// The length of this transition, in milliseconds
+    pub fn duration_millis(self) -> u32 {
+        match self {
+            Transition::Fade => 150,
+            Transition::FadeScale => 200,
+            Transition::SlideFromTop | Transition::SlideFromBottom |
+                Transition::SlideFromLeft | Transition::SlideFromRight => 250,
+        }
+    }
+
+    /// The transform and opacity of a widget of the specified `size` when the transition is `shown`, from
+    /// zero when fully hidden to one when fully shown
+    pub(crate) fn at(self, shown: f32, size: Point) -> (Option<Transform>, f32) {
+        // ease out, so the modal settles gently into place
+        let hidden = (1.0 - shown).powi(3);
+
+        match self {
+            Transition::Fade => (None, 1.0 - hidden),
+            Transition::FadeScale => {
+                let scale = 1.0 - 0.1 * hidden;
+                (Some(Transform::scale(scale, scale)), 1.0 - hidden)
+            },
+            Transition::SlideFromTop => (Some(Transform::offset(0.0, -size.y * hidden)), 1.0),
+            Transition::SlideFromBottom => (Some(Transform::offset(0.0, size.y * hidden)), 1.0),
+            Transition::SlideFromLeft => (Some(Transform::offset(-size.x * hidden, 0.0)), 1.0),
+            Transition::SlideFromRight => (Some(Transform::offset(size.x * hidden, 0.0)), 1.0),
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use crate::{DisplayClass, MouseButton};
+
+/**
+An interaction with a widget, recorded when the [`event_queue`](struct.BuildOptions.html#structfield.event_queue)
+option is enabled.  Events are collected as each frame is built, and may be retrieved with
+[`Context::take_events`](struct.Context.html#method.take_events) once the frame has been drawn.  This allows
+applications, such as those using an ECS, to handle UI events separately from the code building the UI.
+The same interactions are still reported through the usual return values, such as
+[`WidgetState`](struct.WidgetState.html).
+
+# Example
+```
+// after drawing the frame
+for event in context.take_events() {
+    match event {
+        UiEvent::Clicked { id, .. } if id == "save_button" => save(),
+        UiEvent::ValueChanged { id } => println!("{} changed", id),
+        _ => (),
+    }
+}
+```
+**/
+#[derive(Clone, Debug, PartialEq)]
+pub enum UiEvent {
+    /// A widget which [`wants_mouse`](struct.WidgetBuilder.html#method.wants_mouse) was clicked
+    Clicked {
+        /// The id of the clicked widget
+        id: String,
+
+        /// The mouse button used for the click
+        button: MouseButton,
+    },
+
+    /// The value of a widget, such as an input field, slider, or combo box, was changed by the user
+    ValueChanged {
+        /// The id of the changed widget
+        id: String,
+    },
+
+    /// A link within a [`text_area`](struct.Frame.html#method.text_area) was clicked
+    LinkClicked {
+        /// The id of the text area
+        id: String,
+
+        /// The URL of the clicked link
+        url: String,
+    },
+
+    /// The user started dragging an item, such as in a
+    /// [`reorderable_list`](struct.Frame.html#method.reorderable_list)
+    DragStarted {
+        /// The id of the widget containing the item
+        id: String,
+
+        /// The index of the dragged item
+        index: usize,
+    },
+
+    /// A widget with an explicit [`id`](struct.WidgetBuilder.html#method.id) was drawn, after not being drawn
+    /// on the previous frame
+    Appeared {
+        /// The id of the widget
+        id: String,
+    },
+
+    /// A widget with an explicit [`id`](struct.WidgetBuilder.html#method.id) which was drawn on the previous
+    /// frame was not drawn on this frame, for example because it or its parent was closed
+    Disappeared {
+        /// The id of the widget
+        id: String,
+    },
+
+    /// Keyboard focus moved to a different widget, or was removed
+    FocusChanged {
+        /// The id of the widget with keyboard focus, if any
+        id: Option<String>,
+    },
+
+    /// The [`DisplayClass`](enum.DisplayClass.html) changed, due to the display being resized
+    DisplayClassChanged {
+        /// The new display class
+        class: DisplayClass,
+    },
+}
+
+/**
+The kind of a user interaction with a widget, sent to the
+[`interaction callback`](struct.Context.html#method.set_interaction_callback) for UI telemetry and analytics.
+**/
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Interaction {
+    /// A widget which [`wants_mouse`](struct.WidgetBuilder.html#method.wants_mouse) was clicked
+    Clicked,
+
+    /// A widget, such as a window or modal, was opened via [`Frame.open`](struct.Frame.html#method.open)
+    /// or a related method
+    Opened,
+
+    /// A widget, such as a window or modal, was closed via [`Frame.close`](struct.Frame.html#method.close)
+    /// or a related method
+    Closed,
+
+    /// A widget gained keyboard focus
+    FocusGained,
+
+    /// A widget lost keyboard focus
+    FocusLost,
+}
+
+/**
+A controller feedback event, sent to the [`haptic callback`](struct.Context.html#method.set_haptic_callback)
+while [`gamepad input`](struct.Context.html#method.set_gamepad_active) is active.  Applications typically
+map each event to a short controller rumble.
+**/
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum HapticEvent {
+    /// Keyboard focus moved to a different widget
+    FocusMoved,
+
+    /// A widget was clicked, or otherwise activated
+    Confirm,
+
+    /// An action could not be carried out, sent via [`Frame.haptic`](struct.Frame.html#method.haptic)
+    Error,
+}
@@ -0,0 +1,406 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::Frame;
+
+/// A named set of file extensions, used to restrict the files shown by a
+/// [`file_picker`](struct.Frame.html#method.file_picker).
+#[derive(Copy, Clone, Debug)]
+pub struct FileFilter<'a> {
+    /// The name shown to the user, such as `Images`
+    pub name: &'a str,
+
+    /// The extensions matched by this filter, without the leading `.`, such as `png`.  Matching is
+    /// case insensitive.  If empty, all files are matched.
+    pub extensions: &'a [&'a str],
+}
+
+impl<'a> FileFilter<'a> {
+    /// Returns whether the file at `path` is matched by this filter.
+    pub fn matches(&self, path: &Path) -> bool {
+        if self.extensions.is_empty() { return true; }
+
+        let ext = match path.extension() {
+            None => return false,
+            Some(ext) => ext.to_string_lossy(),
+        };
+
+        self.extensions.iter().any(|e| e.eq_ignore_ascii_case(&ext))
+    }
+}
+
+impl<'a> fmt::Display for FileFilter<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name)
+    }
+}
+
+/// What a [`file_picker`](struct.Frame.html#method.file_picker) is used to select.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FileDialogMode {
+    /// An existing file
+    Open,
+
+    /// A file name to save to, which may or may not exist
+    Save,
+
+    /// A directory
+    Directory,
+}
+
+impl Frame {
+    /// Opens the [`file_picker`](#method.file_picker) with the specified `id` as a modal, showing
+    /// the contents of the directory `dir`.
+    pub fn open_file_picker<P: AsRef<Path>>(&mut self, id: &str, dir: P) {
+        let dir = dir.as_ref().to_string_lossy().to_string();
+        self.modify(format!("{}_dir", id), |state| state.text = Some(dir));
+        self.modify(format!("{}_filename", id), |state| state.text = Some(String::new()));
+        self.modify(format!("{}_error", id), |state| state.text = None);
+        self.open_modal(id);
+    }
+
+    /**
+    A file browser window, listing the directories and files in the current directory with the standard library's
+    [`std::fs`](https://doc.rust-lang.org/std/fs/index.html) functions.  This is useful on platforms or in games
+    where native dialogs are not available or not wanted.  The window is initially closed, and is opened with
+    [`open_file_picker`](#method.open_file_picker).  The `id` must be unique.
+
+    The window contains the current `path` as [`breadcrumbs`](#method.breadcrumbs), a scrollpane of `entries`
+    with a `directory` button for each subdirectory and a `file` button for each file matching the current filter,
+    a `filename` input field, a `filter` combo box when `filters` is not empty, and `new_folder`, `confirm`, and
+    `cancel` buttons.  Clicking a directory navigates into it, while clicking a file selects it, and double
+    clicking confirms it.  Problems, such as a directory that cannot be read, are shown in an `error` label.
+    Files and directories beginning with `.` are not shown.
+
+    When the user confirms their choice, the window is closed and the selected path is returned.  In
+    [`Save`](enum.FileDialogMode.html#variant.Save) mode, the first extension of the current filter is added
+    to file names without an extension.  In [`Directory`](enum.FileDialogMode.html#variant.Directory) mode, the
+    current directory is returned.
+
+    An example theme definition:
+    ```yaml
+    file_picker:
+      from: window
+      size: [400, 400]
+      children:
+        path:
+          from: breadcrumbs
+        entries:
+          from: scrollpane
+          size_from: [Parent, Normal]
+          height: 240
+          children:
+            content:
+              layout: Vertical
+              children:
+                directory:
+                  from: button
+                  size_from: [Parent, Normal]
+                  text_align: Left
+                  foreground: gui/icon_folder
+                file:
+                  from: button
+                  size_from: [Parent, Normal]
+                  text_align: Left
+        filename:
+          from: input_field
+          size_from: [Parent, Normal]
+        filter:
+          from: combo_box
+        new_folder_row:
+          layout: Horizontal
+          size_from: [Parent, Children]
+          children:
+            folder_name:
+              from: input_field
+            create:
+              from: button
+              text: "Create"
+        new_folder:
+          from: button
+          text: "New Folder"
+        confirm:
+          from: button
+          text: "OK"
+        cancel:
+          from: button
+          text: "Cancel"
+        error:
+          from: label
+          text_color: "#F44"
+          size_from: [Parent, FontLine]
+    ```
+
+    # Example
+    ```
+    fn load_menu(ui: &mut Frame) {
+        if ui.button("button", "Load...").clicked {
+            ui.open_file_picker("load_picker", "saves");
+        }
+
+        let filters = [FileFilter { name: "Saves", extensions: &["sav"] }];
+        if let Some(path) = ui.file_picker("file_picker", "load_picker", FileDialogMode::Open, &filters) {
+            println!("Loading {:?}", path);
+        }
+    }
+    ```
+    **/
+    pub fn file_picker(&mut self, theme: &str, id: &str, mode: FileDialogMode, filters: &[FileFilter]) -> Option<PathBuf> {
+        self.context_internal().borrow_mut().init_state(id, false, true);
+        if !self.is_open(id) { return None; }
+
+        let dir_id = format!("{}_dir", id);
+        let filename_id = format!("{}_filename", id);
+        let filter_id = format!("{}_filter", id);
+        let new_folder_id = format!("{}_new_folder", id);
+        let folder_name_id = format!("{}_folder_name", id);
+        let error_id = format!("{}_error", id);
+
+        let dir = match self.text_for(&dir_id) {
+            Some(dir) => PathBuf::from(dir),
+            None => std::env::current_dir().unwrap_or_default(),
+        };
+        let filename = self.text_for(&filename_id).unwrap_or_default();
+        let filter_name = self.text_for(&filter_id);
+        let filter = filters.iter().find(|f| Some(f.name) == filter_name.as_deref()).or_else(|| filters.first());
+
+        let mut error = self.text_for(&error_id);
+        let entries = match read_entries(&dir, filter) {
+            Ok(entries) => entries,
+            Err(e) => {
+                error = Some(format!("Unable to read {}: {}", dir.display(), e));
+                Vec::new()
+            }
+        };
+
+        let mut ancestors: Vec<&Path> = dir.ancestors().filter(|path| !path.as_os_str().is_empty()).collect();
+        ancestors.reverse();
+        let segments: Vec<String> = ancestors.iter().map(|path| match path.file_name() {
+            None => path.to_string_lossy().to_string(),
+            Some(name) => name.to_string_lossy().to_string(),
+        }).collect();
+        let segments: Vec<&str> = segments.iter().map(|s| s.as_str()).collect();
+
+        let mut navigate = None;
+        let mut select = None;
+        let mut confirm = false;
+        let mut cancel = false;
+        let mut toggle_new_folder = false;
+        let mut create_folder = false;
+        let mut new_filter = None;
+
+        self.start(theme).window(id).children(|ui| {
+            if let Some(index) = ui.breadcrumbs("path", &segments) {
+                navigate = Some(ancestors[index].to_path_buf());
+            }
+
+            ui.start("entries").scrollpane(&format!("{}_entries", id)).children(|ui| {
+                for (name, is_dir) in entries.iter() {
+                    if *is_dir {
+                        if ui.button("directory", name).clicked {
+                            navigate = Some(dir.join(name));
+                        }
+                    } else {
+                        let entry_id = format!("{}_entry_{}", id, name);
+                        if ui.start("file")
+                            .id(&entry_id)
+                            .text(name)
+                            .active(mode != FileDialogMode::Directory && *name == filename)
+                            .wants_mouse(true)
+                            .finish()
+                            .clicked {
+                            select = Some(name.clone());
+                            confirm = ui.check_double_click(&entry_id);
+                        }
+                    }
+                }
+            });
+
+            if mode != FileDialogMode::Directory {
+                ui.input_field("filename", &filename_id, None);
+            }
+
+            if let Some(filter) = filter {
+                if let Some(chosen) = ui.combo_box("filter", &filter_id, filter, filters.iter()) {
+                    new_filter = Some(chosen.name.to_string());
+                }
+            }
+
+            if ui.is_open(&new_folder_id) {
+                ui.start("new_folder_row").children(|ui| {
+                    ui.input_field("folder_name", &folder_name_id, None);
+                    create_folder = ui.child("create").clicked;
+                });
+            }
+
+            toggle_new_folder = ui.child("new_folder").clicked;
+            confirm |= ui.child("confirm").clicked;
+            cancel = ui.child("cancel").clicked;
+
+            if let Some(error) = error.as_ref() {
+                ui.label("error", error);
+            }
+        });
+
+        if let Some(name) = new_filter {
+            self.modify(&filter_id, |state| state.text = Some(name));
+        }
+
+        if let Some(name) = select {
+            self.modify(&filename_id, |state| state.text = Some(name));
+        }
+
+        if toggle_new_folder {
+            let open = !self.is_open(&new_folder_id);
+            self.modify(&new_folder_id, |state| state.is_open = open);
+        }
+
+        if create_folder {
+            let name = self.text_for(&folder_name_id).unwrap_or_default();
+            match std::fs::create_dir(dir.join(&name)) {
+                Ok(()) => {
+                    self.modify(&new_folder_id, |state| state.is_open = false);
+                    self.modify(&folder_name_id, |state| state.text = Some(String::new()));
+                    self.modify(&error_id, |state| state.text = None);
+                },
+                Err(e) => {
+                    let message = format!("Unable to create folder {}: {}", name, e);
+                    self.modify(&error_id, |state| state.text = Some(message));
+                }
+            }
+        }
+
+        if let Some(path) = navigate {
+            let path = path.to_string_lossy().to_string();
+            self.modify(&dir_id, |state| state.text = Some(path));
+            self.modify(&error_id, |state| state.text = None);
+            return None;
+        }
+
+        if cancel {
+            self.close(id);
+            return None;
+        }
+
+        if !confirm { return None; }
+
+        let filename = self.text_for(&filename_id).unwrap_or_default();
+        let path = match mode {
+            FileDialogMode::Directory => dir,
+            FileDialogMode::Open => {
+                let path = dir.join(&filename);
+                if filename.is_empty() || !path.is_file() {
+                    let message = format!("File {} does not exist", filename);
+                    self.modify(&error_id, |state| state.text = Some(message));
+                    return None;
+                }
+                path
+            },
+            FileDialogMode::Save => {
+                if filename.is_empty() {
+                    self.modify(&error_id, |state| state.text = Some("Enter a file name".to_string()));
+                    return None;
+                }
+
+                let mut path = dir.join(&filename);
+                if path.extension().is_none() {
+                    if let Some(ext) = filter.and_then(|f| f.extensions.first()) {
+                        path.set_extension(ext);
+                    }
+                }
+                path
+            }
+        };
+
+        self.close(id);
+        Some(path)
+    }
+}
+
+#[cfg(feature="native_dialog")]
+impl Frame {
+    /**
+    Opens the platform's native file dialog, using [`rfd`](https://docs.rs/rfd/), in the specified `mode` and
+    showing only files matching the specified `filters`.  Requires the `native_dialog` feature.  The dialog is
+    created immediately, which on some platforms must happen on the main thread, but is then run asynchronously so
+    that your app continues to update and draw while it is open.  Each frame, you should check for the user's
+    choice with [`file_dialog_result`](#method.file_dialog_result), using the same `id`.  If a dialog with the same
+    `id` is already open, this does nothing.
+
+    # Example
+    ```
+    fn import(ui: &mut Frame) {
+        if ui.button("button", "Import...").clicked {
+            let filters = [FileFilter { name: "Images", extensions: &["png", "jpg"] }];
+            ui.open_file_dialog("import", FileDialogMode::Open, &filters);
+        }
+
+        if let Some(path) = ui.file_dialog_result("import") {
+            println!("Importing {:?}", path);
+        }
+    }
+    ```
+    **/
+    pub fn open_file_dialog(&mut self, id: &str, mode: FileDialogMode, filters: &[FileFilter]) {
+        if self.is_file_dialog_open(id) { return; }
+
+        let mut dialog = rfd::AsyncFileDialog::new();
+        for filter in filters {
+            if !filter.extensions.is_empty() {
+                dialog = dialog.add_filter(filter.name, filter.extensions);
+            }
+        }
+
+        type DialogFuture = std::pin::Pin<Box<dyn std::future::Future<Output = Option<rfd::FileHandle>> + Send>>;
+        let future: DialogFuture = match mode {
+            FileDialogMode::Open => Box::pin(dialog.pick_file()),
+            FileDialogMode::Save => Box::pin(dialog.save_file()),
+            FileDialogMode::Directory => Box::pin(dialog.pick_folder()),
+        };
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let path = futures::executor::block_on(future).map(|handle| handle.path().to_path_buf());
+            // the receiver may have been dropped if the context was dropped
+            let _ = tx.send(path);
+        });
+
+        self.context_internal().borrow_mut().add_native_dialog(id.to_string(), rx);
+    }
+
+    /// Returns whether the native file dialog with the specified `id`, opened with
+    /// [`open_file_dialog`](#method.open_file_dialog), is still open.
+    pub fn is_file_dialog_open(&self, id: &str) -> bool {
+        self.context_internal().borrow().is_native_dialog_open(id)
+    }
+
+    /// Checks whether the native file dialog with the specified `id`, opened with
+    /// [`open_file_dialog`](#method.open_file_dialog), has finished.  Returns the selected path
+    /// once, on the first call after the user makes a choice.  Returns `None` while the dialog is still
+    /// open, if the user cancelled the dialog, or if no dialog with this `id` was opened.
+    pub fn file_dialog_result(&mut self, id: &str) -> Option<PathBuf> {
+        self.context_internal().borrow_mut().poll_native_dialog(id).flatten()
+    }
+}
+
+/// Reads the visible entries in `dir`, as names and whether each is a directory, with
+/// directories listed first.
+fn read_entries(dir: &Path, filter: Option<&FileFilter>) -> std::io::Result<Vec<(String, bool)>> {
+    let mut entries = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') { continue; }
+
+        let path = entry.path();
+        let is_dir = path.is_dir();
+        if let Some(filter) = filter {
+            if !is_dir && !filter.matches(&path) { continue; }
+        }
+
+        entries.push((name, is_dir));
+    }
+
+    entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.to_lowercase().cmp(&b.0.to_lowercase())));
+    Ok(entries)
+}
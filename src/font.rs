@@ -2,17 +2,44 @@ use rustc_hash::FxHashMap;
 
 use crate::theme_definition::CharacterRange;
 use crate::render::{TexCoord, DrawList, FontHandle, DummyDrawList};
-use crate::{Point, Rect, Align, Color};
+use crate::{Point, Rect, Align, Color, TextWrap};
+
+const SOFT_HYPHEN: char = '\u{ad}';
+
+// inline images are laid out as characters in the supplementary private use area, encoding the
+// width and height of the image in whole logical pixels, from 1 up to INLINE_IMAGE_MAX_SIZE
+const INLINE_IMAGE_FIRST: u32 = 0xF0000;
+const INLINE_IMAGE_MAX_SIZE: u32 = 255;
+
+/// The character standing in for an inline image of the specified logical `size` in laid out text
+pub(crate) fn inline_image_char(size: Point) -> char {
+    let dim = |value: f32| (value.round() as u32).clamp(1, INLINE_IMAGE_MAX_SIZE) - 1;
+    let code = INLINE_IMAGE_FIRST + dim(size.x) * INLINE_IMAGE_MAX_SIZE + dim(size.y);
+    char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER)
+}
+
+fn inline_image_size(c: char) -> Option<Point> {
+    let code = (c as u32).checked_sub(INLINE_IMAGE_FIRST)?;
+    if code >= INLINE_IMAGE_MAX_SIZE * INLINE_IMAGE_MAX_SIZE { return None; }
+
+    let x = code / INLINE_IMAGE_MAX_SIZE + 1;
+    let y = code % INLINE_IMAGE_MAX_SIZE + 1;
+    Some(Point::new(x as f32, y as f32))
+}
 
 pub struct FontSource {
     pub(crate) font: rusttype::Font<'static>,
 }
 
+#[derive(Copy, Clone)]
 pub struct FontChar {
     pub size: Point,
     pub(crate) tex_coords: [TexCoord; 2],
     pub x_advance: f32,
     pub y_offset: f32,
+
+    // for an inline image, the index of the image within the text
+    pub(crate) inline_image: Option<usize>,
 }
 
 impl Default for FontChar {
@@ -22,6 +49,7 @@ impl Default for FontChar {
             tex_coords: [TexCoord::new(0.0, 0.0), TexCoord::new(0.0, 0.0)],
             x_advance: 0.0,
             y_offset: 0.0,
+            inline_image: None,
         }
     }
 }
@@ -32,23 +60,40 @@ pub struct FontSummary {
     pub line_height: f32,
 }
 
+#[derive(Clone)]
 pub struct Font {
     handle: FontHandle,
     characters: FxHashMap<char, FontChar>,
     line_height: f32,
     ascent: f32,
+    texture_size: [u32; 2],
 }
 
 impl Font {
-    pub(crate) fn new(handle: FontHandle, characters: FxHashMap<char, FontChar>, line_height: f32, ascent: f32) -> Font {
+    pub(crate) fn new(
+        handle: FontHandle,
+        characters: FxHashMap<char, FontChar>,
+        line_height: f32,
+        ascent: f32,
+        texture_size: [u32; 2],
+    ) -> Font {
         Font {
             handle,
             characters,
             line_height,
             ascent,
+            texture_size,
         }
     }
 
+    /// The width and height of the texture holding this font's characters, in pixels
+    pub(crate) fn texture_size(&self) -> [u32; 2] { self.texture_size }
+
+    /// An approximation of the memory used by this font's character data, in bytes
+    pub(crate) fn heap_bytes(&self) -> usize {
+        self.characters.capacity() * std::mem::size_of::<(char, FontChar)>()
+    }
+
     fn char(&self, c: char) -> Option<&FontChar> {
         self.characters.get(&c)
     }
@@ -59,12 +104,12 @@ impl Font {
 
     pub fn handle(&self) -> FontHandle { self.handle }
 
+    /// Lays out `text`, returning the final cursor position and the height of all lines of the text
     pub(crate) fn layout(
         &self,
         params: FontDrawParams,
         text: &str,
-        cursor: &mut Point,
-    ) {
+    ) -> TextLayout {
         let mut draw_list = DummyDrawList::new();
         let mut renderer = FontRenderer::new(
             self,
@@ -73,6 +118,7 @@ impl Font {
             Rect::default(),
         );
         renderer.render(text);
+        let height = renderer.size.y;
 
         if text.is_empty() {
             // compute the cursor position for empty text
@@ -81,16 +127,72 @@ impl Font {
             renderer.adjust_all_y();
         }
 
-        *cursor = renderer.pos;
+        TextLayout { cursor: renderer.pos, height }
+    }
+
+    /// Lays out `text` and returns the caret rect at each character index, followed by a zero width rect at the
+    /// end of the text.  Each rect spans the character's advance and the line height.
+    pub(crate) fn carets(&self, params: FontDrawParams, text: &str) -> Vec<Rect> {
+        let mut draw_list = DummyDrawList::new();
+        let mut renderer = FontRenderer::new(
+            self,
+            &mut draw_list,
+            params,
+            Rect::default(),
+        );
+        renderer.carets = Some(Vec::new());
+        renderer.render(text);
+
+        if text.is_empty() {
+            renderer.adjust_line_x();
+            renderer.size.y += 2.0 * renderer.font.line_height;
+            renderer.adjust_all_y();
+        }
+
+        let line_height = self.line_height;
+        let end = Rect::new(renderer.pos, Point::new(0.0, line_height));
+        let mut carets = vec![None; text.chars().count()];
+        for (index, pos, width) in renderer.carets.take().unwrap_or_default() {
+            // a newline is also laid out as an empty character at the start of the next line, so keep the first caret
+            if carets[index].is_none() {
+                carets[index] = Some(Rect::new(pos, Point::new(width, line_height)));
+            }
+        }
+
+        // characters which are not laid out, such as soft hyphens, share the caret of the following character
+        let mut next = end;
+        let mut out: Vec<Rect> = carets.into_iter().rev().map(|caret| {
+            next = caret.unwrap_or(Rect::new(next.pos, Point::new(0.0, line_height)));
+            next
+        }).collect();
+        out.reverse();
+        out.push(end);
+        out
+    }
+
+    /// Lays out `text` and splits it into pages, each consisting of the lines which fit within the height of
+    /// the text area.  Returns the index, in characters, of the first character drawn on each page.
+    pub(crate) fn pages(&self, params: FontDrawParams, text: &str) -> Vec<usize> {
+        let mut draw_list = DummyDrawList::new();
+        let mut renderer = FontRenderer::new(
+            self,
+            &mut draw_list,
+            params,
+            Rect::default(),
+        );
+        renderer.render(text);
+        renderer.page_starts
     }
 
+    /// Draws `text` into `draw_list`.  Returns the index and the rect, in the same coordinates as the text, of each
+    /// inline image in the text which should be drawn.  Inline images are drawn separately, as they use image textures.
     pub(crate) fn draw<D: DrawList>(
         &self,
         draw_list: &mut D,
         params: FontDrawParams,
         text: &str,
         clip: Rect,
-    ) {
+    ) -> Vec<(usize, Rect)> {
         let mut renderer = FontRenderer::new(
             self,
             draw_list,
@@ -98,6 +200,165 @@ impl Font {
             clip
         );
         renderer.render(text);
+        renderer.images
+    }
+}
+
+/**
+The caret positions within a widget's laid out text, written by
+[`WidgetBuilder::trigger_text_carets`](struct.WidgetBuilder.html#method.trigger_text_carets).  Maps character indices
+to caret rects, for positioning a caret or drawing a selection, and positions to the nearest character index, for
+placing the caret with the mouse.  Indices are in characters, not bytes; index 0 is before the first character and
+[`len`](#method.len) is after the last.  Rects are in logical pixels, relative to the widget's inner area, so they
+may be used directly as the [`pos`](struct.WidgetBuilder.html#method.pos) of a child widget.
+**/
+#[derive(Clone, Debug, Default)]
+pub struct TextCarets {
+    origin: Point,
+    rects: Vec<Rect>,
+}
+
+impl TextCarets {
+    pub(crate) fn new(origin: Point, rects: Vec<Rect>) -> TextCarets {
+        TextCarets { origin, rects }
+    }
+
+    /// Returns the number of characters in the text.  This is the index of the caret at the end of the text.
+    pub fn len(&self) -> usize {
+        self.rects.len().saturating_sub(1)
+    }
+
+    /// Returns whether the text is empty, or no text has been laid out
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the rect for the caret before the character at `index`, spanning the character's width and the line
+    /// height.  The rect at the end of the text has zero width.  Returns `None` if `index` is past the end of the text.
+    pub fn rect(&self, index: usize) -> Option<Rect> {
+        self.rects.get(index).copied()
+    }
+
+    /// Returns the index of the caret nearest to `pos`, relative to the widget's inner area.  The nearest line is found
+    /// first, and then the nearest caret on that line.  Returns `None` if no text has been laid out.
+    pub fn index_at(&self, pos: Point) -> Option<usize> {
+        let line_dist = |rect: &Rect| (pos.y - (rect.pos.y + rect.size.y / 2.0)).abs();
+        let line = self.rects.iter().min_by(|a, b| line_dist(a).total_cmp(&line_dist(b)))?.pos.y;
+
+        self.rects.iter().enumerate()
+            .filter(|(_, rect)| rect.pos.y == line)
+            .min_by(|(_, a), (_, b)| (pos.x - a.pos.x).abs().total_cmp(&(pos.x - b.pos.x).abs()))
+            .map(|(index, _)| index)
+    }
+
+    /// Returns the index of the caret nearest to the screen position `pos`, such as the
+    /// [`mouse position`](struct.Frame.html#method.mouse_pos).  See [`index_at`](#method.index_at).
+    pub fn index_at_screen(&self, pos: Point) -> Option<usize> {
+        self.index_at(pos - self.origin)
+    }
+
+    /// Returns whether the screen position `pos` is over one of the characters of the text
+    pub(crate) fn is_over_text(&self, pos: Point) -> bool {
+        let pos = pos - self.origin;
+        self.rects.iter().take(self.len()).any(|rect| rect.is_inside(pos))
+    }
+}
+
+/// The result of laying out a piece of text
+#[derive(Copy, Clone, Default)]
+pub(crate) struct TextLayout {
+    /// The position of the text cursor after the last character
+    pub cursor: Point,
+
+    /// The total height of all lines of the text
+    pub height: f32,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+struct TextLayoutKey {
+    font: FontHandle,
+    area_size: [u32; 2],
+    pos: [u32; 2],
+    indent: u32,
+    align: Align,
+    wrap: TextWrap,
+    soft_hyphens: bool,
+    scale_factor: u32,
+}
+
+impl TextLayoutKey {
+    fn new(font: FontHandle, params: &FontDrawParams) -> TextLayoutKey {
+        TextLayoutKey {
+            font,
+            area_size: [params.area_size.x.to_bits(), params.area_size.y.to_bits()],
+            pos: [params.pos.x.to_bits(), params.pos.y.to_bits()],
+            indent: params.indent.to_bits(),
+            align: params.align,
+            wrap: params.wrap,
+            soft_hyphens: params.soft_hyphens,
+            scale_factor: params.scale_factor.to_bits(),
+        }
+    }
+}
+
+/// Caches the result of text layouts across frames, so that unchanging text does not
+/// need to be measured again each frame.  Entries are keyed on the text along with all
+/// layout parameters, so a change to any of them results in a new layout.  Entries not
+/// used for a full frame are discarded.
+#[derive(Default)]
+pub(crate) struct TextLayoutCache {
+    current: FxHashMap<String, Vec<(TextLayoutKey, TextLayout)>>,
+    last: FxHashMap<String, Vec<(TextLayoutKey, TextLayout)>>,
+}
+
+impl TextLayoutCache {
+    pub(crate) fn next_frame(&mut self) {
+        self.last = std::mem::take(&mut self.current);
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.current.clear();
+        self.last.clear();
+    }
+
+    /// An approximation of the memory used by the cached layouts, in bytes
+    pub(crate) fn heap_bytes(&self) -> usize {
+        self.current.iter().chain(self.last.iter()).map(|(text, entries)| {
+            std::mem::size_of::<(String, Vec<(TextLayoutKey, TextLayout)>)>() + text.capacity() +
+                entries.capacity() * std::mem::size_of::<(TextLayoutKey, TextLayout)>()
+        }).sum()
+    }
+
+    /// Returns the final cursor position and text height from laying out `text` with the specified
+    /// `font` and `params`, computing the layout only if it is not already cached.
+    pub(crate) fn layout(&mut self, font: &Font, params: FontDrawParams, text: &str) -> TextLayout {
+        let key = TextLayoutKey::new(font.handle, &params);
+
+        if let Some(entries) = self.current.get(text) {
+            if let Some((_, layout)) = entries.iter().find(|(k, _)| *k == key) {
+                return *layout;
+            }
+        }
+
+        // move any entries for this text used last frame into the current frame
+        if let Some((text, entries)) = self.last.remove_entry(text) {
+            self.current.insert(text, entries);
+        }
+
+        if let Some(entries) = self.current.get(text) {
+            if let Some((_, layout)) = entries.iter().find(|(k, _)| *k == key) {
+                return *layout;
+            }
+        }
+
+        let layout = font.layout(params, text);
+
+        match self.current.get_mut(text) {
+            Some(entries) => entries.push((key, layout)),
+            None => { self.current.insert(text.to_string(), vec![(key, layout)]); }
+        }
+
+        layout
     }
 }
 
@@ -109,6 +370,8 @@ struct FontRenderer<'a,  D> {
     scale_factor: f32,
     clip: Rect,
     align: Align,
+    wrap: TextWrap,
+    soft_hyphens: bool,
     color: Color,
 
     area_size: Point,
@@ -117,11 +380,31 @@ struct FontRenderer<'a,  D> {
     pos: Point,
     size: Point,
     cur_line_index: usize,
+    cur_line_empty: bool,
+
+    // characters at or after this index are laid out but not drawn
+    visible_chars: usize,
+    page_starts: Vec<usize>,
+    page_top: f32,
 
-    cur_word: Vec<&'a FontChar>,
+    // the characters of the current word, along with their index in the text
+    cur_word: Vec<(FontChar, usize)>,
     cur_word_width: f32,
 
+    // positions in the current word where a soft hyphen allows a break, along
+    // with the width of the word up to that position
+    cur_word_breaks: Vec<(usize, f32)>,
+
     is_first_line_with_indent: bool,
+
+    // if recording, the position and width of each character placed, along with its index in the text
+    carets: Option<Vec<(usize, Point, f32)>>,
+    cur_line_caret: usize,
+
+    // the index and position of each inline image placed
+    images: Vec<(usize, Rect)>,
+    image_count: usize,
+    cur_line_image: usize,
 }
 
 impl<'a, D: DrawList> FontRenderer<'a, D> {
@@ -138,6 +421,8 @@ impl<'a, D: DrawList> FontRenderer<'a, D> {
             draw_list,
             initial_index,
             align: params.align,
+            wrap: params.wrap,
+            soft_hyphens: params.soft_hyphens,
             color: params.color,
             scale_factor: params.scale_factor,
             clip,
@@ -146,27 +431,57 @@ impl<'a, D: DrawList> FontRenderer<'a, D> {
             pos: Point::new(params.pos.x + params.indent, params.pos.y),
             size: Point::new(params.indent, 0.0),
             cur_line_index: initial_index,
+            cur_line_empty: true,
+            visible_chars: params.visible_chars.unwrap_or(usize::MAX),
+            page_starts: vec![0],
+            page_top: params.pos.y,
             cur_word: Vec::new(),
             cur_word_width: 0.0,
+            cur_word_breaks: Vec::new(),
             is_first_line_with_indent: params.indent > 0.0,
+            carets: None,
+            cur_line_caret: 0,
+            images: Vec::new(),
+            image_count: 0,
+            cur_line_image: 0,
         }
     }
 
     fn render(&mut self, text: &str) {
-        for c in text.chars() {
-            let font_char = match self.font.char(c) {
-                None => continue, // TODO draw a special character here?
-                Some(char) => char,
+        for (index, c) in text.chars().enumerate() {
+            if c == SOFT_HYPHEN {
+                if self.soft_hyphens && !self.cur_word.is_empty() {
+                    self.cur_word_breaks.push((self.cur_word.len(), self.cur_word_width));
+                }
+                continue;
+            }
+
+            let font_char = match (inline_image_size(c), self.font.char(c)) {
+                (Some(size), _) => {
+                    self.image_count += 1;
+                    FontChar {
+                        size: size * self.scale_factor,
+                        x_advance: size.x * self.scale_factor,
+                        inline_image: Some(self.image_count - 1),
+                        ..FontChar::default()
+                    }
+                },
+                (None, None) => continue, // TODO draw a special character here?
+                (None, Some(char)) => *char,
             };
 
             if c == '\n' {
                 self.draw_cur_word();
+                self.record_caret(index, 0.0);
                 self.next_line();
             } else if c.is_whitespace() {
                 self.draw_cur_word();
 
+                let width = if self.line_is_empty() { 0.0 } else { font_char.x_advance };
+                self.record_caret(index, width);
+
                 // don't draw whitespace at the start of a line
-                if self.cur_line_index != self.draw_list.len() || self.is_first_line_with_indent {
+                if !self.line_is_empty() {
                     self.pos.x += font_char.x_advance;
                     self.size.x += font_char.x_advance;
                 }
@@ -175,23 +490,16 @@ impl<'a, D: DrawList> FontRenderer<'a, D> {
             }
 
             self.cur_word_width += font_char.x_advance;
-            self.cur_word.push(font_char);
+            self.cur_word.push((font_char, index));
 
             if self.size.x + self.cur_word_width > self.area_size.x {
-                //if the word was so long that we drew nothing at all
-                if self.cur_line_index == self.draw_list.len() && !self.is_first_line_with_indent {
-                    self.draw_cur_word();
-                    self.next_line();
-                } else {
-                    self.next_line();
-                    self.draw_cur_word();
-                }
+                self.wrap_cur_word();
             }
         }
 
         self.draw_cur_word();
 
-        if self.cur_line_index < self.draw_list.len() {    
+        if !self.cur_line_empty {
             // adjust characters on the last line
             self.adjust_line_x();
             self.size.y += self.font.line_height;
@@ -200,8 +508,113 @@ impl<'a, D: DrawList> FontRenderer<'a, D> {
         self.adjust_all_y();
     }
 
+    fn line_is_empty(&self) -> bool {
+        self.cur_line_empty && !self.is_first_line_with_indent
+    }
+
+    // called when the current word has just overflowed the available width
+    fn wrap_cur_word(&mut self) {
+        match self.wrap {
+            TextWrap::None => (),
+            TextWrap::Character => self.break_cur_word(),
+            TextWrap::Word => {
+                if self.break_at_soft_hyphen() { return; }
+
+                // if the word is too long for a line by itself, let it overflow
+                if !self.line_is_empty() {
+                    self.next_line();
+                }
+            },
+            TextWrap::WordThenCharacter => {
+                if self.break_at_soft_hyphen() { return; }
+
+                if self.line_is_empty() {
+                    self.break_cur_word();
+                } else {
+                    self.next_line();
+                    if self.cur_word_width > self.area_size.x {
+                        self.break_cur_word();
+                    }
+                }
+            }
+        }
+    }
+
+    // draws all but the last character of the current word and moves to the next line
+    fn break_cur_word(&mut self) {
+        let count = self.cur_word.len() - 1;
+        if count == 0 && self.line_is_empty() {
+            // a single character that is too wide to fit at all
+            self.draw_cur_word();
+        } else {
+            self.draw_chars(count);
+        }
+        self.next_line();
+    }
+
+    // breaks the current word at the last soft hyphen that leaves room for a hyphen
+    // character at the end of the line.  returns false if there is no such soft hyphen
+    fn break_at_soft_hyphen(&mut self) -> bool {
+        let font = self.font;
+        let hyphen = match font.char('-') {
+            None => return false,
+            Some(hyphen) => *hyphen,
+        };
+
+        let available = self.area_size.x - self.size.x - hyphen.x_advance;
+        let index = match self.cur_word_breaks.iter().rev().find(|(_, width)| *width <= available) {
+            None => return false,
+            Some((index, _)) => *index,
+        };
+
+        // the hyphen is shown along with the character before it
+        let hyphen_index = self.cur_word[index - 1].1;
+        self.draw_chars(index);
+        self.draw_char(&hyphen, hyphen_index);
+        self.next_line();
+        true
+    }
+
     fn draw_cur_word(&mut self) {
-        for font_char in self.cur_word.drain(..) {
+        self.draw_chars(self.cur_word.len());
+        self.cur_word_width = 0.0;
+        self.cur_word_breaks.clear();
+    }
+
+    // draws the first count characters of the current word
+    fn draw_chars(&mut self, count: usize) {
+        let mut width = 0.0;
+        for i in 0..count {
+            let (font_char, index) = self.cur_word[i];
+            self.record_caret(index, font_char.x_advance);
+            self.draw_char(&font_char, index);
+            width += font_char.x_advance;
+        }
+        self.cur_word.drain(..count);
+        self.cur_word_width -= width;
+
+        self.cur_word_breaks.retain(|(index, _)| *index > count);
+        for (index, break_width) in self.cur_word_breaks.iter_mut() {
+            *index -= count;
+            *break_width -= width;
+        }
+    }
+
+    fn draw_char(&mut self, font_char: &FontChar, index: usize) {
+        // start a new page at the first character of a line that does not fit on the current page
+        if self.cur_line_empty && self.pos.y > self.page_top &&
+            self.pos.y + self.font.line_height - self.page_top > self.area_size.y {
+            self.page_starts.push(index);
+            self.page_top = self.pos.y;
+        }
+        self.cur_line_empty = false;
+
+        if let (Some(image), true) = (font_char.inline_image, index < self.visible_chars) {
+            let x = (self.pos.x * self.scale_factor).round() / self.scale_factor;
+            // the bottom of the image is placed on the baseline
+            let y = (self.pos.y + self.font.ascent - font_char.size.y).round();
+            self.images.push((image, Rect::new(Point::new(x, y), font_char.size)));
+        } else if index < self.visible_chars {
             let x = (self.pos.x * self.scale_factor).round() / self.scale_factor;
             let y = (self.pos.y + font_char.y_offset + self.font.ascent).round();
 
@@ -212,10 +625,15 @@ impl<'a, D: DrawList> FontRenderer<'a, D> {
                 self.color,
                 self.clip,
             );
-            self.pos.x += font_char.x_advance;
-            self.size.x += font_char.x_advance;
         }
-        self.cur_word_width = 0.0;
+        self.pos.x += font_char.x_advance;
+        self.size.x += font_char.x_advance;
+    }
+
+    fn record_caret(&mut self, index: usize, width: f32) {
+        if let Some(carets) = self.carets.as_mut() {
+            carets.push((index, self.pos, width));
+        }
     }
 
     fn next_line(&mut self) {
@@ -226,6 +644,9 @@ impl<'a, D: DrawList> FontRenderer<'a, D> {
         self.adjust_line_x();
         self.pos.x = self.initial_pos.x;
         self.cur_line_index = self.draw_list.len();
+        self.cur_line_caret = self.carets.as_ref().map_or(0, |carets| carets.len());
+        self.cur_line_image = self.images.len();
+        self.cur_line_empty = true;
         self.size.x = 0.0;
     }
 
@@ -244,6 +665,10 @@ impl<'a, D: DrawList> FontRenderer<'a, D> {
         };
 
         self.pos.y += y_offset;
+        if let Some(carets) = self.carets.as_mut() {
+            carets.iter_mut().for_each(|(_, pos, _)| pos.y += y_offset);
+        }
+        self.images.iter_mut().for_each(|(_, rect)| rect.pos.y += y_offset);
         self.draw_list.back_adjust_positions(
             self.initial_index,
             Point { x: 0.0, y: y_offset }
@@ -265,8 +690,12 @@ impl<'a, D: DrawList> FontRenderer<'a, D> {
         };
     
         self.pos.x += x_offset;
+        if let Some(carets) = self.carets.as_mut() {
+            carets[self.cur_line_caret..].iter_mut().for_each(|(_, pos, _)| pos.x += x_offset);
+        }
 
         let x = (x_offset * self.scale_factor).round() / self.scale_factor;
+        self.images[self.cur_line_image..].iter_mut().for_each(|(_, rect)| rect.pos.x += x);
 
         self.draw_list.back_adjust_positions(
             self.cur_line_index,
@@ -354,6 +783,7 @@ impl<'a> FontTextureWriter<'a> {
             self.characters,
             v_metrics.ascent - v_metrics.descent + v_metrics.line_gap,
             v_metrics.ascent,
+            [self.tex_width, self.tex_height],
         );
 
         Ok(FontTextureOut {
@@ -413,15 +843,22 @@ impl<'a> FontTextureWriter<'a> {
             tex_coords,
             x_advance: glyph.unpositioned().h_metrics().advance_width,
             y_offset,
+            inline_image: None,
         }
     }
 }
 
+#[derive(Copy, Clone)]
 pub struct FontDrawParams {
     pub area_size: Point,
     pub pos: Point,
     pub indent: f32,
     pub align: Align,
+    pub wrap: TextWrap,
+    pub soft_hyphens: bool,
     pub color: Color,
     pub scale_factor: f32,
+
+    /// If specified, only this many characters of the text are drawn, although the full text is laid out
+    pub visible_chars: Option<usize>,
 }
\ No newline at end of file
@@ -0,0 +1,138 @@
+use crate::{Frame, Rect};
+
+/**
+A helper for laying out the rows of a [`form`](struct.Frame.html#method.form).  Each call to
+[`field`](#method.field) adds a row consisting of a label and a field.
+**/
+pub struct Form<'a> {
+    frame: &'a mut Frame,
+    label_width: f32,
+    max_label_width: f32,
+}
+
+impl<'a> Form<'a> {
+    /**
+    Adds a row to the form, consisting of a `label` widget showing the `label` text, followed by a `field` widget
+    whose content is created by the `f` closure.  All labels in the form share the same width.  If `required` is
+    set, a `required` marker widget is shown after the label text.  If an `error` is specified, it is shown in an
+    `error` widget below the field's content.  Returns the result of `f`, or `None` if `f` was not called because the
+    row or field is not shown, such as when it is hidden by the theme.
+    **/
+    pub fn field<R, F: FnOnce(&mut Frame) -> R>(&mut self, label: &str, required: bool, error: Option<&str>, f: F) -> Option<R> {
+        let label_width = self.label_width;
+        let mut max_label_width = self.max_label_width;
+
+        let mut result = None;
+        self.frame.start("row").children(|ui| {
+            let (mut outer, mut inner) = (Rect::default(), Rect::default());
+            ui.start("label")
+            .width(label_width)
+            .trigger_layout(&mut outer)
+            .trigger_layout_inner(&mut inner)
+            .children(|ui| {
+                let mut rect = Rect::default();
+                ui.start("text").text(label).trigger_layout(&mut rect).finish();
+
+                if required {
+                    ui.start("required").trigger_layout(&mut rect).finish();
+                }
+
+                let border_width = outer.size.x - inner.size.x;
+                max_label_width = max_label_width.max(rect.pos.x + rect.size.x - inner.pos.x + border_width);
+            });
+
+            ui.start("field").width(-label_width).children(|ui| {
+                result = Some(f(ui));
+
+                if let Some(error) = error {
+                    ui.label("error", error);
+                }
+            });
+        });
+
+        self.max_label_width = max_label_width;
+        result
+    }
+
+    /// Returns the underlying [`Frame`](struct.Frame.html), for adding widgets between rows.
+    pub fn frame(&mut self) -> &mut Frame {
+        self.frame
+    }
+}
+
+impl Frame {
+    /**
+    A form, consisting of rows of labels and fields added via [`Form.field`](struct.Form.html#method.field) in
+    the `f` closure.  The label column is sized to fit the widest label, including its `required` marker and
+    `border`, but no narrower than `min_label_width`, a theme `custom` float defaulting to zero.  The label widths
+    are measured as the form is built, and so take effect on the following frame.  The width of each `field`
+    is reduced by the width of the label column, so fields should normally use a `Parent` width.  The `id` must
+    be unique.
+
+    An example theme definition:
+    ```yaml
+    form:
+      layout: Vertical
+      layout_spacing: [0, 5]
+      size_from: [Parent, Children]
+      custom:
+        min_label_width: 80
+      children:
+        row:
+          layout: Horizontal
+          size_from: [Parent, Children]
+          children:
+            label:
+              layout: Horizontal
+              height: 24
+              border: { right: 8 }
+              children:
+                text:
+                  from: label
+                  size_from: [Text, Parent]
+                required:
+                  from: label
+                  text: "*"
+                  text_color: "#F44"
+                  size_from: [Text, Parent]
+            field:
+              layout: Vertical
+              size_from: [Parent, Children]
+              children:
+                error:
+                  from: label
+                  font: small
+                  text_color: "#F44"
+                  size_from: [Parent, FontLine]
+    ```
+
+    # Example
+    ```
+    fn account(ui: &mut Frame, name_error: Option<&str>) {
+        ui.form("form", "account_form", |form| {
+            form.field("Name", true, name_error, |ui| {
+                ui.input_field("input_field", "name_input", None);
+            });
+            form.field("Email", false, None, |ui| {
+                ui.input_field("input_field", "email_input", None);
+            });
+        });
+    }
+    ```
+    **/
+    pub fn form<F: FnOnce(&mut Form)>(&mut self, theme: &str, id: &str, f: F) {
+        let measured_width = self.modify(id, |state| state.content_size.x);
+
+        let builder = self.start(theme);
+        let label_width = builder.custom_float("min_label_width", 0.0).max(measured_width);
+
+        let mut max_label_width = 0.0;
+        builder.id(id).children(|ui| {
+            let mut form = Form { frame: ui, label_width, max_label_width: 0.0 };
+            f(&mut form);
+            max_label_width = form.max_label_width;
+        });
+
+        self.modify(id, |state| state.content_size.x = max_label_width);
+    }
+}
@@ -0,0 +1,274 @@
+//! Helpers for consistently formatting numbers, percentages, durations, and dates for display.
+//!
+//! Formatting follows a global [`Locale`](struct.Locale.html), which should be set once at startup
+//! with [`set_locale`](fn.set_locale.html), for example based on the user's OS locale with
+//! [`Locale::for_name`](struct.Locale.html#method.for_name).  Each of the free functions in this module,
+//! such as [`int`](fn.int.html) or [`percent`](fn.percent.html), uses the global locale.
+//! For use in labels, a [`Value`](enum.Value.html) may be passed to
+//! [`label_fmt`](../struct.Frame.html#method.label_fmt) or used with the `format!` macro.
+//!
+//! # Example
+//! ```
+//! thyme::format::set_locale(thyme::format::Locale::for_name("de_DE"));
+//!
+//! assert_eq!(thyme::format::int(1234567), "1.234.567");
+//! assert_eq!(thyme::format::decimal(1234.5, 2), "1.234,50");
+//! assert_eq!(thyme::format::percent(0.256, 1), "25,6%");
+//! assert_eq!(thyme::format::duration(std::time::Duration::from_secs(3723)), "1:02:03");
+//! assert_eq!(thyme::format::date(2023, 7, 17), "17.07.2023");
+//! ```
+
+use std::fmt;
+use std::time::Duration;
+
+use parking_lot::{const_mutex, Mutex};
+
+static LOCALE: Mutex<Locale> = const_mutex(Locale::new());
+
+/// Sets the global [`Locale`](struct.Locale.html) used by the formatting functions in this module.
+pub fn set_locale(locale: Locale) {
+    *LOCALE.lock() = locale;
+}
+
+/// Returns the current global [`Locale`](struct.Locale.html).
+pub fn locale() -> Locale {
+    *LOCALE.lock()
+}
+
+/// Formats the integer `value` with thousands separators.  See [`Locale::int`](struct.Locale.html#method.int).
+pub fn int(value: i64) -> String {
+    locale().int(value)
+}
+
+/// Formats `value` with exactly `places` decimal places.  See [`Locale::decimal`](struct.Locale.html#method.decimal).
+pub fn decimal(value: f64, places: usize) -> String {
+    locale().decimal(value, places)
+}
+
+/// Formats `fraction` as a percentage.  See [`Locale::percent`](struct.Locale.html#method.percent).
+pub fn percent(fraction: f64, places: usize) -> String {
+    locale().percent(fraction, places)
+}
+
+/// Formats the specified date.  See [`Locale::date`](struct.Locale.html#method.date).
+pub fn date(year: i32, month: u32, day: u32) -> String {
+    locale().date(year, month, day)
+}
+
+/// Formats `duration` as `h:mm:ss`, or as `m:ss` if it is less than one hour.  Fractional
+/// seconds are truncated.  Durations are formatted the same way in all locales.
+pub fn duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (hours, minutes, seconds) = (secs / 3600, (secs / 60) % 60, secs % 60);
+
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+/// The order of the year, month, and day components when formatting a date.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DateOrder {
+    /// Year, then month, then day, such as `2023-07-17`
+    YearMonthDay,
+
+    /// Month, then day, then year, such as `07/17/2023`
+    MonthDayYear,
+
+    /// Day, then month, then year, such as `17.07.2023`
+    DayMonthYear,
+}
+
+/// The set of conventions used to format numbers and dates.  The default is US English.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Locale {
+    /// The character used to group every three digits in the integer part of a number, if any
+    pub thousands_separator: Option<char>,
+
+    /// The character separating the integer and fractional parts of a number
+    pub decimal_separator: char,
+
+    /// The ordering of components in a date
+    pub date_order: DateOrder,
+
+    /// The character placed between the components of a date
+    pub date_separator: char,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Locale {
+    /// Creates the default, US English, locale.
+    pub const fn new() -> Locale {
+        Locale {
+            thousands_separator: Some(','),
+            decimal_separator: '.',
+            date_order: DateOrder::MonthDayYear,
+            date_separator: '/',
+        }
+    }
+
+    /// Returns the locale matching the specified `name`, such as `de_DE.UTF-8` or `fr-CA`.  Both the
+    /// language and, if present, region are considered.  Unknown names result in the
+    /// [`default`](#method.new) locale.
+    pub fn for_name(name: &str) -> Locale {
+        let mut parts = name.split(['.', '@']).next().unwrap_or_default().split(['_', '-']);
+        let language = parts.next().unwrap_or_default().to_ascii_lowercase();
+        let region = parts.next().unwrap_or_default().to_ascii_uppercase();
+
+        let (thousands_separator, decimal_separator, date_order, date_separator) = match language.as_str() {
+            "en" => match region.as_str() {
+                "US" | "" => return Locale::new(),
+                _ => (Some(','), '.', DateOrder::DayMonthYear, '/'),
+            },
+            "de" if region == "CH" => (Some('\''), '.', DateOrder::DayMonthYear, '.'),
+            "cs" | "da" | "de" | "fi" | "nb" | "nn" | "no" | "pl" | "ru" | "tr" | "uk" => {
+                (Some('.'), ',', DateOrder::DayMonthYear, '.')
+            },
+            "fr" => (Some(' '), ',', DateOrder::DayMonthYear, '/'),
+            "es" | "it" | "pt" | "el" => (Some('.'), ',', DateOrder::DayMonthYear, '/'),
+            "nl" => (Some('.'), ',', DateOrder::DayMonthYear, '-'),
+            "sv" => (Some(' '), ',', DateOrder::YearMonthDay, '-'),
+            "ja" | "ko" | "zh" => (Some(','), '.', DateOrder::YearMonthDay, '/'),
+            _ => return Locale::new(),
+        };
+
+        Locale { thousands_separator, decimal_separator, date_order, date_separator }
+    }
+
+    /// Formats the integer `value` with this locale's thousands separator.
+    pub fn int(&self, value: i64) -> String {
+        let digits = value.unsigned_abs().to_string();
+        let mut out = String::with_capacity(digits.len() * 4 / 3 + 1);
+        if value < 0 { out.push('-'); }
+        self.push_grouped(&mut out, &digits);
+        out
+    }
+
+    /// Formats `value` rounded to exactly `places` decimal places, with this locale's thousands
+    /// and decimal separators.
+    pub fn decimal(&self, value: f64, places: usize) -> String {
+        if !value.is_finite() { return value.to_string(); }
+
+        let raw = format!("{:.*}", places, value.abs());
+        let (int_part, frac_part) = match raw.split_once('.') {
+            None => (raw.as_str(), ""),
+            Some((int_part, frac_part)) => (int_part, frac_part),
+        };
+
+        let mut out = String::with_capacity(raw.len() * 4 / 3 + 1);
+
+        // don't display negative zero
+        if value < 0.0 && raw.bytes().any(|b| b.is_ascii_digit() && b != b'0') {
+            out.push('-');
+        }
+
+        self.push_grouped(&mut out, int_part);
+        if !frac_part.is_empty() {
+            out.push(self.decimal_separator);
+            out.push_str(frac_part);
+        }
+        out
+    }
+
+    /// Formats `fraction` as a percentage with `places` decimal places, so that `0.5` is formatted as `50%`.
+    pub fn percent(&self, fraction: f64, places: usize) -> String {
+        let mut out = self.decimal(fraction * 100.0, places);
+        out.push('%');
+        out
+    }
+
+    /// Formats the date with the specified `year`, `month` (1 to 12), and `day` (1 to 31), using this locale's
+    /// ordering and separator.  The month and day are zero padded to two digits.
+    pub fn date(&self, year: i32, month: u32, day: u32) -> String {
+        let sep = self.date_separator;
+        match self.date_order {
+            DateOrder::YearMonthDay => format!("{}{}{:02}{}{:02}", year, sep, month, sep, day),
+            DateOrder::MonthDayYear => format!("{:02}{}{:02}{}{}", month, sep, day, sep, year),
+            DateOrder::DayMonthYear => format!("{:02}{}{:02}{}{}", day, sep, month, sep, year),
+        }
+    }
+
+    fn push_grouped(&self, out: &mut String, digits: &str) {
+        let sep = match self.thousands_separator {
+            None => {
+                out.push_str(digits);
+                return;
+            }, Some(sep) => sep,
+        };
+
+        let offset = digits.len() % 3;
+        for (i, c) in digits.chars().enumerate() {
+            if i > 0 && i % 3 == offset {
+                out.push(sep);
+            }
+            out.push(c);
+        }
+    }
+}
+
+/// A value to be formatted using the global [`Locale`](struct.Locale.html).  The formatted
+/// string is obtained via the `Display` implementation, or a `Value` may be passed directly to
+/// [`label_fmt`](../struct.Frame.html#method.label_fmt).
+///
+/// # Example
+/// ```
+/// use thyme::format::Value;
+/// let text = format!("Gold: {}  Time: {}", Value::Int(12500), Value::Duration(elapsed));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Value {
+    /// An integer, formatted with [`int`](fn.int.html)
+    Int(i64),
+
+    /// A number with the specified number of decimal places, formatted with [`decimal`](fn.decimal.html)
+    Decimal(f64, usize),
+
+    /// A fraction with the specified number of decimal places, formatted with [`percent`](fn.percent.html)
+    Percent(f64, usize),
+
+    /// A duration, formatted with [`duration`](fn.duration.html)
+    Duration(Duration),
+
+    /// A year, month, and day, formatted with [`date`](fn.date.html)
+    Date(i32, u32, u32),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match *self {
+            Value::Int(value) => int(value),
+            Value::Decimal(value, places) => decimal(value, places),
+            Value::Percent(fraction, places) => percent(fraction, places),
+            Value::Duration(value) => duration(value),
+            Value::Date(year, month, day) => date(year, month, day),
+        };
+        f.write_str(&text)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(value: i32) -> Value { Value::Int(value as i64) }
+}
+
+impl From<u32> for Value {
+    fn from(value: u32) -> Value { Value::Int(value as i64) }
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Value { Value::Int(value) }
+}
+
+impl From<usize> for Value {
+    fn from(value: usize) -> Value { Value::Int(value as i64) }
+}
+
+impl From<Duration> for Value {
+    fn from(value: Duration) -> Value { Value::Duration(value) }
+}
@@ -1,18 +1,24 @@
 use std::collections::HashMap;
+use std::fmt::Display;
+use std::ops::Range;
 use std::cell::RefCell;
 use std::rc::Rc;
 
 use serde::{Deserialize, Serialize};
 
+use crate::bench;
 use crate::context::{Context, ContextInternal, InputModifiers};
 use crate::{
-    AnimState, AnimStateKey, Rect, Point, WidgetBuilder, PersistentState, Align,
+    AccessibleWidget, AnimState, AnimStateKey, Border, DisplayClass, Effect, Transition, Rect, Point, WidgetBuilder, PersistentState, Align, HapticEvent, SystemCursor, UiEvent,
 };
-use crate::theme::ThemeSet;
+use crate::theme::{ThemeSet, WidgetCursor};
 use crate::image::ImageHandle;
 use crate::widget::Widget;
 use crate::theme_definition::CustomData;
 
+/// The maximum time between two clicks on a widget for them to count as a double click
+const DOUBLE_CLICK_MILLIS: u32 = 400;
+
 const MOUSE_NOT_TAKEN: MouseState =
     MouseState { clicked: false, anim: AnimState::normal(), dragged: Point { x: 0.0, y: 0.0 }, button: None };
 
@@ -37,14 +43,25 @@ pub struct Frame {
     cur_rend_group: RendGroup,
 
     parent_index: usize,
+    depth: usize,
+    limit_exceeded: Option<String>,
     child_request_rebound_parent: Option<u32>,
     pub(crate) in_modal_tree: bool,
+    pub(crate) in_passthrough_tree: bool,
     parent_max_child_bounds: Rect,
     max_child_bounds: Rect,
 
+    // the same as the max child bounds, but only including the children themselves
+    parent_children_bounds: Option<Rect>,
+    children_bounds: Option<Rect>,
+
     generated_ids: HashMap<String, u32>,
 
     mouse_cursor: Option<(ImageHandle, Align)>,
+    system_cursor: Option<SystemCursor>,
+    cursor_locked: bool,
+    raw_mouse_drag: bool,
+    hover_cursor: Option<WidgetCursor>,
     mouse_anim_state: AnimState,
 
     variables: HashMap<String, String>,
@@ -72,21 +89,39 @@ impl Frame {
                 start: 0,
                 num: 0,
                 order: RendGroupOrder::Normal,
+                passthrough: false,
             }],
             parent_index: 0,
+            depth: 0,
+            limit_exceeded: None,
             child_request_rebound_parent: None,
             in_modal_tree: false,
+            in_passthrough_tree: false,
             parent_max_child_bounds: Rect::default(),
             max_child_bounds: Rect::default(),
+            parent_children_bounds: None,
+            children_bounds: None,
             generated_ids: HashMap::default(),
             mouse_cursor: None,
+            system_cursor: None,
+            cursor_locked: false,
+            raw_mouse_drag: false,
+            hover_cursor: None,
             mouse_anim_state,
             variables: HashMap::new(),
         }
     }
 
     pub(crate) fn mouse_cursor(&self) -> Option<(ImageHandle, Align, AnimState)> {
-        self.mouse_cursor.map(|(image, align)| (image, align, self.mouse_anim_state))
+        let cursor = self.mouse_cursor.or(match self.hover_cursor {
+            Some(WidgetCursor::Image(image, align)) => Some((image, align)),
+            _ => None,
+        });
+        cursor.map(|(image, align)| (image, align, self.mouse_anim_state))
+    }
+
+    pub(crate) fn set_hover_cursor(&mut self, cursor: WidgetCursor) {
+        self.hover_cursor = Some(cursor);
     }
 
     pub(crate) fn generate_id(&mut self, id: String) -> String {
@@ -102,6 +137,13 @@ impl Frame {
         output
     }
 
+    /// Undoes the most recent [`generate_id`](#method.generate_id) for `id`, when the generated id is replaced
+    pub(crate) fn release_id(&mut self, id: &str) {
+        if let Some(index) = self.generated_ids.get_mut(id) {
+            *index = index.saturating_sub(1);
+        }
+    }
+
     /// Returns the overall Thyme [`Context`](struct.Context.html).
     pub fn context(&self) -> &Context {
         &self.context
@@ -116,7 +158,7 @@ impl Frame {
 
         let mut context = self.context.internal().borrow_mut();
 
-        if context.has_modal() && !self.in_modal_tree {
+        if self.in_passthrough_tree || (context.has_modal() && !self.in_modal_tree) {
             return None;
         }
 
@@ -127,8 +169,9 @@ impl Frame {
         }
 
         let bounds = Rect::new(widget.pos(), widget.size());
-        if !bounds.is_inside(context.mouse_pos()) {
-            return None;
+        match widget.to_local(context.mouse_pos()) {
+            Some(mouse_pos) if bounds.is_inside(mouse_pos) => (),
+            _ => return None,
         }
 
         let point = context.take_mouse_wheel();
@@ -144,7 +187,7 @@ impl Frame {
 
         let mut context = self.context.internal().borrow_mut();
 
-        if context.has_modal() && !self.in_modal_tree {
+        if self.in_passthrough_tree || (context.has_modal() && !self.in_modal_tree) {
             return MOUSE_NOT_TAKEN;
         }
 
@@ -154,8 +197,13 @@ impl Frame {
             }
         }
 
+        let mouse_pos = match widget.to_local(context.mouse_pos()) {
+            None => return MOUSE_NOT_TAKEN,
+            Some(mouse_pos) => mouse_pos,
+        };
+
         if context.mouse_pressed_outside() || self.mouse_taken.is_some() ||
-            !widget.clip().is_inside(context.mouse_pos()) {
+            !widget.clip().is_inside(mouse_pos) {
             return MOUSE_NOT_TAKEN;
         }
 
@@ -181,7 +229,7 @@ impl Frame {
         }
 
         let bounds = Rect::new(widget.pos(), widget.size());
-        if !bounds.is_inside(context.mouse_pos()) {
+        if !bounds.is_inside(mouse_pos) {
             return MOUSE_NOT_TAKEN;
         }
 
@@ -211,6 +259,18 @@ impl Frame {
         self.parent_max_child_bounds = bounds;
     }
 
+    pub(crate) fn children_bounds(&self) -> Option<Rect> { self.children_bounds }
+
+    pub(crate) fn set_children_bounds(&mut self, bounds: Option<Rect>) {
+        self.children_bounds = bounds;
+    }
+
+    pub(crate) fn parent_children_bounds(&self) -> Option<Rect> { self.parent_children_bounds }
+
+    pub(crate) fn set_parent_children_bounds(&mut self, bounds: Option<Rect>) {
+        self.parent_children_bounds = bounds;
+    }
+
     pub(crate) fn child_request_rebound_parent(&self) -> Option<u32> {
         self.child_request_rebound_parent
     }
@@ -250,6 +310,54 @@ impl Frame {
     */
     #[must_use]
     pub fn start(&mut self, theme: &str) -> WidgetBuilder {
+        self.start_with(theme, None)
+    }
+
+    /**
+    Starts creating a new child widget, as in [`start`](#method.start), but with an id derived from the specified
+    `index` rather than the order in which widgets are created.  This is useful for widgets created in a loop, such
+    as list items, so that each widget's [`PersistentState`](struct.PersistentState.html) stays with the same item
+    when items are inserted, removed, or reordered.  The `index` must be unique among widgets with the same parent
+    and `theme`, and would typically be a stable key for the item, such as a database id, rather than its position.
+
+    # Example
+    ```
+    fn create_list(ui: &mut Frame, items: &[Item]) {
+        for item in items {
+            ui.start_indexed("item", item.id).text(&item.name).finish();
+        }
+    }
+    ```
+    **/
+    #[must_use]
+    pub fn start_indexed(&mut self, theme: &str, index: u64) -> WidgetBuilder<'_> {
+        self.start_with(theme, Some(&index))
+    }
+
+    /**
+    Starts creating a new child widget, as in [`start_indexed`](#method.start_indexed), but with an id derived from
+    an arbitrary `key`, such as a name or file path.  The [`PersistentState`](struct.PersistentState.html) of the
+    widget, such as scroll, expanded, and text state, follows the key rather than the order in which widgets are
+    created.  Since child ids are derived from their parent's id, the state of any children of the widget also
+    follows the key.  The `key` must be unique among widgets with the same parent and `theme`.
+
+    # Example
+    ```
+    fn create_files(ui: &mut Frame, files: &[String]) {
+        for file in files {
+            ui.start_keyed("file", file).children(|ui| {
+                ui.input_field("input_field", "rename", None);
+            });
+        }
+    }
+    ```
+    **/
+    #[must_use]
+    pub fn start_keyed<K: Display>(&mut self, theme: &str, key: K) -> WidgetBuilder<'_> {
+        self.start_with(theme, Some(&key))
+    }
+
+    fn start_with(&mut self, theme: &str, key: Option<&dyn Display>) -> WidgetBuilder<'_> {
         let parent = &self.widgets[self.parent_index];
 
         let theme_id = if parent.theme_id().is_empty() {
@@ -258,7 +366,7 @@ impl Frame {
             format!("{}/{}", parent.theme_id(), theme)
         };
 
-        WidgetBuilder::new(self, self.parent_index, theme_id, theme)
+        WidgetBuilder::new(self, self.parent_index, theme_id, theme, key)
     }
 
     // ui builder methods
@@ -269,6 +377,64 @@ impl Frame {
         context.display_size() / context.scale_factor()
     }
 
+    /**
+    Returns the [`DisplayClass`](enum.DisplayClass.html) of the current logical display width, based on the
+    [`display_breakpoints`](struct.BuildOptions.html#structfield.display_breakpoints) option.
+
+    # Example
+    ```
+    fn editor(ui: &mut Frame) {
+        if ui.display_class() == DisplayClass::Small {
+            ui.start("sidebar_menu_button").finish();
+        } else {
+            ui.start("sidebar").children(|ui| {
+                ui.button("button", "Open");
+                ui.button("button", "Save");
+            });
+        }
+    }
+    ```
+    **/
+    pub fn display_class(&self) -> DisplayClass {
+        // the class is always set when the frame is created
+        self.context_internal().borrow().display_class().unwrap_or(DisplayClass::Large)
+    }
+
+    /// Returns true if the [`display class`](#method.display_class) is different from the class in the previous
+    /// frame.  This is never true for the first frame.  The change is also recorded as a
+    /// [`UiEvent`](enum.UiEvent.html) when the event queue is enabled.
+    pub fn display_class_changed(&self) -> bool {
+        self.context_internal().borrow().display_class_changed()
+    }
+
+    pub(crate) fn push_event(&mut self, event: UiEvent) {
+        self.context_internal().borrow_mut().push_event(event);
+    }
+
+    /// Plays the specified `sound` through the [`sound callback`](struct.Context.html#method.set_sound_callback),
+    /// if one is set.  The callback is invoked once this frame has been drawn.
+    pub fn play_sound(&mut self, sound: &str) {
+        self.context_internal().borrow_mut().play_sound(sound);
+    }
+
+    /// Sends the specified haptic `event` to the [`haptic callback`](struct.Context.html#method.set_haptic_callback),
+    /// if one is set and gamepad input is active.  The callback is invoked once this frame has been drawn.
+    pub fn haptic(&mut self, event: HapticEvent) {
+        self.context_internal().borrow_mut().haptic(event);
+    }
+
+    /// Requests that another frame be drawn after this one, for widgets which change over time,
+    /// such as custom animations.  See [`Context::needs_redraw`](struct.Context.html#method.needs_redraw).
+    pub fn request_redraw(&mut self) {
+        self.context_internal().borrow_mut().request_redraw();
+    }
+
+    /// Returns the current safe area insets, in logical pixels.  See
+    /// [`Context::set_safe_area_insets`](struct.Context.html#method.set_safe_area_insets)
+    pub fn safe_area_insets(&self) -> Border {
+        self.context_internal().borrow().safe_area_insets()
+    }
+
     /// Returns the current state of the keyboard modifier keys
     pub fn input_modifiers(&self) -> InputModifiers {
         let context = self.context_internal().borrow();
@@ -293,6 +459,14 @@ impl Frame {
         context.mouse_pos()
     }
 
+    /// Returns whether the specified mouse `button` was clicked, i.e. released after being pressed, since the
+    /// previous frame, regardless of whether the mouse is over a widget.  This is useful for handling the back and
+    /// forward side buttons for navigation.
+    pub fn mouse_clicked(&self, button: MouseButton) -> bool {
+        let context = self.context_internal().borrow();
+        context.mouse_clicked(button)
+    }
+
     /// Returns the current mouse position and size, in logical pixels
     pub fn mouse_rect(&self) -> Rect {
         let context = self.context_internal().borrow();
@@ -324,20 +498,32 @@ impl Frame {
     /// the tooltip render position.  Otherwise, returns `None`.
     /// See `mouse_time_in_current_widget`.
     pub fn tooltip_ready(&mut self) -> Option<Point> {
+        self.tooltip_ready_with(None, None)
+    }
+
+    pub(crate) fn tooltip_ready_with(&mut self, delay: Option<u32>, duration: Option<u32>) -> Option<Point> {
         let mut context = self.context_internal().borrow_mut();
         let mouse_rect = self.mouse_rect_for_pos(context.mouse_pos(), context.themes());
-        context.tooltip_ready(mouse_rect)
+        context.tooltip_ready(mouse_rect, delay, duration)
     }
 
     /// Sets the mouse cursor to the specified image with alignment.  If you are hiding the default
     /// OS cursor, this should be called at least once every frame you want to show a cursor.  If it
-    /// is called multiple times, the last call will take effect.  The image will automatically inherit
+    /// is called multiple times, the last call will take effect.  This overrides any image `cursor`
+    /// theme attribute of the hovered widget.  The image will automatically inherit
     /// `Normal` and `Pressed` animation states.  See `set_mouse_state` to override this behavior.
     pub fn set_mouse_cursor(&mut self, image: &str, align: Align) {
         let image = self.context.find_image(image);
         self.mouse_cursor = image.map(|image| (image, align));
     }
 
+    /// Sets the platform mouse cursor for this frame, overriding any `cursor` theme attribute
+    /// of the hovered widget.  If it is called multiple times, the last call will take effect.
+    /// See [`Context.system_cursor`](struct.Context.html#method.system_cursor).
+    pub fn set_system_cursor(&mut self, cursor: SystemCursor) {
+        self.system_cursor = Some(cursor);
+    }
+
     /// Manually set the Mouse cursor to the specified `state`.  This is used when
     /// drawing the specified mouse cursor image.  The mouse will automatically inherit
     /// `Normal` and `Pressed` states by default.  This overrides that behavior.
@@ -379,6 +565,13 @@ impl Frame {
         context.set_focus_keyboard(id.into());
     }
 
+    /// Removes keyboard focus from whichever widget currently has it, if any.
+    /// See [`focus_keyboard`](#method.focus_keyboard).
+    pub fn clear_focus_keyboard(&mut self) {
+        let mut context = self.context.internal().borrow_mut();
+        context.clear_focus_keyboard();
+    }
+
     /// Returns whether or not the widget with the specified `id` currently has keyboard focus.
     /// See [`focus_keyboard`](#method.focus_keyboard).
     pub fn is_focus_keyboard(&self, id: &str) -> bool {
@@ -397,6 +590,76 @@ impl Frame {
     /// final position and size.
     pub fn parent_max_child_bounds(&self) -> Rect { self.parent_max_child_bounds }
 
+    /// Returns the clip [`Rect`](struct.Rect.html) of the current parent widget.  Children
+    /// lying entirely outside this area will not be drawn.
+    pub fn parent_clip(&self) -> Rect { self.widgets[self.parent_index].clip() }
+
+    /// Computes which of `count` equally sized items, each `item_height` logical pixels tall,
+    /// would be visible within the current parent's clip area, if laid out starting at the parent's
+    /// current cursor.  The parent's vertical layout spacing is taken into account.  This assumes
+    /// the parent uses a [`Vertical`](enum.Layout.html) layout with children aligned to the top.
+    /// This is typically used with the content of a [`scrollpane`](struct.ScrollpaneBuilder.html)
+    /// containing a large number of items.  See [`visible_children`](#method.visible_children).
+    pub fn visible_range(&self, count: usize, item_height: f32) -> Range<usize> {
+        let parent = &self.widgets[self.parent_index];
+        let step = item_height + parent.layout_spacing().y;
+        if step <= 0.0 { return 0..count; }
+
+        let clip = parent.clip();
+        let start_y = parent.pos().y + parent.border().top + parent.scroll().y + parent.cursor().y;
+
+        let first = ((clip.top() - start_y) / step).floor().max(0.0) as usize;
+        let last = ((clip.bot() - start_y) / step).ceil().max(0.0) as usize;
+
+        first.min(count)..last.min(count)
+    }
+
+    /**
+    Adds `count` equally sized children to the current parent, calling `f` with the index of each child
+    that would be visible within the parent's clip area, as computed by [`visible_range`](#method.visible_range).
+    Children outside the visible area are skipped, but the space they would take up is still
+    accounted for, so the parent (such as a scrollpane's content) keeps its full size.  Each child
+    should be `item_height` logical pixels tall.
+
+    # Example
+    ```
+    fn create_list(ui: &mut Frame, items: &[String]) {
+        ui.scrollpane("scrollpane", "items_content", |ui| {
+            ui.visible_children(items.len(), 20.0, |ui, index| {
+                ui.label("item", &items[index]);
+            });
+        });
+    }
+    ```
+    */
+    pub fn visible_children<F: FnMut(&mut Frame, usize)>(&mut self, count: usize, item_height: f32, mut f: F) {
+        let range = self.visible_range(count, item_height);
+
+        let (start, step) = {
+            let parent = &self.widgets[self.parent_index];
+            let start = parent.pos() + parent.border().tl() + parent.scroll() + parent.cursor();
+            (start, item_height + parent.layout_spacing().y)
+        };
+
+        self.gap(range.start as f32 * step);
+        for index in range.clone() {
+            (f)(self, index);
+        }
+        self.gap((count - range.end) as f32 * step);
+
+        // make sure the skipped children are included in the parent's bounds
+        if count > 0 {
+            let end = start + Point::new(0.0, count as f32 * step - step + item_height);
+            let bounds = self.max_child_bounds
+                .max(Rect::new(start, Point::default()))
+                .max(Rect::new(end, Point::default()));
+            self.max_child_bounds = bounds;
+
+            let children = self.children_bounds.unwrap_or(Rect::new(start, Point::default()));
+            self.children_bounds = Some(children.max(Rect::new(start, Point::default())).max(Rect::new(end, Point::default())));
+        }
+    }
+
     /**
     Returns the current internal time being used by Thyme.  This is useful
     if you want to set a timer to start running based on the current frame,
@@ -471,6 +734,116 @@ impl Frame {
         context.state(id).timer
     }
 
+    /**
+    Returns `true` if at least `millis` milliseconds have passed since the [`timer`](#method.timer) for the
+    widget with the specified `id` was set.  The timer is normally started with
+    [`set_timer_to_now`](#method.set_timer_to_now).  Until the time has passed, a
+    [`redraw`](#method.request_redraw) is requested each frame.
+
+    # Example
+    ```
+    fn notification(ui: &mut Frame, text: &str) {
+        // hide the notification after 3 seconds
+        if ui.after("notification", 3000) {
+            ui.close("notification");
+        }
+
+        ui.window("notification", |ui| {
+            ui.label("label", text);
+        });
+    }
+    ```
+    **/
+    pub fn after(&mut self, id: &str, millis: u32) -> bool {
+        let mut context = self.context.internal().borrow_mut();
+        let elapsed = context.time_millis().saturating_sub(context.state(id).timer);
+        if elapsed < millis {
+            context.request_redraw();
+            false
+        } else {
+            true
+        }
+    }
+
+    /// Records a click on the widget with the specified `id`, returning whether it completes a double click
+    pub(crate) fn check_double_click(&mut self, id: &str) -> bool {
+        let time = self.cur_time_millis();
+        self.modify(id, |state| {
+            let last = state.last_click_millis;
+            let double = last != 0 && time.saturating_sub(last) <= DOUBLE_CLICK_MILLIS;
+            state.last_click_millis = if double { 0 } else { time.max(1) };
+            double
+        })
+    }
+
+    /**
+    Returns `true` once every `millis` milliseconds, based on the [`timer`](#method.timer) for the widget
+    with the specified `id`, which is advanced each time this returns `true`.  This is useful for periodic
+    tasks such as blinking or polling.  A [`redraw`](#method.request_redraw) is requested each frame.
+
+    # Example
+    ```
+    fn caret(ui: &mut Frame, visible: &mut bool) {
+        if ui.every("caret", 500) {
+            *visible = !*visible;
+        }
+
+        ui.start("caret").visible(*visible).finish();
+    }
+    ```
+    **/
+    pub fn every(&mut self, id: &str, millis: u32) -> bool {
+        let mut context = self.context.internal().borrow_mut();
+        context.request_redraw();
+
+        let time = context.time_millis();
+        let state = context.state_mut(id);
+        let elapsed = time.saturating_sub(state.timer);
+        if elapsed < millis {
+            return false;
+        }
+
+        // keep the interval steady even if frames are not evenly spaced
+        state.timer = time - elapsed.checked_rem(millis).unwrap_or(0);
+        true
+    }
+
+    /**
+    Starts playing the specified attention [`Effect`](enum.Effect.html) on the widget with the specified `id`,
+    replacing any effect already playing on it.  The effect is combined with any
+    [`transform`](struct.WidgetBuilder.html#method.transform) of the widget, and a
+    [`redraw`](#method.request_redraw) is requested each frame until it finishes.
+
+    # Example
+    ```
+    fn submit(ui: &mut Frame, name: &str) {
+        if ui.button("submit", "Submit").clicked && name.is_empty() {
+            ui.shake("submit");
+        }
+    }
+    ```
+    **/
+    pub fn play_effect<T: Into<String>>(&mut self, id: T, effect: Effect) {
+        let mut context = self.context.internal().borrow_mut();
+        let time = context.time_millis();
+        context.state_mut(id).effect = Some((effect, time));
+    }
+
+    /// Shakes the widget with the specified `id` from side to side.  See [`play_effect`](#method.play_effect).
+    pub fn shake<T: Into<String>>(&mut self, id: T) {
+        self.play_effect(id, Effect::Shake);
+    }
+
+    /// Briefly grows and shrinks the widget with the specified `id`.  See [`play_effect`](#method.play_effect).
+    pub fn pulse<T: Into<String>>(&mut self, id: T) {
+        self.play_effect(id, Effect::Pulse);
+    }
+
+    /// Fades the widget with the specified `id` out and in twice.  See [`play_effect`](#method.play_effect).
+    pub fn flash<T: Into<String>>(&mut self, id: T) {
+        self.play_effect(id, Effect::Flash);
+    }
+
     /// Sets the internal `scroll` of the [`PersistentState`](struct.PersistentState.html) for
     /// the widget with the specified `id`.  Useful for [`Scrollpanes`](struct.WidgetBuilder.html#method.scrollpane).
     pub fn scroll(&self, id: &str) -> Point {
@@ -486,6 +859,33 @@ impl Frame {
         state.scroll = state.scroll + Point { x, y }
     }
 
+    /// Returns the size of the content of the [`scrollpane`](struct.WidgetBuilder.html#method.scrollpane) with the
+    /// specified content `id`, measured from the bounds of its children during the previous frame.  Together with
+    /// [`viewport_size`](#method.viewport_size) and [`scroll`](#method.scroll), this may be used to build custom
+    /// scrollbars, content minimaps, or indicators of how much content is out of view.
+    pub fn content_size(&self, id: &str) -> Point {
+        let context = self.context.internal().borrow();
+        context.state(id).content_size
+    }
+
+    /// Returns the size of the visible area of the [`scrollpane`](struct.WidgetBuilder.html#method.scrollpane) with
+    /// the specified content `id`, as measured during the previous frame.  See [`content_size`](#method.content_size).
+    pub fn viewport_size(&self, id: &str) -> Point {
+        let context = self.context.internal().borrow();
+        context.state(id).viewport_size
+    }
+
+    /// Returns how far the [`scrollpane`](struct.WidgetBuilder.html#method.scrollpane) with the specified content `id`
+    /// is scrolled along each axis, from 0.0 at the start of the content to 1.0 at the end.  An axis where all of
+    /// the content is visible is at 0.0.  See [`content_size`](#method.content_size).
+    pub fn scroll_fraction(&self, id: &str) -> Point {
+        let context = self.context.internal().borrow();
+        let state = context.state(id);
+        let range = state.content_size - state.viewport_size;
+        let fraction = |scroll: f32, range: f32| if range > 0.0 && scroll < 0.0 { (-scroll / range).min(1.0) } else { 0.0 };
+        Point::new(fraction(state.scroll.x, range.x), fraction(state.scroll.y, range.y))
+    }
+
     /// Returns the current `text` associated with the [`PersistentState`](struct.PersistentState.html) of
     /// the widget with the specified `id`.  Useful for [`input fields`](#method.input_field).
     pub fn text_for(&self, id: &str) -> Option<String> {
@@ -506,6 +906,19 @@ impl Frame {
         context.state_mut(id).expanded = expanded;
     }
 
+    /// Returns whether the window with the specified `id` is shaded, showing only its titlebar.
+    /// See [`WindowBuilder::shadeable`](struct.WindowBuilder.html#method.shadeable).
+    pub fn is_shaded(&self, id: &str) -> bool {
+        let context = self.context.internal().borrow();
+        context.state(id).shaded
+    }
+
+    /// Sets the shaded value for the given window to `shaded`.  See [`is_shaded`](#method.is_shaded)
+    pub fn set_shaded<T: Into<String>>(&mut self, id: T, shaded: bool) {
+        let mut context = self.context.internal().borrow_mut();
+        context.state_mut(id).shaded = shaded;
+    }
+
     /// Returns whether the widget with the specified `id` is open in its [`PersistentState`](struct.PersistentState.html).
     /// If not open, widgets are not visible.
     pub fn is_open(&self, id: &str) -> bool {
@@ -517,12 +930,14 @@ impl Frame {
     /// associated with that widget, as well as setting the overall Thyme modal to the specified widget.
     /// When a modal is open, only the modal and its children may receive input.  There may be only one modal open at a time.
     /// If the specified `id` is closed, i.e. via [`close`](#method.close), the modal state ends.
+    /// Keyboard focus moves to the first [`focusable`](struct.WidgetBuilder.html#method.focusable) widget in the modal
+    /// once it is built, and Tab navigation is kept within the modal.  When the modal ends, the previous focus is restored.
     pub fn open_modal<T: Into<String>>(&mut self, id: T) {
         let id: String = id.into();
 
         let mut context = self.context.internal().borrow_mut();
         context.set_top_rend_group_id(&id);
-        context.state_mut(id.clone()).is_open = true;
+        context.set_open(id.clone(), true);
         context.set_modal(id);
     }
 
@@ -534,23 +949,43 @@ impl Frame {
         });
     }
 
+    /**
+    Sets the currently open modal, if there is one, to play the specified [`Transition`](enum.Transition.html) as it
+    opens and closes.  When the modal is closed, such as with [`close`](#method.close), it remains open until the
+    closing transition completes, so [`is_open`](#method.is_open) is still true during the transition.
+
+    # Example
+    ```
+    if ui.button("settings_button", "Settings").clicked {
+        ui.open_modal("settings");
+        ui.set_modal_transition(Transition::FadeScale);
+    }
+    ```
+    **/
+    pub fn set_modal_transition(&mut self, transition: Transition) {
+        let mut context = self.context.internal().borrow_mut();
+        context.mut_modal(|modal| {
+            modal.transition = Some(transition);
+        });
+    }
+
     /// Opens the widget with the specified `id`.  This modifies the [`PersistentState`](struct.PersistentState.html).
     /// See [`is_open`](#method.is_open)
     pub fn open<T: Into<String>>(&mut self, id: T) {
         let id = id.into();
         let mut context = self.context.internal().borrow_mut();
         context.set_top_rend_group_id(&id);
-        context.state_mut(id).is_open = true;
+        context.set_open(id, true);
     }
 
     /// Closes the widget with the specified `id`.  This modifies the [`PersistentState`](struct.PersistentState.html).
     /// See [`is_open`](#method.is_open).  If the widget was the current modal, resets Thyme so there is no longer a modal.
+    /// A modal with a [`transition`](#method.set_modal_transition) is instead closed once its transition completes.
     pub fn close<T: Into<String>>(&mut self, id: T) {
         let id = id.into();
 
         let mut context = self.context.internal().borrow_mut();
-        context.clear_modal_if_match(&id);
-        context.state_mut(id).is_open = false;
+        context.close_widget(id);
     }
 
     /// Opens the current parent widget.  See [`open`](#method.open).
@@ -558,15 +993,14 @@ impl Frame {
         let mut context = self.context.internal().borrow_mut();
         let id = self.widgets[self.parent_index].id();
         context.set_top_rend_group_id(id);
-        context.state_mut(id).is_open = true;
+        context.set_open(id, true);
     }
 
     /// Closes the current parent widget.  See [`close`](#method.close).
     pub fn close_parent(&mut self) {
         let mut context = self.context.internal().borrow_mut();
         let id = self.widgets[self.parent_index].id();
-        context.clear_modal_if_match(id);
-        context.state_mut(id).is_open = false;
+        context.close_widget(id.to_string());
     }
 
     /// Completely clears all [`PersistentState`](struct.PersistentState.html) associated with the 
@@ -679,19 +1113,59 @@ impl Frame {
         self.child_request_rebound_parent = Some(self.widgets.len() as u32 - 1);
     }
 
+    pub(crate) fn enter_children(&mut self) { self.depth += 1; }
+
+    pub(crate) fn exit_children(&mut self) { self.depth -= 1; }
+
+    /// Returns whether the widget with the specified `id`, optionally starting a `new_render_group`, may be added
+    /// to the current parent without exceeding the widget limits.  Otherwise, a diagnostic naming the parent is
+    /// recorded, to be logged when the frame is finished.
+    pub(crate) fn within_widget_limits(&mut self, id: &str, new_render_group: bool) -> bool {
+        let (max_widgets, max_depth, max_render_groups) = self.context.internal().borrow().widget_limits();
+
+        let exceeded = if self.widgets.len() >= max_widgets {
+            format!("{} widgets per frame", max_widgets)
+        } else if self.depth >= max_depth {
+            format!("{} nested child widgets", max_depth)
+        } else if new_render_group && self.render_groups.len() >= max_render_groups {
+            format!("{} render groups per frame", max_render_groups)
+        } else {
+            return true;
+        };
+
+        if self.limit_exceeded.is_none() {
+            let parent = self.widgets[self.parent_index].id();
+            self.limit_exceeded = Some(format!(
+                "Exceeded the limit of {} adding '{}' to parent '{}'.  Widgets beyond the limit are \
+                not built; check for widgets created in an unbounded loop or recursion.",
+                exceeded, id, parent
+            ));
+        }
+
+        false
+    }
+
     pub(crate) fn push_widget(&mut self, mut widget: Widget) {
         widget.set_rend_group(self.cur_rend_group);
         self.render_groups[self.cur_rend_group.index as usize].num += 1;
         self.widgets.push(widget);
     }
 
+    pub(crate) fn lock_cursor(&mut self) {
+        self.cursor_locked = true;
+    }
+
+    pub(crate) fn use_raw_mouse_drag(&mut self) {
+        self.raw_mouse_drag = true;
+    }
+
     pub(crate) fn cur_render_group(&self) -> RendGroup { self.cur_rend_group }
 
     pub(crate) fn prev_render_group(&mut self, group: RendGroup) {
         self.cur_rend_group = group;
     }
 
-    pub(crate) fn next_render_group(&mut self, rect: Rect, id: String, order: RendGroupOrder) {
+    pub(crate) fn next_render_group(&mut self, rect: Rect, id: String, order: RendGroupOrder, passthrough: bool) {
         let widgets_len = self.widgets.len();
         let index = self.render_groups.len() as u16;
         let cur_rend_group = RendGroup { index };
@@ -703,6 +1177,7 @@ impl Frame {
             start: widgets_len,
             num: 0,
             order,
+            passthrough,
         });
         self.cur_rend_group = cur_rend_group;
     }
@@ -712,11 +1187,52 @@ impl Frame {
     }
 
     pub(crate) fn finish_frame(self) -> (Context, Vec<Widget>, Vec<RendGroupDef>) {
+        bench::end_profile_frame();
+
         let (top_rend_group, mouse_pos) = {
             let mut context = self.context.internal().borrow_mut();
 
             context.check_set_rend_group_top(&self.render_groups);
 
+            let system_cursor = self.system_cursor.or(match self.hover_cursor {
+                Some(WidgetCursor::System(cursor)) => Some(cursor),
+                _ => None,
+            });
+            context.set_system_cursor(system_cursor.unwrap_or_default());
+            context.set_cursor_locked(self.cursor_locked);
+            context.set_raw_mouse_drag(self.raw_mouse_drag);
+
+            let accessible = self.widgets.iter().filter(|widget| {
+                widget.visible() && (widget.aria_label().is_some() || widget.description().is_some())
+            }).map(|widget| AccessibleWidget {
+                id: widget.id().to_string(),
+                theme_id: widget.theme_id().to_string(),
+                rect: Rect::new(widget.pos(), widget.size()),
+                label: widget.aria_label().map(|label| context.resolve_text(label).into_owned()),
+                description: widget.description().map(|desc| context.resolve_text(desc).into_owned()),
+            }).collect();
+            context.set_accessible_widgets(accessible);
+
+            let custom = self.widgets.iter().filter(|widget| !widget.custom_data().is_empty()).map(|widget| {
+                (widget.id().to_string(), widget.custom_data().to_vec())
+            }).collect();
+            context.set_custom_widget_data(custom);
+
+            let focus_order = self.widgets.iter().filter(|widget| widget.visible() && widget.focusable()).map(|widget| {
+                (widget.id().to_string(), self.widgets[widget.focus_scope()].id().to_string())
+            }).collect();
+            context.set_focus_order(focus_order);
+            context.report_widget_limit(self.limit_exceeded.clone());
+
+            let timed = self.widgets.iter().any(|widget| {
+                if !widget.visible() || widget.clipped_out() { return false; }
+                let themes = context.themes();
+                widget.background().into_iter().chain(widget.foreground()).any(|handle| themes.image(handle).is_timed())
+            });
+            if timed {
+                context.request_redraw();
+            }
+
             (context.top_rend_group(), context.mouse_pos())
         };
 
@@ -729,9 +1245,15 @@ impl Frame {
             }
         });
 
+        let render_groups = {
+            let context = self.context.internal().borrow();
+            let parents: HashMap<&str, &str> = context.window_parents().collect();
+            if parents.is_empty() { render_groups } else { order_child_windows(render_groups, &parents) }
+        };
+
         let mut mouse_in_rend_group = None;
         for rend_group in render_groups.iter() {
-            if rend_group.rect.is_inside(mouse_pos) {
+            if !rend_group.passthrough && rend_group.rect.is_inside(mouse_pos) {
                 mouse_in_rend_group = Some(rend_group.group);
                 break;
             }
@@ -739,6 +1261,31 @@ impl Frame {
 
         self.context.internal().borrow_mut().next_frame(self.mouse_taken, mouse_in_rend_group);
 
+        // the callback is run without borrowing the context, as it may hold a reference to it
+        let (sounds, callback) = self.context.internal().borrow_mut().take_sounds();
+        if let Some(mut callback) = callback {
+            for sound in sounds.iter() {
+                callback(sound);
+            }
+            self.context.internal().borrow_mut().restore_sound_callback(callback);
+        }
+
+        let (haptics, callback) = self.context.internal().borrow_mut().take_haptics();
+        if let Some(mut callback) = callback {
+            for event in haptics {
+                callback(event);
+            }
+            self.context.internal().borrow_mut().restore_haptic_callback(callback);
+        }
+
+        let (interactions, callback) = self.context.internal().borrow_mut().take_interactions();
+        if let Some(mut callback) = callback {
+            for (id, interaction, time_millis) in interactions {
+                callback(&id, interaction, time_millis);
+            }
+            self.context.internal().borrow_mut().restore_interaction_callback(callback);
+        }
+
         (self.context, self.widgets, render_groups)
     }
 }
@@ -748,6 +1295,48 @@ pub(crate) struct RendGroup {
     index: u16,
 }
 
+/// Moves the render group of each child window directly in front of the group of its parent window, so that
+/// child windows stay above their parents.  The `groups` are ordered from front to back.
+fn order_child_windows(groups: Vec<RendGroupDef>, parents: &HashMap<&str, &str>) -> Vec<RendGroupDef> {
+    let index_of: HashMap<&str, usize> = groups.iter().enumerate().map(|(index, group)| (group.id(), index)).collect();
+
+    let mut children = vec![Vec::new(); groups.len()];
+    let mut roots = Vec::new();
+    for (index, group) in groups.iter().enumerate() {
+        let parent = parents.get(group.id()).and_then(|parent| index_of.get(parent)).copied();
+        match parent {
+            Some(parent) if group.order == RendGroupOrder::Normal && groups[parent].order == RendGroupOrder::Normal => {
+                children[parent].push(index);
+            },
+            _ => roots.push(index),
+        }
+    }
+
+    fn visit(index: usize, children: &[Vec<usize>], visited: &mut [bool], order: &mut Vec<usize>) {
+        if visited[index] { return; }
+        visited[index] = true;
+
+        for &child in &children[index] {
+            visit(child, children, visited, order);
+        }
+        order.push(index);
+    }
+
+    let mut visited = vec![false; groups.len()];
+    let mut order = Vec::with_capacity(groups.len());
+    for &root in &roots {
+        visit(root, &children, &mut visited, &mut order);
+    }
+
+    // windows parented in a cycle keep their position
+    for index in 0..groups.len() {
+        visit(index, &children, &mut visited, &mut order);
+    }
+
+    let mut groups: Vec<Option<RendGroupDef>> = groups.into_iter().map(Some).collect();
+    order.into_iter().filter_map(|index| groups[index].take()).collect()
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
 pub(crate) enum RendGroupOrder {
     #[default]
@@ -764,6 +1353,9 @@ pub(crate) struct RendGroupDef {
     start: usize,
     num: usize,
     order: RendGroupOrder,
+
+    // whether the group is skipped when finding the group under the mouse
+    passthrough: bool,
 }
 
 impl RendGroupDef {
@@ -787,6 +1379,43 @@ pub enum MouseButton {
 
     /// The middle mouse button
     Middle,
+
+    /// The back side button, typically used for navigation
+    Back,
+
+    /// The forward side button, typically used for navigation
+    Forward,
+
+    /// An additional mouse button, numbered from zero.  See
+    /// [`BuildOptions::mouse_buttons`](struct.BuildOptions.html#structfield.mouse_buttons).
+    Other(u16),
+}
+
+impl MouseButton {
+    /// Returns the index of this button, as passed to
+    /// [`Context::set_mouse_pressed`](struct.Context.html#method.set_mouse_pressed)
+    pub fn index(self) -> usize {
+        match self {
+            MouseButton::Left => 0,
+            MouseButton::Right => 1,
+            MouseButton::Middle => 2,
+            MouseButton::Back => 3,
+            MouseButton::Forward => 4,
+            MouseButton::Other(index) => index as usize + 5,
+        }
+    }
+
+    /// Returns the button with the specified `index`.  See [`index`](#method.index).
+    pub fn from_index(index: usize) -> MouseButton {
+        match index {
+            0 => MouseButton::Left,
+            1 => MouseButton::Right,
+            2 => MouseButton::Middle,
+            3 => MouseButton::Back,
+            4 => MouseButton::Forward,
+            _ => MouseButton::Other((index - 5) as u16),
+        }
+    }
 }
 
 #[macro_export]
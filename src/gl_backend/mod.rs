@@ -3,10 +3,13 @@ use std::error::Error;
 use crate::font::{Font, FontSource, FontTextureWriter, FontDrawParams};
 use crate::image::ImageDrawParams;
 use crate::render::{
-    view_matrix, DrawList, DrawMode, FontHandle, Renderer, TexCoord, TextureData, TextureHandle,
+    store_handle, triangle_vertices, view_matrix, DisplayRotation, DrawList, DrawMode, DrawVertex, FontHandle, Renderer, TexCoord,
+    TextureData, TextureHandle, TransformedDrawList,
 };
 use crate::theme_definition::CharacterRange;
-use crate::{Color, Frame, Point, Rect};
+use crate::accessibility::HighContrastText;
+use crate::widget::Widget;
+use crate::{AnimState, AnimStateKey, Color, ColorTransform, CompressedFormat, Frame, Point, Rect};
 
 mod program;
 use program::Program;
@@ -35,9 +38,13 @@ use vertex_buffer::VAO;
 /// to the GPU is constructed each frame in the immediate mode UI model, the amount of data is minimized
 /// by sending only a single `Vertex` for each Image, with the vertex components including the rectangular position and
 /// texture coordinates.  The actual individual on-screen vertices are then constructed with a Geometry shader.
+///
+/// Widgets may draw their images with custom fragment shaders, registered with
+/// [`register_shader`](#method.register_shader).
 pub struct GLRenderer {
-    base_program: Program,
-    font_program: Program,
+    // None while suspended
+    programs: Option<Programs>,
+    shaders: Vec<CustomShader>,
 
     // assets loaded from the context
     textures: Vec<GLTexture>,
@@ -47,6 +54,8 @@ pub struct GLRenderer {
     draw_list: GLDrawList,
     groups: Vec<DrawGroup>,
     matrix: [[f32; 4]; 4],
+    color_transform: [[f32; 3]; 3],
+    time_secs: f32,
 }
 
 impl Default for GLRenderer {
@@ -58,19 +67,126 @@ impl Default for GLRenderer {
 impl GLRenderer {
     /// Creates a GLRenderer
     pub fn new() -> GLRenderer {
-        let base_program = Program::new(VERT_SHADER_SRC, GEOM_SHADER_SRC, FRAGMENT_SHADER_SRC);
-
-        let font_program = Program::new(VERT_SHADER_SRC, GEOM_SHADER_SRC, FONT_FRAGMENT_SHADER_SRC);
-
         GLRenderer {
-            base_program,
-            font_program,
+            programs: Some(Programs::new()),
+            shaders: Vec::new(),
             fonts: Vec::new(),
             textures: Vec::new(),
             draw_list: GLDrawList::new(),
             groups: Vec::new(),
-            matrix: view_matrix(Point::default(), Point { x: 100.0, y: 100.0 }),
+            matrix: view_matrix(Point::default(), Point { x: 100.0, y: 100.0 }, DisplayRotation::Deg0),
+            color_transform: ColorTransform::None.uniform(),
+            time_secs: 0.0,
+        }
+    }
+
+    /**
+    Registers a custom fragment shader with the specified `name`, which widgets may use to draw their background
+    and foreground images by specifying `shader: name` in their theme, or with
+    [`WidgetBuilder::shader`](struct.WidgetBuilder.html#method.shader).  Registering a shader with an existing
+    name replaces it.  Returns `Err` with the compile log if the shader is invalid.
+
+    The `source` is a complete GLSL fragment shader, receiving the following inputs and uniforms:
+    - `in vec2 v_tex_coords` - the texture coordinates of the image
+    - `in vec4 v_color` - the image color, including any tint
+    - `in vec2 v_pos` - the position of the fragment, in physical pixels
+    - `uniform sampler2D tex` - the image texture
+    - `uniform mat3 color_transform` - the [`color transform`](struct.Context.html#method.set_color_transform)
+    - `uniform float time` - the UI time, in seconds
+    - `uniform vec4 widget_rect` - the position and size of the widget, in physical pixels
+    - `uniform int anim_state` - the widget's animation state as bit flags: 1 for hover, 2 for pressed,
+      4 for active, and 8 for disabled
+
+    # Example
+    ```
+    renderer.register_shader("scanlines", r#"
+        #version 150
+
+        in vec2 v_tex_coords;
+        in vec4 v_color;
+        in vec2 v_pos;
+
+        out vec4 color;
+
+        uniform sampler2D tex;
+        uniform mat3 color_transform;
+        uniform float time;
+
+        void main() {
+            color = v_color * texture(tex, v_tex_coords);
+            color.rgb = color_transform * color.rgb;
+            color.rgb *= 0.8 + 0.2 * sin(v_pos.y * 2.0 + time * 4.0);
+        }
+    "#)?;
+    ```
+    **/
+    pub fn register_shader(&mut self, name: &str, source: &str) -> Result<(), GlError> {
+        let programs = match self.programs {
+            None => None,
+            Some(_) => Some(CustomPrograms::new(source).map_err(GlError::Shader)?),
+        };
+
+        let shader = CustomShader { name: name.to_string(), source: source.to_string(), programs };
+        match self.shaders.iter_mut().find(|shader| shader.name == name) {
+            None => self.shaders.push(shader),
+            Some(existing) => *existing = shader,
         }
+
+        Ok(())
+    }
+
+    /// The shader and uniforms used to draw the images of the `widget`, if it uses a registered shader
+    fn widget_shader(&self, widget: &Widget, scale: f32) -> Option<ShaderUniforms> {
+        let name = widget.shader()?;
+        let shader = self.shaders.iter().position(|shader| shader.name == name)?;
+
+        let pos = widget.pos() * scale;
+        let size = widget.size() * scale;
+        let anim_state = widget.anim_state();
+        let flags = [AnimStateKey::Hover, AnimStateKey::Pressed, AnimStateKey::Active, AnimStateKey::Disabled];
+        let anim_state = flags.iter().enumerate()
+            .filter(|(_, key)| anim_state.contains(**key))
+            .fold(0, |flags, (index, _)| flags | (1 << index));
+
+        Some(ShaderUniforms { shader, rect: [pos.x, pos.y, size.x, size.y], anim_state })
+    }
+
+    /**
+    Releases all of the OpenGL resources held by this renderer, including textures, font atlases, and shader
+    programs, such as when a mobile app is suspended and its OpenGL context is about to be lost.  This should
+    be called while the context is still current.  Frames drawn while suspended are discarded.
+
+    Once a new context is current, call [`resume`](#method.resume) and then
+    [`Context::restore_resources`](struct.Context.html#method.restore_resources) to register the textures
+    and fonts again.
+    **/
+    pub fn suspend(&mut self) {
+        self.textures.clear();
+        self.fonts.clear();
+        self.programs = None;
+        for shader in self.shaders.iter_mut() {
+            shader.programs = None;
+        }
+    }
+
+    /// Recreates the shader programs released by [`suspend`](#method.suspend), including any
+    /// [`custom shaders`](#method.register_shader), using the current OpenGL context.
+    /// Does nothing if this renderer is not suspended.
+    pub fn resume(&mut self) {
+        if self.programs.is_some() { return; }
+
+        self.programs = Some(Programs::new());
+        for shader in self.shaders.iter_mut() {
+            match CustomPrograms::new(&shader.source) {
+                Ok(programs) => shader.programs = Some(programs),
+                Err(e) => log::error!("Unable to compile shader '{}': {}", shader.name, e),
+            }
+        }
+    }
+
+    /// Returns whether this renderer is currently [`suspended`](#method.suspend)
+    pub fn is_suspended(&self) -> bool {
+        self.programs.is_none()
     }
 
     fn font(&self, font: FontHandle) -> &GLTexture {
@@ -89,6 +205,30 @@ impl GLRenderer {
         }
     }
 
+    /// Reads the RGBA pixels of the current framebuffer, with the specified size in physical pixels, such as
+    /// after calling [`draw_frame`](#method.draw_frame) but before swapping buffers.  Rows are returned from
+    /// the top of the framebuffer to the bottom, for use as a screenshot or with a
+    /// [`Recorder`](struct.Recorder.html).
+    pub fn read_pixels(&self, width: u32, height: u32) -> Vec<u8> {
+        let row_len = (width * 4) as usize;
+        let mut data = vec![0; row_len * height as usize];
+        unsafe {
+            gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+            gl::ReadPixels(
+                0,
+                0,
+                width as _,
+                height as _,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                data.as_mut_ptr() as *mut _,
+            );
+        }
+
+        // OpenGL rows start at the bottom of the framebuffer
+        data.chunks_exact(row_len).rev().flatten().copied().collect()
+    }
+
     /// Draws the specified [`Frame`](struct.Frame.html) to the Glium surface, usually the Glium Frame.
     pub fn draw_frame(&mut self, frame: Frame) {
         let mouse_cursor = frame.mouse_cursor();
@@ -96,10 +236,12 @@ impl GLRenderer {
         let context = context.internal().borrow();
 
         let time_millis = context.time_millis();
+        self.time_secs = time_millis as f32 / 1000.0;
         let display_pos = Point::default();
         let display_size = context.display_size();
         let scale = context.scale_factor();
-        self.matrix = view_matrix(display_pos, display_size);
+        self.matrix = view_matrix(display_pos, display_size, context.display_rotation());
+        self.color_transform = context.color_transform().uniform();
 
         self.draw_list.clear();
         self.groups.clear();
@@ -118,7 +260,7 @@ impl GLRenderer {
 
             // render backgrounds
             for widget in render_group.iter(&widgets) {
-                if !widget.visible() {
+                if !widget.visible() || widget.clipped_out() {
                     continue;
                 }
                 let image_handle = match widget.background() {
@@ -127,11 +269,14 @@ impl GLRenderer {
                 };
                 let time_millis = time_millis - context.base_time_millis_for(widget.id());
                 let image = context.themes().image(image_handle);
+                let transformed = widget.transform().is_some();
+                let shader = self.widget_shader(widget, scale);
 
-                self.write_group_if_changed(&mut draw_mode, DrawMode::Image(image.texture()));
+                self.write_group_if_changed(&mut draw_mode, DrawMode::image(image.texture(), transformed), shader);
 
+                let mut draw_list = TransformedDrawList::new(&mut self.draw_list, widget.transform(), scale);
                 image.draw(
-                    &mut self.draw_list,
+                    &mut draw_list,
                     ImageDrawParams {
                         pos: widget.pos().into(),
                         size: widget.size().into(),
@@ -142,25 +287,29 @@ impl GLRenderer {
                         color: widget.image_color(),
                     },
                 );
+                draw_list.finish();
             }
 
             // render foregrounds & text
             for widget in render_group.iter(&widgets) {
-                if !widget.visible() {
+                if !widget.visible() || widget.clipped_out() {
                     continue;
                 }
 
                 let border = widget.border();
                 let fg_pos = widget.pos() + border.tl();
                 let fg_size = widget.inner_size();
+                let transformed = widget.transform().is_some();
 
                 if let Some(image_handle) = widget.foreground() {
                     let time_millis = time_millis - context.base_time_millis_for(widget.id());
                     let image = context.themes().image(image_handle);
-                    self.write_group_if_changed(&mut draw_mode, DrawMode::Image(image.texture()));
+                    let shader = self.widget_shader(widget, scale);
+                    self.write_group_if_changed(&mut draw_mode, DrawMode::image(image.texture(), transformed), shader);
 
+                    let mut draw_list = TransformedDrawList::new(&mut self.draw_list, widget.transform(), scale);
                     image.draw(
-                        &mut self.draw_list,
+                        &mut draw_list,
                         ImageDrawParams {
                             pos: fg_pos.into(),
                             size: fg_size.into(),
@@ -171,38 +320,102 @@ impl GLRenderer {
                             color: widget.image_color(),
                         },
                     );
+                    draw_list.finish();
+                }
+
+                if let Some(triangles) = widget.triangles() {
+                    let image = context.themes().backplate();
+                    self.write_group_if_changed(&mut draw_mode, DrawMode::Triangles(image.texture()), None);
+                    let mut draw_list = TransformedDrawList::new(&mut self.draw_list, widget.transform(), scale);
+                    triangles.draw(&mut draw_list, widget, scale);
+                    draw_list.finish();
                 }
 
                 if let Some(text) = widget.text() {
                     if let Some(font_sum) = widget.font() {
-                        self.write_group_if_changed(
-                            &mut draw_mode,
-                            DrawMode::Font(font_sum.handle),
-                        );
                         let font = context.themes().font(font_sum.handle);
 
-                        let params = FontDrawParams {
+                        let mut params = FontDrawParams {
                             area_size: fg_size * scale,
                             pos: fg_pos * scale,
                             indent: widget.text_indent(),
                             align: widget.text_align(),
+                            wrap: widget.text_wrap(),
+                            soft_hyphens: widget.text_soft_hyphens(),
                             color: widget.text_color(),
                             scale_factor: context.scale_factor(),
+                            visible_chars: widget.text_reveal(),
                         };
 
-                        font.draw(
-                            &mut self.draw_list,
+                        if context.high_contrast() {
+                            let contrast = HighContrastText::new(font, params, text, widget.clip() * scale);
+                            params.color = contrast.text_color;
+
+                            if let Some(plate) = contrast.plate {
+                                let image = context.themes().backplate();
+                                self.write_group_if_changed(&mut draw_mode, DrawMode::image(image.texture(), transformed), None);
+                                let mut draw_list = TransformedDrawList::new(&mut self.draw_list, widget.transform(), scale);
+                                image.draw(
+                                    &mut draw_list,
+                                    ImageDrawParams {
+                                        pos: plate.pos.into(),
+                                        size: plate.size.into(),
+                                        anim_state: AnimState::normal(),
+                                        clip: widget.clip(),
+                                        time_millis,
+                                        scale,
+                                        color: contrast.plate_color,
+                                    }
+                                );
+                                draw_list.finish();
+                            }
+                        }
+
+                        self.write_group_if_changed(
+                            &mut draw_mode,
+                            DrawMode::font(font_sum.handle, transformed),
+                            None,
+                        );
+
+                        let mut draw_list = TransformedDrawList::new(&mut self.draw_list, widget.transform(), scale);
+                        let inline_images = font.draw(
+                            &mut draw_list,
                             params,
                             text,
                             widget.clip() * scale,
-                        )
+                        );
+                        draw_list.finish();
+
+                        for (index, rect) in inline_images {
+                            let image_handle = match widget.inline_images().get(index) {
+                                None => continue,
+                                Some(handle) => *handle,
+                            };
+                            let image = context.themes().image(image_handle);
+                            self.write_group_if_changed(&mut draw_mode, DrawMode::image(image.texture(), transformed), None);
+
+                            let mut draw_list = TransformedDrawList::new(&mut self.draw_list, widget.transform(), scale);
+                            image.draw(
+                                &mut draw_list,
+                                ImageDrawParams {
+                                    pos: (rect.pos / scale).into(),
+                                    size: (rect.size / scale).into(),
+                                    anim_state: widget.anim_state(),
+                                    clip: widget.clip(),
+                                    time_millis: time_millis - context.base_time_millis_for(widget.id()),
+                                    scale,
+                                    color: widget.image_color(),
+                                },
+                            );
+                            draw_list.finish();
+                        }
                     }
                 }
             }
 
             // render anything from the final draw calls
-            if let Some(mode) = draw_mode {
-                self.write_group(mode);
+            if let Some((mode, shader)) = draw_mode {
+                self.write_group(mode, shader);
             }
         }
 
@@ -224,33 +437,57 @@ impl GLRenderer {
             };
 
             image.draw(&mut self.draw_list, params);
-            self.write_group(DrawMode::Image(image.texture()));
+            self.write_group(DrawMode::Image(image.texture()), None);
         }
 
+        let quads = self.groups.iter().map(|group| group.mode.primitives(group.end - group.start)).sum();
+        context.record_quads(quads);
+
         unsafe {
             gl::Enable(gl::FRAMEBUFFER_SRGB);
         }
+        let programs = match self.programs.as_ref() {
+            None => return,
+            Some(programs) => programs,
+        };
+
         // create the vertex buffer and draw all groups
         let vao = VAO::new(&self.draw_list.vertices);
         vao.bind();
 
-        let font_uniform_tex = self.font_program.get_uniform_location("tex");
-        let font_uniform_matrix = self.font_program.get_uniform_location("matrix");
+        let font_uniform_tex = programs.font.get_uniform_location("tex");
+        let font_uniform_matrix = programs.font.get_uniform_location("matrix");
+        let font_uniform_color_transform = programs.font.get_uniform_location("color_transform");
+
+        let base_uniform_tex = programs.base.get_uniform_location("tex");
+        let base_uniform_matrix = programs.base.get_uniform_location("matrix");
+        let base_uniform_color_transform = programs.base.get_uniform_location("color_transform");
+
+        let triangle_uniform_tex = programs.triangle.get_uniform_location("tex");
+        let triangle_uniform_matrix = programs.triangle.get_uniform_location("matrix");
+        let triangle_uniform_color_transform = programs.triangle.get_uniform_location("color_transform");
 
-        let base_uniform_tex = self.base_program.get_uniform_location("tex");
-        let base_uniform_matrix = self.base_program.get_uniform_location("matrix");
+        let font_triangle_uniform_tex = programs.font_triangle.get_uniform_location("tex");
+        let font_triangle_uniform_matrix = programs.font_triangle.get_uniform_location("matrix");
+        let font_triangle_uniform_color_transform = programs.font_triangle.get_uniform_location("color_transform");
 
         for group in &self.groups {
+            if let Some(uniforms) = group.shader {
+                if self.draw_custom_group(group, uniforms) { continue; }
+            }
+
             match group.mode {
                 DrawMode::Font(font_handle) => {
                     let font = self.font(font_handle);
 
                     font.bind(0);
-                    self.font_program.use_program();
+                    programs.font.use_program();
 
-                    self.font_program
+                    programs.font
                         .uniform_matrix4fv(font_uniform_matrix, false, &self.matrix);
-                    self.font_program.uniform1i(font_uniform_tex, 0);
+                    programs.font
+                        .uniform_matrix3fv(font_uniform_color_transform, false, &self.color_transform);
+                    programs.font.uniform1i(font_uniform_tex, 0);
 
                     unsafe {
                         gl::DrawArrays(gl::POINTS, group.start as _, (group.end - group.start) as _)
@@ -260,11 +497,13 @@ impl GLRenderer {
                     let texture = self.texture(tex_handle);
 
                     texture.bind(0);
-                    self.base_program.use_program();
+                    programs.base.use_program();
 
-                    self.base_program.uniform1i(base_uniform_tex, 0);
-                    self.base_program
+                    programs.base.uniform1i(base_uniform_tex, 0);
+                    programs.base
                         .uniform_matrix4fv(base_uniform_matrix, false, &self.matrix);
+                    programs.base
+                        .uniform_matrix3fv(base_uniform_color_transform, false, &self.color_transform);
 
                     unsafe {
                         gl::Disable(gl::FRAMEBUFFER_SRGB);
@@ -273,30 +512,101 @@ impl GLRenderer {
                         gl::DrawArrays(gl::POINTS, group.start as _, (group.end - group.start) as _)
                     };
                 }
+                DrawMode::Triangles(tex_handle) => {
+                    let texture = self.texture(tex_handle);
+
+                    texture.bind(0);
+                    programs.triangle.use_program();
+
+                    programs.triangle.uniform1i(triangle_uniform_tex, 0);
+                    programs.triangle
+                        .uniform_matrix4fv(triangle_uniform_matrix, false, &self.matrix);
+                    programs.triangle
+                        .uniform_matrix3fv(triangle_uniform_color_transform, false, &self.color_transform);
+
+                    unsafe {
+                        gl::Disable(gl::FRAMEBUFFER_SRGB);
+                    }
+                    unsafe {
+                        gl::DrawArrays(gl::TRIANGLES, group.start as _, (group.end - group.start) as _)
+                    };
+                }
+                DrawMode::FontTriangles(font_handle) => {
+                    let font = self.font(font_handle);
+
+                    font.bind(0);
+                    programs.font_triangle.use_program();
+
+                    programs.font_triangle
+                        .uniform_matrix4fv(font_triangle_uniform_matrix, false, &self.matrix);
+                    programs.font_triangle
+                        .uniform_matrix3fv(font_triangle_uniform_color_transform, false, &self.color_transform);
+                    programs.font_triangle.uniform1i(font_triangle_uniform_tex, 0);
+
+                    unsafe {
+                        gl::DrawArrays(gl::TRIANGLES, group.start as _, (group.end - group.start) as _)
+                    };
+                }
             };
         }
     }
 
-    fn write_group_if_changed(&mut self, mode: &mut Option<DrawMode>, desired_mode: DrawMode) {
+    /// Draws the `group` with its custom shader.  Returns false if the shader is not available, in which
+    /// case the group should be drawn normally.
+    fn draw_custom_group(&self, group: &DrawGroup, uniforms: ShaderUniforms) -> bool {
+        let programs = match self.shaders[uniforms.shader].programs.as_ref() {
+            None => return false,
+            Some(programs) => programs,
+        };
+
+        let (program, texture, primitive) = match group.mode {
+            DrawMode::Image(tex_handle) => (&programs.image, tex_handle, gl::POINTS),
+            DrawMode::Triangles(tex_handle) => (&programs.triangles, tex_handle, gl::TRIANGLES),
+            DrawMode::Font(_) | DrawMode::FontTriangles(_) => return false,
+        };
+
+        self.texture(texture).bind(0);
+        program.use_program();
+        program.uniform1i(program.get_uniform_location("tex"), 0);
+        program.uniform_matrix4fv(program.get_uniform_location("matrix"), false, &self.matrix);
+        program.uniform_matrix3fv(program.get_uniform_location("color_transform"), false, &self.color_transform);
+        program.uniform1f(program.get_uniform_location("time"), self.time_secs);
+        program.uniform4f(program.get_uniform_location("widget_rect"), uniforms.rect);
+        program.uniform1i(program.get_uniform_location("anim_state"), uniforms.anim_state);
+
+        unsafe {
+            gl::Disable(gl::FRAMEBUFFER_SRGB);
+            gl::DrawArrays(primitive, group.start as _, (group.end - group.start) as _);
+        }
+
+        true
+    }
+
+    fn write_group_if_changed(
+        &mut self,
+        mode: &mut Option<(DrawMode, Option<ShaderUniforms>)>,
+        desired_mode: DrawMode,
+        shader: Option<ShaderUniforms>,
+    ) {
         match mode {
-            None => *mode = Some(desired_mode),
-            Some(cur_mode) => {
-                if *cur_mode != desired_mode {
-                    self.write_group(*cur_mode);
-                    *mode = Some(desired_mode);
+            None => *mode = Some((desired_mode, shader)),
+            Some((cur_mode, cur_shader)) => {
+                if *cur_mode != desired_mode || *cur_shader != shader {
+                    self.write_group(*cur_mode, *cur_shader);
+                    *mode = Some((desired_mode, shader));
                 }
             }
         }
     }
 
-    fn write_group(&mut self, mode: DrawMode) {
+    fn write_group(&mut self, mode: DrawMode, shader: Option<ShaderUniforms>) {
         let end = self.draw_list.vertices.len();
         // if this is the first draw group, start at 0
         let start = match self.groups.last() {
             None => 0,
             Some(group) => group.end,
         };
-        self.groups.push(DrawGroup { start, end, mode });
+        self.groups.push(DrawGroup { start, end, mode, shader });
     }
 }
 
@@ -316,12 +626,39 @@ impl Renderer for GLRenderer {
             gl::RGBA8,
         );
 
-        assert!(handle.id() <= self.textures.len());
-        if handle.id() == self.textures.len() {
-            self.textures.push(gl_texture);
-        } else {
-            self.textures[handle.id()] = gl_texture;
-        }
+        store_handle(&mut self.textures, handle.id(), gl_texture, empty_texture);
+
+        Ok(TextureData::new(handle, dimensions.0, dimensions.1))
+    }
+
+    fn register_compressed_texture(
+        &mut self,
+        handle: TextureHandle,
+        image_data: &[u8],
+        dimensions: (u32, u32),
+        format: CompressedFormat,
+    ) -> Result<TextureData, crate::Error> {
+        // the S3TC and ASTC formats are extensions, and so are not included in the core bindings
+        const COMPRESSED_RGBA_S3TC_DXT1_EXT: u32 = 0x83F1;
+        const COMPRESSED_RGBA_S3TC_DXT5_EXT: u32 = 0x83F3;
+        const COMPRESSED_RGBA_ASTC_4X4_KHR: u32 = 0x93B0;
+
+        let internal_format = match format {
+            CompressedFormat::Bc1 => COMPRESSED_RGBA_S3TC_DXT1_EXT,
+            CompressedFormat::Bc3 => COMPRESSED_RGBA_S3TC_DXT5_EXT,
+            CompressedFormat::Bc7 => gl::COMPRESSED_RGBA_BPTC_UNORM,
+            CompressedFormat::Astc4x4 => COMPRESSED_RGBA_ASTC_4X4_KHR,
+        };
+
+        let gl_texture = GLTexture::new_compressed(
+            image_data,
+            dimensions,
+            gl::LINEAR,
+            gl::CLAMP_TO_EDGE,
+            internal_format,
+        ).ok_or_else(|| crate::Error::Theme(format!("The graphics device does not support {:?} compressed textures", format)))?;
+
+        store_handle(&mut self.textures, handle.id(), gl_texture, empty_texture);
 
         Ok(TextureData::new(handle, dimensions.0, dimensions.1))
     }
@@ -349,21 +686,54 @@ impl Renderer for GLRenderer {
             gl::R8,
         );
 
-        assert!(handle.id() <= self.fonts.len());
-        if handle.id() == self.fonts.len() {
-            self.fonts.push(font_texture);
-        } else {
-            self.fonts[handle.id()] = font_texture;
-        }
+        store_handle(&mut self.fonts, handle.id(), font_texture, empty_texture);
 
         Ok(writer_out.font)
     }
 }
 
+/// A placeholder for a texture or font handle which has not been registered
+fn empty_texture() -> GLTexture {
+    GLTexture::new(&[0, 0, 0, 0], (1, 1), gl::NEAREST, gl::CLAMP_TO_EDGE, gl::RGBA, gl::RGBA8)
+}
+
 struct DrawGroup {
     start: usize,
     end: usize,
     mode: DrawMode,
+    shader: Option<ShaderUniforms>,
+}
+
+/// The per widget uniforms for drawing a group with a custom shader
+#[derive(Copy, Clone, PartialEq)]
+struct ShaderUniforms {
+    shader: usize,
+    rect: [f32; 4],
+    anim_state: i32,
+}
+
+/// A custom fragment shader registered by name, with the source retained to rebuild it after a suspend
+struct CustomShader {
+    name: String,
+    source: String,
+
+    // None while suspended
+    programs: Option<CustomPrograms>,
+}
+
+/// The programs for drawing images and triangles with a custom fragment shader
+struct CustomPrograms {
+    image: Program,
+    triangles: Program,
+}
+
+impl CustomPrograms {
+    fn new(fragment_src: &str) -> Result<CustomPrograms, String> {
+        Ok(CustomPrograms {
+            image: Program::try_new(VERT_SHADER_SRC, Some(GEOM_SHADER_SRC), fragment_src)?,
+            triangles: Program::try_new(TRIANGLE_VERT_SHADER_SRC, None, fragment_src)?,
+        })
+    }
 }
 
 // Pass through the vertex to the geometry shader where the rectangle is built
@@ -412,6 +782,7 @@ const GEOM_SHADER_SRC: &str = r#"
 
   out vec2 v_tex_coords;
   out vec4 v_color;
+  out vec2 v_pos;
 
   uniform mat4 matrix;
 
@@ -430,6 +801,7 @@ const GEOM_SHADER_SRC: &str = r#"
     gl_ClipDistance[2] = position.y - clip_pos.y;
     gl_ClipDistance[3] = clip_pos.y + clip_size.y - position.y;
 	gl_Position = matrix * position;
+	v_pos = position.xy;
 	v_tex_coords = g_tex0[0];
 	v_color = g_color[0];
 	EmitVertex();
@@ -441,6 +813,7 @@ const GEOM_SHADER_SRC: &str = r#"
     gl_ClipDistance[2] = position.y - clip_pos.y;
     gl_ClipDistance[3] = clip_pos.y + clip_size.y - position.y;
 	gl_Position = matrix * position;
+	v_pos = position.xy;
 	v_tex_coords = vec2(g_tex0[0].x, g_tex1[0].y);
 	v_color = g_color[0];
     EmitVertex();
@@ -452,6 +825,7 @@ const GEOM_SHADER_SRC: &str = r#"
     gl_ClipDistance[2] = position.y - clip_pos.y;
     gl_ClipDistance[3] = clip_pos.y + clip_size.y - position.y;
 	gl_Position = matrix * position;
+	v_pos = position.xy;
 	v_tex_coords = vec2(g_tex1[0].x, g_tex0[0].y);
 	v_color = g_color[0];
     EmitVertex();
@@ -463,6 +837,7 @@ const GEOM_SHADER_SRC: &str = r#"
     gl_ClipDistance[2] = position.y - clip_pos.y;
     gl_ClipDistance[3] = clip_pos.y + clip_size.y - position.y;
     gl_Position = matrix * position;
+    v_pos = position.xy;
     v_tex_coords = g_tex1[0];
     v_color = g_color[0];
     EmitVertex();
@@ -471,6 +846,34 @@ const GEOM_SHADER_SRC: &str = r#"
   }
 "#;
 
+// Triangles are drawn directly from their vertices, using only the position, first tex coord, color, and clip
+const TRIANGLE_VERT_SHADER_SRC: &str = r#"
+  #version 330
+
+  layout(location = 0) in vec2 position;
+  layout(location = 2) in vec2 tex0;
+  layout(location = 4) in vec4 color;
+  layout(location = 5) in vec2 clip_pos;
+  layout(location = 6) in vec2 clip_size;
+
+  out vec2 v_tex_coords;
+  out vec4 v_color;
+  out vec2 v_pos;
+
+  uniform mat4 matrix;
+
+  void main() {
+    gl_ClipDistance[0] = position.x - clip_pos.x;
+    gl_ClipDistance[1] = clip_pos.x + clip_size.x - position.x;
+    gl_ClipDistance[2] = position.y - clip_pos.y;
+    gl_ClipDistance[3] = clip_pos.y + clip_size.y - position.y;
+    gl_Position = matrix * vec4(position, 0.0, 1.0);
+    v_pos = position;
+    v_tex_coords = tex0;
+    v_color = color;
+  }
+"#;
+
 const FRAGMENT_SHADER_SRC: &str = r#"
   #version 150
 
@@ -480,9 +883,11 @@ const FRAGMENT_SHADER_SRC: &str = r#"
   out vec4 color;
 
   uniform sampler2D tex;
+  uniform mat3 color_transform;
 
   void main() {
     color = v_color * texture(tex, v_tex_coords);
+    color.rgb = color_transform * color.rgb;
   }
 "#;
 
@@ -495,12 +900,31 @@ const FONT_FRAGMENT_SHADER_SRC: &str = r#"
     out vec4 color;
 
     uniform sampler2D tex;
+    uniform mat3 color_transform;
     
     void main() {
-        color = vec4(v_color.rgb, texture(tex, v_tex_coords).r);
+        color = vec4(color_transform * v_color.rgb, texture(tex, v_tex_coords).r);
     }
 "#;
 
+struct Programs {
+    base: Program,
+    font: Program,
+    triangle: Program,
+    font_triangle: Program,
+}
+
+impl Programs {
+    fn new() -> Programs {
+        Programs {
+            base: Program::new(VERT_SHADER_SRC, Some(GEOM_SHADER_SRC), FRAGMENT_SHADER_SRC),
+            font: Program::new(VERT_SHADER_SRC, Some(GEOM_SHADER_SRC), FONT_FRAGMENT_SHADER_SRC),
+            triangle: Program::new(TRIANGLE_VERT_SHADER_SRC, None, FRAGMENT_SHADER_SRC),
+            font_triangle: Program::new(TRIANGLE_VERT_SHADER_SRC, None, FONT_FRAGMENT_SHADER_SRC),
+        }
+    }
+}
+
 struct GLDrawList {
     vertices: Vec<GLVertex>,
 }
@@ -549,6 +973,23 @@ impl DrawList for GLDrawList {
 
         self.vertices.push(vert);
     }
+
+    fn push_triangles(&mut self, vertices: &[DrawVertex], indices: &[u32], clip: Rect) {
+        for triangle in triangle_vertices(vertices, indices) {
+            for vertex in triangle {
+                let tex = [vertex.tex.x(), vertex.tex.y()];
+                self.vertices.push(GLVertex {
+                    position: vertex.pos,
+                    size: [0.0, 0.0],
+                    tex0: tex,
+                    tex1: tex,
+                    color: vertex.color.into(),
+                    clip_pos: clip.pos.into(),
+                    clip_size: clip.size.into(),
+                });
+            }
+        }
+    }
 }
 
 #[derive(Copy, Clone)]
@@ -574,6 +1015,9 @@ pub enum GlError {
 
     /// No window was able to be created
     NoWindow,
+
+    /// A custom shader failed to compile, with the compile log
+    Shader(String),
 }
 
 impl std::fmt::Display for GlError {
@@ -583,6 +1027,7 @@ impl std::fmt::Display for GlError {
             Glutin(e) => write!(f, "Glutin error: {}", e),
             DipslayContextCreation(e) => write!(f, "Display Context Creation: {}", e),
             NoWindow => write!(f, "No window was created."),
+            Shader(log) => write!(f, "Shader compile error: {}", log),
         }
     }
 }
@@ -593,7 +1038,7 @@ impl Error for GlError {
         match self {
             Glutin(e) => Some(e),
             DipslayContextCreation(e) => Some(e.as_ref()),
-            NoWindow => None,
+            NoWindow | Shader(_) => None,
         }
     }
 }
\ No newline at end of file
@@ -3,26 +3,57 @@ pub struct Program {
 }
 
 impl Program {
-    pub fn new(vertex_shader: &str, geom_shader: &str, fragment_shader: &str) -> Program {
-        let program_handle = unsafe { gl::CreateProgram() };
+    pub fn new(vertex_shader: &str, geom_shader: Option<&str>, fragment_shader: &str) -> Program {
+        match Program::try_new(vertex_shader, geom_shader, fragment_shader) {
+            Ok(program) => program,
+            Err(e) => panic!("Error creating shader program: {}", e),
+        }
+    }
+
+    /// Creates a program, returning the compile or link log if any shader is invalid
+    pub fn try_new(vertex_shader: &str, geom_shader: Option<&str>, fragment_shader: &str) -> Result<Program, String> {
+        let shaders = unsafe {
+            let mut shaders = vec![create_shader(gl::VERTEX_SHADER, vertex_shader)];
+            if let Some(src) = geom_shader {
+                shaders.push(create_shader(gl::GEOMETRY_SHADER, src));
+            }
+            shaders.push(create_shader(gl::FRAGMENT_SHADER, fragment_shader));
+            shaders
+        };
+
+        if let Some(Err(e)) = shaders.iter().find(|shader| shader.is_err()) {
+            let e = e.clone();
+            for shader in shaders.into_iter().flatten() {
+                unsafe { gl::DeleteShader(shader); }
+            }
+            return Err(e);
+        }
 
-        let vertex_shader = unsafe { create_shader(gl::VERTEX_SHADER, vertex_shader) };
-        let geom_shader = unsafe { create_shader(gl::GEOMETRY_SHADER, geom_shader) };
-        let fragment_shader = unsafe { create_shader(gl::FRAGMENT_SHADER, fragment_shader) };
+        let program_handle = unsafe { gl::CreateProgram() };
+        let program = Program { program_handle };
 
         unsafe {
-            gl::AttachShader(program_handle, vertex_shader);
-            gl::AttachShader(program_handle, geom_shader);
-            gl::AttachShader(program_handle, fragment_shader);
+            for shader in shaders.iter().flatten() {
+                gl::AttachShader(program_handle, *shader);
+            }
 
             gl::LinkProgram(program_handle);
 
-            gl::DeleteShader(vertex_shader);
-            gl::DeleteShader(geom_shader);
-            gl::DeleteShader(fragment_shader);
+            for shader in shaders.iter().flatten() {
+                gl::DeleteShader(*shader);
+            }
+
+            let mut success = gl::FALSE as gl::types::GLint;
+            gl::GetProgramiv(program_handle, gl::LINK_STATUS, &mut success);
+            if success != gl::TRUE as gl::types::GLint {
+                let info_log = [0u8; 513];
+                let mut error_size = 0i32;
+                gl::GetProgramInfoLog(program_handle, 512, &mut error_size, info_log.as_ptr() as _);
+                return Err(String::from_utf8_lossy(&info_log[..error_size as usize]).to_string());
+            }
         }
 
-        Program { program_handle }
+        Ok(program)
     }
 
     pub fn uniform_matrix4fv(
@@ -36,12 +67,35 @@ impl Program {
         }
     }
 
+    pub fn uniform_matrix3fv(
+        &self,
+        uniform_location: i32,
+        transposed: bool,
+        matrix: &[[f32; 3]; 3],
+    ) {
+        unsafe {
+            gl::UniformMatrix3fv(uniform_location, 1, transposed as u8, matrix.as_ptr() as _);
+        }
+    }
+
     pub fn uniform1i(&self, uniform_location: i32, value: i32) {
         unsafe {
             gl::Uniform1i(uniform_location, value);
         }
     }
 
+    pub fn uniform1f(&self, uniform_location: i32, value: f32) {
+        unsafe {
+            gl::Uniform1f(uniform_location, value);
+        }
+    }
+
+    pub fn uniform4f(&self, uniform_location: i32, value: [f32; 4]) {
+        unsafe {
+            gl::Uniform4f(uniform_location, value[0], value[1], value[2], value[3]);
+        }
+    }
+
     pub fn get_uniform_location(&self, name: &str) -> i32 {
         let name = std::ffi::CString::new(name).unwrap();
         unsafe { gl::GetUniformLocation(self.program_handle, name.as_ptr() as _) }
@@ -54,8 +108,8 @@ impl Program {
     }
 }
 
-unsafe fn create_shader(shader_type: u32, src: &str) -> u32 {
-    let shader_str = std::ffi::CString::new(src).unwrap();
+unsafe fn create_shader(shader_type: u32, src: &str) -> Result<u32, String> {
+    let shader_str = std::ffi::CString::new(src).map_err(|e| e.to_string())?;
     
     let gl_handle = gl::CreateShader(shader_type);
     gl::ShaderSource(gl_handle, 1, &shader_str.as_ptr() as _, std::ptr::null());
@@ -67,14 +121,12 @@ unsafe fn create_shader(shader_type: u32, src: &str) -> u32 {
         let info_log = [0u8; 513];
         let mut error_size = 0i32;
         gl::GetShaderInfoLog(gl_handle, 512, &mut error_size, info_log.as_ptr() as _);
-        let info_log = std::str::from_utf8(&info_log[..error_size as usize]);
-        panic!(
-            "Error compile failed with error: {:?} for: {:?}",
-            info_log, gl_handle
-        );
+        let info_log = String::from_utf8_lossy(&info_log[..error_size as usize]).to_string();
+        gl::DeleteShader(gl_handle);
+        return Err(info_log);
     }
 
-    gl_handle
+    Ok(gl_handle)
 }
 
 impl Drop for Program {
@@ -57,6 +57,55 @@ impl GLTexture {
         texture
     }
 
+    /// Creates a texture from GPU compressed `image_data`, returning `None` if the driver rejects the
+    /// `internal_format`, typically because it is not supported by the device.
+    pub fn new_compressed(
+        image_data: &[u8],
+        dimensions: (u32, u32),
+        filter: u32,
+        wrap: u32,
+        internal_format: u32,
+    ) -> Option<GLTexture> {
+        let mut texture = GLTexture {
+            texture_handle: 0,
+            data: image_data.to_vec(),
+        };
+
+        unsafe {
+            // clear any previous error so that only errors from the upload are checked
+            while gl::GetError() != gl::NO_ERROR {}
+
+            gl::GenTextures(1, &mut texture.texture_handle);
+            gl::BindTexture(gl::TEXTURE_2D, texture.texture_handle);
+
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, wrap as _);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, wrap as _);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_R, wrap as _);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, filter as _);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, filter as _);
+
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_BASE_LEVEL, 0);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAX_LEVEL, 0);
+
+            gl::CompressedTexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                internal_format,
+                dimensions.0 as _,
+                dimensions.1 as _,
+                0,
+                texture.data.len() as _,
+                texture.data.as_ptr() as _,
+            );
+
+            if gl::GetError() != gl::NO_ERROR {
+                return None;
+            }
+        }
+
+        Some(texture)
+    }
+
     pub fn bind(&self, idx: i32) {
         let bind_location = match idx {
             0 => gl::TEXTURE0,
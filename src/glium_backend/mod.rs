@@ -6,14 +6,20 @@ use std::borrow::Cow;
 use glium::{implement_vertex, uniform, DrawParameters, program::{ProgramCreationError, ProgramCreationInput}, Program, Surface};
 use glium::backend::{Context, Facade};
 use glium::uniforms::{MagnifySamplerFilter, MinifySamplerFilter, Sampler, SamplerBehavior, SamplerWrapFunction};
-use glium::texture::{Texture2d, RawImage2d};
+use glium::texture::{CompressedTexture2d, Texture2d, RawImage2d};
 use glium::index::PrimitiveType;
 
 use crate::image::ImageDrawParams;
-use crate::render::{view_matrix, TexCoord, DrawList, DrawMode, Renderer, TextureHandle, TextureData, FontHandle};
+use crate::render::{
+    store_handle, triangle_vertices, view_matrix, DisplayRotation, TexCoord, DrawList, DrawMode, DrawVertex, Renderer, TextureHandle,
+    TextureData, FontHandle, TransformedDrawList,
+};
 use crate::font::{Font, FontSource, FontTextureWriter, FontDrawParams};
 use crate::theme_definition::CharacterRange;
-use crate::{Frame, Point, Color, Rect};
+use crate::accessibility::HighContrastText;
+use crate::compressed::CompressedFormat;
+use crate::widget::Widget;
+use crate::{AnimState, ColorTransform, Frame, Point, Color, Rect};
 
 /// A Thyme [`Renderer`](trait.Renderer.html) for [`Glium`](https://github.com/glium/glium).
 ///
@@ -37,15 +43,21 @@ pub struct GliumRenderer {
     context: Rc<Context>,
     base_program: Program,
     font_program: Program,
+    triangle_program: Program,
+    font_triangle_program: Program,
 
     // assets loaded from the context
-    textures: Vec<GliumTexture>,
+    textures: Vec<GliumTexture<ImageTexture>>,
     fonts: Vec<GliumTexture>,
 
+    // custom shaders used by widgets, which are not supported and have already been warned about
+    unsupported_shaders: Vec<String>,
+
     // per frame data
     draw_list: GliumDrawList,
     groups: Vec<DrawGroup>,
     matrix: [[f32; 4]; 4],
+    color_transform: [[f32; 3]; 3],
     params: DrawParameters<'static>,
 }
 
@@ -82,15 +94,47 @@ impl GliumRenderer {
             },
         )?;
 
+        let triangle_program = Program::new(
+            facade,
+            ProgramCreationInput::SourceCode {
+                vertex_shader: TRIANGLE_VERT_SHADER_SRC,
+                tessellation_control_shader: None,
+                tessellation_evaluation_shader: None,
+                geometry_shader: None,
+                fragment_shader: FRAGMENT_SHADER_SRC,
+                transform_feedback_varyings: None,
+                outputs_srgb: true,
+                uses_point_size: false,
+            },
+        )?;
+
+        let font_triangle_program = Program::new(
+            facade,
+            ProgramCreationInput::SourceCode {
+                vertex_shader: TRIANGLE_VERT_SHADER_SRC,
+                tessellation_control_shader: None,
+                tessellation_evaluation_shader: None,
+                geometry_shader: None,
+                fragment_shader: FONT_FRAGMENT_SHADER_SRC,
+                transform_feedback_varyings: None,
+                outputs_srgb: true,
+                uses_point_size: false,
+            },
+        )?;
+
         Ok(GliumRenderer {
             context,
             base_program,
             font_program,
+            triangle_program,
+            font_triangle_program,
             fonts: Vec::new(),
             textures: Vec::new(),
+            unsupported_shaders: Vec::new(),
             draw_list: GliumDrawList::new(),
             groups: Vec::new(),
-            matrix: view_matrix(Point::default(), Point { x: 100.0, y: 100.0 }),
+            matrix: view_matrix(Point::default(), Point { x: 100.0, y: 100.0 }, DisplayRotation::Deg0),
+            color_transform: ColorTransform::None.uniform(),
             params: DrawParameters {
                 blend: glium::Blend::alpha_blending(),
                 clip_planes_bitmask: 0b1111, //enable the first 4 clip planes
@@ -103,10 +147,42 @@ impl GliumRenderer {
         &self.fonts[font.id()]
     }
 
-    fn texture(&self, texture: TextureHandle) -> &GliumTexture {
+    fn texture(&self, texture: TextureHandle) -> &GliumTexture<ImageTexture> {
         &self.textures[texture.id()]
     }
 
+    /// Logs a warning the first time a widget uses each custom shader, as they are only supported by the GL renderer
+    fn check_shader(&mut self, widget: &Widget) {
+        let name = match widget.shader() {
+            None => return,
+            Some(name) => name,
+        };
+
+        if !self.unsupported_shaders.iter().any(|shader| shader == name) {
+            log::warn!("Custom shader '{}' used by widget '{}' is not supported by the Glium renderer", name, widget.id());
+            self.unsupported_shaders.push(name.to_string());
+        }
+    }
+
+    fn store_texture(&mut self, handle: TextureHandle, texture: ImageTexture) {
+        let sampler = SamplerBehavior {
+            minify_filter: MinifySamplerFilter::Linear,
+            magnify_filter: MagnifySamplerFilter::Linear,
+            wrap_function: (
+                SamplerWrapFunction::Clamp,
+                SamplerWrapFunction::Clamp,
+                SamplerWrapFunction::Clamp,
+            ),
+            ..Default::default()
+        };
+
+        let context = &self.context;
+        store_handle(&mut self.textures, handle.id(), GliumTexture { texture, sampler }, || GliumTexture {
+            texture: ImageTexture::Rgba(empty_texture(context)),
+            sampler,
+        });
+    }
+
     /// Draws the specified [`Frame`](struct.Frame.html) to the Glium surface, usually the Glium Frame.
     pub fn draw_frame<T: Surface>(&mut self, target: &mut T, frame: Frame) -> Result<(), GliumError> {
         let mouse_cursor = frame.mouse_cursor();
@@ -117,7 +193,8 @@ impl GliumRenderer {
         let display_pos = Point::default();
         let display_size = context.display_size();
         let scale = context.scale_factor();
-        self.matrix = view_matrix(display_pos, display_size);
+        self.matrix = view_matrix(display_pos, display_size, context.display_rotation());
+        self.color_transform = context.color_transform().uniform();
 
         self.draw_list.clear();
         self.groups.clear();
@@ -127,18 +204,21 @@ impl GliumRenderer {
 
             // render backgrounds
             for widget in render_group.iter(&widgets) {
-                if !widget.visible() { continue; }
+                if !widget.visible() || widget.clipped_out() { continue; }
+                self.check_shader(widget);
                 let image_handle = match widget.background() {
                     None => continue,
                     Some(handle) => handle,
                 };
                 let time_millis = time_millis - context.base_time_millis_for(widget.id());
                 let image = context.themes().image(image_handle);
+                let transformed = widget.transform().is_some();
     
-                self.write_group_if_changed(&mut draw_mode, DrawMode::Image(image.texture()));
+                self.write_group_if_changed(&mut draw_mode, DrawMode::image(image.texture(), transformed));
                 
+                let mut draw_list = TransformedDrawList::new(&mut self.draw_list, widget.transform(), scale);
                 image.draw(
-                    &mut self.draw_list,
+                    &mut draw_list,
                     ImageDrawParams {
                         pos: widget.pos().into(),
                         size: widget.size().into(),
@@ -149,23 +229,26 @@ impl GliumRenderer {
                         color: widget.image_color(),
                     }
                 );
+                draw_list.finish();
             }
 
             // render foregrounds & text
             for widget in render_group.iter(&widgets) {
-                if !widget.visible() { continue; }
+                if !widget.visible() || widget.clipped_out() { continue; }
 
                 let border = widget.border();
                 let fg_pos = widget.pos() + border.tl();
                 let fg_size = widget.inner_size();
+                let transformed = widget.transform().is_some();
     
                 if let Some(image_handle) = widget.foreground() {
                     let time_millis = time_millis - context.base_time_millis_for(widget.id());
                     let image = context.themes().image(image_handle);
-                    self.write_group_if_changed(&mut draw_mode, DrawMode::Image(image.texture()));
+                    self.write_group_if_changed(&mut draw_mode, DrawMode::image(image.texture(), transformed));
     
+                    let mut draw_list = TransformedDrawList::new(&mut self.draw_list, widget.transform(), scale);
                     image.draw(
-                        &mut self.draw_list,
+                        &mut draw_list,
                         ImageDrawParams {
                             pos: fg_pos.into(),
                             size: fg_size.into(),
@@ -176,28 +259,91 @@ impl GliumRenderer {
                             color: widget.image_color(),
                         }
                     );
+                    draw_list.finish();
+                }
+
+                if let Some(triangles) = widget.triangles() {
+                    let image = context.themes().backplate();
+                    self.write_group_if_changed(&mut draw_mode, DrawMode::Triangles(image.texture()));
+                    let mut draw_list = TransformedDrawList::new(&mut self.draw_list, widget.transform(), scale);
+                    triangles.draw(&mut draw_list, widget, scale);
+                    draw_list.finish();
                 }
     
                 if let Some(text) = widget.text() {
                     if let Some(font_sum) = widget.font() {
-                        self.write_group_if_changed(&mut draw_mode, DrawMode::Font(font_sum.handle));
                         let font = context.themes().font(font_sum.handle);
     
-                        let params = FontDrawParams {
+                        let mut params = FontDrawParams {
                             area_size: fg_size * scale,
                             pos: fg_pos * scale,
                             indent: widget.text_indent(),
                             align: widget.text_align(),
+                            wrap: widget.text_wrap(),
+                            soft_hyphens: widget.text_soft_hyphens(),
                             color: widget.text_color(),
                             scale_factor: context.scale_factor(),
+                            visible_chars: widget.text_reveal(),
                         };
 
-                        font.draw(
-                            &mut self.draw_list,
+                        if context.high_contrast() {
+                            let contrast = HighContrastText::new(font, params, text, widget.clip() * scale);
+                            params.color = contrast.text_color;
+
+                            if let Some(plate) = contrast.plate {
+                                let image = context.themes().backplate();
+                                self.write_group_if_changed(&mut draw_mode, DrawMode::image(image.texture(), transformed));
+                                let mut draw_list = TransformedDrawList::new(&mut self.draw_list, widget.transform(), scale);
+                                image.draw(
+                                    &mut draw_list,
+                                    ImageDrawParams {
+                                        pos: plate.pos.into(),
+                                        size: plate.size.into(),
+                                        anim_state: AnimState::normal(),
+                                        clip: widget.clip(),
+                                        time_millis,
+                                        scale,
+                                        color: contrast.plate_color,
+                                    }
+                                );
+                                draw_list.finish();
+                            }
+                        }
+
+                        self.write_group_if_changed(&mut draw_mode, DrawMode::font(font_sum.handle, transformed));
+
+                        let mut draw_list = TransformedDrawList::new(&mut self.draw_list, widget.transform(), scale);
+                        let inline_images = font.draw(
+                            &mut draw_list,
                             params,
                             text,
                             widget.clip() * scale,
-                        )
+                        );
+                        draw_list.finish();
+
+                        for (index, rect) in inline_images {
+                            let image_handle = match widget.inline_images().get(index) {
+                                None => continue,
+                                Some(handle) => *handle,
+                            };
+                            let image = context.themes().image(image_handle);
+                            self.write_group_if_changed(&mut draw_mode, DrawMode::image(image.texture(), transformed));
+
+                            let mut draw_list = TransformedDrawList::new(&mut self.draw_list, widget.transform(), scale);
+                            image.draw(
+                                &mut draw_list,
+                                ImageDrawParams {
+                                    pos: (rect.pos / scale).into(),
+                                    size: (rect.size / scale).into(),
+                                    anim_state: widget.anim_state(),
+                                    clip: widget.clip(),
+                                    time_millis: time_millis - context.base_time_millis_for(widget.id()),
+                                    scale,
+                                    color: widget.image_color(),
+                                },
+                            );
+                            draw_list.finish();
+                        }
                     }
                 }
             }
@@ -229,6 +375,9 @@ impl GliumRenderer {
             self.write_group(DrawMode::Image(image.texture()));
         }
 
+        let quads = self.groups.iter().map(|group| group.mode.primitives(group.end - group.start)).sum();
+        context.record_quads(quads);
+
         // create the vertex buffer and draw all groups
         let vertices = glium::VertexBuffer::immutable(
             &self.context, &self.draw_list.vertices
@@ -237,31 +386,22 @@ impl GliumRenderer {
         for group in &self.groups {
             match group.mode {
                 DrawMode::Font(font_handle) => {
-                    let font = self.font(font_handle);
-                    let uniforms = uniform! {
-                        tex: Sampler(&font.texture, font.sampler),
-                        matrix: self.matrix,
-                    };
-                    target.draw(
-                        vertices.slice(group.start..group.end).unwrap(),
-                        indices,
-                        &self.font_program,
-                        &uniforms,
-                        &self.params
-                    )?;
+                    let slice = vertices.slice(group.start..group.end).unwrap();
+                    self.draw_font(target, slice, indices, &self.font_program, font_handle)?;
                 },
                 DrawMode::Image(tex_handle) => {
-                    let texture = self.texture(tex_handle);
-                    let uniforms = uniform! {
-                        tex: Sampler(&texture.texture, texture.sampler),
-                        matrix: self.matrix,
-                    };
-                    target.draw(vertices.slice(group.start..group.end).unwrap(),
-                        indices,
-                        &self.base_program,
-                        &uniforms,
-                        &self.params
-                    )?;
+                    let slice = vertices.slice(group.start..group.end).unwrap();
+                    self.draw_image(target, slice, indices, &self.base_program, tex_handle)?;
+                },
+                DrawMode::Triangles(tex_handle) => {
+                    let slice = vertices.slice(group.start..group.end).unwrap();
+                    let indices = glium::index::NoIndices(PrimitiveType::TrianglesList);
+                    self.draw_image(target, slice, indices, &self.triangle_program, tex_handle)?;
+                }
+                DrawMode::FontTriangles(font_handle) => {
+                    let slice = vertices.slice(group.start..group.end).unwrap();
+                    let indices = glium::index::NoIndices(PrimitiveType::TrianglesList);
+                    self.draw_font(target, slice, indices, &self.font_triangle_program, font_handle)?;
                 }
             };
         }
@@ -269,6 +409,58 @@ impl GliumRenderer {
         Ok(())
     }
 
+    fn draw_font<T: Surface>(
+        &self,
+        target: &mut T,
+        vertices: glium::vertex::VertexBufferSlice<GliumVertex>,
+        indices: glium::index::NoIndices,
+        program: &Program,
+        font_handle: FontHandle,
+    ) -> Result<(), GliumError> {
+        let font = self.font(font_handle);
+        let uniforms = uniform! {
+            tex: Sampler(&font.texture, font.sampler),
+            matrix: self.matrix,
+            color_transform: self.color_transform,
+        };
+        target.draw(vertices, indices, program, &uniforms, &self.params)?;
+
+        Ok(())
+    }
+
+    fn draw_image<T: Surface>(
+        &self,
+        target: &mut T,
+        vertices: glium::vertex::VertexBufferSlice<GliumVertex>,
+        indices: glium::index::NoIndices,
+        program: &Program,
+        tex_handle: TextureHandle,
+    ) -> Result<(), GliumError> {
+        let texture = self.texture(tex_handle);
+
+        // the uniforms have a different type for each kind of texture
+        match &texture.texture {
+            ImageTexture::Rgba(tex) => {
+                let uniforms = uniform! {
+                    tex: Sampler(tex, texture.sampler),
+                    matrix: self.matrix,
+                    color_transform: self.color_transform,
+                };
+                target.draw(vertices, indices, program, &uniforms, &self.params)?;
+            },
+            ImageTexture::Compressed(tex) => {
+                let uniforms = uniform! {
+                    tex: Sampler(tex, texture.sampler),
+                    matrix: self.matrix,
+                    color_transform: self.color_transform,
+                };
+                target.draw(vertices, indices, program, &uniforms, &self.params)?;
+            },
+        }
+
+        Ok(())
+    }
+
     fn write_group_if_changed(
         &mut self,
         mode: &mut Option<DrawMode>,
@@ -306,25 +498,37 @@ impl Renderer for GliumRenderer {
         dimensions: (u32, u32),
     ) -> Result<TextureData, crate::Error> {
         let image = RawImage2d::from_raw_rgba(image_data.to_vec(), dimensions);
-        let texture = Texture2d::new(&self.context, image).unwrap();
+        let texture = ImageTexture::Rgba(Texture2d::new(&self.context, image).unwrap());
+        self.store_texture(handle, texture);
 
-        let sampler = SamplerBehavior {
-            minify_filter: MinifySamplerFilter::Linear,
-            magnify_filter: MagnifySamplerFilter::Linear,
-            wrap_function: (
-                SamplerWrapFunction::Clamp,
-                SamplerWrapFunction::Clamp,
-                SamplerWrapFunction::Clamp,
+        Ok(TextureData::new(handle, dimensions.0, dimensions.1))
+    }
+
+    fn register_compressed_texture(
+        &mut self,
+        handle: TextureHandle,
+        image_data: &[u8],
+        dimensions: (u32, u32),
+        format: CompressedFormat,
+    ) -> Result<TextureData, crate::Error> {
+        let glium_format = match format {
+            CompressedFormat::Bc1 => glium::texture::CompressedFormat::S3tcDxt1Alpha,
+            CompressedFormat::Bc3 => glium::texture::CompressedFormat::S3tcDxt5Alpha,
+            CompressedFormat::Bc7 => glium::texture::CompressedFormat::BptcUnorm4,
+            CompressedFormat::Astc4x4 => return Err(
+                crate::Error::Theme("The Glium renderer does not support ASTC compressed textures".to_string())
             ),
-            ..Default::default()
         };
 
-        assert!(handle.id() <= self.textures.len());
-        if handle.id() == self.textures.len() {
-            self.textures.push(GliumTexture { texture, sampler });
-        } else {
-            self.textures[handle.id()] = GliumTexture { texture, sampler };
-        }
+        let texture = CompressedTexture2d::with_compressed_data(
+            &self.context,
+            image_data,
+            dimensions.0,
+            dimensions.1,
+            glium_format,
+            glium::texture::CompressedMipmapsOption::NoMipmap,
+        ).map_err(|e| crate::Error::Theme(format!("Unable to create {:?} compressed texture: {:?}", format, e)))?;
+        self.store_texture(handle, ImageTexture::Compressed(texture));
 
         Ok(TextureData::new(handle, dimensions.0, dimensions.1))
     }
@@ -366,29 +570,37 @@ impl Renderer for GliumRenderer {
             ..Default::default()
         };
 
-        assert!(handle.id() <= self.fonts.len());
-        if handle.id() == self.fonts.len() {
-            self.fonts.push(GliumTexture { texture: font_tex, sampler });
-        } else {
-            self.fonts[handle.id()] = GliumTexture { texture: font_tex, sampler };
-        }
-        
+        let context = &self.context;
+        store_handle(&mut self.fonts, handle.id(), GliumTexture { texture: font_tex, sampler }, || GliumTexture {
+            texture: empty_texture(context),
+            sampler,
+        });
 
         Ok(writer_out.font)
     }
 }
 
+/// A placeholder for a texture or font handle which has not been registered
+fn empty_texture(context: &Rc<Context>) -> Texture2d {
+    Texture2d::empty(context, 1, 1).unwrap()
+}
+
 struct DrawGroup {
     start: usize,
     end: usize,
     mode: DrawMode,
 }
 
-struct GliumTexture {
-    texture: Texture2d,
+struct GliumTexture<T = Texture2d> {
+    texture: T,
     sampler: SamplerBehavior,
 }
 
+enum ImageTexture {
+    Rgba(Texture2d),
+    Compressed(CompressedTexture2d),
+}
+
 /// An Error originating from the [`GliumRenderer`](struct.GliumRenderer.html)
 #[derive(Debug)]
 pub enum GliumError {
@@ -578,6 +790,32 @@ const GEOM_SHADER_SRC: &str = r#"
   }
 "#;
 
+// Triangles are drawn directly from their vertices, using only the position, first tex coord, color, and clip
+const TRIANGLE_VERT_SHADER_SRC: &str = r#"
+  #version 140
+
+  in vec2 position;
+  in vec2 tex0;
+  in vec4 color;
+  in vec2 clip_pos;
+  in vec2 clip_size;
+
+  out vec2 v_tex_coords;
+  out vec4 v_color;
+
+  uniform mat4 matrix;
+
+  void main() {
+    gl_ClipDistance[0] = position.x - clip_pos.x;
+    gl_ClipDistance[1] = clip_pos.x + clip_size.x - position.x;
+    gl_ClipDistance[2] = position.y - clip_pos.y;
+    gl_ClipDistance[3] = clip_pos.y + clip_size.y - position.y;
+    gl_Position = matrix * vec4(position, 0.0, 1.0);
+    v_tex_coords = tex0;
+    v_color = color;
+  }
+"#;
+
 const FRAGMENT_SHADER_SRC: &str = r#"
   #version 140
 
@@ -587,9 +825,11 @@ const FRAGMENT_SHADER_SRC: &str = r#"
   out vec4 color;
 
   uniform sampler2D tex;
+  uniform mat3 color_transform;
 
   void main() {
     color = v_color * texture(tex, v_tex_coords);
+    color.rgb = color_transform * color.rgb;
   }
 "#;
 
@@ -602,9 +842,10 @@ const FONT_FRAGMENT_SHADER_SRC: &str = r#"
     out vec4 color;
 
     uniform sampler2D tex;
+    uniform mat3 color_transform;
     
     void main() {
-        color = vec4(v_color.rgb, texture(tex, v_tex_coords).r);
+        color = vec4(color_transform * v_color.rgb, texture(tex, v_tex_coords).r);
     }
 "#;
 
@@ -654,6 +895,23 @@ impl DrawList for GliumDrawList {
 
         self.vertices.push(vert);
     }
+
+    fn push_triangles(&mut self, vertices: &[DrawVertex], indices: &[u32], clip: Rect) {
+        for triangle in triangle_vertices(vertices, indices) {
+            for vertex in triangle {
+                let tex = [vertex.tex.x(), vertex.tex.y()];
+                self.vertices.push(GliumVertex {
+                    position: vertex.pos,
+                    size: [0.0, 0.0],
+                    tex0: tex,
+                    tex1: tex,
+                    color: vertex.color.into(),
+                    clip_pos: clip.pos.into(),
+                    clip_size: clip.size.into(),
+                });
+            }
+        }
+    }
 }
 
 #[derive(Copy, Clone)]
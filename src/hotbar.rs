@@ -0,0 +1,189 @@
+use crate::{Frame, Rect};
+
+/// A slot in a [`hotbar`](struct.Frame.html#method.hotbar).
+#[derive(Copy, Clone, Debug, Default)]
+pub struct HotbarSlot<'a> {
+    /// The image of the item in this slot, with an ID of the form "{image_set_id}/{image_id}", or `None`
+    /// if the slot is empty
+    pub image: Option<&'a str>,
+
+    /// The number of items stacked in this slot.  The count is only shown when it is greater than one.
+    pub count: u32,
+
+    /// The remaining fraction of the item's cooldown, from 1.0 when it starts down to 0.0 when the item is ready
+    pub cooldown: f32,
+
+    /// The label of the key bound to this slot, if any
+    pub keybind: Option<&'a str>,
+}
+
+/// An interaction with a [`hotbar`](struct.Frame.html#method.hotbar).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum HotbarEvent {
+    /// The item in the slot with the specified index was clicked while it was ready
+    Activated(usize),
+
+    /// The item in slot `from` was dragged and dropped onto slot `to`.  Typically, the items in the two
+    /// slots should be swapped.
+    Moved {
+        /// The slot the item was dragged from
+        from: usize,
+
+        /// The slot the item was dropped onto
+        to: usize,
+    },
+
+    /// The item in the slot with the specified index was dragged and dropped outside of the hotbar
+    DroppedOutside(usize),
+}
+
+impl Frame {
+    /**
+    A row of equally sized item `slots`, such as the action bar of a game.  Each `slot` child shows its
+    item as the foreground of its `icon` child, or an `empty` child if it has no item, along with a `keybind`
+    label, a `count` badge for stacks, and a cooldown `overlay`, as in a
+    [`cooldown_button`](#method.cooldown_button).  Items may be dragged between slots, with the dragged item
+    drawn by the `drag_icon` child, centered on the mouse and above the other slots.  A slot is dragged as soon as it is pressed, and
+    dropping an item back onto its own slot activates it.  The `id` must be unique.  Returns any interaction
+    with the hotbar this frame.
+
+    An example theme definition:
+    ```yaml
+    hotbar:
+      layout: Horizontal
+      layout_spacing: [4, 0]
+      size_from: [Children, Children]
+      children:
+        slot:
+          wants_mouse: true
+          background: gui/slot
+          size: [40, 40]
+          border: { all: 2 }
+          children:
+            icon:
+              width_from: Parent
+              height_from: Parent
+            empty:
+              width_from: Parent
+              height_from: Parent
+              foreground: gui/empty_slot
+            overlay:
+              width_from: Parent
+              height_from: Parent
+              image_color: "#0008"
+            keybind:
+              font: small
+              align: TopLeft
+              size_from: [Text, FontLine]
+            count:
+              font: small
+              align: BotRight
+              size_from: [Text, FontLine]
+        drag_icon:
+          size: [36, 36]
+    ```
+
+    # Example
+    ```
+    fn action_bar(ui: &mut Frame, actions: &mut [Option<&'static str>; 4]) {
+        let keys = ["1", "2", "3", "4"];
+        let slots: Vec<HotbarSlot> = actions.iter().zip(keys.iter()).map(|(action, key)| HotbarSlot {
+            image: *action,
+            keybind: Some(key),
+            ..Default::default()
+        }).collect();
+
+        match ui.hotbar("hotbar", "action_bar", &slots) {
+            Some(HotbarEvent::Activated(index)) => println!("Using {:?}", actions[index]),
+            Some(HotbarEvent::Moved { from, to }) => actions.swap(from, to),
+            Some(HotbarEvent::DroppedOutside(index)) => actions[index] = None,
+            None => (),
+        }
+    }
+    ```
+    **/
+    pub fn hotbar(&mut self, theme: &str, id: &str, slots: &[HotbarSlot]) -> Option<HotbarEvent> {
+        let dragging = self.context_internal().borrow_mut().drag_source(id).filter(|index| *index < slots.len());
+        let mouse_pos = self.mouse_pos();
+
+        let builder = self.start(theme);
+        let fill = builder.custom_string("cooldown_style", "sweep".to_string()) == "fill";
+
+        let mut result = None;
+        let mut drag_start = None;
+        let mut dropped = false;
+
+        builder.id(id).children(|ui| {
+            let mut rects = Vec::with_capacity(slots.len());
+            for (index, slot) in slots.iter().enumerate() {
+                let mut rect = Rect::default();
+                let state = ui.start_indexed("slot", index as u64)
+                .wants_mouse(true)
+                .active(dragging == Some(index))
+                .trigger_layout(&mut rect)
+                .children(|ui| {
+                    match slot.image {
+                        Some(image) => if dragging != Some(index) {
+                            ui.start("icon").foreground(image).finish();
+                        },
+                        None => { ui.child("empty"); },
+                    }
+
+                    if slot.cooldown > 0.0 {
+                        ui.cooldown_overlay(slot.cooldown.min(1.0), fill);
+                    }
+
+                    if let Some(keybind) = slot.keybind {
+                        ui.label("keybind", keybind);
+                    }
+
+                    if slot.count > 1 {
+                        ui.label("count", slot.count.to_string());
+                    }
+                });
+                rects.push(rect);
+
+                if dragging.is_none() {
+                    if state.pressed && slot.image.is_some() {
+                        drag_start = Some(index);
+                    } else if state.clicked && slot.image.is_some() && slot.cooldown <= 0.0 {
+                        result = Some(HotbarEvent::Activated(index));
+                    }
+                } else if dragging == Some(index) && !state.pressed {
+                    dropped = true;
+                }
+            }
+
+            let from = match dragging {
+                None => return,
+                Some(from) => from,
+            };
+
+            if dropped {
+                let slot = &slots[from];
+                result = match rects.iter().position(|rect| rect.is_inside(mouse_pos)) {
+                    Some(to) if to == from => if slot.cooldown <= 0.0 { Some(HotbarEvent::Activated(from)) } else { None },
+                    Some(to) => Some(HotbarEvent::Moved { from, to }),
+                    None => Some(HotbarEvent::DroppedOutside(from)),
+                };
+            } else if let Some(image) = slots[from].image {
+                let mut rect = Rect::default();
+                ui.start("drag_icon")
+                .trigger_layout(&mut rect)
+                .screen_pos(mouse_pos.x - rect.size.x / 2.0, mouse_pos.y - rect.size.y / 2.0)
+                .foreground(image)
+                .unparent()
+                .unclip()
+                .finish();
+            }
+        });
+
+        if let Some(index) = drag_start {
+            self.context_internal().borrow_mut().set_drag_source(Some((id.to_string(), index)));
+        } else if dropped {
+            self.context_internal().borrow_mut().set_drag_source(None);
+        }
+
+        result
+    }
+}
@@ -47,7 +47,8 @@ enum ImageKind {
         once: bool,
     },
     Animated {
-        states: Vec<(AnimState, Image)>
+        states: Vec<(AnimState, Image)>,
+        tints: Vec<(AnimState, Color)>,
     }
 }
 
@@ -74,6 +75,16 @@ impl Image {
 
     pub fn base_size(&self) -> Point { self.base_size }
 
+    /// Returns whether this image, or any of its sub images, changes over time
+    pub(crate) fn is_timed(&self) -> bool {
+        match &self.kind {
+            ImageKind::Timed { .. } => true,
+            ImageKind::Collected { sub_images } => sub_images.iter().any(|sub| sub.image.is_timed()),
+            ImageKind::Animated { states, .. } => states.iter().any(|(_, image)| image.is_timed()),
+            _ => false,
+        }
+    }
+
     pub(crate) fn draw<D: DrawList>(
         &self,
         draw_list: &mut D,
@@ -220,8 +231,8 @@ impl Image {
                     frames[frame_index].draw(draw_list, params);
                 }
             },
-            ImageKind::Animated { states } => {
-                self.draw_animated(draw_list, states, params);
+            ImageKind::Animated { states, tints } => {
+                self.draw_animated(draw_list, states, tints, params);
             }
         }
     }
@@ -331,7 +342,7 @@ impl Image {
                 base_size = size;
                 ImageKind::Timed { frame_time_millis: *frame_time_millis, frames: frames_out, once: *once }
             },
-            ImageDefinitionKind::Animated { states } => {
+            ImageDefinitionKind::Animated { states, tints } => {
                 let mut size = Point::default();
                 let mut states_out: Vec<(AnimState, Image)> = Vec::new();
                 for (state, id) in states {
@@ -341,7 +352,10 @@ impl Image {
                 }
 
                 base_size = size;
-                ImageKind::Animated { states: states_out }
+                ImageKind::Animated {
+                    states: states_out,
+                    tints: tints.iter().map(|(state, color)| (*state, *color)).collect(),
+                }
             }
         };
 
@@ -357,13 +371,21 @@ impl Image {
         &self,
         draw_list: &mut D,
         states: &[(AnimState, Image)],
-        params: ImageDrawParams,
+        tints: &[(AnimState, Color)],
+        mut params: ImageDrawParams,
     ) {
-        for (state, image) in states {
-            if state == &params.anim_state {
-                image.draw(draw_list, params);
-                break;
+        let tint = tints.iter().find(|(state, _)| *state == params.anim_state).map(|(_, color)| *color);
+
+        // tinted states without their own image use the normal image
+        let image = states.iter().find(|(state, _)| *state == params.anim_state).or_else(|| {
+            tint.and_then(|_| states.iter().find(|(state, _)| *state == AnimState::normal()))
+        });
+
+        if let Some((_, image)) = image {
+            if let Some(tint) = tint {
+                params.color = params.color * tint;
             }
+            image.draw(draw_list, params);
         }
     }
 
@@ -0,0 +1,54 @@
+/// Transparent pixels left between packed images, and around the edge of the atlas, to avoid sampling neighbors
+const PADDING: u32 = 1;
+
+/// RGBA image data combining several images, with the position of each within the atlas
+pub(crate) struct PackedAtlas {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+    pub positions: Vec<[u32; 2]>,
+}
+
+/**
+Packs the specified RGBA `images`, each consisting of the data, width, and height, into a single atlas.
+Images are placed on rows ordered from tallest to shortest, with the atlas width chosen to give a roughly
+square result.  The returned positions are in the same order as `images`.
+**/
+pub(crate) fn pack_images(images: &[(&[u8], u32, u32)]) -> PackedAtlas {
+    let area: u64 = images.iter().map(|(_, w, h)| u64::from(w + PADDING) * u64::from(h + PADDING)).sum();
+    let max_width = images.iter().map(|(_, w, _)| *w).max().unwrap_or_default();
+    let width = ((area as f64).sqrt().ceil() as u32).max(max_width + 2 * PADDING);
+
+    let mut order: Vec<usize> = (0..images.len()).collect();
+    order.sort_by_key(|index| std::cmp::Reverse(images[*index].2));
+
+    let mut positions = vec![[0, 0]; images.len()];
+    let mut x = PADDING;
+    let mut y = PADDING;
+    let mut row_height = 0;
+    for index in order {
+        let (_, w, h) = images[index];
+        if x + w + PADDING > width {
+            x = PADDING;
+            y += row_height + PADDING;
+            row_height = 0;
+        }
+
+        positions[index] = [x, y];
+        x += w + PADDING;
+        row_height = row_height.max(h);
+    }
+    let height = y + row_height + PADDING;
+
+    let mut data = vec![0; (width * height * 4) as usize];
+    for ((image, w, h), [x, y]) in images.iter().zip(positions.iter()) {
+        let row_len = (*w * 4) as usize;
+        for row in 0..*h {
+            let src = (row * w * 4) as usize;
+            let dest = (((y + row) * width + x) * 4) as usize;
+            data[dest..dest + row_len].copy_from_slice(&image[src..src + row_len]);
+        }
+    }
+
+    PackedAtlas { width, height, data, positions }
+}
@@ -0,0 +1,207 @@
+use crate::{Form, Frame};
+
+/**
+A value which can be shown and edited in an [`inspector`](struct.Frame.html#method.inspector).  `Inspect` is
+implemented for `bool`, the integer and floating point primitives, and `String`.  It may be implemented for
+structs with [`inspect_struct`](macro.inspect_struct.html) and for enums with
+[`inspect_enum`](macro.inspect_enum.html).
+**/
+pub trait Inspect {
+    /// Creates the widgets for editing this value.  The `id` is unique to this value and may be used
+    /// for any widgets requiring an id.  Returns whether the value was changed.
+    fn inspect(&mut self, ui: &mut Frame, id: &str) -> bool;
+
+    /// Adds this value as a row in the `form` of its parent struct, with the specified `label`.  By default, this
+    /// adds a [`field`](struct.Form.html#method.field) containing the widgets from [`inspect`](#tymethod.inspect).
+    /// Returns whether the value was changed.
+    fn inspect_field(&mut self, form: &mut Form, id: &str, label: &str) -> bool {
+        form.field(label, false, None, |ui| self.inspect(ui, id)).unwrap_or_default()
+    }
+}
+
+impl Inspect for bool {
+    fn inspect(&mut self, ui: &mut Frame, _id: &str) -> bool {
+        ui.bind_checkbox("checkbox", "", self)
+    }
+}
+
+impl Inspect for String {
+    fn inspect(&mut self, ui: &mut Frame, id: &str) -> bool {
+        ui.bind_text("input_field", id, self)
+    }
+}
+
+macro_rules! impl_inspect_int {
+    ($($ty:ty),*) => {
+        $(
+            impl Inspect for $ty {
+                fn inspect(&mut self, ui: &mut Frame, _id: &str) -> bool {
+                    match ui.spinner("spinner", *self, <$ty>::MIN, <$ty>::MAX) {
+                        -1 => *self = self.saturating_sub(1),
+                        1 => *self = self.saturating_add(1),
+                        _ => return false,
+                    }
+                    true
+                }
+            }
+        )*
+    };
+}
+
+impl_inspect_int!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+macro_rules! impl_inspect_float {
+    ($($ty:ty),*) => {
+        $(
+            impl Inspect for $ty {
+                fn inspect(&mut self, ui: &mut Frame, _id: &str) -> bool {
+                    let delta = ui.wrapping_spinner("spinner", format!("{:.2}", self));
+                    *self += delta as $ty * 0.1;
+                    delta != 0
+                }
+            }
+        )*
+    };
+}
+
+impl_inspect_float!(f32, f64);
+
+impl Frame {
+    /**
+    An inspector, showing an editable property grid for `value`.  Structs are shown as a `form`, with one
+    row per field, while nested structs are shown in a `section`
+    [`collapsing_header`](#method.collapsing_header).  Booleans are edited with a `checkbox`
+    [`toggle_button`](#method.toggle_button), integers and floats with a `spinner`, or a `slider` if a range
+    is specified in [`inspect_struct`](macro.inspect_struct.html), strings with an `input_field`, and enums with
+    a `combo_box`.  Floats without a range are changed in steps of 0.1.  The `id` must be unique.  Returns whether
+    anything was changed.
+
+    An example theme definition:
+    ```yaml
+    inspector:
+      layout: Vertical
+      size_from: [Parent, Children]
+      children:
+        form:
+          from: form
+        section:
+          from: collapsing_header
+        checkbox:
+          from: button
+          size: [24, 24]
+        slider:
+          from: horizontal_slider
+        spinner:
+          from: spinner
+        input_field:
+          from: input_field
+          width_from: Parent
+        combo_box:
+          from: combo_box
+    ```
+
+    # Example
+    ```
+    #[derive(Clone, PartialEq)]
+    enum Quality { Low, High }
+
+    impl std::fmt::Display for Quality {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "{}", match self { Quality::Low => "Low", Quality::High => "High" })
+        }
+    }
+
+    struct Light { intensity: f32, color: String }
+    struct Settings { name: String, enabled: bool, count: u32, quality: Quality, light: Light }
+
+    thyme::inspect_enum!(Quality { Low, High });
+    thyme::inspect_struct!(Light { intensity in 0.0..=1.0, color });
+    thyme::inspect_struct!(Settings { name, enabled, count, quality, light });
+
+    fn settings_window(ui: &mut Frame, settings: &mut Settings) {
+        if ui.inspector("inspector", "settings", settings) {
+            println!("Settings changed");
+        }
+    }
+    ```
+    **/
+    pub fn inspector<T: Inspect + ?Sized>(&mut self, theme: &str, id: &str, value: &mut T) -> bool {
+        let mut changed = false;
+        self.start(theme).id(id).children(|ui| {
+            changed = value.inspect(ui, &format!("{}_value", id));
+        });
+        changed
+    }
+}
+
+/**
+Implements [`Inspect`](trait.Inspect.html) for a struct, showing each of the listed fields in a row of a form,
+labeled with the field name.  Each field must itself implement `Inspect`.  An `f32` field may be followed by
+`in` and a range, such as `volume in 0.0..=1.0`, to edit it with a slider.  Nested structs are shown in a
+collapsing section.  See [`inspector`](struct.Frame.html#method.inspector).
+
+# Example
+```
+struct Options { volume: f32, show_fps: bool, name: String }
+
+thyme::inspect_struct!(Options { volume in 0.0..=1.0, show_fps, name });
+```
+**/
+#[macro_export]
+macro_rules! inspect_struct {
+    ($ty:ty { $($field:ident $(in $range:expr)?),* $(,)? }) => {
+        impl $crate::Inspect for $ty {
+            fn inspect(&mut self, ui: &mut $crate::Frame, id: &str) -> bool {
+                let mut changed = false;
+                ui.form("form", &format!("{}_form", id), |form| {
+                    $(
+                        let field_id = format!("{}_{}", id, stringify!($field));
+                        changed |= $crate::inspect_struct!(@field self.$field, form, &field_id, stringify!($field) $(, $range)?);
+                    )*
+                });
+                changed
+            }
+
+            fn inspect_field(&mut self, form: &mut $crate::Form, id: &str, label: &str) -> bool {
+                let mut changed = false;
+                form.frame().collapsing_header("section", id, label, |ui| {
+                    changed = self.inspect(ui, id);
+                });
+                changed
+            }
+        }
+    };
+    (@field $value:expr, $form:ident, $id:expr, $label:expr) => {
+        $crate::Inspect::inspect_field(&mut $value, $form, $id, $label)
+    };
+    (@field $value:expr, $form:ident, $id:expr, $label:expr, $range:expr) => {
+        $form.field($label, false, None, |ui| ui.bind_slider("slider", &mut $value, $range)).unwrap_or_default()
+    };
+}
+
+/**
+Implements [`Inspect`](trait.Inspect.html) for an enum with the listed unit variants, shown as a combo box.
+The enum must implement `Clone`, `PartialEq`, and `Display`.  See [`inspector`](struct.Frame.html#method.inspector).
+
+# Example
+```
+thyme::inspect_enum!(Quality { Low, Medium, High });
+```
+**/
+#[macro_export]
+macro_rules! inspect_enum {
+    ($ty:ident { $($variant:ident),* $(,)? }) => {
+        impl $crate::Inspect for $ty {
+            fn inspect(&mut self, ui: &mut $crate::Frame, id: &str) -> bool {
+                let values = [$($ty::$variant),*];
+                match ui.combo_box("combo_box", id, self, values.iter()) {
+                    Some(value) if value != self => {
+                        *self = value.clone();
+                        true
+                    },
+                    _ => false,
+                }
+            }
+        }
+    };
+}
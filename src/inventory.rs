@@ -0,0 +1,305 @@
+use crate::{Align, Frame, Point, Rect};
+
+/// An item in an [`inventory`](struct.Frame.html#method.inventory) grid.
+#[derive(Copy, Clone, Debug)]
+pub struct InventoryItem<'a> {
+    /// The column and row of the cell containing the top left corner of this item
+    pub pos: [u32; 2],
+
+    /// The number of columns and rows of cells occupied by this item
+    pub size: [u32; 2],
+
+    /// The image of this item, with an ID of the form "{image_set_id}/{image_id}"
+    pub image: &'a str,
+
+    /// The number of items in this stack.  The count is only shown when it is greater than one.
+    pub count: u32,
+
+    /// An image drawn around this item, such as to show its rarity, with an ID of the form
+    /// "{image_set_id}/{image_id}"
+    pub frame: Option<&'a str>,
+}
+
+impl<'a> InventoryItem<'a> {
+    /// Creates a new single cell item with the specified `image`, at the specified column and row
+    pub fn new(image: &'a str, column: u32, row: u32) -> InventoryItem<'a> {
+        InventoryItem { pos: [column, row], size: [1, 1], image, count: 1, frame: None }
+    }
+}
+
+/// An interaction with an [`inventory`](struct.Frame.html#method.inventory) grid.  Items are identified
+/// by their index in the list of items passed to the inventory.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum InventoryEvent {
+    /// The item was clicked, or dragged and dropped back in place
+    Clicked(usize),
+
+    /// The item was dragged to empty cells, with its top left corner at the column and row `to`
+    Moved {
+        /// The dragged item
+        item: usize,
+
+        /// The new column and row of the item
+        to: [u32; 2],
+    },
+
+    /// The item was dragged onto exactly one `other` item, and the two should typically be swapped or merged
+    Swapped {
+        /// The dragged item
+        item: usize,
+
+        /// The item it was dropped onto
+        other: usize,
+    },
+
+    /// The item was dragged to empty cells while holding Shift, and its stack should typically be split,
+    /// with part of it placed at the column and row `to`
+    Split {
+        /// The dragged item
+        item: usize,
+
+        /// The column and row for the split off part of the stack
+        to: [u32; 2],
+    },
+
+    /// The item was dragged and dropped outside of the inventory
+    DroppedOutside(usize),
+}
+
+impl Frame {
+    /**
+    A grid of `columns` by `rows` square cells, containing the specified `items`.  Each cell is drawn with
+    a `cell` child, sized by the theme `custom` floats `cell_size` and `cell_spacing`, defaulting to 40 and 2.
+    Each item is drawn with an `item` child covering its cells, containing its `frame`, `icon`, and `count`.
+    When an item is hovered, `tooltip` is called with the item index to add the children of a `tooltip`
+    widget.
+
+    Items are dragged as soon as they are pressed, with the `drag_icon` child following the mouse and the
+    `drop_target` child showing the cells the item would be dropped onto.  The `id` must be unique.
+    Returns any interaction with the inventory this frame.  The inventory does not modify the items, and
+    so the application decides whether a move is allowed, how stacks are split, and so on.
+
+    An example theme definition:
+    ```yaml
+    inventory:
+      background: gui/window_bg
+      size_from: [Children, Children]
+      border: { all: 4 }
+      custom:
+        cell_size: 40.0
+        cell_spacing: 2.0
+      children:
+        cell:
+          background: gui/slot
+        drop_target:
+          background: gui/slot_highlight
+        item:
+          wants_mouse: true
+          children:
+            frame:
+              width_from: Parent
+              height_from: Parent
+            icon:
+              width_from: Parent
+              height_from: Parent
+              border: { all: 2 }
+            count:
+              font: small
+              align: BotRight
+              size_from: [Text, FontLine]
+        drag_icon:
+          image_color: "#FFFA"
+        tooltip:
+          background: gui/button
+          size_from: [Children, Children]
+          border: { all: 4 }
+          children:
+            name:
+              font: small
+              size_from: [Text, FontLine]
+    ```
+
+    # Example
+    ```
+    struct Item {
+        name: String,
+        image: String,
+        count: u32,
+        pos: [u32; 2],
+    }
+
+    fn backpack(ui: &mut Frame, items: &mut Vec<Item>) {
+        let grid: Vec<InventoryItem> = items.iter().map(|item| InventoryItem {
+            count: item.count,
+            ..InventoryItem::new(&item.image, item.pos[0], item.pos[1])
+        }).collect();
+
+        let event = ui.inventory("inventory", "backpack", 8, 4, &grid, |ui, index| {
+            ui.label("name", items[index].name.clone());
+        });
+
+        match event {
+            Some(InventoryEvent::Moved { item, to }) => items[item].pos = to,
+            Some(InventoryEvent::Swapped { item, other }) => {
+                let pos = items[item].pos;
+                items[item].pos = items[other].pos;
+                items[other].pos = pos;
+            },
+            _ => (),
+        }
+    }
+    ```
+    **/
+    pub fn inventory<F: FnMut(&mut Frame, usize)>(
+        &mut self,
+        theme: &str,
+        id: &str,
+        columns: u32,
+        rows: u32,
+        items: &[InventoryItem],
+        mut tooltip: F,
+    ) -> Option<InventoryEvent> {
+        let dragging = self.context_internal().borrow_mut().drag_source(id).filter(|index| *index < items.len());
+        let mouse_pos = self.mouse_pos();
+        let split = self.input_modifiers().shift;
+        let grab_offset = self.modify(id, |state| state.grab_offset).unwrap_or_default();
+
+        let builder = self.start(theme);
+        let cell_size = builder.custom_float("cell_size", 40.0);
+        let spacing = builder.custom_float("cell_spacing", 2.0);
+        let stride = cell_size + spacing;
+        let extent = |cells: u32| cells as f32 * stride - spacing;
+
+        let mut result = None;
+        let mut drag_start = None;
+        let mut dropped = false;
+
+        builder.id(id).children(|ui| {
+            let grid = Rect::new(ui.parent_bounds().pos, Point::new(extent(columns), extent(rows)));
+
+            for row in 0..rows {
+                for column in 0..columns {
+                    ui.start_indexed("cell", u64::from(row * columns + column))
+                    .align(Align::TopLeft)
+                    .pos(column as f32 * stride, row as f32 * stride)
+                    .size(cell_size, cell_size)
+                    .finish();
+                }
+            }
+
+            // the cell under the top left corner of the dragged item, if the item fits in the grid there
+            let target = dragging.and_then(|index| {
+                let [width, height] = items[index].size;
+                let corner = mouse_pos - grab_offset - grid.pos;
+                let (column, row) = ((corner.x / stride).round(), (corner.y / stride).round());
+                if column < 0.0 || row < 0.0 { return None; }
+
+                let (column, row) = (column as u32, row as u32);
+                if column + width > columns || row + height > rows { return None; }
+                Some([column, row])
+            });
+
+            if let (Some(index), Some([column, row])) = (dragging, target) {
+                let [width, height] = items[index].size;
+                ui.start("drop_target")
+                .align(Align::TopLeft)
+                .pos(column as f32 * stride, row as f32 * stride)
+                .size(extent(width), extent(height))
+                .finish();
+            }
+
+            for (index, item) in items.iter().enumerate() {
+                let [column, row] = item.pos;
+                let [width, height] = item.size;
+
+                let mut rect = Rect::default();
+                let state = ui.start_indexed("item", index as u64)
+                .align(Align::TopLeft)
+                .pos(column as f32 * stride, row as f32 * stride)
+                .size(extent(width), extent(height))
+                .wants_mouse(true)
+                .active(dragging == Some(index))
+                .trigger_layout(&mut rect)
+                .children(|ui| {
+                    if let Some(frame) = item.frame {
+                        ui.start("frame").foreground(frame).finish();
+                    }
+
+                    ui.start("icon").foreground(item.image).finish();
+
+                    if item.count > 1 {
+                        ui.label("count", item.count.to_string());
+                    }
+                });
+
+                if dragging.is_none() {
+                    if state.pressed {
+                        drag_start = Some((index, mouse_pos - rect.pos));
+                    } else if state.clicked {
+                        result = Some(InventoryEvent::Clicked(index));
+                    } else if state.hovered {
+                        ui.start("tooltip").render_as_tooltip().children(|ui| tooltip(ui, index));
+                    }
+                } else if dragging == Some(index) && !state.pressed {
+                    dropped = true;
+                }
+            }
+
+            let from = match dragging {
+                None => return,
+                Some(from) => from,
+            };
+            let item = &items[from];
+
+            if !dropped {
+                let [width, height] = item.size;
+                let pos = mouse_pos - grab_offset;
+                ui.start("drag_icon")
+                .size(extent(width), extent(height))
+                .screen_pos(pos.x, pos.y)
+                .foreground(item.image)
+                .unparent()
+                .unclip()
+                .finish();
+                return;
+            }
+
+            if !grid.is_inside(mouse_pos) {
+                result = Some(InventoryEvent::DroppedOutside(from));
+                return;
+            }
+
+            let to = match target {
+                None => return,
+                Some(to) => to,
+            };
+
+            let covers = |other: &InventoryItem| {
+                to[0] < other.pos[0] + other.size[0] && other.pos[0] < to[0] + item.size[0] &&
+                    to[1] < other.pos[1] + other.size[1] && other.pos[1] < to[1] + item.size[1]
+            };
+            let overlapped: Vec<usize> = items.iter().enumerate()
+                .filter(|(index, other)| *index != from && covers(other))
+                .map(|(index, _)| index)
+                .collect();
+
+            result = match (split, overlapped.as_slice()) {
+                _ if to == item.pos => Some(InventoryEvent::Clicked(from)),
+                (true, []) => Some(InventoryEvent::Split { item: from, to }),
+                (false, []) => Some(InventoryEvent::Moved { item: from, to }),
+                (false, [other]) => Some(InventoryEvent::Swapped { item: from, other: *other }),
+                _ => None,
+            };
+        });
+
+        if let Some((index, offset)) = drag_start {
+            self.modify(id, |state| state.grab_offset = Some(offset));
+            self.context_internal().borrow_mut().set_drag_source(Some((id.to_string(), index)));
+        } else if dropped {
+            self.modify(id, |state| state.grab_offset = None);
+            self.context_internal().borrow_mut().set_drag_source(None);
+        }
+
+        result
+    }
+}
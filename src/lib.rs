@@ -5,11 +5,15 @@ Thyme is a highly customizable, themable immediate mode GUI toolkit for Rust.
 
 It is designed to be performant and flexible enough for use both in prototyping and production games and applications.
 Requiring a theme and image sources adds some additional development cost compared to many other immediate mode toolkits,
-however the advantage is full flexibility and control over the ultimate appearance of your UI.
+however the advantage is full flexibility and control over the ultimate appearance of your UI.  To get started without
+any assets, enable the `default_font` feature and use the built in default theme with
+[`ContextBuilder::build_with_defaults`](struct.ContextBuilder.html#method.build_with_defaults).
 
 To use Thyme, you need to choose a renderer and event handling support.
 There are currently three renderers built in - one using [Glium](https://github.com/glium/glium),
 one using [wgpu](https://github.com/gfx-rs/wgpu-rs)), and one using raw OpenGL (https://github.com/brendanzab/gl-rs/)).
+For other graphics APIs or engines, the [`DrawDataRenderer`](struct.DrawDataRenderer.html) produces plain
+[`DrawData`](struct.DrawData.html) for each frame, which may be drawn by your own code.
 [winit](https://github.com/rust-windowing/winit) is currently supported for event handling.
 You also need a theme definition  with associated images and fonts.  Thyme logs errors using the
 [`log`](https://github.com/rust-lang/log) crate.  A very simple logger that sends messages to stdout
@@ -64,7 +68,8 @@ fn create_ui(ui: &mut Frame) {
 # Overview
 
 For common use cases, the [`AppBuilder`](struct.AppBuilder.html) struct is available to allow you to create a simple
-application with just a few lines of code.
+application with just a few lines of code.  [`app::run`](app/fn.run.html) goes one step further, building the
+app and running its event loop with a single call.
 
 In more general cases, you first create the [`ContextBuilder`](struct.ContextBuilder.html) and register resources
 with it. Once done, you [`build`](struct.ContextBuilder.html#method.build) the associated [`Context`](struct.Context.html).
@@ -78,9 +83,11 @@ See the examples for details on how to use both of the above methods.
 When creating a [`ContextBuilder`](struct.ContextBuilder.html), you need to specify a theme.  You can keep the
 theme fairly small with just a base set of widgets, defining most things in code, or go the other way around.
 
-The theme can be defined from any [`serde`](https://serde.rs/)
-compatible source, with the examples in this project using [`YAML`](https://yaml.org/).
-The theme has several sections: `fonts`, `image_sets`, and `widgets`.
+The theme can be defined from any self-describing [`serde`](https://serde.rs/) format, such as JSON or TOML,
+with the examples in this project using [`YAML`](https://yaml.org/).  Non self-describing formats, such as
+bincode, are not supported, as variables are resolved before the theme is deserialized.
+The theme has several sections: `fonts`, `image_sets`, and `widgets`, as well as optional `templates`, which
+define widget trees that may be created with [`build_from_template`](struct.Frame.html#method.build_from_template).
 
 ## Fonts
 The `fonts` section consists of a mapping, with `IDs` mapped
@@ -105,8 +112,21 @@ fonts:
   small:
     source: roboto
     size: 16
+  # support Latin-1 plus Cyrillic and Greek
+  multilingual:
+    source: roboto
+    size: 16
+    characters:
+      - Default
+      - Cyrillic
+      - Greek
 ```
 
+Instead of a range, an entry in `characters` may be the name of a
+[`CharacterRangePreset`](enum.CharacterRangePreset.html).  Additional characters may also be added to
+all fonts in code, for example based on the user's locale, with
+[`add_font_characters_for_os_locale`](struct.ContextBuilder.html#method.add_font_characters_for_os_locale).
+
 ## Image Sets
 Images are defined as a series of `image_sets`.  Each image_set has an `id`, used as the first
 part of the ID of each image in the set.  The complete image ID is equal to `image_set_id/image_id`.
@@ -128,6 +148,23 @@ all images will be drawn at 1 image pixel to 1 physical screen pixel when the di
 2 physical screen pixels on a hi-dpi display with a scale factor of 2.  By setting the scale factor of the image set to 0.5, you
 can use the full resolution on hi-dpi displays, but you will need twice the image resolution to get the same UI size.
 
+### Packed Image Sets
+Instead of a `source`, an image_set may list individual image `files`, a `directory` of image files, or both.  The images
+are packed into a single texture when the theme is built, and each is added to the set as a simple image with an ID equal to
+its file name, without the extension.  Paths are relative to the working directory, and the files are watched for live reload.
+Other images in the set may reference the packed images, and an image defined in the set takes precedence over a file of the
+same name.
+```yaml
+image_sets:
+  icons:
+    directory: assets/icons
+    files:
+      - assets/extra/close.png
+    images:
+      close_hover:
+        from: close
+```
+
 ### Image Sampling
 Building images as sub-images of a larger spritesheet is convenient, but you need to be aware of texture sampling issues.  Because of
 floating point rounding, graphics cards will sometimes partially sample pixels just outside the defined area of your images.  To avoid
@@ -259,6 +296,17 @@ The referenced images are specified by `id`, and can include Simple, Composed, o
       Active + Pressed: button_pressed_active
 ```
 
+Animated images may also specify `tints`, a color for each state which is multiplied with the image color.  A tinted state
+without its own image uses the `Normal` image, allowing simple hover and pressed effects from a single image:
+```yaml
+  button:
+    states:
+      Normal: button_normal
+    tints:
+      Hover: "#DDDDFF"
+      Pressed: "#AAAAAA"
+```
+
 Images which contain references to other images are parsed in a particular order - `Collected`, then `Animated`, then
 `Timed`.  This means an `Animated` image may reference a `Collected` image, but not the other way around.  All of these
 image types may contain references to the basic image types - `Solid`, `Simple`, `Composed`, `ComposedHorizontal`, and
@@ -351,13 +399,25 @@ the widget will layout its children.
      text: Hello
      text_color: "#FFAA00"
      text_align: Center
+     text_wrap: Word
+     text_soft_hyphens: true
      font: medium
      image_color: "#FFFFFF"
+     image_tints:
+       Hover: "#DDDDFF"
      background: gui/button
      foreground: gui/button_icon
      tooltip: "This is a button!"
+     aria_label: "Submit"
+     description: "Submits the form"
+     cursor: Pointer
+     # OR cursor: { image: gui/cursor_hand, align: TopLeft }
+     shader: scanlines
+     sound_hover: ui_hover
+     sound_click: ui_click
      wants_mouse: true
      wants_scroll: false
+     hit_test: true
      pos: [10, 10]
      size: [100, 0]
      width_from: Normal
@@ -382,28 +442,105 @@ include floats, integers, and strings.
       min_height: 25.0
       secondary_font: "Bold"
 ```
+
+### Variables and expressions
+Numeric widget attributes - `pos`, `screen_pos`, `size`, `width`, `height`, `layout_spacing`, and `border` - as well as
+font `size`, may be specified as simple arithmetic expressions.  Expressions may reference named constants from the
+`variables` section, which may be defined in any theme file.  Within a widget, `parent` refers to the same attribute of
+the parent widget.  Expressions are evaluated when the theme is built, including on live reload, so related sizes can
+be kept consistent by changing a single variable.
+```yaml
+variables:
+  spacing: 4
+  button_width: "100 + 2 * spacing"
+widgets:
+  panel:
+    size: ["button_width + 2 * spacing", 200]
+    border: { all: "spacing" }
+    children:
+      inner:
+        size: ["parent - 2 * spacing", 20]
+```
+
+### Conditional overrides
+A theme file may contain an `overrides` section, allowing a single theme to adapt to different display sizes and
+platforms.  Each override has a `when` condition, together with any theme sections such as `variables`, `fonts`, or
+`widgets`.  When the condition matches, the override's sections are merged into the theme, replacing the values
+they specify.  Overrides are applied in order, before variables and expressions are evaluated.  The conditions are:
+* `min_width`, `max_width`, `min_height`, `max_height` - bounds on the logical display size, inclusive.
+* `platform` - the operating system, such as `windows`, `linux`, or `android`, the `desktop` or `mobile` form factor,
+  or the application specified [`platform`](struct.BuildOptions.html#structfield.platform).
+
+All of the specified conditions must match.  The theme is rebuilt by
+[`check_live_reload`](struct.Context.html#method.check_live_reload) whenever the display size changes such that a
+different set of overrides applies.
+```yaml
+variables:
+  spacing: 4
+overrides:
+  - when: { max_width: 1280 }
+    variables:
+      spacing: 2
+  - when: { platform: mobile }
+    fonts:
+      medium:
+        size: 24
+```
 !*/
 
 #![deny(missing_docs)]
 
+#[cfg(any(feature="glium_backend", feature="gl_backend"))]
+pub mod app;
 pub mod bench;
+pub mod format;
 pub mod log;
 
+mod accessibility;
 mod app_builder;
+mod bind;
+mod bundle;
+mod clock;
+mod compressed;
+mod conformance;
 mod context;
 mod context_builder;
+mod dialogue;
+mod draw_data;
+mod effect;
+mod event;
+mod file_picker;
 mod font;
+mod form;
 mod frame;
+mod hotbar;
 mod image;
+mod image_packing;
+mod inspect;
+mod inventory;
 mod key_event;
+mod log_viewer;
+mod nameplate;
 mod theme;
 mod recipes;
+#[cfg(feature="recording")]
+mod recording;
 mod render;
 mod resource;
 mod theme_definition;
+mod theme_expr;
+mod theme_overrides;
 mod point;
 mod scrollpane;
+#[cfg(feature="image")]
+mod snapshot;
+mod selection;
+mod table;
+mod template;
 mod text_area;
+mod toolbar;
+mod transform;
+mod virtual_keyboard;
 mod widget;
 mod window;
 mod winit_io;
@@ -425,19 +562,53 @@ mod gl_backend;
 #[cfg(feature = "gl_backend")]
 pub use gl_backend::{GLRenderer, GlError};
 
+#[cfg(feature = "lua")]
+mod lua;
+
+pub use accessibility::{AccessibleWidget, ColorBlindness, ColorTransform};
+pub use bundle::ThemeBundle;
+pub use clock::{Clock, ManualClock, SystemClock};
+pub use compressed::CompressedFormat;
+pub use file_picker::{FileDialogMode, FileFilter};
+pub use conformance::{ConformanceResult, ConformanceSuite};
+pub use draw_data::{DrawData, DrawDataRenderer, DrawGroup, DrawPrimitive, DrawQuad, DrawTexture, DrawTriangle, TextureImage};
+pub use effect::{Effect, Transition};
+pub use event::{HapticEvent, Interaction, UiEvent};
+pub use font::TextCarets;
+pub use form::Form;
+pub use inspect::Inspect;
+pub use inventory::{InventoryEvent, InventoryItem};
+pub use nameplate::Nameplate;
 pub use frame::{Frame, MouseButton};
+pub use hotbar::{HotbarEvent, HotbarSlot};
 pub use key_event::KeyEvent;
+pub use log_viewer::{LogBuffer, LogMessage};
 pub use point::{Rect, Point, Border};
+pub use transform::Transform;
 pub use widget::{WidgetBuilder, WidgetState};
-pub use context_builder::{BuildOptions, ContextBuilder};
-pub use context::{Context, PersistentState, InputModifiers, SavedContext};
-pub use scrollpane::{ScrollpaneBuilder, ShowElement};
-pub use theme_definition::{AnimStateKey, AnimState, Align, Color, Layout, WidthRelative, HeightRelative};
-pub use window::WindowBuilder;
-pub use recipes::{InputFieldResult, InputFieldKeyboard};
+pub use context_builder::{BuildOptions, BuildProgress, BuildStage, ContextBuild, ContextBuilder};
+pub use context::{Context, PersistentState, InputModifiers, SavedContext, ScrollConfig};
+#[cfg(feature="image")]
+pub use snapshot::{SnapshotResult, SnapshotTester, UPDATE_SNAPSHOTS_VAR};
+pub use scrollpane::{ScrollAnchor, ScrollpaneBuilder, ShowElement};
+pub use selection::{Selection, SelectionMode};
+pub use theme_definition::{
+    AnimStateKey, AnimState, Align, CharacterRange, CharacterRangePreset, Color, CustomData, Layout, SystemCursor,
+    TextWrap, WidthRelative, HeightRelative
+};
+pub use window::{WindowBuilder, WindowPlacement};
+#[cfg(feature="recording")]
+pub use recording::Recorder;
+pub use recipes::{IconPlacement, InputFieldResult, InputFieldKeyboard, OverlayPlacement};
+pub use table::{CellEditor, CellEdit, TableEdit};
+pub use text_area::TextAreaState;
+pub use theme::WidgetThemeInfo;
+pub use toolbar::ToolbarItem;
+pub use virtual_keyboard::{KeyboardLayout, KeyboardNav};
 pub use winit_io::{WinitIo, WinitError};
 
-pub use render::{IO, Renderer};
+pub use resource::{FontUsage, ResourceReport, SharedResources, TextureUsage};
+pub use render::{DisplayClass, DisplayRotation, IO, Renderer, Vertex};
 
 /// A generic error that can come from a variety of internal sources.
 #[derive(Debug)]
@@ -464,6 +635,10 @@ pub enum Error {
     #[cfg(feature="image")]
     Image(::image::error::ImageError),
 
+    /// An error that occurred writing a recording using the `png` crate.
+    #[cfg(feature="recording")]
+    Png(::png::EncodingError),
+
     /// An error originating from Glium
     #[cfg(feature="glium_backend")]
     Glium(crate::glium_backend::GliumError),
@@ -487,6 +662,9 @@ impl std::fmt::Display for Error {
             #[cfg(feature="image")]
             Image(error) => write!(f, "Image Error: {}", error),
 
+            #[cfg(feature="recording")]
+            Png(error) => write!(f, "PNG Error: {}", error),
+
             #[cfg(feature="glium_backend")]
             Glium(error) => write!(f, "Glium Error: {}", error),
 
@@ -510,6 +688,9 @@ impl std::error::Error for Error {
             #[cfg(feature="image")]
             Image(error) => Some(error),
 
+            #[cfg(feature="recording")]
+            Png(error) => Some(error),
+
             #[cfg(feature="glium_backend")]
             Glium(error) => Some(error),
 
@@ -1,18 +1,79 @@
 /*!
 A minimal logger for use with Thyme.
 
-Logs all messages to standard output.
+Logs messages to standard output, and retains the most recent messages in a [`LogBuffer`](../struct.LogBuffer.html),
+so they may be shown in game with a [`log_viewer`](../struct.Frame.html#method.log_viewer).  Use [`init`](fn.init.html)
+for the default configuration, or a [`LoggerBuilder`](struct.LoggerBuilder.html) to set per-module levels, timestamps,
+and the number of retained messages.  Levels may be changed while the program is running with
+[`set_level`](fn.set_level.html) and [`set_module_level`](fn.set_module_level.html), for example from an in-game console.
+
+# Example
+```
+thyme::log::LoggerBuilder::new(log::Level::Info)
+    .module_level("wgpu_core", log::LevelFilter::Warn)
+    .timestamps(true)
+    .init()?;
+
+// each frame
+if let Some(buffer) = thyme::log::buffer() {
+    ui.log_viewer("log_viewer", "console", &buffer);
+}
+```
 !*/
 
-use log::{Level, Log, Record, Metadata, SetLoggerError};
+use log::{Level, LevelFilter, Log, Record, Metadata, SetLoggerError};
+use parking_lot::{const_mutex, Mutex};
 
-struct SimpleLogger {
-    level: Level,
+use crate::LogBuffer;
+
+/// The default number of messages retained by the logger
+const DEFAULT_CAPACITY: usize = 500;
+
+static FILTERS: Mutex<Filters> = const_mutex(Filters::new());
+static BUFFER: Mutex<Option<LogBuffer>> = const_mutex(None);
+
+struct Filters {
+    level: LevelFilter,
+
+    // sorted with the longest module path first, so the most specific module is found first
+    modules: Vec<(String, LevelFilter)>,
+}
+
+impl Filters {
+    const fn new() -> Filters {
+        Filters { level: LevelFilter::Trace, modules: Vec::new() }
+    }
+
+    fn level_for(&self, target: &str) -> LevelFilter {
+        self.modules.iter()
+            .find(|(module, _)| target == module || target.strip_prefix(module.as_str()).is_some_and(|rest| rest.starts_with("::")))
+            .map(|(_, level)| *level)
+            .unwrap_or(self.level)
+    }
+
+    fn set_module_level(&mut self, module: String, level: LevelFilter) {
+        self.modules.retain(|(existing, _)| *existing != module);
+        self.modules.push((module, level));
+        self.modules.sort_by_key(|(module, _)| std::cmp::Reverse(module.len()));
+    }
+
+    /// Applies the most verbose level of any filter as the global maximum, so that the `log` macros
+    /// skip messages which would be filtered out anyway
+    fn apply_max_level(&self) {
+        let max = self.modules.iter().map(|(_, level)| *level).fold(self.level, Ord::max);
+        log::set_max_level(max);
+    }
+}
+
+struct ThymeLogger {
+    print: bool,
+    timestamps: bool,
+    buffer: LogBuffer,
 }
 
-impl Log for SimpleLogger {
+impl Log for ThymeLogger {
     fn enabled(&self, metadata: &Metadata) -> bool {
-        metadata.level() <= self.level
+        metadata.level() <= FILTERS.lock().level_for(metadata.target())
     }
 
     fn log(&self, record: &Record) {
@@ -24,22 +85,127 @@ impl Log for SimpleLogger {
             record.module_path().unwrap_or_default()
         };
 
-        println!("{:<5} <{}> {}", record.level().to_string(), target, record.args());
+        if self.print {
+            if self.timestamps {
+                let time = self.buffer.elapsed().as_secs_f32();
+                println!("{:>10.3} {:<5} <{}> {}", time, record.level().to_string(), target, record.args());
+            } else {
+                println!("{:<5} <{}> {}", record.level().to_string(), target, record.args());
+            }
+        }
+
+        self.buffer.push(record.level(), target, record.args().to_string());
     }
 
     fn flush(&self) { }
 }
 
+/// Configures and installs the Thyme logger.  See the [`module documentation`](index.html).
+pub struct LoggerBuilder {
+    level: Level,
+    modules: Vec<(String, LevelFilter)>,
+    print: bool,
+    timestamps: bool,
+    capacity: usize,
+}
+
+impl LoggerBuilder {
+    /// Creates a new builder, logging messages at the specified `level` or more severe.  By default, messages are
+    /// printed to standard output without timestamps, and the most recent 500 messages are retained.
+    pub fn new(level: Level) -> LoggerBuilder {
+        LoggerBuilder {
+            level,
+            modules: Vec::new(),
+            print: true,
+            timestamps: false,
+            capacity: DEFAULT_CAPACITY,
+        }
+    }
+
+    /// Uses the specified `level` for messages from `module` and its submodules, such as `thyme` or
+    /// `my_game::audio`, instead of the default level.  The most specific matching module is used.
+    #[must_use]
+    pub fn module_level<T: Into<String>>(mut self, module: T, level: LevelFilter) -> LoggerBuilder {
+        self.modules.push((module.into(), level));
+        self
+    }
+
+    /// Sets whether messages are printed to standard output.  If `false`, messages are only retained in the
+    /// [`buffer`](fn.buffer.html).
+    #[must_use]
+    pub fn print(mut self, print: bool) -> LoggerBuilder {
+        self.print = print;
+        self
+    }
+
+    /// Sets whether printed messages begin with the time since the logger was installed, in seconds.  Retained
+    /// messages always record their [`time`](../struct.LogMessage.html#structfield.time).
+    #[must_use]
+    pub fn timestamps(mut self, timestamps: bool) -> LoggerBuilder {
+        self.timestamps = timestamps;
+        self
+    }
+
+    /// Sets the number of most recent messages retained in the [`buffer`](fn.buffer.html)
+    #[must_use]
+    pub fn capacity(mut self, capacity: usize) -> LoggerBuilder {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Installs the logger.  This should only be called once per program.  Returns the buffer of retained messages,
+    /// which may also be obtained later with [`buffer`](fn.buffer.html).
+    pub fn init(self) -> Result<LogBuffer, SetLoggerError> {
+        let buffer = LogBuffer::new(self.capacity);
+        let logger = Box::new(ThymeLogger { print: self.print, timestamps: self.timestamps, buffer: buffer.clone() });
+        log::set_logger(Box::leak(logger))?;
+
+        let mut filters = FILTERS.lock();
+        filters.level = self.level.to_level_filter();
+        filters.modules.clear();
+        for (module, level) in self.modules {
+            filters.set_module_level(module, level);
+        }
+        filters.apply_max_level();
+
+        *BUFFER.lock() = Some(buffer.clone());
+        Ok(buffer)
+    }
+}
+
 /// Initiales the logger at the specified log level.  This should only be called once per program.
+/// See [`LoggerBuilder`](struct.LoggerBuilder.html) for more options.
 pub fn init(level: Level) -> Result<(), SetLoggerError> {
-    let logger = Box::new(SimpleLogger { level });
-
-    log::set_logger(Box::leak(logger))?;
-    log::set_max_level(level.to_level_filter());
+    LoggerBuilder::new(level).init()?;
     Ok(())
 }
 
 /// Initializes the logger at the `Trace` level.  This should only be called once per program.
 pub fn init_all() -> Result<(), SetLoggerError> {
     init(Level::Trace)
-}
\ No newline at end of file
+}
+
+/// Returns the messages retained by the logger, or `None` if the logger has not been installed
+pub fn buffer() -> Option<LogBuffer> {
+    BUFFER.lock().clone()
+}
+
+/// Sets the `level` used for messages from modules without their own level
+pub fn set_level(level: LevelFilter) {
+    let mut filters = FILTERS.lock();
+    filters.level = level;
+    filters.apply_max_level();
+}
+
+/// Sets the `level` used for messages from `module` and its submodules.  See
+/// [`LoggerBuilder::module_level`](struct.LoggerBuilder.html#method.module_level).
+pub fn set_module_level<T: Into<String>>(module: T, level: LevelFilter) {
+    let mut filters = FILTERS.lock();
+    filters.set_module_level(module.into(), level);
+    filters.apply_max_level();
+}
+
+/// Returns the level used for messages with the specified `target`, normally a module path
+pub fn level_for(target: &str) -> LevelFilter {
+    FILTERS.lock().level_for(target)
+}
@@ -0,0 +1,301 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+use parking_lot::Mutex;
+
+use crate::{Frame, ScrollAnchor};
+
+/// The default number of messages retained by a [`LogBuffer`](struct.LogBuffer.html)
+const DEFAULT_CAPACITY: usize = 500;
+
+const LEVELS: [Level; 5] = [Level::Error, Level::Warn, Level::Info, Level::Debug, Level::Trace];
+
+/// A single message stored in a [`LogBuffer`](struct.LogBuffer.html)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogMessage {
+    /// The severity of the message
+    pub level: Level,
+
+    /// The target of the message, normally the module path it was logged from
+    pub target: String,
+
+    /// The message text
+    pub text: String,
+
+    /// The time the message was added, relative to when the buffer was created
+    pub time: Duration,
+}
+
+struct LogBufferInner {
+    start: Instant,
+    capacity: usize,
+    level: LevelFilter,
+    messages: VecDeque<LogMessage>,
+}
+
+/**
+A fixed size, in-memory store of log messages, displayed with a [`log_viewer`](struct.Frame.html#method.log_viewer).
+Once the buffer is full, the oldest message is dropped for each new one.  Clones of a `LogBuffer` share the same
+messages, so one clone may be installed as the application's logger, as the buffer implements
+[`log::Log`](https://docs.rs/log/latest/log/trait.Log.html), while another is passed to the log viewer each frame.
+Messages may also be added directly with [`push`](#method.push), for example for game events or chat.  The
+[`Thyme logger`](log/index.html) also retains its messages in a `LogBuffer`, while printing them as well.
+
+# Example
+```
+let buffer = thyme::LogBuffer::new(1000);
+buffer.init(log::Level::Info)?;
+
+// each frame
+ui.log_viewer("log_viewer", "console", &buffer);
+```
+**/
+#[derive(Clone)]
+pub struct LogBuffer {
+    inner: Arc<Mutex<LogBufferInner>>,
+}
+
+impl LogBuffer {
+    /// Creates a new, empty buffer retaining up to `capacity` messages
+    pub fn new(capacity: usize) -> LogBuffer {
+        LogBuffer {
+            inner: Arc::new(Mutex::new(LogBufferInner {
+                start: Instant::now(),
+                capacity: capacity.max(1),
+                level: LevelFilter::Trace,
+                messages: VecDeque::new(),
+            }))
+        }
+    }
+
+    /// Installs a clone of this buffer as the global logger, retaining messages at the specified `level` or
+    /// more severe.  This should only be called once per program.
+    pub fn init(&self, level: Level) -> Result<(), SetLoggerError> {
+        self.inner.lock().level = level.to_level_filter();
+
+        log::set_logger(Box::leak(Box::new(self.clone())))?;
+        log::set_max_level(level.to_level_filter());
+        Ok(())
+    }
+
+    /// Adds a message with the specified `level`, `target`, and `text`, dropping the oldest message if
+    /// the buffer is full
+    pub fn push<T: Into<String>, U: Into<String>>(&self, level: Level, target: T, text: U) {
+        let mut inner = self.inner.lock();
+        if inner.messages.len() >= inner.capacity {
+            inner.messages.pop_front();
+        }
+
+        let time = inner.start.elapsed();
+        inner.messages.push_back(LogMessage { level, target: target.into(), text: text.into(), time });
+    }
+
+    /// Returns the time since this buffer was created
+    pub(crate) fn elapsed(&self) -> Duration {
+        self.inner.lock().start.elapsed()
+    }
+
+    /// Returns a copy of all messages currently in the buffer, from oldest to newest
+    pub fn messages(&self) -> Vec<LogMessage> {
+        self.inner.lock().messages.iter().cloned().collect()
+    }
+
+    /// Returns the maximum number of messages retained by this buffer
+    pub fn capacity(&self) -> usize {
+        self.inner.lock().capacity
+    }
+
+    /// Returns the number of messages currently in the buffer
+    pub fn len(&self) -> usize {
+        self.inner.lock().messages.len()
+    }
+
+    /// Returns whether the buffer contains no messages
+    pub fn is_empty(&self) -> bool {
+        self.inner.lock().messages.is_empty()
+    }
+
+    /// Removes all messages from the buffer
+    pub fn clear(&self) {
+        self.inner.lock().messages.clear();
+    }
+}
+
+impl Default for LogBuffer {
+    fn default() -> Self { LogBuffer::new(DEFAULT_CAPACITY) }
+}
+
+impl Log for LogBuffer {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.inner.lock().level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) { return; }
+
+        let target = if !record.target().is_empty() {
+            record.target()
+        } else {
+            record.module_path().unwrap_or_default()
+        };
+
+        self.push(record.level(), target, record.args().to_string());
+    }
+
+    fn flush(&self) { }
+}
+
+impl Frame {
+    /**
+    A log panel showing the messages in `buffer`, for an in-game console or debug overlay.  The `id` must be unique.
+    The panel consists of a `filters` row with a `filter` toggle button for each log level, a `search` input field,
+    and a `messages` scrollpane.  Only messages at the enabled levels, and containing the search text in their
+    text or target, ignoring case, are shown.  Each message is drawn with the child theme for its level, which
+    is one of `error`, `warn`, `info`, `debug`, or `trace`, allowing each level to have its own color.
+
+    While the messages are scrolled to the bottom, the panel automatically scrolls to show new messages as they
+    arrive.  Scrolling up pauses this, so older messages may be read, until the panel is scrolled to the bottom again.
+
+    An example theme definition:
+    ```yaml
+    log_viewer:
+      layout: Vertical
+      size_from: [Parent, Parent]
+      children:
+        filters:
+          layout: Horizontal
+          size_from: [Parent, Children]
+          children:
+            filter:
+              from: button
+              size: [60, 24]
+        search:
+          from: input_field
+          width_from: Parent
+        messages:
+          from: scrollpane
+          size_from: [Parent, Parent]
+          children:
+            content:
+              layout: Vertical
+              children:
+                message:
+                  from: label
+                  text_align: Left
+                  size_from: [Parent, FontLine]
+                error:
+                  from: message
+                  text_color: "#F44"
+                warn:
+                  from: message
+                  text_color: "#FC4"
+                info:
+                  from: message
+                debug:
+                  from: message
+                  text_color: "#AAA"
+                trace:
+                  from: message
+                  text_color: "#888"
+    ```
+
+    # Example
+    ```
+    fn console(ui: &mut Frame, buffer: &LogBuffer) {
+        ui.window("console_window", |ui| {
+            ui.log_viewer("log_viewer", "console", buffer);
+        });
+    }
+    ```
+    **/
+    pub fn log_viewer(&mut self, theme: &str, id: &str, buffer: &LogBuffer) {
+        let search_id = format!("{}_search", id);
+        let messages_id = format!("{}_messages", id);
+        let follow_id = format!("{}_follow", id);
+        let shown_ids: Vec<String> = LEVELS.iter().map(|level| format!("{}_show_{}", id, level_theme(*level))).collect();
+
+        // persistent state is open by default, so all levels are initially shown
+        let shown: Vec<bool> = shown_ids.iter().map(|shown_id| self.is_open(shown_id)).collect();
+        let search = self.text_for(&search_id).unwrap_or_default().to_lowercase();
+
+        // follow new messages unless the user has scrolled up from the bottom
+        let range = (self.content_size(&messages_id) - self.viewport_size(&messages_id)).y;
+        let scroll = self.scroll(&messages_id).y;
+        let paused = !self.is_open(&follow_id) && !at_end(scroll, range);
+        let start_scroll = if !paused && range > 0.0 { -range } else { scroll };
+        if !paused {
+            self.modify(&messages_id, |state| state.scroll.y = start_scroll);
+        }
+
+        let mut toggled = None;
+        self.start(theme).children(|ui| {
+            ui.start("filters").children(|ui| {
+                for (index, level) in LEVELS.iter().enumerate() {
+                    if ui.toggle_button("filter", level_name(*level), shown[index]).clicked {
+                        toggled = Some(index);
+                    }
+                }
+            });
+
+            ui.input_field("search", &search_id, None);
+
+            let anchor = if paused { ScrollAnchor::Clamp } else { ScrollAnchor::Relative };
+            ui.start("messages").scrollpane(&messages_id).scroll_anchor(anchor).children(|ui| {
+                for message in buffer.messages() {
+                    let index = LEVELS.iter().position(|level| *level == message.level).unwrap_or_default();
+                    if !shown[index] { continue; }
+
+                    if !search.is_empty() && !message.text.to_lowercase().contains(&search) &&
+                        !message.target.to_lowercase().contains(&search) {
+                        continue;
+                    }
+
+                    let time = message.time.as_secs_f32();
+                    ui.label(level_theme(message.level), format!("{:.3} <{}> {}", time, message.target, message.text));
+                }
+            });
+        });
+
+        if let Some(index) = toggled {
+            self.modify(&shown_ids[index], |state| state.is_open = !shown[index]);
+        }
+
+        let range = (self.content_size(&messages_id) - self.viewport_size(&messages_id)).y;
+        let scroll = self.scroll(&messages_id).y;
+        let now_at_end = at_end(scroll, range);
+        let paused = paused || (scroll > start_scroll + 0.5 && !now_at_end);
+        self.modify(&follow_id, |state| state.is_open = !paused);
+
+        // the content grew past the visible area this frame, so scroll to it on the next
+        if !paused && !now_at_end {
+            self.request_redraw();
+        }
+    }
+}
+
+/// Returns whether a scrollpane with the specified vertical `scroll` and scrollable `range` is at the bottom
+fn at_end(scroll: f32, range: f32) -> bool {
+    range <= 0.0 || scroll <= 1.0 - range
+}
+
+fn level_name(level: Level) -> &'static str {
+    match level {
+        Level::Error => "Error",
+        Level::Warn => "Warn",
+        Level::Info => "Info",
+        Level::Debug => "Debug",
+        Level::Trace => "Trace",
+    }
+}
+
+fn level_theme(level: Level) -> &'static str {
+    match level {
+        Level::Error => "error",
+        Level::Warn => "warn",
+        Level::Info => "info",
+        Level::Debug => "debug",
+        Level::Trace => "trace",
+    }
+}
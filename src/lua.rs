@@ -0,0 +1,155 @@
+use mlua::{Function, Lua, Table, UserData, UserDataMethods};
+
+use crate::{Frame, WidgetState};
+
+impl Frame {
+    /**
+    Calls the global Lua function with the specified `name`, passing it a `ui` object which may be used to create
+    widgets in this frame.  This allows UI logic to be written in Lua scripts, for example by modders or designers,
+    which are loaded into `lua` by the application.  Any errors, including the function not existing, are logged
+    using [`log`](#method.log), which shows each distinct message only once.  Requires the `lua` feature.
+
+    The `ui` object provides the following methods, which mirror the equivalent methods on `Frame`:
+    - `ui:label(theme, text)`
+    - `ui:button(theme, text)`, returning whether the button was clicked
+    - `ui:toggle_button(theme, text, active)`, returning whether the button was clicked
+    - `ui:input_field(theme, id)`, returning the current text of the field
+    - `ui:horizontal_slider(theme, min, max, value)`, returning the new value if it was changed, or `nil`
+    - `ui:window(theme, children)`, where `children` is a function receiving the `ui` for the window's content
+    - `ui:scrollpane(theme, content_id, children)`
+    - `ui:widget(theme, options, children)`, where `options` is an optional table which may contain `id`, `text`,
+      `active`, `enabled`, `visible`, and `wants_mouse`, and `children` is an optional function.  Returns a table
+      with the `visible`, `hovered`, `pressed`, and `clicked` fields of the [`WidgetState`](struct.WidgetState.html)
+    - `ui:is_open(id)`, `ui:open(id)`, `ui:close(id)`, and `ui:text_for(id)`
+
+    # Example
+    ```
+    // loaded once, for example from a mod's script file
+    lua.load(r#"
+        function main_menu(ui)
+            ui:window("main_menu", function(ui)
+                if ui:button("button", "Play") then
+                    print("Play clicked")
+                end
+            end)
+        end
+    "#).exec()?;
+
+    // then each frame
+    ui.call_lua(&lua, "main_menu");
+    ```
+    **/
+    pub fn call_lua(&mut self, lua: &Lua, name: &str) {
+        let result = lua.globals().get::<_, Function>(name).and_then(|function| call_with_frame(lua, self, &function));
+
+        if let Err(e) = result {
+            self.log(log::Level::Error, format!("Error calling Lua function '{}': {}", name, e));
+        }
+    }
+}
+
+fn call_with_frame<'lua>(lua: &'lua Lua, ui: &mut Frame, function: &Function<'lua>) -> mlua::Result<()> {
+    lua.scope(|scope| {
+        let ui = scope.create_nonstatic_userdata(LuaFrame(ui))?;
+        function.call::<_, ()>(ui)
+    })
+}
+
+fn call_children<'lua>(lua: &'lua Lua, ui: &mut Frame, children: Option<Function<'lua>>, result: &mut mlua::Result<()>) {
+    if let Some(children) = children {
+        *result = call_with_frame(lua, ui, &children);
+    }
+}
+
+fn state_table<'lua>(lua: &'lua Lua, state: WidgetState) -> mlua::Result<Table<'lua>> {
+    let table = lua.create_table()?;
+    table.set("visible", state.visible)?;
+    table.set("hovered", state.hovered)?;
+    table.set("pressed", state.pressed)?;
+    table.set("clicked", state.clicked)?;
+    Ok(table)
+}
+
+struct LuaFrame<'a>(&'a mut Frame);
+
+impl UserData for LuaFrame<'_> {
+    fn add_methods<'lua, M: UserDataMethods<'lua, Self>>(methods: &mut M) {
+        methods.add_method_mut("label", |_, this, (theme, text): (String, String)| {
+            this.0.label(&theme, text);
+            Ok(())
+        });
+
+        methods.add_method_mut("button", |_, this, (theme, text): (String, String)| {
+            Ok(this.0.button(&theme, text).clicked)
+        });
+
+        methods.add_method_mut("toggle_button", |_, this, (theme, text, active): (String, String, bool)| {
+            Ok(this.0.toggle_button(&theme, text, active).clicked)
+        });
+
+        methods.add_method_mut("input_field", |_, this, (theme, id): (String, String)| {
+            this.0.input_field(&theme, &id, None);
+            Ok(this.0.text_for(&id))
+        });
+
+        methods.add_method_mut("horizontal_slider", |_, this, (theme, min, max, value): (String, f32, f32, f32)| {
+            Ok(this.0.horizontal_slider(&theme, min, max, value))
+        });
+
+        methods.add_method_mut("window", |lua, this, (theme, children): (String, Option<Function>)| {
+            let mut result = Ok(());
+            this.0.window(&theme, |ui| call_children(lua, ui, children, &mut result));
+            result
+        });
+
+        methods.add_method_mut("scrollpane", |lua, this, (theme, content_id, children): (String, String, Option<Function>)| {
+            let mut result = Ok(());
+            this.0.scrollpane(&theme, &content_id, |ui| call_children(lua, ui, children, &mut result));
+            result
+        });
+
+        methods.add_method_mut("widget", |lua, this, (theme, options, children): (String, Option<Table>, Option<Function>)| {
+            let mut builder = this.0.start(&theme);
+
+            if let Some(options) = options {
+                if let Some(id) = options.get::<_, Option<String>>("id")? {
+                    builder = builder.id(id);
+                }
+                if let Some(text) = options.get::<_, Option<String>>("text")? {
+                    builder = builder.text(text);
+                }
+                if let Some(active) = options.get::<_, Option<bool>>("active")? {
+                    builder = builder.active(active);
+                }
+                if let Some(enabled) = options.get::<_, Option<bool>>("enabled")? {
+                    builder = builder.enabled(enabled);
+                }
+                if let Some(visible) = options.get::<_, Option<bool>>("visible")? {
+                    builder = builder.visible(visible);
+                }
+                if let Some(wants_mouse) = options.get::<_, Option<bool>>("wants_mouse")? {
+                    builder = builder.wants_mouse(wants_mouse);
+                }
+            }
+
+            let mut result = Ok(());
+            let state = builder.children(|ui| call_children(lua, ui, children, &mut result));
+            result?;
+            state_table(lua, state)
+        });
+
+        methods.add_method("is_open", |_, this, id: String| Ok(this.0.is_open(&id)));
+
+        methods.add_method_mut("open", |_, this, id: String| {
+            this.0.open(id);
+            Ok(())
+        });
+
+        methods.add_method_mut("close", |_, this, id: String| {
+            this.0.close(id);
+            Ok(())
+        });
+
+        methods.add_method("text_for", |_, this, id: String| Ok(this.0.text_for(&id)));
+    }
+}
@@ -0,0 +1,197 @@
+use crate::{Frame, Point, Rect, WidgetBuilder};
+
+/// A nameplate drawn over an entity in the game world, by [`nameplates`](struct.Frame.html#method.nameplates).
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Nameplate<'a> {
+    /// The position of the entity projected onto the screen, in logical pixels, which the bottom center of the
+    /// nameplate is anchored to.  `None` if the entity is not visible, such as when it is behind the camera.
+    pub pos: Option<Point>,
+
+    /// The distance of the entity from the camera.  Nearer nameplates are drawn over farther ones, and keep
+    /// their position when nameplates overlap.
+    pub depth: f32,
+
+    /// The name shown on the nameplate
+    pub name: &'a str,
+
+    /// The fractions, from 0.0 to 1.0, of each bar shown on the nameplate, such as health or mana
+    pub bars: &'a [f32],
+
+    /// The images of any icons shown on the nameplate, such as status effects, with IDs of the form
+    /// "{image_set_id}/{image_id}"
+    pub icons: &'a [&'a str],
+}
+
+// unlike Rect::intersects, rects which only touch do not overlap
+fn overlaps(a: Rect, b: Rect) -> bool {
+    a.pos.x < b.pos.x + b.size.x && b.pos.x < a.pos.x + a.size.x &&
+        a.pos.y < b.pos.y + b.size.y && b.pos.y < a.pos.y + a.size.y
+}
+
+impl Frame {
+    /**
+    Draws a nameplate, using the specified `theme`, for each of the `plates`, positioned over entities in the
+    game world.  Each frame, the application projects the positions of the entities onto the screen, and this
+    method handles the placement of the nameplates.  Nameplates whose entity is not visible, or which would
+    lie entirely off screen, are skipped.
+
+    Each nameplate shows its name with the `name` child, each bar with a `bar` child whose `fill` child is
+    clipped to the bar's fraction, and its icons with `icon` children inside an `icons` child.  All nameplates
+    have the size specified by the theme, which should not depend on their children.
+
+    Overlapping nameplates are resolved according to the theme `custom` string `overlap`.  With `push`, the
+    default, nameplates are moved upwards until they no longer overlap any nearer nameplate.  With `fade`,
+    nameplates overlapping a nearer nameplate are drawn with their alpha multiplied by the `custom` float
+    `faded_alpha`, defaulting to 0.3.  With `none`, overlaps are not resolved.
+
+    Nameplate ids are based on their index in `plates`, so the order should be kept consistent between frames.
+    Returns the index of the nameplate that was clicked, if any, such as for selecting a target.
+
+    An example theme definition:
+    ```yaml
+    nameplate:
+      size: [120, 34]
+      layout: Vertical
+      custom:
+        overlap: push
+      children:
+        name:
+          font: small
+          text_color: "#FFF"
+          width_from: Parent
+          height_from: FontLine
+          text_align: Center
+        bar:
+          background: gui/bar_bg
+          width_from: Parent
+          height: 6
+          children:
+            fill:
+              background: gui/bar_fill
+              width_from: Parent
+              height_from: Parent
+        icons:
+          layout: Horizontal
+          width_from: Parent
+          height: 12
+          children:
+            icon:
+              size: [12, 12]
+    ```
+
+    # Example
+    ```
+    fn draw_nameplates(ui: &mut Frame, camera: &Camera, monsters: &[Monster]) {
+        let health: Vec<[f32; 1]> = monsters.iter().map(|m| [m.health / m.max_health]).collect();
+        let plates: Vec<Nameplate> = monsters.iter().zip(health.iter()).map(|(monster, health)| Nameplate {
+            pos: camera.project(monster.head_pos()),
+            depth: camera.distance(monster.pos()),
+            name: &monster.name,
+            bars: health,
+            icons: &[],
+        }).collect();
+
+        if let Some(index) = ui.nameplates("nameplate", &plates) {
+            select_target(&monsters[index]);
+        }
+    }
+    ```
+    **/
+    pub fn nameplates(&mut self, theme: &str, plates: &[Nameplate]) -> Option<usize> {
+        let overlap = self.custom_string(theme, "overlap", "push".to_string());
+        let faded_alpha = self.custom_float(theme, "faded_alpha", 0.3);
+        let screen = Rect::new(Point::default(), self.display_size());
+
+        // nearest first
+        let mut visible: Vec<(usize, Point)> = plates.iter().enumerate()
+            .filter_map(|(index, plate)| plate.pos.map(|pos| (index, pos)))
+            .collect();
+        visible.sort_by(|(a, _), (b, _)| {
+            plates[*a].depth.partial_cmp(&plates[*b].depth).unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let farthest = match visible.last() {
+            None => return None,
+            Some((index, _)) => *index,
+        };
+
+        // all nameplates have the same size, so measure the first one to be drawn
+        let mut rect = Rect::default();
+        let first_builder = self.start_indexed(theme, farthest as u64).trigger_layout(&mut rect);
+        let size = rect.size;
+
+        let mut placed: Vec<(usize, Rect, f32)> = Vec::with_capacity(visible.len());
+        for (index, pos) in visible {
+            let mut rect = Rect::new(Point::new(pos.x - size.x / 2.0, pos.y - size.y), size);
+            let mut alpha = 1.0;
+
+            match overlap.as_str() {
+                "none" => (),
+                "fade" => if placed.iter().any(|(_, other, _)| overlaps(rect, *other)) {
+                    alpha = faded_alpha;
+                },
+                // bounded, as rounding may leave the rect overlapping the nameplate it was just moved above
+                _ => for _ in 0..placed.len() {
+                    match placed.iter().find(|(_, other, _)| overlaps(rect, *other)) {
+                        None => break,
+                        Some((_, other, _)) => rect.pos.y = other.pos.y - size.y,
+                    }
+                },
+            }
+
+            placed.push((index, rect, alpha));
+        }
+
+        // draw the farthest first, so nearer nameplates are on top
+        let mut placed = placed.into_iter().rev();
+        let mut clicked = None;
+        if let Some((index, rect, alpha)) = placed.next() {
+            if !overlaps(rect, screen) {
+                first_builder.visible(false).finish();
+            } else if nameplate(first_builder, &plates[index], rect, alpha) {
+                clicked = Some(index);
+            }
+        }
+
+        for (index, rect, alpha) in placed {
+            if !overlaps(rect, screen) { continue; }
+
+            let builder = self.start_indexed(theme, index as u64);
+            if nameplate(builder, &plates[index], rect, alpha) {
+                clicked = Some(index);
+            }
+        }
+
+        clicked
+    }
+}
+
+/// Draws a single nameplate at `rect` with the specified `alpha`, returning whether it was clicked
+fn nameplate(builder: WidgetBuilder, plate: &Nameplate, rect: Rect, alpha: f32) -> bool {
+    builder
+    .size(rect.size.x, rect.size.y)
+    .screen_pos(rect.pos.x, rect.pos.y)
+    .fade(alpha)
+    .children(|ui| {
+        ui.start("name").text(plate.name).fade(alpha).finish();
+
+        for frac in plate.bars {
+            ui.start("bar").fade(alpha).children(|ui| {
+                let mut rect = Rect::default();
+                ui.start("fill")
+                .fade(alpha)
+                .trigger_layout(&mut rect)
+                .clip(Rect::new(rect.pos, Point::new(rect.size.x * frac.clamp(0.0, 1.0), rect.size.y)))
+                .finish();
+            });
+        }
+
+        if !plate.icons.is_empty() {
+            ui.start("icons").fade(alpha).children(|ui| {
+                for icon in plate.icons {
+                    ui.start("icon").foreground(icon).fade(alpha).finish();
+                }
+            });
+        }
+    }).clicked
+}
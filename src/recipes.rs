@@ -1,6 +1,7 @@
+use std::f32::consts::{PI, TAU};
 use std::fmt::Display;
 
-use crate::{Align, Frame, KeyEvent, Point, Rect, WidgetState};
+use crate::{format, Align, Color, Frame, HeightRelative, KeyEvent, Layout, Point, Rect, SelectionMode, TextCarets, Transform, UiEvent, Vertex, WidgetBuilder, WidgetState};
 
 // Specific widget builders and convenience methods
 impl Frame {
@@ -36,6 +37,24 @@ impl Frame {
         self.start(theme).text(text).finish()
     }
 
+    /**
+    A label displaying the specified `value`, formatted according to the global
+    [`Locale`](format/struct.Locale.html).  This ensures that numbers, durations, and so on are
+    displayed consistently throughout your UI.  See [`label`](#method.label).
+
+    # Example
+    ```
+    fn hud(ui: &mut Frame, gold: i64, elapsed: Duration) {
+        ui.label_fmt("gold_label", gold);
+        ui.label_fmt("time_label", elapsed);
+        ui.label_fmt("accuracy_label", format::Value::Percent(0.875, 1));
+    }
+    ```
+    **/
+    pub fn label_fmt<V: Into<format::Value>>(&mut self, theme: &str, value: V) -> WidgetState {
+        self.label(theme, value.into().to_string())
+    }
+
     /**
     A simple label, but specifically designed to extend over multiple lines.  Generally,
     you should use `height_from: Normal` and `text_align: TopLeft`. Computes the widget height based on the theme width
@@ -83,6 +102,100 @@ impl Frame {
         self.start(theme).text(label).wants_mouse(true).finish()
     }
 
+    /**
+    A button showing only an image, with the ID `image`, drawn as the foreground of its `icon` child.
+    The `image` must be registered in the theme's image definitions, with an ID of the form
+    "{image_set_id}/{image_id}".  This is useful for toolbars and similar, where many buttons share
+    the same theme.
+
+    An example theme definition:
+    ```yaml
+    icon_button:
+      wants_mouse: true
+      background: gui/small_button
+      size: [28, 28]
+      children:
+        icon:
+          align: Center
+          size: [20, 20]
+    ```
+
+    # Example
+    ```
+    fn save_button(ui: &mut Frame) {
+        if ui.icon_button("icon_button", "icons/save").clicked {
+            println!("Saving");
+        }
+    }
+    ```
+    */
+    pub fn icon_button(&mut self, theme: &str, image: &str) -> WidgetState {
+        self.start(theme).wants_mouse(true).children(|ui| {
+            ui.start("icon").foreground(image).finish();
+        })
+    }
+
+    /**
+    A button with both an image, drawn as the foreground of its `icon` child, and a text `label`, shown in its
+    `label` child.  The icon is placed to the left, right, or above the label according to `placement`.  The
+    icon and label are separated by `icon_spacing` logical pixels, a theme `custom` float defaulting to 4.
+    Normally, the button should be sized from its children.
+
+    An example theme definition:
+    ```yaml
+    icon_text_button:
+      wants_mouse: true
+      background: gui/small_button
+      size_from: [Children, Children]
+      border: { all: 4 }
+      custom:
+        icon_spacing: 6
+      children:
+        icon:
+          size: [16, 16]
+        label:
+          font: small
+          size_from: [Text, FontLine]
+    ```
+
+    # Example
+    ```
+    fn open_button(ui: &mut Frame) {
+        if ui.icon_text_button("icon_text_button", "icons/open", "Open", IconPlacement::Left).clicked {
+            println!("Opening");
+        }
+    }
+    ```
+    */
+    pub fn icon_text_button<T: Into<String>>(
+        &mut self,
+        theme: &str,
+        image: &str,
+        label: T,
+        placement: IconPlacement,
+    ) -> WidgetState {
+        let builder = self.start(theme);
+        let spacing = builder.custom_float("icon_spacing", 4.0);
+
+        let builder = match placement {
+            IconPlacement::Left | IconPlacement::Right => {
+                builder.layout(Layout::Horizontal).layout_spacing(Point::new(spacing, 0.0))
+            },
+            IconPlacement::Top => builder.layout(Layout::Vertical).layout_spacing(Point::new(0.0, spacing)),
+        };
+
+        let label = label.into();
+        builder.wants_mouse(true).children(|ui| {
+            if placement == IconPlacement::Right {
+                ui.label("label", label);
+                ui.start("icon").foreground(image).finish();
+            } else {
+                ui.start("icon").foreground(image).finish();
+                ui.label("label", label);
+            }
+        })
+    }
+
     /**
     A simple vertical slider.  The slider button can be dragged by the user.  The position
     of the button is based on the relative distance of `value` from `min` and `max`.
@@ -110,7 +223,10 @@ impl Frame {
         let mut inner = Rect::default();
         let mut new_value = None;
 
-        self.start(theme)
+        let builder = self.start(theme);
+        let id = builder.widget.id().to_string();
+
+        builder
         .wants_mouse(true)
         .trigger_layout_inner(&mut inner)
         .children(|ui| {
@@ -134,6 +250,10 @@ impl Frame {
             }
         });
 
+        if new_value.is_some() {
+            self.push_event(UiEvent::ValueChanged { id });
+        }
+
         new_value
     }
 
@@ -173,7 +293,10 @@ impl Frame {
         let mut inner = Rect::default();
         let mut new_value = None;
 
-        self.start(theme)
+        let builder = self.start(theme);
+        let id = builder.widget.id().to_string();
+
+        builder
         .wants_mouse(true)
         .trigger_layout_inner(&mut inner)
         .children(|ui| {
@@ -197,6 +320,10 @@ impl Frame {
             }
         });
 
+        if new_value.is_some() {
+            self.push_event(UiEvent::ValueChanged { id });
+        }
+
         new_value
     }
 
@@ -241,7 +368,10 @@ impl Frame {
     pub fn spinner<T: PartialOrd + Display>(&mut self, theme: &str, value: T, min: T, max: T) -> i32 {
         let mut delta = 0;
 
-        self.start(theme).children(|ui| {
+        let builder = self.start(theme);
+        let id = builder.widget.id().to_string();
+
+        builder.children(|ui| {
             if ui.start("decrease").enabled(value > min).finish().clicked {
                 delta = -1;
             }
@@ -253,6 +383,10 @@ impl Frame {
             }
         });
 
+        if delta != 0 {
+            self.push_event(UiEvent::ValueChanged { id });
+        }
+
         delta
     }
 
@@ -265,7 +399,10 @@ impl Frame {
     pub fn wrapping_spinner<T: Display>(&mut self, theme: &str, value: T) -> i32 {
         let mut delta = 0;
 
-        self.start(theme).children(|ui| {
+        let builder = self.start(theme);
+        let id = builder.widget.id().to_string();
+
+        builder.children(|ui| {
             if ui.start("decrease").finish().clicked {
                 delta = -1;
             }
@@ -277,6 +414,10 @@ impl Frame {
             }
         });
 
+        if delta != 0 {
+            self.push_event(UiEvent::ValueChanged { id });
+        }
+
         delta
     }
 
@@ -326,10 +467,70 @@ impl Frame {
         title: F,
         children: G
     ) {
+        self.tree_node(theme, id, initially_expanded, None, title, children);
+    }
+
+    /**
+    A [`tree`](#method.tree) node which may be selected.  Each node is identified by its `index` within the
+    [`Selection`](struct.Selection.html) stored for `selection_id`, normally the id of the root node, with all nodes
+    of a tree sharing the same `selection_id`.  Clicking the node, outside of its `expand` and `collapse` buttons and
+    any children which want the mouse, updates the selection according to `mode`.  The node is
+    [`active`](struct.WidgetBuilder.html#method.active) while selected.  Returns whether the node is selected.
+
+    # Example
+    ```
+    fn folders(ui: &mut Frame, names: &[&str]) {
+        ui.selectable_tree("tree", "root", true, "folders", 0, SelectionMode::Single, |ui| {
+            ui.label("label", "Folders");
+        }, |ui| {
+            for (index, name) in names.iter().enumerate() {
+                ui.selectable_tree("tree", name, false, "folders", index + 1, SelectionMode::Single, |ui| {
+                    ui.label("label", *name);
+                }, |_| {});
+            }
+        });
+    }
+    ```
+    **/
+    #[allow(clippy::too_many_arguments)]
+    pub fn selectable_tree<F: FnOnce(&mut Frame), G: FnOnce(&mut Frame)>(
+        &mut self,
+        theme: &str,
+        id: &str,
+        initially_expanded: bool,
+        selection_id: &str,
+        index: usize,
+        mode: SelectionMode,
+        title: F,
+        children: G
+    ) -> bool {
+        let selected = self.selection(selection_id).is_selected(index);
+        if self.tree_node(theme, id, initially_expanded, Some(selected), title, children).clicked {
+            self.select(selection_id, index, mode);
+        }
+
+        self.selection(selection_id).is_selected(index)
+    }
+
+    /// A [`tree`](#method.tree) node, which wants the mouse and is active if `selected` is specified
+    fn tree_node<F: FnOnce(&mut Frame), G: FnOnce(&mut Frame)>(
+        &mut self,
+        theme: &str,
+        id: &str,
+        initially_expanded: bool,
+        selected: Option<bool>,
+        title: F,
+        children: G
+    ) -> WidgetState {
         self.context_internal().borrow_mut().init_state(id, true, initially_expanded);
         let expanded = self.is_expanded(id);
 
-        self.start(theme).children(|ui| {
+        let mut builder = self.start(theme);
+        if let Some(selected) = selected {
+            builder = builder.wants_mouse(true).active(selected);
+        }
+
+        builder.children(|ui| {
             (title)(ui);
 
             if expanded {
@@ -341,179 +542,715 @@ impl Frame {
             } else if ui.child("expand").clicked {
                 ui.set_expanded(id, true);
             }
-        });
+        })
     }
 
-    // TODO menubar
-
     /**
-    A drop down box. It displays its currently active selection (`current`), and opens a modal popup to select a new
-    choice from the list of `values` when the user clicks on it.  The specified `id` must be unique.
-    The method will return a selected choice on the frame the user clicks on it, otherwise returning `None`.
+    A section with a clickable `header` showing the `title` text, and content created by the `children` closure
+    which is shown only when the section is expanded.  Clicking the header toggles the section.  The expanded
+    state is stored in the [`PersistentState`](struct.PersistentState.html) of the `id`, and may be queried or set
+    with [`is_expanded`](#method.is_expanded) and [`set_expanded`](#method.set_expanded).  The header and its
+    `arrow` child are [`active`](struct.WidgetBuilder.html#method.active) when expanded.
 
-    An example theme definition;  See [`ScrollpaneBuilder`](struct.ScrollpaneBuilder.html) for the scrollpane example.
+    When toggled, the height of the `content` widget is animated over `animation_time` milliseconds, a theme
+    `custom` float defaulting to 150.  Once fully expanded, the `content` widget takes its height from the theme,
+    normally [`Children`](enum.HeightRelative.html#variant.Children).  Returns whether the section is expanded.
+    See also [`accordion`](#method.accordion).
+
+    An example theme definition:
     ```yaml
-    combo_box:
-      from: button
+    collapsing_header:
+      layout: Vertical
+      size_from: [Parent, Children]
+      custom:
+        animation_time: 200
       children:
-        expand:
-          size: [12, 12]
-          align: Right
-          foreground: gui/arrow_down
-        combo_box_popup:
-          from: scrollpane
-          width_from: Parent
-          height_from: Normal
-          size: [10, 75]
-          background: gui/small_button_normal
+        header:
+          from: button
+          size_from: [Parent, Normal]
+          height: 24
+          text_align: Left
+          border: { left: 24 }
+          wants_mouse: true
           children:
-            content:
-              size: [-18, -10]
-              children:
-                entry:
-                  from: button
-                  width_from: Parent
-                  size: [0, 25]
-            scrollbar_vertical:
-              size: [20, 20]
+            arrow:
+              align: Left
+              pos: [-20, 0]
+              size: [16, 16]
+              background: gui/arrow_right
+              image_color: "#FFF"
+        content:
+          layout: Vertical
+          size_from: [Parent, Children]
+          border: { left: 8 }
     ```
-    */
-    pub fn combo_box<'a, T: Display>(
+
+    # Example
+    ```
+    fn properties(ui: &mut Frame, name: &str, mass: f32) {
+        ui.collapsing_header("collapsing_header", "transform_section", "Transform", |ui| {
+            ui.label("label", name);
+            ui.label("label", format!("Mass: {}", mass));
+        });
+    }
+    ```
+    **/
+    pub fn collapsing_header<F: FnOnce(&mut Frame)>(
         &mut self,
         theme: &str,
         id: &str,
-        current: &T,
-        values: impl Iterator<Item=&'a T>,
-    ) -> Option<&'a T> {
-        let popup_id = format!("{}_popup", id);
-
-        let mut result = None;
-        let open_result = self.start(theme)
-        .text(current.to_string())
-        .wants_mouse(true)
-        .children(|ui| {
-            ui.child("expand");
+        title: &str,
+        children: F,
+    ) -> bool {
+        self.context_internal().borrow_mut().init_state(id, true, false);
+        let expanded = self.is_expanded(id);
+        let elapsed = self.cur_time_millis().saturating_sub(self.timer(id)) as f32;
+        let content_height = self.modify(id, |state| state.content_size.y);
+
+        let builder = self.start(theme);
+        let animation_time = builder.custom_float("animation_time", 150.0).max(0.0);
+        let progress = if animation_time > 0.0 { (elapsed / animation_time).min(1.0) } else { 1.0 };
+        let fraction = if expanded { progress } else { 1.0 - progress };
+        if progress < 1.0 {
+            builder.frame.request_redraw();
+        }
 
-            ui.start("combo_box_popup")
-            .id(&popup_id)
-            .initially_open(false)
-            .unclip()
-            .unparent()
-            .new_render_group()
-            .scrollpane(&format!("{}_content", popup_id))
+        let mut toggle = false;
+        builder.id(id).children(|ui| {
+            toggle = ui.start("header")
+            .text(title)
+            .active(expanded)
+            .wants_mouse(true)
             .children(|ui| {
-                for value in values {
-                    if ui.button("entry", value.to_string()).clicked {
-                        result = Some(value);
-                        ui.close(&popup_id);
-                    }
-                }
-            });
+                ui.start("arrow").active(expanded).finish();
+            }).clicked;
 
+            if fraction <= 0.0 { return; }
+
+            let content = ui.start("content");
+            let content = if fraction < 1.0 {
+                content.height_from(HeightRelative::Normal).height(content_height * fraction)
+            } else {
+                content
+            };
+
+            content.children(|ui| {
+                (children)(ui);
+                let size = ui.parent_max_child_bounds().size;
+                ui.modify(id, |state| state.content_size = size);
+            });
         });
-        if open_result.clicked {
-            self.open_modal(&popup_id);
-            self.close_modal_on_click_outside();
-        }
 
-        result
-    }
+        if toggle {
+            self.set_section_expanded(id, !expanded);
+        }
 
-    /// A simple toggle button that can be toggle on or off, based on the passed in `active` state.
-    ///
-    /// See [`button`](#method.button) for a YAML example.
-    pub fn toggle_button<T: Into<String>>(&mut self, theme: &str, label: T, active: bool) -> WidgetState {
-        self.start(theme).text(label).active(active).wants_mouse(true).finish()
+        expanded
     }
 
     /**
-    Creates a simple text input field.  The `id` that is passed in must be unique.
-    The text input will grab keyboard focus when the user clicks on it, allowing
-    the user to type text.  The return value will be `None` if no event occurred
-    this frame, or will contain the character added or key event if an event did occur.
-    Optionally, pass an initial_value which will set the field's text if it
-    is not already set.
+    A group of [`collapsing_header`](#method.collapsing_header) sections, one for each of the specified `titles`,
+    of which at most one may be expanded at a time.  Expanding one section collapses the others.  The content of
+    each section is created by the `f` closure, which is passed the index of the section.  Each section has an
+    id formed from the specified `id` and the section's index.  Returns the index of the expanded section, if any.
 
-    An example YAML theme definition:
+    An example theme definition:
     ```yaml
-    input_field:
-      font: small
-      border: { height: 4, width: 5 }
-      background: gui/input_field
-      text_align: Left
-      wants_mouse: true
-      size: [150, 24]
-      child_align: TopLeft
+    accordion:
+      layout: Vertical
+      size_from: [Parent, Children]
       children:
-        caret:
-          size: [2, -2]
-          height_from: Parent
-          background: gui/caret
+        section:
+          from: collapsing_header
     ```
 
     # Example
     ```
-    fn select_name(ui: &mut Frame, name: &mut String) {
-        if let Some(text) = ui.input_field("input_field", "unique_id", None) {
-            *name = text;
-        }
+    fn settings(ui: &mut Frame) {
+        ui.accordion("accordion", "settings", &["Video", "Audio"], |ui, index| {
+            match index {
+                0 => ui.label("label", "Resolution"),
+                _ => ui.label("label", "Volume"),
+            };
+        });
     }
     ```
-    */
-    pub fn input_field(&mut self, theme: &str, id: &str, initial_value: Option<String>) -> InputFieldResult {
-        let mut output = InputFieldResult {
-            cursor: Point::default(),
-            keyboard: None,
-        };
-
-        self.modify(id, |state| {
-            let text = match state.text.as_mut() {
-                Some(text) => text,
-                None => {
-                    state.text = Some(initial_value.unwrap_or_default());
-                    state.text.as_mut().unwrap()
-                }
-            };
-
-            if let Some(c) = state.characters.pop() {
-                match c {
-                    '\x08' => { text.pop(); }, // backspace
-                    '\r' => {}, // do nothing on enter, user will receive this as a key event as well
-                    _ => {
-                        output.keyboard = Some(InputFieldKeyboard::Char(c));
-                        text.push(c);
-                    },
-                }
-            }
-
-            if output.keyboard.is_none() {
-                if let Some(e) = state.key_events.pop() {
-                    output.keyboard = Some(InputFieldKeyboard::KeyEvent(e));
+    **/
+    pub fn accordion<F: FnMut(&mut Frame, usize)>(
+        &mut self,
+        theme: &str,
+        id: &str,
+        titles: &[&str],
+        mut f: F,
+    ) -> Option<usize> {
+        let section_id = |index: usize| format!("{}_{}", id, index);
+
+        let mut toggled = None;
+        self.start(theme).id(id).children(|ui| {
+            for (index, title) in titles.iter().enumerate() {
+                let id = section_id(index);
+                let was_expanded = ui.collapsing_header("section", &id, title, |ui| f(ui, index));
+                if !was_expanded && ui.is_expanded(&id) {
+                    toggled = Some(index);
                 }
             }
         });
-        let mut text_pos = Point::default();
-
-        let result = self.start(theme)
-        .id(id)
-        .trigger_text_layout(&mut text_pos)
-        .children(|ui| {
-            if ui.is_focus_keyboard(id) {
-                ui.start("caret").pos(text_pos.x, text_pos.y).finish();
-            }
-        });
 
-        output.cursor = text_pos;
+        // collapse all but the newly expanded section
+        let expanded_index = toggled.or_else(|| (0..titles.len()).find(|index| self.is_expanded(&section_id(*index))));
 
-        if result.clicked {
-            self.focus_keyboard(id);
+        for index in 0..titles.len() {
+            let id = section_id(index);
+            if Some(index) != expanded_index && self.is_expanded(&id) {
+                self.set_section_expanded(&id, false);
+            }
         }
 
-        output
+        expanded_index
+    }
+
+    fn set_section_expanded(&mut self, id: &str, expanded: bool) {
+        let time = self.cur_time_millis();
+        self.modify(id, |state| {
+            state.expanded = expanded;
+            state.timer = time;
+        });
     }
 
     /**
-    Creates a simple progress bar.  The drawing will be clipped based on the size
+    A floating widget, positioned relative to the bounds of the previously created widget with the id
+    `anchor_id` according to `placement`.  If there is not enough room on the screen in the requested direction,
+    the widget is flipped to the opposite side of the anchor, and it is always shifted to remain on screen.  The
+    widget is drawn in an [`always_top`](struct.WidgetBuilder.html#method.always_top) render group, is not
+    clipped by its parent, and does not affect its parent's layout.  This is the building block for drop downs,
+    pickers, and popovers.  The content is created by the `children` closure.
+
+    The anchor widget must have been created earlier in the current frame; if it was not, nothing is shown.
+    When the widget is sized from its children, its size is not known until it has been built, so the size from
+    the previous frame is used when positioning it.
+
+    An example theme definition:
+    ```yaml
+    popover:
+      background: gui/window_bg
+      border: { all: 5 }
+      layout: Vertical
+      size_from: [Children, Children]
+    ```
+
+    # Example
+    ```
+    fn color_picker(ui: &mut Frame) {
+        ui.start("button").id("color_button").text("Color").wants_mouse(true).finish();
+
+        if ui.is_open("color_popover") {
+            ui.overlay_anchored("popover", "color_button", OverlayPlacement::Below, |ui| {
+                ui.label("label", "Choose a color");
+            });
+        }
+    }
+    ```
+    **/
+    pub fn overlay_anchored<F: FnOnce(&mut Frame)>(
+        &mut self,
+        theme: &str,
+        anchor_id: &str,
+        placement: OverlayPlacement,
+        children: F,
+    ) -> WidgetState {
+        let anchor = (0..self.num_widgets()).rev()
+            .map(|index| self.widget(index))
+            .find(|widget| widget.id() == anchor_id)
+            .map(|widget| Rect::new(widget.pos(), widget.size()));
+
+        let anchor = match anchor {
+            None => return self.start(theme).visible(false).finish(),
+            Some(anchor) => anchor,
+        };
+
+        let mut rect = Rect::default();
+        let builder = self.start(theme).trigger_layout(&mut rect);
+        let id = builder.widget().id().to_string();
+        let last_size = builder.frame.modify(&id, |state| state.content_size);
+        let size = Point::new(
+            if last_size.x > 0.0 { last_size.x } else { rect.size.x },
+            if last_size.y > 0.0 { last_size.y } else { rect.size.y },
+        );
+
+        let display = builder.frame.display_size();
+        let fits = |placement: OverlayPlacement| match placement {
+            OverlayPlacement::Below => anchor.pos.y + anchor.size.y + size.y <= display.y,
+            OverlayPlacement::Above => anchor.pos.y - size.y >= 0.0,
+            OverlayPlacement::Right => anchor.pos.x + anchor.size.x + size.x <= display.x,
+            OverlayPlacement::Left => anchor.pos.x - size.x >= 0.0,
+        };
+
+        let placement = if fits(placement) || !fits(placement.opposite()) { placement } else { placement.opposite() };
+
+        let pos = match placement {
+            OverlayPlacement::Below => Point::new(anchor.pos.x, anchor.pos.y + anchor.size.y),
+            OverlayPlacement::Above => Point::new(anchor.pos.x, anchor.pos.y - size.y),
+            OverlayPlacement::Right => Point::new(anchor.pos.x + anchor.size.x, anchor.pos.y),
+            OverlayPlacement::Left => Point::new(anchor.pos.x - size.x, anchor.pos.y),
+        };
+        let pos = Point::new(
+            pos.x.min(display.x - size.x).max(0.0),
+            pos.y.min(display.y - size.y).max(0.0),
+        );
+
+        let index = builder.frame.num_widgets();
+        let state = builder
+            .screen_pos(pos.x, pos.y)
+            .unclip()
+            .unparent()
+            .always_top()
+            .children(children);
+
+        if state.visible {
+            let size = self.widget(index).size();
+            self.modify(&id, |state| state.content_size = size);
+        }
+
+        state
+    }
+
+    // TODO menubar
+
+    /**
+    A horizontal row of breadcrumbs, one for each of the specified path `segments`, such as the
+    directories in a file path.  Each segment is a `segment` button, separated by a `separator` widget.  The final
+    segment, representing the current location, is [`active`](struct.WidgetBuilder.html#method.active).
+    Returns the index of the segment that was clicked, if any.
+
+    An example theme definition:
+    ```yaml
+    breadcrumbs:
+      layout: Horizontal
+      size_from: [Parent, Children]
+      children:
+        segment:
+          from: button
+          width_from: Text
+        separator:
+          font: small
+          text: ">"
+          size_from: [Text, FontLine]
+    ```
+
+    # Example
+    ```
+    fn path_bar(ui: &mut Frame, path: &mut Vec<String>) {
+        let segments: Vec<&str> = path.iter().map(|s| s.as_str()).collect();
+        if let Some(index) = ui.breadcrumbs("breadcrumbs", &segments) {
+            path.truncate(index + 1);
+        }
+    }
+    ```
+    **/
+    pub fn breadcrumbs(&mut self, theme: &str, segments: &[&str]) -> Option<usize> {
+        let mut result = None;
+        self.start(theme).children(|ui| {
+            for (index, segment) in segments.iter().enumerate() {
+                if index > 0 {
+                    ui.child("separator");
+                }
+
+                let active = index + 1 == segments.len();
+                if ui.start("segment").text(*segment).active(active).wants_mouse(true).finish().clicked {
+                    result = Some(index);
+                }
+            }
+        });
+        result
+    }
+
+    /**
+    Page selection buttons for a set of `total` pages, with `current` being the index of the current page,
+    starting at zero.  Consists of `previous` and `next` buttons, and `page` buttons labeled with page numbers
+    starting at one.  The current page's button is [`active`](struct.WidgetBuilder.html#method.active).  When
+    there are many pages, only the first page, the last page, and the pages within `neighbors` (a theme `custom`
+    integer, defaulting to 1) of the current page are shown, with each run of hidden pages replaced by an `ellipsis`
+    widget.  Returns the index of the newly selected page, if the user clicked a page other than the current one.
+
+    An example theme definition:
+    ```yaml
+    pagination:
+      layout: Horizontal
+      layout_spacing: [4, 0]
+      size_from: [Children, Children]
+      custom:
+        neighbors: 2
+      children:
+        previous:
+          from: button
+          text: "<"
+          size: [24, 24]
+        next:
+          from: button
+          text: ">"
+          size: [24, 24]
+        page:
+          from: button
+          size: [24, 24]
+        ellipsis:
+          from: label
+          text: "..."
+          size: [24, 24]
+    ```
+
+    # Example
+    ```
+    fn results(ui: &mut Frame, page: &mut usize, total: usize) {
+        if let Some(new_page) = ui.pagination("pagination", *page, total) {
+            *page = new_page;
+        }
+    }
+    ```
+    **/
+    pub fn pagination(&mut self, theme: &str, current: usize, total: usize) -> Option<usize> {
+        if total == 0 { return None; }
+        let current = current.min(total - 1);
+
+        let builder = self.start(theme);
+        let neighbors = builder.custom_int("neighbors", 1).max(0) as usize;
+
+        let mut result = None;
+        builder.children(|ui| {
+            if ui.start("previous").enabled(current > 0).wants_mouse(true).finish().clicked {
+                result = Some(current - 1);
+            }
+
+            let mut last_shown = None;
+            for page in 0..total {
+                let shown = page == 0 || page + 1 == total ||
+                    (page + neighbors >= current && page <= current + neighbors);
+
+                // a single hidden page is shown rather than replaced by an ellipsis
+                let gap_of_one = page > 0 && page + 1 < total &&
+                    (page + neighbors + 1 == current && page == 1 || page == current + neighbors + 1 && page + 2 == total);
+
+                if !shown && !gap_of_one {
+                    continue;
+                }
+
+                if let Some(last) = last_shown {
+                    if page > last + 1 {
+                        ui.child("ellipsis");
+                    }
+                }
+                last_shown = Some(page);
+
+                if ui.start("page")
+                    .text((page + 1).to_string())
+                    .active(page == current)
+                    .wants_mouse(true)
+                    .finish()
+                    .clicked && page != current {
+                    result = Some(page);
+                }
+            }
+
+            if ui.start("next").enabled(current + 1 < total).wants_mouse(true).finish().clicked {
+                result = Some(current + 1);
+            }
+        });
+        result
+    }
+
+    /**
+    A drop down box. It displays its currently active selection (`current`), and opens a modal popup to select a new
+    choice from the list of `values` when the user clicks on it.  The specified `id` must be unique.
+    The method will return a selected choice on the frame the user clicks on it, otherwise returning `None`.
+
+    An example theme definition;  See [`ScrollpaneBuilder`](struct.ScrollpaneBuilder.html) for the scrollpane example.
+    ```yaml
+    combo_box:
+      from: button
+      children:
+        expand:
+          size: [12, 12]
+          align: Right
+          foreground: gui/arrow_down
+        combo_box_popup:
+          from: scrollpane
+          width_from: Parent
+          height_from: Normal
+          size: [10, 75]
+          background: gui/small_button_normal
+          children:
+            content:
+              size: [-18, -10]
+              children:
+                entry:
+                  from: button
+                  width_from: Parent
+                  size: [0, 25]
+            scrollbar_vertical:
+              size: [20, 20]
+    ```
+    */
+    pub fn combo_box<'a, T: Display>(
+        &mut self,
+        theme: &str,
+        id: &str,
+        current: &T,
+        values: impl Iterator<Item=&'a T>,
+    ) -> Option<&'a T> {
+        let popup_id = format!("{}_popup", id);
+
+        let mut result = None;
+        let open_result = self.start(theme)
+        .text(current.to_string())
+        .wants_mouse(true)
+        .children(|ui| {
+            ui.child("expand");
+
+            ui.start("combo_box_popup")
+            .id(&popup_id)
+            .initially_open(false)
+            .unclip()
+            .unparent()
+            .new_render_group()
+            .scrollpane(&format!("{}_content", popup_id))
+            .children(|ui| {
+                for value in values {
+                    if ui.button("entry", value.to_string()).clicked {
+                        result = Some(value);
+                        ui.close(&popup_id);
+                    }
+                }
+            });
+
+        });
+        if open_result.clicked {
+            self.open_modal(&popup_id);
+            self.close_modal_on_click_outside();
+        }
+
+        if result.is_some() {
+            self.push_event(UiEvent::ValueChanged { id: id.to_string() });
+        }
+
+        result
+    }
+
+    /// A simple toggle button that can be toggle on or off, based on the passed in `active` state.
+    ///
+    /// See [`button`](#method.button) for a YAML example.
+    pub fn toggle_button<T: Into<String>>(&mut self, theme: &str, label: T, active: bool) -> WidgetState {
+        self.start(theme).text(label).active(active).wants_mouse(true).finish()
+    }
+
+    /**
+    Creates a simple text input field.  The `id` that is passed in must be unique.
+    The text input will grab keyboard focus when the user clicks on it, or when it is
+    reached with Tab navigation, allowing the user to type text.  The return value will be `None` if no event occurred
+    this frame, or will contain the character added or key event if an event did occur.
+    Optionally, pass an initial_value which will set the field's text if it
+    is not already set.
+
+    An example YAML theme definition:
+    ```yaml
+    input_field:
+      font: small
+      border: { height: 4, width: 5 }
+      background: gui/input_field
+      text_align: Left
+      wants_mouse: true
+      size: [150, 24]
+      child_align: TopLeft
+      children:
+        caret:
+          size: [2, -2]
+          height_from: Parent
+          background: gui/caret
+    ```
+
+    # Example
+    ```
+    fn select_name(ui: &mut Frame, name: &mut String) {
+        if let Some(text) = ui.input_field("input_field", "unique_id", None) {
+            *name = text;
+        }
+    }
+    ```
+    */
+    pub fn input_field(&mut self, theme: &str, id: &str, initial_value: Option<String>) -> InputFieldResult {
+        let mut output = InputFieldResult {
+            cursor: Point::default(),
+            keyboard: None,
+        };
+
+        let mut changed = false;
+        self.modify(id, |state| {
+            let text = match state.text.as_mut() {
+                Some(text) => text,
+                None => {
+                    state.text = Some(initial_value.unwrap_or_default());
+                    state.text.as_mut().unwrap()
+                }
+            };
+
+            if let Some(c) = state.characters.pop() {
+                match c {
+                    '\x08' => { changed = text.pop().is_some(); }, // backspace
+                    '\r' => {}, // do nothing on enter, user will receive this as a key event as well
+                    '\t' => {}, // tab moves the keyboard focus instead
+                    _ => {
+                        output.keyboard = Some(InputFieldKeyboard::Char(c));
+                        text.push(c);
+                        changed = true;
+                    },
+                }
+            }
+
+            if output.keyboard.is_none() {
+                if let Some(e) = state.key_events.pop() {
+                    output.keyboard = Some(InputFieldKeyboard::KeyEvent(e));
+                }
+            }
+        });
+        if changed {
+            self.push_event(UiEvent::ValueChanged { id: id.to_string() });
+        }
+
+        let mut carets = TextCarets::default();
+
+        let builder = self.start(theme)
+        .id(id)
+        .focusable(true)
+        .trigger_text_carets(&mut carets);
+
+        let text_pos = carets.rect(carets.len()).map(|rect| rect.pos).unwrap_or_default();
+        let result = builder.children(|ui| {
+            if ui.is_focus_keyboard(id) {
+                ui.start("caret").pos(text_pos.x, text_pos.y).finish();
+            }
+        });
+
+        output.cursor = text_pos;
+
+        if result.clicked {
+            self.focus_keyboard(id);
+        }
+
+        output
+    }
+
+    /**
+    A vertical list of `count` items which the user may reorder by dragging.  Each item is created as an `item`
+    widget containing a `handle` widget, followed by any widgets created by the `f` closure, which is passed the
+    index of the item.  The user drags items by their handle; while dragging, the dragged item is set
+    [`active`](struct.WidgetBuilder.html#method.active) and an `insert_indicator` widget is drawn at the position
+    the item will be moved to.  When the item is dropped, this returns the original index of the item and
+    the index it should be moved to, as `(from, to)`.  Applying the move is the responsibility of the caller,
+    typically with `let item = vec.remove(from); vec.insert(to, item);`.  The `id` must be unique.
+
+    An example YAML theme definition:
+    ```yaml
+    reorderable_list:
+      layout: Vertical
+      size_from: [Parent, Children]
+      children:
+        item:
+          layout: Horizontal
+          size_from: [Parent, Normal]
+          height: 24
+          background: gui/button
+          children:
+            handle:
+              wants_mouse: true
+              size: [16, 16]
+              foreground: gui/drag_handle
+            label:
+              from: label
+        insert_indicator:
+          size: [0, 2]
+          width_from: Parent
+          background: gui/caret
+    ```
+
+    # Example
+    ```
+    fn load_order(ui: &mut Frame, mods: &mut Vec<String>) {
+        let moved = ui.reorderable_list("reorderable_list", "load_order", mods.len(), |ui, index| {
+            ui.label("label", mods[index].clone());
+        });
+
+        if let Some((from, to)) = moved {
+            let item = mods.remove(from);
+            mods.insert(to, item);
+        }
+    }
+    ```
+    **/
+    pub fn reorderable_list<F: FnMut(&mut Frame, usize)>(
+        &mut self,
+        theme: &str,
+        id: &str,
+        count: usize,
+        mut f: F,
+    ) -> Option<(usize, usize)> {
+        let dragging = self.context_internal().borrow_mut().drag_source(id).filter(|index| *index < count);
+        let mouse_y = self.mouse_pos().y;
+
+        let mut result = None;
+        let mut drag_start = None;
+        let mut dropped = false;
+
+        self.start(theme).id(id).children(|ui| {
+            let mut rects = Vec::with_capacity(count);
+            for index in 0..count {
+                let mut rect = Rect::default();
+                let mut handle_pressed = false;
+                ui.start("item")
+                .active(dragging == Some(index))
+                .trigger_layout(&mut rect)
+                .children(|ui| {
+                    handle_pressed = ui.start("handle").wants_mouse(true).finish().pressed;
+                    f(ui, index);
+                });
+                rects.push(rect);
+
+                if handle_pressed && dragging.is_none() {
+                    drag_start = Some(index);
+                } else if dragging == Some(index) && !handle_pressed {
+                    dropped = true;
+                }
+            }
+
+            let (from, first) = match (dragging, rects.first()) {
+                (Some(from), Some(first)) => (from, first),
+                _ => return,
+            };
+
+            // the drop position is before the first item whose center is below the mouse
+            let to = rects.iter().position(|rect| rect.pos.y + rect.size.y / 2.0 > mouse_y).unwrap_or(count);
+            let y = match rects.get(to) {
+                Some(rect) => rect.pos.y,
+                None => rects[count - 1].pos.y + rects[count - 1].size.y,
+            };
+            ui.start("insert_indicator").screen_pos(first.pos.x, y).finish();
+
+            if dropped {
+                let to = if to > from { to - 1 } else { to };
+                if to != from {
+                    result = Some((from, to));
+                }
+            }
+        });
+
+        if let Some(index) = drag_start {
+            self.context_internal().borrow_mut().set_drag_source(Some((id.to_string(), index)));
+        } else if dropped {
+            self.context_internal().borrow_mut().set_drag_source(None);
+        }
+
+        result
+    }
+
+    /**
+    Creates a simple progress bar.  The drawing will be clipped based on the size
     of the widget and the passed in `frac`.
 
     An example YAML theme definition:
@@ -560,6 +1297,217 @@ impl Frame {
         });
     }
 
+    /**
+    Creates an indeterminate spinner, such as for a task of unknown length.  The widget is rotated
+    continuously, at the rate in degrees per second specified by the theme `custom` float `rotation_speed`,
+    defaulting to 360.  To instead animate the spinner with a `Timed` image, set the speed to zero.
+
+    An example YAML theme definition:
+    ```yaml
+    loading_spinner:
+      foreground: gui/spinner
+      size: [32, 32]
+      custom:
+        rotation_speed: 270.0
+    ```
+    **/
+    pub fn loading_spinner(&mut self, theme: &str) {
+        let time = self.cur_time_millis();
+        let builder = self.start(theme);
+        let speed = builder.custom_float("rotation_speed", 360.0);
+        spin(builder, time, speed).finish();
+
+        self.request_redraw();
+    }
+
+    /**
+    A full screen loading overlay, drawn on top of all other widgets and blocking input to them.  The overlay
+    fades in when `visible` becomes true and fades out when it becomes false, over the number of milliseconds
+    specified by the theme `custom` float `fade_millis`, defaulting to 300.  While shown, it contains a
+    [`loading_spinner`](#method.loading_spinner) with the `spinner` child, the `progress`, if known, with a `bar` child whose
+    `fill` child is clipped to the progress fraction, and one of the `tips`, with the `tip` child.  The tips are
+    shown in turn, each for the number of milliseconds specified by the `custom` float `tip_millis`, defaulting
+    to 5000.  The `id` must be unique, and whether the overlay is shown is stored as the `toggled` field of
+    its [`PersistentState`](struct.PersistentState.html).
+
+    An example YAML theme definition:
+    ```yaml
+    loading_screen:
+      background: gui/black
+      layout: Vertical
+      child_align: Bot
+      border: { bot: 40 }
+      layout_spacing: [0, 10]
+      custom:
+        fade_millis: 500.0
+        tip_millis: 4000.0
+      children:
+        spinner:
+          foreground: gui/spinner
+          size: [32, 32]
+        bar:
+          background: gui/bar_bg
+          size: [300, 12]
+          children:
+            fill:
+              background: gui/bar_fill
+              width_from: Parent
+              height_from: Parent
+        tip:
+          font: medium
+          text_align: Center
+          width_from: Parent
+          height_from: FontLine
+    ```
+
+    # Example
+    ```
+    fn draw_loading(ui: &mut Frame, loader: &Loader) {
+        let tips = ["Press Space to jump", "Potions restore health over time"];
+        ui.loading_screen("loading_screen", "loading", !loader.is_done(), Some(loader.progress()), &tips);
+    }
+    ```
+    **/
+    pub fn loading_screen(&mut self, theme: &str, id: &str, visible: bool, progress: Option<f32>, tips: &[&str]) {
+        let time = self.cur_time_millis();
+        let fade_millis = self.custom_float(theme, "fade_millis", 300.0).max(1.0);
+        let tip_millis = self.custom_float(theme, "tip_millis", 5000.0).max(1.0);
+
+        let fraction = |start: u32| (time.saturating_sub(start) as f32 / fade_millis).min(1.0);
+        let (alpha, start) = self.modify(id, |state| {
+            if state.toggled != visible {
+                // reverse the direction of the fade, keeping the current opacity.  a start
+                // time of zero is reserved for an overlay which has never been shown
+                let remaining = 1.0 - fraction(state.base_time_millis);
+                state.toggled = visible;
+                state.base_time_millis = time.saturating_sub((remaining * fade_millis) as u32).max(1);
+            }
+
+            let start = state.base_time_millis;
+            let alpha = match (visible, start) {
+                (true, _) => fraction(start),
+                (false, 0) => 0.0,
+                (false, _) => 1.0 - fraction(start),
+            };
+            (alpha, start)
+        });
+
+        if alpha <= 0.0 { return; }
+
+        let display_size = self.display_size();
+        self.start(theme)
+        .id(id)
+        .size(display_size.x, display_size.y)
+        .screen_pos(0.0, 0.0)
+        .always_top()
+        .wants_mouse(true)
+        .fade(alpha)
+        .children(|ui| {
+            let spinner = ui.start("spinner");
+            let speed = spinner.custom_float("rotation_speed", 360.0);
+            spin(spinner, time, speed).fade(alpha).finish();
+
+            if let Some(progress) = progress {
+                ui.start("bar").fade(alpha).children(|ui| {
+                    let mut rect = Rect::default();
+                    ui.start("fill")
+                    .fade(alpha)
+                    .trigger_layout(&mut rect)
+                    .clip(Rect::new(rect.pos, Point::new(rect.size.x * progress.clamp(0.0, 1.0), rect.size.y)))
+                    .finish();
+                });
+            }
+
+            if !tips.is_empty() {
+                let index = (time.saturating_sub(start) as f32 / tip_millis) as usize % tips.len();
+                ui.start("tip").text(tips[index]).fade(alpha).finish();
+            }
+        });
+
+        self.request_redraw();
+    }
+
+    /**
+    A button which can't be clicked while it is cooling down, such as an ability button.  The `remaining`
+    fraction of the cooldown, from 1.0 when the cooldown starts down to 0.0 when the button is ready, is shown
+    by the `overlay` child.  By default, the overlay is a sweep of solid triangles, drawn with the overlay's
+    `image_color`, which clears clockwise from the top as the cooldown completes.  If the theme `custom` string
+    `cooldown_style` is `fill`, the overlay's background image is instead shown over the `remaining` fraction
+    from the bottom of the button.  While cooling down, the button is disabled and any `remaining_text`,
+    such as the seconds remaining, is shown in the `remaining` child.
+
+    An example theme definition:
+    ```yaml
+    cooldown_button:
+      wants_mouse: true
+      background: gui/small_button
+      font: small
+      text_align: Bot
+      size: [48, 48]
+      border: { all: 4 }
+      children:
+        overlay:
+          width_from: Parent
+          height_from: Parent
+          image_color: "#0008"
+        remaining:
+          font: medium
+          text_align: Center
+          width_from: Parent
+          height_from: Parent
+    ```
+
+    # Example
+    ```
+    fn fireball_button(ui: &mut Frame, cooldown_left: f32, cooldown_total: f32) {
+        let text = format!("{:.0}", cooldown_left.ceil());
+        let remaining = cooldown_left / cooldown_total;
+        if ui.cooldown_button("cooldown_button", "F", remaining, Some(&text)).clicked {
+            println!("Casting fireball");
+        }
+    }
+    ```
+    **/
+    pub fn cooldown_button<T: Into<String>>(
+        &mut self,
+        theme: &str,
+        label: T,
+        remaining: f32,
+        remaining_text: Option<&str>,
+    ) -> WidgetState {
+        let remaining = remaining.clamp(0.0, 1.0);
+        let builder = self.start(theme);
+        let fill = builder.custom_string("cooldown_style", "sweep".to_string()) == "fill";
+
+        builder.text(label).wants_mouse(true).enabled(remaining <= 0.0).children(|ui| {
+            if remaining <= 0.0 { return; }
+
+            ui.cooldown_overlay(remaining, fill);
+
+            if let Some(text) = remaining_text {
+                ui.label("remaining", text);
+            }
+        })
+    }
+
+    /// Adds the `overlay` child showing the `remaining` fraction of a cooldown, as a sweep or a `fill`.
+    /// See [`cooldown_button`](#method.cooldown_button).
+    pub(crate) fn cooldown_overlay(&mut self, remaining: f32, fill: bool) {
+        let mut rect = Rect::default();
+        let overlay = self.start("overlay").trigger_layout_inner(&mut rect);
+        if fill {
+            let height = rect.size.y * remaining;
+            overlay.clip(Rect::new(
+                Point::new(rect.pos.x, rect.pos.y + rect.size.y - height),
+                Point::new(rect.size.x, height),
+            )).finish();
+        } else {
+            let color = overlay.widget().image_color();
+            let (vertices, indices) = sweep_triangles(rect.size, 1.0 - remaining, color);
+            overlay.triangles(vertices, indices).finish();
+        }
+    }
+
     /**
     Creates a simple tooltip with the specified text.  The tooltip is placed based on the
     position of the mouse.
@@ -615,6 +1563,364 @@ impl Frame {
     pub fn scrollpane<F: FnOnce(&mut Frame)>(&mut self, theme: &str, content_id: &str, children: F) {
         self.start(theme).scrollpane(content_id).children(children);
     }
+
+    /**
+    A debug window showing the [`frame profile`](bench/fn.frame_profile.html) for the most recently
+    completed frame, with one row per widget theme.  Clicking on a column header sorts the rows by that
+    column.  The `theme` is also used as the window ID.  Profiling must be enabled with
+    [`bench::set_frame_profiling`](bench/fn.set_frame_profiling.html) for the window to show any data.
+
+    An example theme definition:
+    ```yaml
+    frame_profiler:
+      from: window
+      size: [500, 400]
+      children:
+        titlebar:
+          children:
+            title:
+              text: "Frame Profiler"
+        header:
+          size_from: [Parent, Children]
+          layout: Horizontal
+          children:
+            name_column:
+              from: button
+              size: [200, 24]
+            column:
+              from: button
+              size: [60, 24]
+        rows:
+          from: scrollpane
+          size: [0, -30]
+          pos: [0, 30]
+          children:
+            content:
+              children:
+                row:
+                  size_from: [Parent, Children]
+                  layout: Horizontal
+                  children:
+                    name_cell:
+                      from: label
+                      size: [200, 20]
+                    cell:
+                      from: label
+                      size: [60, 20]
+    ```
+
+    # Example
+    ```
+    fn debug_ui(ui: &mut Frame) {
+        thyme::bench::set_frame_profiling(true);
+        ui.frame_profiler("frame_profiler");
+    }
+    ```
+    **/
+    pub fn frame_profiler(&mut self, theme: &str) {
+        use crate::bench::{self, ProfileSort};
+
+        let profile = bench::frame_profile();
+        let cur_sort = bench::frame_profile_sort();
+
+        self.start(theme)
+        .window(theme)
+        .children(|ui| {
+            ui.start("header").children(|ui| {
+                let columns = [
+                    ("name_column", "Theme", ProfileSort::ThemeId),
+                    ("column", "Count", ProfileSort::Count),
+                    ("column", "Build", ProfileSort::Build),
+                    ("column", "Layout", ProfileSort::Layout),
+                    ("column", "Text", ProfileSort::Text),
+                ];
+
+                for (column_theme, label, sort) in columns {
+                    if ui.toggle_button(column_theme, label, sort == cur_sort).clicked {
+                        bench::set_frame_profile_sort(sort);
+                    }
+                }
+            });
+
+            ui.scrollpane("rows", &format!("{}_rows", theme), |ui| {
+                for entry in profile.entries() {
+                    ui.start("row").children(|ui| {
+                        ui.label("name_cell", entry.theme_id());
+                        ui.label("cell", entry.count().to_string());
+                        ui.label("cell", format!("{:.1}", bench::micros(entry.build())));
+                        ui.label("cell", format!("{:.1}", bench::micros(entry.layout())));
+                        ui.label("cell", format!("{:.1}", bench::micros(entry.text())));
+                    });
+                }
+            });
+        });
+    }
+
+    /**
+    A compact rolling graph of a sampled value, such as the frame time, for performance overlays.  Each call adds
+    the `latest` value to the samples stored in the [`PersistentState`](struct.PersistentState.html) for `id`, which
+    must be unique, dropping the oldest samples beyond the theme `custom` int `samples`, which defaults to 120.
+    Values which are not finite are ignored.
+
+    The samples are drawn as a line in the `graph` child, with the newest sample on the right, using the graph's
+    `image_color` and the `line_width` custom float, which defaults to 1.0.  The value at the top of the graph is
+    the `max_value` custom float, or the largest sample if it is not set.  Samples at or above the `warn_threshold`
+    and `error_threshold` custom floats are instead drawn with the `warn_color` and `error_color` custom strings,
+    and each threshold is marked with a line across the graph.  The minimum, average, and maximum samples are shown
+    in the `min`, `avg`, and `max` labels inside the `stats` child, with their text colored in the same way.
+
+    An example theme definition:
+    ```yaml
+    sample_graph:
+      background: gui/window_bg
+      layout: Vertical
+      border: { all: 4 }
+      size: [200, 90]
+      custom:
+        samples: 100
+        max_value: 50.0
+        warn_threshold: 16.7
+        error_threshold: 33.3
+        warn_color: "#FC4"
+        error_color: "#F44"
+      children:
+        graph:
+          width_from: Parent
+          height: 60
+          image_color: "#4F4"
+        stats:
+          layout: Horizontal
+          size_from: [Parent, Children]
+          children:
+            min:
+              from: label
+              font: small
+              size: [64, 20]
+            avg:
+              from: min
+            max:
+              from: min
+    ```
+
+    # Example
+    ```
+    fn perf_overlay(ui: &mut Frame, frame_time: std::time::Duration) {
+        ui.sample_graph("sample_graph", "frame_time", frame_time.as_secs_f32() * 1000.0);
+    }
+    ```
+    **/
+    pub fn sample_graph(&mut self, theme: &str, id: &str, latest: f32) {
+        let builder = self.start(theme);
+        let capacity = builder.custom_int("samples", 120).max(2) as usize;
+        let line_width = builder.custom_float("line_width", 1.0);
+        let max_value = builder.custom_float("max_value", 0.0);
+        let thresholds = SampleThresholds {
+            warn: builder.custom_float("warn_threshold", f32::INFINITY),
+            error: builder.custom_float("error_threshold", f32::INFINITY),
+            warn_color: Color::parse_str(&builder.custom_string("warn_color", "#FC4".to_string()))
+                .unwrap_or_else(Color::yellow),
+            error_color: Color::parse_str(&builder.custom_string("error_color", "#F44".to_string()))
+                .unwrap_or_else(Color::red),
+        };
+
+        builder.children(|ui| {
+            let samples = ui.modify(id, |state| {
+                if latest.is_finite() {
+                    state.samples.push(latest);
+                }
+                let excess = state.samples.len().saturating_sub(capacity);
+                state.samples.drain(..excess);
+                state.samples.clone()
+            });
+
+            let min = samples.iter().copied().fold(f32::INFINITY, f32::min);
+            let max = samples.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+            let avg = samples.iter().sum::<f32>() / samples.len().max(1) as f32;
+            let top = if max_value > 0.0 { max_value } else { max.max(f32::EPSILON) };
+
+            let mut rect = Rect::default();
+            let graph = ui.start("graph").trigger_layout_inner(&mut rect);
+            let color = graph.widget().image_color();
+            let (vertices, indices) = graph_triangles(&samples, capacity, rect.size, top, line_width, color, &thresholds);
+            graph.triangles(vertices, indices).finish();
+
+            if samples.is_empty() { return; }
+
+            ui.start("stats").children(|ui| {
+                for (child, name, value) in [("min", "Min", min), ("avg", "Avg", avg), ("max", "Max", max)] {
+                    let builder = ui.start(child).text(format!("{} {:.1}", name, value));
+                    match thresholds.color(value) {
+                        None => builder.finish(),
+                        Some(color) => builder.text_color(color).finish(),
+                    };
+                }
+            });
+        });
+    }
+
+    /**
+    Shows the error from the most recent failed theme rebuild, such as a YAML typo found during live reload,
+    drawn on top of all other widgets.  Does nothing if there is no error.  The last successfully built theme
+    remains in use while the error is shown, so the overlay is drawn with it.  The error text is shown in the
+    `message` child, as a [`multiline_label`](#method.multiline_label), and clicking the `retry` button
+    child rebuilds the theme on the next [`live reload check`](struct.Context.html#method.check_live_reload).
+    The [`AppBuilder`](struct.AppBuilder.html) main loops show this automatically using the `reload_error`
+    theme, if it is defined.  See [`Context::reload_error`](struct.Context.html#method.reload_error).
+
+    An example YAML theme definition:
+    ```yaml
+    reload_error:
+      background: gui/window_bg
+      wants_mouse: true
+      layout: Vertical
+      layout_spacing: [5, 5]
+      border: { all: 10 }
+      align: Top
+      pos: [0, 10]
+      size: [600, 0]
+      height_from: Children
+      children:
+        title:
+          from: label
+          font: medium
+          text: "Unable to reload theme"
+        message:
+          from: label
+          text_align: TopLeft
+          width_from: Parent
+          height_from: Normal
+        retry:
+          from: button
+          text: "Retry"
+    ```
+
+    # Example
+    ```
+    fn draw_ui(ui: &mut Frame) {
+        // draw the application UI
+        ui.reload_error("reload_error");
+    }
+    ```
+    **/
+    pub fn reload_error(&mut self, theme: &str) {
+        let error = match self.context_internal().borrow().reload_error() {
+            None => return,
+            Some(error) => error.to_string(),
+        };
+
+        self.start(theme)
+        .always_top()
+        .wants_mouse(true)
+        .children(|ui| {
+            ui.child("title");
+            ui.multiline_label("message", error);
+
+            if ui.start("retry").wants_mouse(true).finish().clicked {
+                ui.context_internal().borrow_mut().retry_reload();
+            }
+        });
+    }
+}
+
+/// The triangles covering the part of a rectangle of the specified `size` which lies clockwise from the
+/// `start` fraction of a full turn, measured from the top center, to the end of the turn
+fn sweep_triangles(size: Point, start: f32, color: Color) -> (Vec<Vertex>, Vec<u32>) {
+    let half = size * 0.5;
+    let start = start * TAU;
+
+    // the point on the edge of the rectangle in the direction of the angle from its center
+    let edge = |angle: f32| {
+        let (x, y) = (angle.sin(), -angle.cos());
+        let dist = (half.x / x.abs()).min(half.y / y.abs());
+        Vertex::new(Point::new(half.x + x * dist, half.y + y * dist), color)
+    };
+
+    let corner = half.x.atan2(half.y);
+    let corners = [corner, PI - corner, PI + corner, TAU - corner];
+
+    let mut vertices = vec![Vertex::new(half, color), edge(start)];
+    vertices.extend(corners.iter().filter(|angle| **angle > start).map(|angle| edge(*angle)));
+    vertices.push(edge(TAU));
+
+    let indices = (1..vertices.len() as u32 - 1).flat_map(|index| [0, index, index + 1]).collect();
+    (vertices, indices)
+}
+
+/// The theme thresholds of a [`sample_graph`](struct.Frame.html#method.sample_graph), and their colors
+struct SampleThresholds {
+    warn: f32,
+    error: f32,
+    warn_color: Color,
+    error_color: Color,
+}
+
+impl SampleThresholds {
+    /// The color for a sample of the specified `value`, or `None` if it is below both thresholds
+    fn color(&self, value: f32) -> Option<Color> {
+        if value >= self.error {
+            Some(self.error_color)
+        } else if value >= self.warn {
+            Some(self.warn_color)
+        } else {
+            None
+        }
+    }
+}
+
+/// Triangles drawing the `samples` of a [`sample_graph`](struct.Frame.html#method.sample_graph) as a line of
+/// the specified `width` within `size`, with room for `capacity` samples and the value `top` at the top, along
+/// with a line across the graph for each threshold below `top`
+fn graph_triangles(
+    samples: &[f32],
+    capacity: usize,
+    size: Point,
+    top: f32,
+    width: f32,
+    color: Color,
+    thresholds: &SampleThresholds,
+) -> (Vec<Vertex>, Vec<u32>) {
+    let step = size.x / (capacity - 1) as f32;
+    let offset = capacity - samples.len();
+    let y = |value: f32| size.y * (1.0 - (value / top).clamp(0.0, 1.0));
+
+    let mut vertices = Vec::new();
+    let mut indices = Vec::new();
+
+    for (threshold, threshold_color) in [(thresholds.warn, thresholds.warn_color), (thresholds.error, thresholds.error_color)] {
+        if threshold > top { continue; }
+
+        let start = Vertex::new(Point::new(0.0, y(threshold)), threshold_color);
+        let end = Vertex::new(Point::new(size.x, y(threshold)), threshold_color);
+        line_triangles(start, end, width, &mut vertices, &mut indices);
+    }
+
+    let points: Vec<Vertex> = samples.iter().enumerate().map(|(index, value)| {
+        let pos = Point::new((index + offset) as f32 * step, y(*value));
+        Vertex::new(pos, thresholds.color(*value).unwrap_or(color))
+    }).collect();
+
+    for pair in points.windows(2) {
+        line_triangles(pair[0], pair[1], width, &mut vertices, &mut indices);
+    }
+
+    (vertices, indices)
+}
+
+/// Adds a quad of the specified `width` from `start` to `end` to the `vertices` and `indices`
+fn line_triangles(start: Vertex, end: Vertex, width: f32, vertices: &mut Vec<Vertex>, indices: &mut Vec<u32>) {
+    let delta = end.pos - start.pos;
+    let len = (delta.x * delta.x + delta.y * delta.y).sqrt();
+    if len <= 0.0 { return; }
+
+    let normal = Point::new(-delta.y, delta.x) * (width * 0.5 / len);
+    let base = vertices.len() as u32;
+    vertices.extend([
+        Vertex::new(start.pos + normal, start.color),
+        Vertex::new(start.pos - normal, start.color),
+        Vertex::new(end.pos + normal, end.color),
+        Vertex::new(end.pos - normal, end.color),
+    ]);
+    indices.extend([base, base + 1, base + 2, base + 2, base + 1, base + 3]);
 }
 
 /// Result struct returned from the creation of an input field
@@ -635,4 +1941,54 @@ pub enum InputFieldKeyboard {
 
     /// A virtual keycode event
     KeyEvent(KeyEvent),
-}
\ No newline at end of file
+}
+
+/// Where the icon is placed relative to the text in an
+/// [`icon_text_button`](struct.Frame.html#method.icon_text_button)
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum IconPlacement {
+    /// The icon is to the left of the text
+    Left,
+
+    /// The icon is to the right of the text
+    Right,
+
+    /// The icon is above the text
+    Top,
+}
+
+/// Where an [`overlay_anchored`](struct.Frame.html#method.overlay_anchored) widget is placed,
+/// relative to its anchor
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OverlayPlacement {
+    /// Below the anchor, aligned with its left edge
+    Below,
+
+    /// Above the anchor, aligned with its left edge
+    Above,
+
+    /// To the right of the anchor, aligned with its top edge
+    Right,
+
+    /// To the left of the anchor, aligned with its top edge
+    Left,
+}
+
+impl OverlayPlacement {
+    fn opposite(self) -> OverlayPlacement {
+        match self {
+            OverlayPlacement::Below => OverlayPlacement::Above,
+            OverlayPlacement::Above => OverlayPlacement::Below,
+            OverlayPlacement::Right => OverlayPlacement::Left,
+            OverlayPlacement::Left => OverlayPlacement::Right,
+        }
+    }
+}
+
+/// Rotates the widget being built according to the current `time` and the rotation `speed`, in degrees per second
+fn spin(builder: WidgetBuilder, time: u32, speed: f32) -> WidgetBuilder {
+    if speed == 0.0 { return builder; }
+
+    let degrees = (time as f64 / 1000.0 * speed as f64).rem_euclid(360.0);
+    builder.transform(Transform::rotation(degrees as f32))
+}
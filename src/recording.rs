@@ -0,0 +1,216 @@
+use std::io::Write;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::{Clock, Error, Rect, SystemClock};
+
+/// The default limit on the number of frames held by a [`Recorder`](struct.Recorder.html)
+const DEFAULT_MAX_FRAMES: usize = 600;
+
+struct RecordedFrame {
+    data: Vec<u8>,
+    time: Duration,
+}
+
+/**
+Records rendered frames at a fixed rate, for documenting widgets or filing visual bug reports.  Each frame
+is read by the application, for example with [`GLRenderer::read_pixels`](struct.GLRenderer.html#method.read_pixels)
+after drawing, or with [`DrawData::rasterize`](struct.DrawData.html#method.rasterize), and passed to
+[`capture`](#method.capture).  Frames arriving faster than the frame rate are skipped, and reading the pixels
+may be avoided entirely for those frames by checking [`wants_frame`](#method.wants_frame) first.
+
+The recording may be cropped to a region of the display, and is held in memory until written as an animated
+PNG with [`write_apng`](#method.write_apng) or as individual PNG files with [`write_frames`](#method.write_frames).
+All frames must have the same size; frames with a different size than the first, such as after the window is
+resized, are skipped.  Requires the `recording` feature.
+
+# Example
+```
+let mut recorder = thyme::Recorder::new(20).with_region(Rect::new(Point::new(0.0, 0.0), Point::new(400.0, 300.0)));
+
+// each frame, after drawing but before swapping buffers
+if recorder.wants_frame() {
+    let (width, height) = (1280, 720);
+    recorder.capture(width, height, &renderer.read_pixels(width, height));
+}
+
+// once done
+recorder.write_apng(Path::new("recording.png"))?;
+```
+**/
+pub struct Recorder {
+    clock: Box<dyn Clock>,
+    interval: Duration,
+    region: Option<Rect>,
+    max_frames: usize,
+    size: Option<(u32, u32)>,
+    frames: Vec<RecordedFrame>,
+}
+
+impl Recorder {
+    /// Creates a new, empty recorder capturing at most `frame_rate` frames per second
+    pub fn new(frame_rate: u32) -> Recorder {
+        Recorder {
+            clock: Box::new(SystemClock::new()),
+            interval: Duration::from_secs(1) / frame_rate.max(1),
+            region: None,
+            max_frames: DEFAULT_MAX_FRAMES,
+            size: None,
+            frames: Vec::new(),
+        }
+    }
+
+    /// Crops each captured frame to the specified `region`, in physical pixels.  By default, the
+    /// entire frame is recorded.
+    pub fn with_region(self, region: Rect) -> Recorder {
+        Recorder { region: Some(region), ..self }
+    }
+
+    /// Limits the recording to `max_frames` frames, after which further frames are skipped.  This bounds
+    /// the memory used by the recording.  The default value is 600.
+    pub fn with_max_frames(self, max_frames: usize) -> Recorder {
+        Recorder { max_frames, ..self }
+    }
+
+    /// Uses the specified `clock` to time captured frames, such as the same [`Clock`](trait.Clock.html)
+    /// passed to [`Context::set_clock`](struct.Context.html#method.set_clock).  By default, the
+    /// [`SystemClock`](struct.SystemClock.html) is used.
+    pub fn with_clock<C: Clock + 'static>(self, clock: C) -> Recorder {
+        Recorder { clock: Box::new(clock), ..self }
+    }
+
+    /// Returns whether a frame passed to [`capture`](#method.capture) now would be recorded, based on the
+    /// frame rate and frame limit.
+    pub fn wants_frame(&self) -> bool {
+        if self.frames.len() >= self.max_frames { return false; }
+
+        match self.frames.last() {
+            None => true,
+            Some(last) => self.clock.elapsed() >= last.time + self.interval,
+        }
+    }
+
+    /**
+    Records the RGBA pixel `data`, with 8 bits per channel, in rows from the top of the display to the
+    bottom, for a frame of the specified `width` and `height` in physical pixels.  The frame is skipped
+    if it is not [`wanted`](#method.wants_frame) or its cropped size differs from earlier frames.  Returns
+    whether the frame was recorded.
+    **/
+    pub fn capture(&mut self, width: u32, height: u32, data: &[u8]) -> bool {
+        if !self.wants_frame() { return false; }
+
+        if data.len() != (width * height * 4) as usize {
+            log::warn!("Recorder frame data does not match the frame size of {}x{}", width, height);
+            return false;
+        }
+
+        let (x, y, crop_width, crop_height) = self.crop(width, height);
+        match self.size {
+            None => self.size = Some((crop_width, crop_height)),
+            Some(size) => if size != (crop_width, crop_height) {
+                log::warn!("Skipping recorded frame with a size of {}x{}, rather than {}x{}",
+                    crop_width, crop_height, size.0, size.1);
+                return false;
+            }
+        }
+
+        let row_len = (crop_width * 4) as usize;
+        let mut cropped = Vec::with_capacity(row_len * crop_height as usize);
+        for row in y..(y + crop_height) {
+            let start = ((row * width + x) * 4) as usize;
+            cropped.extend_from_slice(&data[start..start + row_len]);
+        }
+
+        self.frames.push(RecordedFrame { data: cropped, time: self.clock.elapsed() });
+        true
+    }
+
+    /// The position and size of the recorded region within a frame of size `width`, `height`
+    fn crop(&self, width: u32, height: u32) -> (u32, u32, u32, u32) {
+        let region = match self.region {
+            None => return (0, 0, width, height),
+            Some(region) => region,
+        };
+
+        let x = (region.pos.x.max(0.0) as u32).min(width);
+        let y = (region.pos.y.max(0.0) as u32).min(height);
+        let crop_width = (region.size.x.max(0.0) as u32).min(width - x);
+        let crop_height = (region.size.y.max(0.0) as u32).min(height - y);
+        (x, y, crop_width, crop_height)
+    }
+
+    /// Returns the number of frames recorded so far
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns the size of the recorded frames in pixels, or `None` if no frames have been recorded
+    pub fn frame_size(&self) -> Option<(u32, u32)> {
+        self.size
+    }
+
+    /// Discards all recorded frames, so that a new recording may be started
+    pub fn clear(&mut self) {
+        self.frames.clear();
+        self.size = None;
+    }
+
+    /// Encodes the recorded frames as an animated PNG, looping forever, with each frame shown for the time
+    /// until the next frame was captured.  Returns `Err` if no frames have been recorded.
+    pub fn to_apng(&self) -> Result<Vec<u8>, Error> {
+        let (width, height) = self.frame_size().ok_or_else(no_frames_error)?;
+
+        let mut out = Vec::new();
+        let mut encoder = png::Encoder::new(&mut out, width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_animated(self.frames.len() as u32, 0).map_err(Error::Png)?;
+
+        let mut writer = encoder.write_header().map_err(Error::Png)?;
+        for (index, frame) in self.frames.iter().enumerate() {
+            let delay = match self.frames.get(index + 1) {
+                None => self.interval,
+                Some(next) => next.time - frame.time,
+            };
+            let delay_millis = delay.as_millis().min(u16::MAX as u128) as u16;
+
+            writer.set_frame_delay(delay_millis, 1000).map_err(Error::Png)?;
+            writer.write_image_data(&frame.data).map_err(Error::Png)?;
+        }
+        writer.finish().map_err(Error::Png)?;
+
+        Ok(out)
+    }
+
+    /// Writes the recorded frames as an animated PNG to the file at `path`.  See [`to_apng`](#method.to_apng).
+    pub fn write_apng(&self, path: &Path) -> Result<(), Error> {
+        let data = self.to_apng()?;
+        std::fs::write(path, data).map_err(Error::IO)
+    }
+
+    /// Writes each recorded frame as a separate PNG file in the directory `dir`, which is created if needed.
+    /// The files are named by frame index, from `frame_00000.png` onwards.
+    pub fn write_frames(&self, dir: &Path) -> Result<(), Error> {
+        let (width, height) = self.frame_size().ok_or_else(no_frames_error)?;
+        std::fs::create_dir_all(dir).map_err(Error::IO)?;
+
+        for (index, frame) in self.frames.iter().enumerate() {
+            let file = std::fs::File::create(dir.join(format!("frame_{:05}.png", index))).map_err(Error::IO)?;
+            let mut file = std::io::BufWriter::new(file);
+
+            let mut encoder = png::Encoder::new(&mut file, width, height);
+            encoder.set_color(png::ColorType::Rgba);
+            encoder.set_depth(png::BitDepth::Eight);
+            let mut writer = encoder.write_header().map_err(Error::Png)?;
+            writer.write_image_data(&frame.data).map_err(Error::Png)?;
+            writer.finish().map_err(Error::Png)?;
+            file.flush().map_err(Error::IO)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn no_frames_error() -> Error {
+    Error::IO(std::io::Error::other("The recording does not contain any frames"))
+}
@@ -1,8 +1,10 @@
 use std::num::NonZeroU16;
 
-use crate::{Color, Rect, Point, Error};
+use crate::{Color, CompressedFormat, Rect, Point, Error};
+use crate::transform::Affine;
 use crate::font::{FontSource, Font};
 use crate::theme_definition::CharacterRange;
+use crate::widget::Widget;
 
 /// A trait to be implemented on the type to be used for Event handling.  See [`WinitIO`](struct.WinitIO.html)
 /// for an example implementation.  The IO handles events from an external source and passes them to the Thyme
@@ -35,26 +37,163 @@ pub trait Renderer {
         image_data: &[u8],
         dimensions: (u32, u32),
     ) -> Result<TextureData, Error>;
+
+    /// Register a texture with Thyme from GPU compressed `image_data` in the specified `format`.  This method is
+    /// called via the [`ContextBuilder`](struct.ContextBuilder.html).  The default implementation returns an
+    /// error; renderers which support compressed textures should override it.
+    fn register_compressed_texture(
+        &mut self,
+        _handle: TextureHandle,
+        _image_data: &[u8],
+        _dimensions: (u32, u32),
+        format: CompressedFormat,
+    ) -> Result<TextureData, Error> {
+        Err(Error::Theme(format!("This renderer does not support {:?} compressed textures", format)))
+    }
+}
+
+/// The rotation of the UI on the display, clockwise.  This is useful for kiosks and handheld
+/// devices where the display is mounted rotated.  See
+/// [`Context::set_display_rotation`](struct.Context.html#method.set_display_rotation).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum DisplayRotation {
+    /// The UI is drawn normally
+    #[default]
+    Deg0,
+
+    /// The UI is rotated 90 degrees clockwise
+    Deg90,
+
+    /// The UI is upside down
+    Deg180,
+
+    /// The UI is rotated 270 degrees clockwise
+    Deg270,
+}
+
+impl DisplayRotation {
+    /// Returns whether this rotation swaps the width and height of the display
+    pub fn is_sideways(self) -> bool {
+        matches!(self, DisplayRotation::Deg90 | DisplayRotation::Deg270)
+    }
+
+    /// Returns the size of the UI for a window of the specified `size`.  This is the
+    /// window size with width and height swapped if the rotation [`is_sideways`](#method.is_sideways).
+    pub fn rotate_size(self, size: Point) -> Point {
+        if self.is_sideways() {
+            Point::new(size.y, size.x)
+        } else {
+            size
+        }
+    }
+
+    /// Converts the specified `pos` in window coordinates, within a window of size `window_size`,
+    /// to UI coordinates.  [`IO`](trait.IO.html) implementations should use this to transform
+    /// mouse and touch positions before passing them to the [`Context`](struct.Context.html).
+    pub fn window_to_ui(self, pos: Point, window_size: Point) -> Point {
+        match self {
+            DisplayRotation::Deg0 => pos,
+            DisplayRotation::Deg90 => Point::new(pos.y, window_size.x - pos.x),
+            DisplayRotation::Deg180 => Point::new(window_size.x - pos.x, window_size.y - pos.y),
+            DisplayRotation::Deg270 => Point::new(window_size.y - pos.y, pos.x),
+        }
+    }
+}
+
+/// A coarse classification of the logical display width, allowing UI code to switch between layouts,
+/// such as collapsing a sidebar into a menu on small displays.  The widths at which the class changes are
+/// set by the [`display_breakpoints`](struct.BuildOptions.html#structfield.display_breakpoints) option.
+/// See [`Frame::display_class`](struct.Frame.html#method.display_class).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DisplayClass {
+    /// A display narrower than the first breakpoint, such as a phone
+    Small,
+
+    /// A display at least as wide as the first breakpoint, but narrower than the second, such as a handheld
+    Medium,
+
+    /// A display at least as wide as the second breakpoint, such as a desktop monitor
+    Large,
+}
+
+impl DisplayClass {
+    /// Returns the display class for the specified logical `width`, using the specified `breakpoints`.
+    pub fn from_width(width: f32, breakpoints: [f32; 2]) -> DisplayClass {
+        if width < breakpoints[0] {
+            DisplayClass::Small
+        } else if width < breakpoints[1] {
+            DisplayClass::Medium
+        } else {
+            DisplayClass::Large
+        }
+    }
 }
 
-pub(crate) fn view_matrix(display_pos: Point, display_size: Point) -> [[f32; 4]; 4] {
+pub(crate) fn view_matrix(display_pos: Point, display_size: Point, rotation: DisplayRotation) -> [[f32; 4]; 4] {
     let left = display_pos.x;
     let right = display_pos.x + display_size.x;
     let top = display_pos.y;
     let bot = display_pos.y + display_size.y;
 
-    [
+    let mut matrix = [
         [         (2.0 / (right - left)),                             0.0,  0.0, 0.0],
         [                            0.0,          (2.0 / (top - bot)),  0.0, 0.0],
         [                            0.0,                             0.0, -1.0, 0.0],
         [(right + left) / (left - right), (top + bot) / (bot - top),  0.0, 1.0],
-    ]
+    ];
+
+    // rotate the result in normalized device coordinates
+    for column in matrix.iter_mut() {
+        let (x, y) = (column[0], column[1]);
+        let (x, y) = match rotation {
+            DisplayRotation::Deg0 => (x, y),
+            DisplayRotation::Deg90 => (y, -x),
+            DisplayRotation::Deg180 => (-x, -y),
+            DisplayRotation::Deg270 => (-y, x),
+        };
+        column[0] = x;
+        column[1] = y;
+    }
+
+    matrix
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum DrawMode {
     Image(TextureHandle),
     Font(FontHandle),
+    Triangles(TextureHandle),
+    FontTriangles(FontHandle),
+}
+
+#[cfg(any(feature = "glium_backend", feature = "gl_backend"))]
+impl DrawMode {
+    /// The mode for drawing images from `texture`, which must be drawn as triangles for `transformed` widgets
+    pub fn image(texture: TextureHandle, transformed: bool) -> DrawMode {
+        if transformed { DrawMode::Triangles(texture) } else { DrawMode::Image(texture) }
+    }
+
+    /// The mode for drawing text with `font`, which must be drawn as triangles for `transformed` widgets
+    pub fn font(font: FontHandle, transformed: bool) -> DrawMode {
+        if transformed { DrawMode::FontTriangles(font) } else { DrawMode::Font(font) }
+    }
+
+    /// The number of primitives drawn by a group of `vertices` in this mode, for the bench quads counter.  Image
+    /// and font groups draw one quad per vertex, while triangle groups use three vertices per triangle.
+    pub fn primitives(self, vertices: usize) -> usize {
+        match self {
+            DrawMode::Image(_) | DrawMode::Font(_) => vertices,
+            DrawMode::Triangles(_) | DrawMode::FontTriangles(_) => vertices / 3,
+        }
+    }
+}
+
+/// A vertex of triangle geometry pushed to a `DrawList`, in physical pixels
+#[derive(Copy, Clone)]
+pub struct DrawVertex {
+    pub pos: [f32; 2],
+    pub tex: TexCoord,
+    pub color: Color,
 }
 
 pub trait DrawList {
@@ -67,6 +206,15 @@ pub trait DrawList {
         clip: Rect,
     );
 
+    /// push the triangles formed by each set of three `indices` into `vertices`.  Triangles
+    /// with an index outside of `vertices` are skipped.
+    fn push_triangles(
+        &mut self,
+        vertices: &[DrawVertex],
+        indices: &[u32],
+        clip: Rect,
+    );
+
     /// the number of vertices currently contained in this list
     fn len(&self) -> usize;
 
@@ -99,11 +247,209 @@ impl DrawList for DummyDrawList {
         self.index += 1;
     }
 
+    fn push_triangles(&mut self, _vertices: &[DrawVertex], indices: &[u32], _clip: Rect) {
+        self.index += indices.len() / 3 * 3;
+    }
+
     fn len(&self) -> usize { self.index }
 
     fn back_adjust_positions(&mut self, _since_index: usize, _amount: Point) {}
 }
 
+/// Stores the texture or font `item` registered with the renderer at the handle `index`.  Any gap before the
+/// `index` is filled with `empty` items, as handles shared between contexts may be registered out of order.
+pub(crate) fn store_handle<T>(items: &mut Vec<T>, index: usize, item: T, empty: impl FnMut() -> T) {
+    if index < items.len() {
+        items[index] = item;
+    } else {
+        items.resize_with(index, empty);
+        items.push(item);
+    }
+}
+
+/// Returns an iterator over the vertices of each triangle formed by `indices` into `vertices`, skipping
+/// any triangle with an out of range index
+pub(crate) fn triangle_vertices<'a>(
+    vertices: &'a [DrawVertex],
+    indices: &'a [u32],
+) -> impl Iterator<Item = [&'a DrawVertex; 3]> + 'a {
+    indices.chunks_exact(3).filter_map(move |tri| {
+        let vertex = |index: u32| vertices.get(index as usize);
+        Some([vertex(tri[0])?, vertex(tri[1])?, vertex(tri[2])?])
+    })
+}
+
+/// A vertex of solid colored triangles drawn by a widget.  See
+/// [`WidgetBuilder::triangles`](struct.WidgetBuilder.html#method.triangles).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Vertex {
+    /// The position of the vertex in logical pixels, relative to the top left corner of the widget's inner area
+    pub pos: Point,
+
+    /// The color of the vertex, which is interpolated across each triangle
+    pub color: Color,
+}
+
+impl Vertex {
+    /// Creates a new vertex at the specified `pos` with the specified `color`
+    pub fn new(pos: Point, color: Color) -> Vertex {
+        Vertex { pos, color }
+    }
+}
+
+/// Solid colored triangles drawn as part of a widget
+#[derive(Clone)]
+pub(crate) struct WidgetTriangles {
+    pub vertices: Vec<Vertex>,
+    pub indices: Vec<u32>,
+}
+
+impl WidgetTriangles {
+    /// Pushes the triangles of the `widget` to the `draw_list`, positioned relative to its inner area and
+    /// clipped to its bounds.  The texture must contain a white pixel at its origin, such as the image used
+    /// for solid images.
+    pub(crate) fn draw<D: DrawList>(&self, draw_list: &mut D, widget: &Widget, scale: f32) {
+        let pos = widget.pos() + widget.border().tl();
+        let clip = widget.clip().min(Rect::new(widget.pos(), widget.size()));
+
+        let vertices: Vec<DrawVertex> = self.vertices.iter().map(|vertex| DrawVertex {
+            pos: ((pos + vertex.pos) * scale).into(),
+            tex: TexCoord::default(),
+            color: vertex.color,
+        }).collect();
+
+        draw_list.push_triangles(&vertices, &self.indices, clip * scale);
+    }
+}
+
+/// A primitive collected by a `TransformedDrawList`, in untransformed physical pixels
+enum TransformedPrimitive {
+    Rect { pos: [f32; 2], size: [f32; 2], tex: [TexCoord; 2], color: Color, clip: Rect },
+    Triangle { vertices: [DrawVertex; 3], clip: Rect },
+}
+
+/**
+A `DrawList` applying a widget's transform to everything drawn to it.  Without a transform, all calls pass
+directly through to the underlying list.  With a transform, rects and triangles are collected so that their
+positions may still be adjusted, such as when aligning text, and are pushed as transformed triangles by
+[`finish`](#method.finish).  Rects are clipped before being transformed, while triangles use the bounds of
+their transformed clip rect.  Any rects must be drawn with a triangle `DrawMode` when transformed.
+**/
+pub(crate) struct TransformedDrawList<'a, D: DrawList> {
+    draw_list: &'a mut D,
+    transform: Option<Affine>,
+    primitives: Vec<TransformedPrimitive>,
+}
+
+impl<'a, D: DrawList> TransformedDrawList<'a, D> {
+    /// Creates a new list drawing to `draw_list`, with the widget `transform` in logical pixels
+    pub(crate) fn new(draw_list: &'a mut D, transform: Option<Affine>, scale: f32) -> TransformedDrawList<'a, D> {
+        TransformedDrawList {
+            draw_list,
+            transform: transform.map(|transform| transform.scaled(scale)),
+            primitives: Vec::new(),
+        }
+    }
+
+    /// Pushes all collected primitives to the underlying list
+    pub(crate) fn finish(self) {
+        let transform = match self.transform {
+            None => return,
+            Some(transform) => transform,
+        };
+
+        for primitive in self.primitives {
+            let (vertices, clip) = match primitive {
+                TransformedPrimitive::Triangle { vertices, clip } => (vertices.to_vec(), clip),
+                TransformedPrimitive::Rect { pos, size, tex, color, clip } => {
+                    let min = Point::from(pos).max(clip.pos);
+                    let max = (Point::from(pos) + Point::from(size)).min(clip.pos + clip.size);
+                    if min.x >= max.x || min.y >= max.y || size[0] <= 0.0 || size[1] <= 0.0 { continue; }
+
+                    let vertex = |x: f32, y: f32| {
+                        let frac_x = (x - pos[0]) / size[0];
+                        let frac_y = (y - pos[1]) / size[1];
+                        DrawVertex {
+                            pos: [x, y],
+                            tex: TexCoord::new(
+                                tex[0].x() + frac_x * (tex[1].x() - tex[0].x()),
+                                tex[0].y() + frac_y * (tex[1].y() - tex[0].y()),
+                            ),
+                            color,
+                        }
+                    };
+
+                    let (tl, tr) = (vertex(min.x, min.y), vertex(max.x, min.y));
+                    let (bl, br) = (vertex(min.x, max.y), vertex(max.x, max.y));
+                    (vec![tl, bl, tr, tr, bl, br], clip)
+                }
+            };
+
+            let vertices: Vec<DrawVertex> = vertices.into_iter().map(|vertex| DrawVertex {
+                pos: transform.apply(vertex.pos.into()).into(),
+                ..vertex
+            }).collect();
+            let indices: Vec<u32> = (0..vertices.len() as u32).collect();
+            self.draw_list.push_triangles(&vertices, &indices, transform.bounds(clip));
+        }
+    }
+}
+
+impl<'a, D: DrawList> DrawList for TransformedDrawList<'a, D> {
+    fn push_rect(
+        &mut self,
+        pos: [f32; 2],
+        size: [f32; 2],
+        tex: [TexCoord; 2],
+        color: Color,
+        clip: Rect,
+    ) {
+        if self.transform.is_none() {
+            self.draw_list.push_rect(pos, size, tex, color, clip);
+        } else {
+            self.primitives.push(TransformedPrimitive::Rect { pos, size, tex, color, clip });
+        }
+    }
+
+    fn push_triangles(&mut self, vertices: &[DrawVertex], indices: &[u32], clip: Rect) {
+        if self.transform.is_none() {
+            self.draw_list.push_triangles(vertices, indices, clip);
+        } else {
+            for [a, b, c] in triangle_vertices(vertices, indices) {
+                self.primitives.push(TransformedPrimitive::Triangle { vertices: [*a, *b, *c], clip });
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        if self.transform.is_none() {
+            self.draw_list.len()
+        } else {
+            self.primitives.len()
+        }
+    }
+
+    fn back_adjust_positions(&mut self, since_index: usize, amount: Point) {
+        if self.transform.is_none() {
+            self.draw_list.back_adjust_positions(since_index, amount);
+            return;
+        }
+
+        let adjust = |pos: &mut [f32; 2]| {
+            pos[0] += amount.x;
+            pos[1] += amount.y;
+        };
+
+        for primitive in self.primitives.iter_mut().skip(since_index) {
+            match primitive {
+                TransformedPrimitive::Rect { pos, .. } => adjust(pos),
+                TransformedPrimitive::Triangle { vertices, .. } => vertices.iter_mut().for_each(|vertex| adjust(&mut vertex.pos)),
+            }
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
 pub struct TextureData {
     handle: TextureHandle,
     size: [u32; 2],
@@ -1,26 +1,170 @@
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
 use std::fs::File;
+use std::hash::{Hash, Hasher};
 use std::io::Read;
 use std::path::{Path, PathBuf};
-use std::sync::{atomic::{AtomicBool, Ordering}, mpsc::{Receiver, channel}};
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, atomic::{AtomicBool, Ordering}, mpsc::{Receiver, TryRecvError, channel}};
 
 use indexmap::IndexMap;
 use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use rustc_hash::FxHashMap;
 
-use crate::Error;
+use crate::{Color, CompressedFormat, Error};
+use crate::compressed::{CompressedTexture, parse_ktx2};
+use crate::image_packing::pack_images;
 use crate::theme::ThemeSet;
-use crate::theme_definition::ThemeDefinition;
-use crate::render::{Renderer, TextureData, TextureHandle};
+use crate::theme_definition::{CharacterRange, FontDefinition, ImageDefinition, ImageDefinitionKind, ImageFill, ThemeDefinition};
+use crate::theme_overrides::{ThemeEnvironment, apply_overrides, matching_overrides};
+use crate::context_builder::{BuildProgress, BuildStage};
+use crate::font::Font;
+use crate::render::{FontHandle, Renderer, TextureData, TextureHandle};
 
 static RELOAD_THEME: AtomicBool = AtomicBool::new(false);
 
+/**
+A summary of the resources held by a [`Context`](struct.Context.html), returned by
+[`Context::resource_report`](struct.Context.html#method.resource_report).  This is useful for display in a
+performance overlay, or for checking memory budgets in automated tests.
+**/
+#[derive(Clone, Debug, Default)]
+pub struct ResourceReport {
+    /// The image textures registered with the renderer, including any packed image sets
+    pub textures: Vec<TextureUsage>,
+
+    /// The fonts registered with the renderer, each with its own texture
+    pub fonts: Vec<FontUsage>,
+
+    /// The number of widget themes
+    pub theme_count: usize,
+
+    /// The number of images defined in the theme
+    pub image_count: usize,
+
+    /// The number of [`PersistentState`](struct.PersistentState.html) entries
+    pub persistent_state_count: usize,
+
+    /// An approximation of the CPU side memory used by the theme, persistent state, and caches, in bytes.
+    /// This does not include textures, which are held by the renderer.
+    pub approximate_heap_bytes: usize,
+}
+
+impl ResourceReport {
+    /// The total number of textures, including font textures
+    pub fn texture_count(&self) -> usize {
+        self.textures.len() + self.fonts.len()
+    }
+
+    /// The total size of all textures, including font textures, in bytes
+    pub fn texture_bytes(&self) -> usize {
+        self.textures.iter().map(|texture| texture.bytes).sum::<usize>() +
+            self.fonts.iter().map(|font| font.bytes()).sum::<usize>()
+    }
+}
+
+/// The size of a texture registered with the renderer.  See [`ResourceReport`](struct.ResourceReport.html).
+#[derive(Clone, Debug)]
+pub struct TextureUsage {
+    /// The id of the image, or of the image set for packed images
+    pub id: String,
+
+    /// The width and height of the texture, in pixels
+    pub size: [u32; 2],
+
+    /// The size of the texture data, in bytes
+    pub bytes: usize,
+}
+
+/// The size of the texture used by a font.  See [`ResourceReport`](struct.ResourceReport.html).
+#[derive(Clone, Debug)]
+pub struct FontUsage {
+    /// The id of the font in the theme
+    pub id: String,
+
+    /// The width and height of the font atlas texture, in pixels
+    pub atlas_size: [u32; 2],
+}
+
+impl FontUsage {
+    /// The size of the font atlas texture data, in bytes.  Font atlases use one byte per pixel.
+    pub fn bytes(&self) -> usize {
+        self.atlas_size[0] as usize * self.atlas_size[1] as usize
+    }
+}
+
+/**
+A registry of renderer textures and fonts, which allows several [`Context`](struct.Context.html)s drawn with the
+same [`Renderer`](trait.Renderer.html) to share their assets, such as one context per document tab or per game
+screen.  Pass the registry to the [`ContextBuilder`](struct.ContextBuilder.html) of each context with
+[`share_resources`](struct.ContextBuilder.html#method.share_resources).
+
+Each context then allocates its texture and font handles from the registry, so contexts never overwrite each
+other's assets in the renderer.  A texture with the same data as one already uploaded, or a font with the same
+source, size, characters, and scale factor as one already baked, is reused rather than registered again.  Cloning
+a `SharedResources` is cheap, with the clone referring to the same registry.
+**/
+#[derive(Clone, Default)]
+pub struct SharedResources {
+    registry: Rc<RefCell<SharedRegistry>>,
+}
+
+impl SharedResources {
+    /// Creates a new, empty registry
+    pub fn new() -> SharedResources {
+        SharedResources::default()
+    }
+
+    /// Returns the number of distinct textures registered with the renderer through this registry
+    pub fn texture_count(&self) -> usize {
+        self.registry.borrow().textures.len()
+    }
+
+    /// Returns the number of distinct fonts baked and registered with the renderer through this registry
+    pub fn font_count(&self) -> usize {
+        self.registry.borrow().fonts.len()
+    }
+}
+
+#[derive(Default)]
+struct SharedRegistry {
+    next_texture: TextureHandle,
+    next_font: FontHandle,
+
+    // keyed by a hash of the asset contents
+    textures: FxHashMap<u64, TextureData>,
+    fonts: FxHashMap<u64, Font>,
+}
+
+fn content_key<T: Hash>(content: T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
 struct ThemeSource {
-    data: Option<ThemeDefinition>,
+    data: Option<Vec<serde_yaml::Value>>,
     files: Option<Vec<PathBuf>>,
+
+    // the overrides that were applied the last time the theme was built
+    active_overrides: Vec<bool>,
+}
+
+struct ImageData {
+    data: Vec<u8>,
+    width: u32,
+    height: u32,
+
+    // the format of compressed data, or None for RGBA data
+    format: Option<CompressedFormat>,
 }
 
 struct ImageSource {
-    data: Option<(Vec<u8>, u32, u32)>,
+    data: Option<ImageData>,
     file: Option<PathBuf>,
+
+    // whether the file is a KTX2 compressed texture
+    compressed: bool,
 }
 
 struct FontSource {
@@ -34,6 +178,17 @@ pub(crate) struct ResourceSet {
     images: Vec<(String, ImageSource)>,
     fonts: Vec<(String, FontSource)>,
     theme: ThemeSource,
+    extra_font_ranges: Vec<CharacterRange>,
+
+    // images packed into image sets, read from the files and directories listed in the theme
+    packed_images: IndexMap<PathBuf, ImageData>,
+    packed_paths: Vec<PathBuf>,
+
+    shared: Option<SharedResources>,
+    placeholders: bool,
+
+    // the assets registered by the most recent build, in registration order
+    registered: Vec<RegisteredAsset>,
 
     watcher: Option<RecommendedWatcher>,
 }
@@ -64,7 +219,14 @@ impl ResourceSet {
             theme: ThemeSource {
                 data: None,
                 files: None,
+                active_overrides: Vec::new(),
             },
+            extra_font_ranges: Vec::new(),
+            packed_images: IndexMap::new(),
+            packed_paths: Vec::new(),
+            shared: None,
+            placeholders: false,
+            registered: Vec::new(),
             watcher,
         }
     }
@@ -88,8 +250,36 @@ impl ResourceSet {
         }
     }
 
-    pub(crate) fn register_theme(&mut self, theme: ThemeDefinition) {
-        self.theme.data = Some(theme);
+    /// Registers the placeholder image, and the embedded default font if that feature is enabled,
+    /// which are used in place of missing images and fonts
+    pub(crate) fn enable_placeholders(&mut self) {
+        self.placeholders = true;
+        let size = PLACEHOLDER_IMAGE_SIZE;
+        self.register_image_from_data(INTERNAL_PLACEHOLDER_IMAGE_ID.to_string(), placeholder_image_data(), size, size);
+
+        #[cfg(feature="default_font")]
+        self.register_default_font();
+    }
+
+    /// Registers the embedded default font source, if it is not already registered
+    #[cfg(feature="default_font")]
+    pub(crate) fn register_default_font(&mut self) {
+        if self.fonts.iter().all(|(id, _)| id != INTERNAL_DEFAULT_FONT_ID) {
+            self.register_font_from_data(INTERNAL_DEFAULT_FONT_ID.to_string(), DEFAULT_FONT_DATA.to_vec());
+        }
+    }
+
+    pub(crate) fn share_resources(&mut self, shared: SharedResources) {
+        self.shared = Some(shared);
+    }
+
+    pub(crate) fn register_theme(&mut self, theme: serde_yaml::Value) {
+        self.register_theme_docs(vec![theme]);
+    }
+
+    /// Registers the theme from several documents, which are merged in order
+    pub(crate) fn register_theme_docs(&mut self, docs: Vec<serde_yaml::Value>) {
+        self.theme.data = Some(docs);
         self.theme.files = None;
     }
 
@@ -115,13 +305,23 @@ impl ResourceSet {
         self.fonts.push((id, FontSource { font: None, data: Some(data), file: None }));
     }
 
-    pub(crate) fn register_image_from_file(&mut self, id: String, path: &Path) {
+    pub(crate) fn add_font_ranges(&mut self, ranges: &[CharacterRange]) {
+        self.extra_font_ranges.extend_from_slice(ranges);
+    }
+
+    pub(crate) fn register_image_from_file(&mut self, id: String, path: &Path, compressed: bool) {
         self.add_path_to_watcher(path);
-        self.images.push((id, ImageSource { data: None, file: Some(path.to_owned()) }));
+        self.images.push((id, ImageSource { data: None, file: Some(path.to_owned()), compressed }));
     }
 
     pub(crate) fn register_image_from_data(&mut self, id: String, data: Vec<u8>, width: u32, height: u32) {
-        self.images.push((id, ImageSource { data: Some((data, width, height)), file: None }));
+        let data = ImageData { data, width, height, format: None };
+        self.images.push((id, ImageSource { data: Some(data), file: None, compressed: false }));
+    }
+
+    pub(crate) fn register_compressed_image_from_data(&mut self, id: String, texture: CompressedTexture) {
+        let data = ImageData { data: texture.data, width: texture.width, height: texture.height, format: Some(texture.format) };
+        self.images.push((id, ImageSource { data: Some(data), file: None, compressed: true }));
     }
 
     pub(crate) fn remove_theme_file(&mut self, path: &Path) {
@@ -141,39 +341,288 @@ impl ResourceSet {
     }
 
     /// Checks for a file watch change and rebuilds the theme if neccessary, clearing the data cache
-    /// and reloading all data.  Will return Ok(None) if there was no change, or Err if there was
+    /// and reloading all data.  The theme is also rebuilt if the set of theme overrides matching `env`
+    /// has changed, or if `force` is set.  Will return Ok(None) if there was no change, or Err if there was
     /// a problem rebuilding the theme.
-    pub(crate) fn check_live_reload<R: Renderer>(&mut self, renderer: &mut R, scale_factor: f32) -> Result<Option<ThemeSet>, Error> {
+    pub(crate) fn check_live_reload<R: Renderer>(
+        &mut self,
+        renderer: &mut R,
+        scale_factor: f32,
+        env: &ThemeEnvironment,
+        force: bool,
+    ) -> Result<Option<ThemeSet>, Error> {
         match RELOAD_THEME.compare_exchange(true, false, Ordering::AcqRel, Ordering::Acquire) {
-            Ok(true) => (),
-            _ => return Ok(None),
+            Ok(true) => self.clear_data_cache(),
+            _ if force => self.clear_data_cache(),
+            _ => if !self.overrides_changed(env) {
+                return Ok(None);
+            }
         }
 
-        self.clear_data_cache();
-        self.cache_data()?;
-
-        let themes = self.build_assets(renderer, scale_factor)?;
+        let themes = self.build_assets(renderer, scale_factor, env)?;
 
         Ok(Some(themes))
     }
 
-    /// Builds all assets and registers them with the renderer.  You must make sure all asset
-    /// data is cached with [`cache_data`](#method.cache_assets) prior to calling this.
-    pub(crate) fn build_assets<R: Renderer>(&mut self, renderer: &mut R, scale_factor: f32) -> Result<ThemeSet, Error> {
-        RELOAD_THEME.store(false, Ordering::Release);
+    /// Builds all assets and registers them with the renderer, reading any asset data which is not
+    /// already cached.  This performs all of the steps of an `AssetBuild` at once.
+    pub(crate) fn build_assets<R: Renderer>(
+        &mut self,
+        renderer: &mut R,
+        scale_factor: f32,
+        env: &ThemeEnvironment,
+    ) -> Result<ThemeSet, Error> {
+        let mut build = AssetBuild::new(true);
+        loop {
+            if let Some(themes) = self.build_step(&mut build, renderer, scale_factor, env)? {
+                return Ok(themes);
+            }
+        }
+    }
+
+    /// Performs the next step of the specified `build`, returning the `ThemeSet` once the final step is complete.
+    pub(crate) fn build_step<R: Renderer>(
+        &mut self,
+        build: &mut AssetBuild,
+        renderer: &mut R,
+        scale_factor: f32,
+        env: &ThemeEnvironment,
+    ) -> Result<Option<ThemeSet>, Error> {
+        let index = build.index;
+        match build.stage {
+            BuildStage::ReadingTheme => {
+                RELOAD_THEME.store(false, Ordering::Release);
+                self.cache_theme()?;
+                let theme_def = self.resolve_theme(env)?;
+                build.packed_sets = self.find_packed_sets(&theme_def)?;
+                build.theme_def = Some(theme_def);
+                build.live_textures = self.registered.iter().filter_map(RegisteredAsset::texture).collect();
+                build.live_fonts = self.registered.iter().filter_map(RegisteredAsset::font).collect();
+                self.spawn_image_decoding(build);
+            },
+            BuildStage::DecodingImages => {
+                // the receiver is always created in the first stage
+                let receiver = build.decoded_images.as_ref().unwrap();
+                let received = if build.blocking {
+                    receiver.recv().map_err(|_| TryRecvError::Disconnected)
+                } else {
+                    receiver.try_recv()
+                };
+
+                let (target, result) = match received {
+                    Ok(received) => received,
+                    // no image has finished decoding yet
+                    Err(TryRecvError::Empty) => return Ok(None),
+                    Err(TryRecvError::Disconnected) => return Err(
+                        Error::Theme("An image decoding thread exited unexpectedly".to_string())
+                    ),
+                };
+
+                match target {
+                    DecodeTarget::Image(index) => {
+                        let (id, src) = &mut self.images[index];
+                        let data = result.map_err(|e| e.into_error(id))?;
+                        log::debug!("Decoded {} bytes from '{:?}' for image '{}'", data.data.len(), src.file, id);
+                        src.data = Some(data);
+                    },
+                    DecodeTarget::Packed(path) => {
+                        let data = result.map_err(|e| e.into_error(&path.to_string_lossy()))?;
+                        log::debug!("Decoded {} bytes from '{:?}' for packing", data.data.len(), path);
+                        self.packed_images.insert(path, data);
+                    },
+                }
+            },
+            BuildStage::ParsingFonts => self.cache_font(index)?,
+            BuildStage::BakingFonts => {
+                if index == 0 {
+                    build.font_sources = self.build_fonts();
+                }
+
+                // the theme is always resolved in the first stage
+                let theme_def = build.theme_def.as_ref().unwrap();
+                let (font_id, font_def) = theme_def.fonts.get_index(index).unwrap();
+                let font = self.bake_font(&mut build.font_handle, &build.live_fonts, font_def, &build.font_sources, renderer, scale_factor)?;
+                build.registered.push(RegisteredAsset::Font(font.handle(), font_def.clone(), scale_factor));
+                build.fonts.push((font_id.to_string(), font));
+            },
+            BuildStage::UploadingTextures if index > self.images.len() => {
+                self.upload_packed_set(build, index - self.images.len() - 1, renderer)?;
+            },
+            BuildStage::UploadingTextures => {
+                // register a 1x1 pixel texture for use with minimal themes
+                let (id, tex_data, dims, format) = if index == 0 {
+                    (INTERNAL_SINGLE_PIX_IMAGE_ID, &[0xff, 0xff, 0xff, 0xff][..], (1, 1), None)
+                } else {
+                    let (id, source) = &self.images[index - 1];
+                    // images are always decoded in an earlier stage
+                    let data = source.data.as_ref().unwrap();
+                    (id.as_str(), &data.data[..], (data.width, data.height), data.format)
+                };
+
+                build.texture_usage.push(TextureUsage { id: id.to_string(), size: [dims.0, dims.1], bytes: tex_data.len() });
+                let tex_data = self.upload_texture(&mut build.texture_handle, &build.live_textures, renderer, tex_data, dims, format)?;
+                build.registered.push(match index {
+                    0 => RegisteredAsset::SinglePixel(tex_data.handle()),
+                    _ => RegisteredAsset::Image(tex_data.handle(), index - 1),
+                });
+                build.textures.insert(id.to_string(), tex_data);
+            },
+            BuildStage::ResolvingThemes => {
+                let mut theme_def = build.theme_def.take().unwrap();
+                let textures = std::mem::take(&mut build.textures);
+                let fonts = std::mem::take(&mut build.fonts);
+                let mut themes = ThemeSet::new(&mut theme_def, textures, fonts, scale_factor, self.placeholders)?;
+                themes.set_texture_usage(std::mem::take(&mut build.texture_usage));
+                self.registered = std::mem::take(&mut build.registered);
+                return Ok(Some(themes));
+            },
+        }
+
+        build.completed += 1;
+        build.index += 1;
+        while build.index >= self.stage_len(build, build.stage) {
+            build.index = 0;
+            build.stage = match build.stage {
+                BuildStage::ReadingTheme => BuildStage::DecodingImages,
+                BuildStage::DecodingImages => BuildStage::ParsingFonts,
+                BuildStage::ParsingFonts => BuildStage::BakingFonts,
+                BuildStage::BakingFonts => BuildStage::UploadingTextures,
+                BuildStage::UploadingTextures | BuildStage::ResolvingThemes => BuildStage::ResolvingThemes,
+            };
+        }
+
+        Ok(None)
+    }
+
+    /// Registers each texture and font from the most recent build with the `renderer` again, using the same
+    /// handles and the cached asset data.  This is used after the renderer has released its GPU resources.
+    pub(crate) fn restore<R: Renderer>(&mut self, renderer: &mut R) -> Result<(), Error> {
+        for index in 0..self.fonts.len() {
+            self.cache_font(index)?;
+        }
+        let font_sources = self.build_fonts();
+
+        for asset in &self.registered {
+            match asset {
+                RegisteredAsset::SinglePixel(handle) => {
+                    renderer.register_texture(*handle, &[0xff, 0xff, 0xff, 0xff], (1, 1))?;
+                },
+                RegisteredAsset::Image(handle, index) => {
+                    let (id, src) = &self.images[*index];
+                    let data = src.data.as_ref().ok_or_else(||
+                        Error::Theme(format!("The data for image '{}' is no longer available", id))
+                    )?;
+                    let dims = (data.width, data.height);
+                    match data.format {
+                        None => renderer.register_texture(*handle, &data.data, dims)?,
+                        Some(format) => renderer.register_compressed_texture(*handle, &data.data, dims, format)?,
+                    };
+                },
+                RegisteredAsset::Packed(handle, paths) => {
+                    let images = paths.iter().map(|path| match self.packed_images.get(path) {
+                        None => Err(Error::Theme(format!("The data for packed image '{:?}' is no longer available", path))),
+                        Some(data) => Ok((&data.data[..], data.width, data.height)),
+                    }).collect::<Result<Vec<_>, Error>>()?;
+                    let atlas = pack_images(&images);
+                    renderer.register_texture(*handle, &atlas.data, (atlas.width, atlas.height))?;
+                },
+                RegisteredAsset::Font(handle, definition, scale_factor) => {
+                    let ranges = ThemeSet::font_ranges(definition, &self.extra_font_ranges);
+                    ThemeSet::bake_font(*handle, definition, &font_sources, renderer, &ranges, *scale_factor)?;
+                },
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the progress of the specified `build`
+    pub(crate) fn build_progress(&self, build: &AssetBuild) -> BuildProgress {
+        let total = [
+            BuildStage::ReadingTheme,
+            BuildStage::DecodingImages,
+            BuildStage::ParsingFonts,
+            BuildStage::BakingFonts,
+            BuildStage::UploadingTextures,
+            BuildStage::ResolvingThemes,
+        ].iter().map(|stage| self.stage_len(build, *stage)).sum();
+
+        BuildProgress { stage: build.stage, completed: build.completed, total }
+    }
 
-        let textures = self.build_images(renderer)?;
-        let fonts = self.build_fonts();
+    /// The number of steps in the specified `stage` of the `build`
+    fn stage_len(&self, build: &AssetBuild, stage: BuildStage) -> usize {
+        match stage {
+            BuildStage::ReadingTheme | BuildStage::ResolvingThemes => 1,
+            BuildStage::DecodingImages => build.images_to_decode,
+            BuildStage::ParsingFonts => self.fonts.len(),
+            BuildStage::BakingFonts => build.theme_def.as_ref().map(|def| def.fonts.len()).unwrap_or_default(),
+            BuildStage::UploadingTextures => self.images.len() + build.packed_sets.len() + 1,
+        }
+    }
 
-        let theme_def = match self.theme.data.as_mut() {
+    /// Applies the theme overrides matching `env` to the theme documents, evaluates any expressions,
+    /// and merges the documents into a single theme definition.
+    fn resolve_theme(&mut self, env: &ThemeEnvironment) -> Result<ThemeDefinition, Error> {
+        let mut docs = match self.theme.data.as_ref() {
             None => {
                 return Err(Error::Theme("Cannot build assets.  No theme specified.".to_string()));
             },
-            Some(def) => def,
+            Some(docs) => docs.clone(),
         };
-        let themes = ThemeSet::new(theme_def, textures, fonts, renderer, scale_factor)?;
 
-        Ok(themes)
+        // record the overrides even if the build fails, so it is not retried until they change
+        self.theme.active_overrides = apply_overrides(&mut docs, env)?;
+
+        // variables may be defined in any of the files
+        crate::theme_expr::evaluate_theme(&mut docs)?;
+
+        let mut theme_def: Option<ThemeDefinition> = None;
+        for (index, doc) in docs.into_iter().enumerate() {
+            let new_theme_def: ThemeDefinition = match serde_yaml::from_value(doc) {
+                Ok(theme) => theme,
+                Err(e) => return Err(Error::Serde(match self.theme.files.as_ref().and_then(|files| files.get(index)) {
+                    None => e.to_string(),
+                    Some(path) => format!("{}: {}", path.display(), e),
+                })),
+            };
+
+            match theme_def.as_mut() {
+                None => theme_def = Some(new_theme_def),
+                Some(theme) => theme.merge(new_theme_def),
+            }
+        }
+
+        let theme_def = theme_def.ok_or_else(|| Error::Theme("No valid theme was specified".to_string()))?;
+
+        #[cfg(feature="default_font")]
+        let theme_def = {
+            let mut theme_def = theme_def;
+            if self.placeholders {
+                theme_def.fonts.entry(INTERNAL_DEFAULT_FONT_ID.to_string()).or_insert(FontDefinition {
+                    source: INTERNAL_DEFAULT_FONT_ID.to_string(),
+                    size: DEFAULT_FONT_SIZE,
+                    characters: Vec::new(),
+                });
+            }
+            theme_def
+        };
+
+        Ok(theme_def)
+    }
+
+    /// Returns whether the set of theme overrides matching `env` differs from the set used
+    /// when the theme was last built.
+    fn overrides_changed(&self, env: &ThemeEnvironment) -> bool {
+        let docs = match self.theme.data.as_ref() {
+            None => return false,
+            Some(docs) => docs,
+        };
+
+        match matching_overrides(docs, env) {
+            Ok(matches) => matches != self.theme.active_overrides,
+            Err(_) => false,
+        }
     }
 
     pub(crate) fn clear_data_cache(&mut self) {
@@ -193,107 +642,297 @@ impl ResourceSet {
                 src.font = None;
             }
         }
+
+        self.packed_images.clear();
     }
 
-    pub(crate) fn cache_data(&mut self) -> Result<(), Error> {
-        if self.theme.data.is_none() {
-            if let Some(theme_source) = self.theme.files.as_ref() {
-                let mut theme_def: Option<ThemeDefinition> = None;
+    fn cache_theme(&mut self) -> Result<(), Error> {
+        if self.theme.data.is_some() { return Ok(()); }
 
-                let mut theme_str = String::new();
-                for path in theme_source.iter() {
-                    let mut file = match File::open(path) {
-                        Ok(file) => file,
-                        Err(e) => return Err(Error::IO(e)),
-                    };
+        if let Some(theme_source) = self.theme.files.as_ref() {
+            let mut docs: Vec<serde_yaml::Value> = Vec::new();
 
-                    theme_str.clear();
-                    match file.read_to_string(&mut theme_str) {
-                        Err(e) => return Err(Error::IO(e)),
-                        Ok(count) => {
-                            log::debug!("Read {} bytes from '{:?}' for theme.", count, path);
-                        }
-                    }
+            let mut theme_str = String::new();
+            for path in theme_source.iter() {
+                let mut file = match File::open(path) {
+                    Ok(file) => file,
+                    Err(e) => return Err(Error::IO(e)),
+                };
 
-                    match theme_def.as_mut() {
-                        None => {
-                            theme_def = Some(match serde_yaml::from_str(&theme_str) {
-                                Ok(theme) => theme,
-                                Err(e) => return Err(Error::Serde(e.to_string())),
-                            });
-                        }, Some(theme) => {
-                            let new_theme_def: ThemeDefinition = match serde_yaml::from_str(&theme_str) {
-                                Ok(theme) => theme,
-                                Err(e) => return Err(Error::Serde(e.to_string())),
-                            };
-
-                            theme.merge(new_theme_def);
-                        }
+                theme_str.clear();
+                match file.read_to_string(&mut theme_str) {
+                    Err(e) => return Err(Error::IO(e)),
+                    Ok(count) => {
+                        log::debug!("Read {} bytes from '{:?}' for theme.", count, path);
                     }
                 }
 
-                if theme_def.is_none() {
-                    return Err(Error::Theme("No valid theme was specified".to_string()));
+                match serde_yaml::from_str(&theme_str) {
+                    Ok(doc) => docs.push(doc),
+                    Err(e) => return Err(Error::Serde(format!("{}: {}", path.display(), e))),
                 }
+            }
 
-                self.theme.data = theme_def;
+            if docs.is_empty() {
+                return Err(Error::Theme("No valid theme was specified".to_string()));
             }
+
+            self.theme.data = Some(docs);
         }
 
-        for (id, src) in self.images.iter_mut() {
+        Ok(())
+    }
+
+    /// Starts decoding each image which is not already cached, on a pool of worker threads sized to the
+    /// available parallelism.  The decoded images are received in the `DecodingImages` stage of the `build`,
+    /// in the order they finish.
+    fn spawn_image_decoding(&self, build: &mut AssetBuild) {
+        let (tx, rx) = channel();
+        build.decoded_images = Some(rx);
+        build.images_to_decode = 0;
+
+        let mut to_decode = Vec::new();
+        for (index, (_, src)) in self.images.iter().enumerate() {
             if src.data.is_some() { continue; }
-            
-            // file must always be some if data is none
-            let path = src.file.as_ref().unwrap();
 
-            let image = match image::open(path) {
-                Ok(image) => image.into_rgba8(),
-                Err(error) => return Err(Error::Image(error)),
-            };
+            // file must always be some if data is none
+            let path = src.file.clone().unwrap();
+            to_decode.push((DecodeTarget::Image(index), path, src.compressed));
+        }
 
-            let dims = image.dimensions();
-            let data = image.into_raw();
+        for (_, path) in build.packed_sets.iter().flat_map(|set| set.images.iter()) {
+            if self.packed_images.contains_key(path) { continue; }
+            if to_decode.iter().any(|(target, _, _)| *target == DecodeTarget::Packed(path.clone())) { continue; }
 
-            log::debug!("Read {} bytes from '{:?}' for image '{}'", data.len(), path, id);
+            to_decode.push((DecodeTarget::Packed(path.clone()), path.clone(), false));
+        }
 
-            src.data = Some((data, dims.0, dims.1));
+        build.images_to_decode = to_decode.len();
+        let workers = std::thread::available_parallelism().map_or(1, |count| count.get()).min(to_decode.len());
+        let queue = Arc::new(Mutex::new(to_decode));
+        for _ in 0..workers {
+            let (tx, queue) = (tx.clone(), Arc::clone(&queue));
+            std::thread::spawn(move || {
+                // the lock is released before decoding, so the other workers may take the next image
+                let next = || queue.lock().unwrap().pop();
+                while let Some((target, path, compressed)) = next() {
+                    // the build may have been cancelled, in which case the result is not needed
+                    if tx.send((target, decode_image(&path, compressed))).is_err() { break; }
+                }
+            });
         }
+    }
+
+    /// Finds the image files to be packed for each image set in the `theme_def` which lists `files`
+    /// or a `directory`.  Image ids are taken from the file names, without the extension.
+    fn find_packed_sets(&mut self, theme_def: &ThemeDefinition) -> Result<Vec<PackedSet>, Error> {
+        let mut sets = Vec::new();
+        for (set_id, set) in theme_def.image_sets.iter() {
+            if set.files.is_empty() && set.directory.is_none() { continue; }
 
-        for (id, src) in self.fonts.iter_mut() {
-            if src.font.is_some() { continue; }
-            
-            let data = if let Some(data) = src.data.as_ref() {
-                data.clone()
-            } else {
-                // file must always be some if data is none
-                let path = src.file.as_ref().unwrap();
-                let data = match std::fs::read(path) {
-                    Ok(data) => data,
-                    Err(error) => return Err(Error::IO(error)),
+            if set.source.is_some() {
+                return Err(Error::Theme(
+                    format!("Image set '{}' may not specify both a source and files to pack", set_id)
+                ));
+            }
+
+            let mut paths: Vec<PathBuf> = set.files.iter().map(PathBuf::from).collect();
+            for path in paths.iter() {
+                self.watch_packed_path(path);
+            }
+
+            if let Some(dir) = set.directory.as_ref() {
+                let dir = Path::new(dir);
+                self.watch_packed_path(dir);
+
+                let mut dir_paths = Vec::new();
+                for entry in std::fs::read_dir(dir).map_err(Error::IO)? {
+                    let path = entry.map_err(Error::IO)?.path();
+                    if path.is_file() && is_image_file(&path) {
+                        dir_paths.push(path);
+                    }
+                }
+
+                // directory order is not consistent between platforms
+                dir_paths.sort();
+                paths.extend(dir_paths);
+            }
+
+            let mut images: Vec<(String, PathBuf)> = Vec::new();
+            for path in paths {
+                let id = match path.file_stem().and_then(|stem| stem.to_str()) {
+                    None => return Err(Error::Theme(format!("Invalid image file {:?} in image set '{}'", path, set_id))),
+                    Some(id) => id.to_string(),
                 };
 
-                log::debug!("Read {} bytes from '{:?}' for font '{}'", data.len(), path, id);
+                if images.iter().any(|(other, _)| *other == id) {
+                    return Err(Error::Theme(format!("Image set '{}' has multiple files for image '{}'", set_id, id)));
+                }
+                images.push((id, path));
+            }
 
-                let result = data.clone();
-                src.data = Some(data);
-                result
-            };
+            sets.push(PackedSet { set_id: set_id.to_string(), images });
+        }
 
-            let font = match rusttype::Font::try_from_vec(data) {
-                Some(font) => font,
-                None => return Err(
-                    Error::FontSource(format!("Unable to parse '{}' as ttf", id))
-                )
-            };
+        Ok(sets)
+    }
 
-            log::debug!("Created rusttype font from '{}'", id);
+    fn watch_packed_path(&mut self, path: &Path) {
+        if self.packed_paths.iter().any(|p| p == path) { return; }
 
-            src.font = Some(font);
+        self.add_path_to_watcher(path);
+        self.packed_paths.push(path.to_owned());
+    }
+
+    /// Packs the images of the packed set at `index` into a single texture and registers it.  The image
+    /// set in the theme then uses the texture as its source, with a simple image for each file.
+    fn upload_packed_set<R: Renderer>(
+        &self,
+        build: &mut AssetBuild,
+        index: usize,
+        renderer: &mut R,
+    ) -> Result<(), Error> {
+        let packed = &build.packed_sets[index];
+
+        // packed images are always decoded in an earlier stage
+        let images: Vec<(&[u8], u32, u32)> = packed.images.iter().map(|(_, path)| {
+            let data = &self.packed_images[path];
+            (&data.data[..], data.width, data.height)
+        }).collect();
+        let atlas = pack_images(&images);
+
+        let texture_id = format!("{}{}", PACKED_IMAGE_ID_PREFIX, packed.set_id);
+        let tex_data = self.upload_texture(
+            &mut build.texture_handle, &build.live_textures, renderer, &atlas.data, (atlas.width, atlas.height), None,
+        )?;
+        build.registered.push(RegisteredAsset::Packed(tex_data.handle(), packed.images.iter().map(|(_, path)| path.clone()).collect()));
+        build.textures.insert(texture_id.clone(), tex_data);
+        build.texture_usage.push(TextureUsage {
+            id: packed.set_id.clone(),
+            size: [atlas.width, atlas.height],
+            bytes: atlas.data.len(),
+        });
+
+        // the theme is always resolved in the first stage
+        let set = build.theme_def.as_mut().unwrap().image_sets.get_mut(&packed.set_id).unwrap();
+        set.source = Some(texture_id);
+
+        // images defined in the theme take precedence
+        for ((id, _), (position, (_, width, height))) in packed.images.iter().zip(atlas.positions.iter().zip(images.iter())) {
+            set.images.entry(id.to_string()).or_insert(ImageDefinition {
+                color: Color::white(),
+                kind: ImageDefinitionKind::Simple { position: *position, size: [*width, *height], fill: ImageFill::None },
+            });
         }
 
         Ok(())
     }
 
+    /// Registers the texture `data` with the renderer.  The handle is taken from the shared resources if present,
+    /// reusing any identical texture already registered there, and otherwise from `next_handle`, skipping the
+    /// `live` handles in use by the current theme.
+    fn upload_texture<R: Renderer>(
+        &self,
+        next_handle: &mut TextureHandle,
+        live: &[TextureHandle],
+        renderer: &mut R,
+        data: &[u8],
+        dims: (u32, u32),
+        format: Option<CompressedFormat>,
+    ) -> Result<TextureData, Error> {
+        let mut register = |handle| match format {
+            None => renderer.register_texture(handle, data, dims),
+            Some(format) => renderer.register_compressed_texture(handle, data, dims, format),
+        };
+
+        let shared = match self.shared.as_ref() {
+            None => return register(allocate(next_handle, live, TextureHandle::next)),
+            Some(shared) => shared,
+        };
+
+        let key = content_key((data, dims, format));
+        let mut registry = shared.registry.borrow_mut();
+        if let Some(tex_data) = registry.textures.get(&key) {
+            return Ok(*tex_data);
+        }
+
+        let tex_data = register(registry.next_texture)?;
+        registry.next_texture = registry.next_texture.next();
+        registry.textures.insert(key, tex_data);
+        Ok(tex_data)
+    }
+
+    /// Bakes the font `definition` and registers it with the renderer.  The handle is taken from the shared
+    /// resources if present, reusing any identical font already baked there, and otherwise from `next_handle`,
+    /// skipping the `live` handles in use by the current theme.
+    fn bake_font<R: Renderer>(
+        &self,
+        next_handle: &mut FontHandle,
+        live: &[FontHandle],
+        definition: &FontDefinition,
+        font_sources: &IndexMap<String, crate::font::FontSource>,
+        renderer: &mut R,
+        scale_factor: f32,
+    ) -> Result<Font, Error> {
+        let ranges = ThemeSet::font_ranges(definition, &self.extra_font_ranges);
+
+        let shared = match self.shared.as_ref() {
+            None => {
+                let handle = allocate(next_handle, live, FontHandle::next);
+                return ThemeSet::bake_font(handle, definition, font_sources, renderer, &ranges, scale_factor);
+            },
+            Some(shared) => shared,
+        };
+
+        let source_data = self.fonts.iter()
+            .find(|(id, _)| *id == definition.source)
+            .and_then(|(_, src)| src.data.as_deref());
+        let key = content_key((source_data, definition.size.to_bits(), &ranges, scale_factor.to_bits()));
+        let mut registry = shared.registry.borrow_mut();
+        if let Some(font) = registry.fonts.get(&key) {
+            return Ok(font.clone());
+        }
+
+        let font = ThemeSet::bake_font(registry.next_font, definition, font_sources, renderer, &ranges, scale_factor)?;
+        registry.next_font = registry.next_font.next();
+        registry.fonts.insert(key, font.clone());
+        Ok(font)
+    }
+
+    fn cache_font(&mut self, index: usize) -> Result<(), Error> {
+        let (id, src) = &mut self.fonts[index];
+        if src.font.is_some() { return Ok(()); }
+
+        let data = if let Some(data) = src.data.as_ref() {
+            data.clone()
+        } else {
+            // file must always be some if data is none
+            let path = src.file.as_ref().unwrap();
+            let data = match std::fs::read(path) {
+                Ok(data) => data,
+                Err(error) => return Err(Error::IO(error)),
+            };
+
+            log::debug!("Read {} bytes from '{:?}' for font '{}'", data.len(), path, id);
+
+            let result = data.clone();
+            src.data = Some(data);
+            result
+        };
+
+        let font = match rusttype::Font::try_from_vec(data) {
+            Some(font) => font,
+            None => return Err(
+                Error::FontSource(format!("Unable to parse '{}' as ttf", id))
+            )
+        };
+
+        log::debug!("Created rusttype font from '{}'", id);
+
+        src.font = Some(font);
+        Ok(())
+    }
+
     fn build_fonts(&mut self) -> IndexMap<String, crate::font::FontSource> {
         let mut output = IndexMap::new();
 
@@ -304,31 +943,203 @@ impl ResourceSet {
 
         output
     }
+}
 
-    fn build_images<R: Renderer>(&self, renderer: &mut R) -> Result<IndexMap<String, TextureData>, Error> {
-        let mut output = IndexMap::new();
-        let mut handle = TextureHandle::default();
+/// The state of an in progress, incremental build of the theme assets.  See `ResourceSet::build_step`.
+pub(crate) struct AssetBuild {
+    stage: BuildStage,
+    index: usize,
+    completed: usize,
+
+    theme_def: Option<ThemeDefinition>,
+    font_sources: IndexMap<String, crate::font::FontSource>,
+    font_handle: FontHandle,
+    fonts: Vec<(String, Font)>,
+    texture_handle: TextureHandle,
+
+    // the handles used by the current theme, which are not replaced until the build completes
+    live_fonts: Vec<FontHandle>,
+    live_textures: Vec<TextureHandle>,
+
+    textures: IndexMap<String, TextureData>,
+    texture_usage: Vec<TextureUsage>,
+    registered: Vec<RegisteredAsset>,
+
+    // whether to wait for images to be decoded, rather than returning from the step
+    blocking: bool,
+    decoded_images: Option<Receiver<(DecodeTarget, DecodedImage)>>,
+    images_to_decode: usize,
+    packed_sets: Vec<PackedSet>,
+}
+
+impl AssetBuild {
+    pub(crate) fn new(blocking: bool) -> AssetBuild {
+        AssetBuild {
+            stage: BuildStage::ReadingTheme,
+            index: 0,
+            completed: 0,
+            theme_def: None,
+            font_sources: IndexMap::new(),
+            font_handle: FontHandle::default(),
+            fonts: Vec::new(),
+            texture_handle: TextureHandle::default(),
+            live_fonts: Vec::new(),
+            live_textures: Vec::new(),
+            textures: IndexMap::new(),
+            texture_usage: Vec::new(),
+            registered: Vec::new(),
+            blocking,
+            decoded_images: None,
+            images_to_decode: 0,
+            packed_sets: Vec::new(),
+        }
+    }
+}
+
+/// An asset registered with the renderer during a build, recorded so that it may be restored
+enum RegisteredAsset {
+    SinglePixel(TextureHandle),
+
+    // the index of the registered image
+    Image(TextureHandle, usize),
+
+    // the paths of the images in the packed set, in packing order
+    Packed(TextureHandle, Vec<PathBuf>),
+
+    // the font definition and scale factor used to bake the font
+    Font(FontHandle, FontDefinition, f32),
+}
+
+impl RegisteredAsset {
+    fn texture(&self) -> Option<TextureHandle> {
+        match self {
+            RegisteredAsset::SinglePixel(handle) | RegisteredAsset::Image(handle, _) |
+                RegisteredAsset::Packed(handle, _) => Some(*handle),
+            RegisteredAsset::Font(..) => None,
+        }
+    }
+
+    fn font(&self) -> Option<FontHandle> {
+        match self {
+            RegisteredAsset::Font(handle, ..) => Some(*handle),
+            _ => None,
+        }
+    }
+}
+
+/// Takes the next handle from `next`, skipping any `live` handles, so that assets in use are not replaced
+fn allocate<H: Copy + PartialEq>(next: &mut H, live: &[H], advance: fn(H) -> H) -> H {
+    while live.contains(next) {
+        *next = advance(*next);
+    }
+
+    let handle = *next;
+    *next = advance(handle);
+    handle
+}
+
+/// An image set whose images are packed from individual files
+struct PackedSet {
+    set_id: String,
+    images: Vec<(String, PathBuf)>,
+}
+
+/// Where a decoded image is stored: a registered image by index, or an image to be packed by path
+#[derive(PartialEq)]
+enum DecodeTarget {
+    Image(usize),
+    Packed(PathBuf),
+}
+
+type DecodedImage = Result<ImageData, DecodeError>;
 
-        // register a 1x1 pixel texture for use with minimal themes
-        let tex_data = [0xff, 0xff, 0xff, 0xff];
-        let tex_data = renderer.register_texture(handle, &tex_data, (1, 1))?;
-        output.insert(INTERNAL_SINGLE_PIX_IMAGE_ID.to_string(), tex_data);
-        handle = handle.next();
-        
-        for (id, source) in self.images.iter() {
-            let (tex_data, width, height) = source.data.as_ref().unwrap();
-            let dims = (*width, *height);
-            let tex_data = renderer.register_texture(handle, tex_data, dims)?;
-            output.insert(id.to_string(), tex_data);
+// crate::Error is not Send with all features, so decoding threads send this instead
+enum DecodeError {
+    IO(std::io::Error),
+    #[cfg(feature="image")]
+    Image(image::ImageError),
+    #[cfg(not(feature="image"))]
+    Unsupported,
+    Compressed(String),
+}
 
-            handle = handle.next();
+impl DecodeError {
+    fn into_error(self, id: &str) -> Error {
+        match self {
+            DecodeError::IO(e) => Error::IO(e),
+            #[cfg(feature="image")]
+            DecodeError::Image(e) => Error::Image(e),
+            #[cfg(not(feature="image"))]
+            DecodeError::Unsupported => Error::Theme(
+                format!("Unable to read image '{}': decoding images requires the image feature", id)
+            ),
+            DecodeError::Compressed(e) => Error::Theme(format!("Unable to read compressed texture '{}': {}", id, e)),
         }
+    }
+}
 
-        Ok(output)
+fn decode_image(path: &Path, compressed: bool) -> DecodedImage {
+    if compressed {
+        let data = std::fs::read(path).map_err(DecodeError::IO)?;
+        let texture = parse_ktx2(&data).map_err(DecodeError::Compressed)?;
+        return Ok(ImageData { data: texture.data, width: texture.width, height: texture.height, format: Some(texture.format) });
     }
+
+    decode_image_file(path)
+}
+
+#[cfg(feature="image")]
+fn decode_image_file(path: &Path) -> DecodedImage {
+    let image = image::open(path).map_err(DecodeError::Image)?.into_rgba8();
+    let dims = image.dimensions();
+    Ok(ImageData { data: image.into_raw(), width: dims.0, height: dims.1, format: None })
+}
+
+#[cfg(not(feature="image"))]
+fn decode_image_file(_path: &Path) -> DecodedImage {
+    Err(DecodeError::Unsupported)
+}
+
+/// Whether the file at `path` has the extension of an image format which can be decoded
+#[cfg(feature="image")]
+fn is_image_file(path: &Path) -> bool {
+    image::ImageFormat::from_path(path).is_ok()
+}
+
+#[cfg(not(feature="image"))]
+fn is_image_file(_path: &Path) -> bool {
+    false
 }
 
 pub(crate) const INTERNAL_SINGLE_PIX_IMAGE_ID: &str = "__INTERNAL_SINGLE_PIX__";
+pub(crate) const INTERNAL_PLACEHOLDER_IMAGE_ID: &str = "__INTERNAL_PLACEHOLDER__";
+pub(crate) const INTERNAL_DEFAULT_FONT_ID: &str = "__INTERNAL_DEFAULT_FONT__";
+
+/// The width and height of the checkered placeholder image, in pixels
+pub(crate) const PLACEHOLDER_IMAGE_SIZE: u32 = 8;
+
+#[cfg(feature="default_font")]
+const DEFAULT_FONT_DATA: &[u8] = include_bytes!("../examples/data/fonts/Roboto-Medium.ttf");
+
+#[cfg(feature="default_font")]
+const DEFAULT_FONT_SIZE: f32 = 16.0;
+
+/// Returns RGBA data for a magenta and black checkerboard, with four squares
+fn placeholder_image_data() -> Vec<u8> {
+    let half = PLACEHOLDER_IMAGE_SIZE / 2;
+    let mut data = Vec::new();
+    for y in 0..PLACEHOLDER_IMAGE_SIZE {
+        for x in 0..PLACEHOLDER_IMAGE_SIZE {
+            match (x < half) == (y < half) {
+                true => data.extend_from_slice(&[0xff, 0x00, 0xff, 0xff]),
+                false => data.extend_from_slice(&[0x00, 0x00, 0x00, 0xff]),
+            }
+        }
+    }
+    data
+}
+
+const PACKED_IMAGE_ID_PREFIX: &str = "__PACKED__/";
 
 fn watcher_loop(rx: Receiver<Result<Event, notify::Error>>) {
     for res in rx {
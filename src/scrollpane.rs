@@ -6,6 +6,9 @@ A [`WidgetBuilder`](struct.WidgetBuilder.html) specifically for creating scrollp
 Create this using [`WidgetBuilder.scrollpane`](struct.WidgetBuilder.html#method.scrollpane).
 Scrollpanes can have fairly complex behavior, and can include optional horizontal and vertical scrollbars.
 Scrollbars are, by default, only shown when the content size exceeds the pane's inner size.
+The `scroll` thumb of each scrollbar is sized according to the fraction of the content which is visible, and
+may be dragged from any point along its length.  Clicking or holding the mouse on the scrollbar track, outside
+the thumb, pages the content towards the mouse.
 There is also a [`scrollpane method`](struct.Frame.html#method.scrollpane) on `Frame` as a convenience for simple cases.
 
 Once you are finished setting up the scrollpane, you call [`children`](#method.children) to add children to the scrollpane
@@ -93,13 +96,15 @@ An example of a theme definition for a scrollpane:
 */
 pub struct ScrollpaneBuilder<'a> {
     builder: WidgetBuilder<'a>,
-    state: ScrollpaneState,
+    state: ScrollpaneState<'a>,
 }
 
-struct ScrollpaneState {
+struct ScrollpaneState<'a> {
     content_id: String,
     show_horiz: ShowElement,
     show_vert: ShowElement,
+    anchor: ScrollAnchor,
+    near_end: Option<(f32, Box<dyn FnOnce() + 'a>)>,
 }
 
 impl<'a> ScrollpaneBuilder<'a> {
@@ -110,6 +115,8 @@ impl<'a> ScrollpaneBuilder<'a> {
                 content_id: content_id.to_string(),
                 show_horiz: ShowElement::Sometimes,
                 show_vert: ShowElement::Sometimes,
+                anchor: ScrollAnchor::Clamp,
+                near_end: None,
             }
         }
     }
@@ -130,6 +137,26 @@ impl<'a> ScrollpaneBuilder<'a> {
         self
     }
 
+    /// Specify how the scroll position is adjusted when the size of the content or of the
+    /// scrollpane itself changes.  See [`ScrollAnchor`](enum.ScrollAnchor.html).  Defaults to `Clamp`.
+    pub fn scroll_anchor(mut self, anchor: ScrollAnchor) -> ScrollpaneBuilder<'a> {
+        self.state.anchor = anchor;
+        self
+    }
+
+    /**
+    Specify a `callback` which is called when the scrollpane is scrolled to within `threshold` logical pixels of
+    the bottom of its content, or when the content does not fill the scrollpane.  This allows more content, such as
+    the next page of a leaderboard or server list, to be loaded on demand.  The callback is called once on reaching
+    the threshold, and again only if the content size changes or the scrollpane is scrolled away and back.  The
+    content size is measured during the previous frame, so newly added content is taken into account on the
+    following frame.
+    **/
+    pub fn on_near_end<F: FnOnce() + 'a>(mut self, threshold: f32, callback: F) -> ScrollpaneBuilder<'a> {
+        self.state.near_end = Some((threshold, Box::new(callback)));
+        self
+    }
+
     /// Consumes this builder to create a scrollpane.  Calls the specified `children` closure
     /// to add children to the scrollpane.
     pub fn children<F: FnOnce(&mut Frame)>(self, children: F) {
@@ -137,11 +164,21 @@ impl<'a> ScrollpaneBuilder<'a> {
         let mut max_scroll = Point::default();
         let mut delta = Point::default();
 
-        let scrollpane_pos = self.builder.widget.pos();
         let state = self.state;
         let content_id = state.content_id;
         let horiz = state.show_horiz;
         let vert = state.show_vert;
+        let anchor = state.anchor;
+        let near_end = state.near_end;
+
+        let (prev_content_size, prev_viewport_size, prev_anchor_pos, scroll, mut grab_offset) = self.builder.frame.modify(&content_id, |state| {
+            (state.content_size, state.viewport_size, state.anchor_pos, state.scroll, state.grab_offset)
+        });
+        let mut grabbed = false;
+        let mut content_size = Point::default();
+        let mut viewport_size = Point::default();
+        let mut anchor_pos = None;
+        let mut anchor_delta = Point::default();
 
         let (ui, pane_result) = self.builder.finish_with(
             Some(|ui: &mut Frame| {
@@ -150,6 +187,7 @@ impl<'a> ScrollpaneBuilder<'a> {
                 // TODO if horizontal and/or vertical scrollbars aren't present,
                 // change the scrollpane content size to fill up the available space
         
+                let first_child = ui.num_widgets();
                 ui.start("content")
                 .id(&content_id)
                 .trigger_layout(&mut content_bounds)
@@ -163,15 +201,48 @@ impl<'a> ScrollpaneBuilder<'a> {
                 let pane_min = pane_bounds.pos;
                 let pane_max = pane_bounds.pos + pane_bounds.size;
 
+                // the pane bounds always include the visible area, so measure the children separately
+                // in order to handle content that no longer reaches the bottom of the visible area
+                let children_bounds = ui.parent_children_bounds().unwrap_or(Rect::new(content_min + scroll, Point::default()));
+                let children_max = children_bounds.pos + children_bounds.size;
+
+                content_size = children_bounds.size;
+                viewport_size = content_bounds.size;
+                match &anchor {
+                    ScrollAnchor::Clamp => (),
+                    ScrollAnchor::Relative => if prev_viewport_size != Point::default() {
+                        let prev_range = prev_content_size - prev_viewport_size;
+                        let range = content_size - viewport_size;
+                        anchor_delta = Point::new(
+                            relative_scroll(scroll.x, prev_range.x, range.x),
+                            relative_scroll(scroll.y, prev_range.y, range.y),
+                        ) - scroll;
+                    },
+                    ScrollAnchor::Child(id) => {
+                        let child = (first_child..ui.num_widgets()).map(|index| ui.widget(index)).find(|widget| widget.id() == id);
+                        if let Some(child) = child {
+                            // the position within the content, independent of the scroll
+                            let pos = child.pos() - content_bounds.pos - scroll;
+                            if let Some(prev_pos) = prev_anchor_pos {
+                                anchor_delta = prev_pos - pos;
+                            }
+                            anchor_pos = Some(pos);
+                        }
+                    }
+                }
+
                 let mut delta_scroll = Point::default();
 
                 let enable_horiz = pane_min.x < content_min.x || pane_max.x > content_max.x;
                 // check whether to show horizontal scrollbar
                 if horiz.show(enable_horiz) {
-                    let mut scroll_button_center_x = 0.0;
-                    let mut scroll_ratio = 1.0;
+                    let scrolled = content_min.x - pane_min.x;
+                    let range = pane_bounds.size.x - content_bounds.size.x;
+                    let mut thumb = Thumb::default();
+                    let mut track_origin = 0.0;
 
                     let scrollbar_result = ui.start("scrollbar_horizontal")
+                    .repeat_on_hold(PAGE_REPEAT_DELAY, PAGE_REPEAT_INTERVAL)
                     .children(|ui| {
                         let mut right_rect = Rect::default();
                         let result = ui.start("right")
@@ -189,52 +260,49 @@ impl<'a> ScrollpaneBuilder<'a> {
                             delta_scroll.x += ui.context().options().line_scroll;
                         }
         
-                        // compute size and position for main scroll button
-                        let start_frac = ((content_min.x - pane_min.x) / pane_bounds.size.x).max(0.0);
-                        let width_frac = content_bounds.size.x / pane_bounds.size.x;
-        
                         // assume left button starts at 0,0 within the parent widget
                         let size_y = left_rect.size.y;
-                        let min_x = left_rect.size.x;
-                        let mut max_x = right_rect.pos.x - left_rect.pos.x;
-                        let mut size_x = width_frac * (max_x - min_x);
-                        if size_x < size_y {
-                            // don't let scroll button get too small
-                            max_x -= size_y - size_x;
-                            size_x = size_y;
-                        }
-
-                        let pos_y = 0.0;
-                        let pos_x = min_x + start_frac * (max_x - min_x);
-
-                        scroll_button_center_x = pos_x + size_x * 0.5 + ui.cursor().x;
-                        let scrollbar_dist = max_x - min_x - size_x; // total distance the scrollbar may move
-                        let content_dist = pane_bounds.size.x - content_bounds.size.x; // total distance the content may move
-                        scroll_ratio = content_dist / (2.0 * scrollbar_dist);
+                        let track_max = right_rect.pos.x - left_rect.pos.x;
+                        thumb = Thumb::new(left_rect.size.x, track_max, size_y, content_bounds.size.x, pane_bounds.size.x, scrolled);
 
+                        let mut thumb_rect = Rect::default();
                         let result = ui.start("scroll")
-                        .size(size_x, size_y)
-                        .pos(pos_x, pos_y)
+                        .size(thumb.size, size_y)
+                        .pos(thumb.pos, 0.0)
                         .enabled(enable_horiz)
+                        .trigger_layout(&mut thumb_rect)
                         .finish();
+                        track_origin = thumb_rect.pos.x - thumb.pos;
         
                         if result.pressed {
-                            delta_scroll.x -= result.moved.x * scroll_ratio;
+                            let mouse_x = ui.mouse_pos().x;
+                            let offset = grab_offset.get_or_insert(Point::new(mouse_x - thumb_rect.pos.x, 0.0)).x;
+                            delta_scroll.x -= thumb.scrolled_at(mouse_x - offset - track_origin, range) - scrolled;
+                            grabbed = true;
                         }
                     });
 
+                    // page towards the mouse when clicking the track
                     if scrollbar_result.clicked {
-                        delta_scroll.x -= (ui.mouse_pos().x - scroll_button_center_x) * scroll_ratio;
+                        let mouse_x = ui.mouse_pos().x - track_origin;
+                        if mouse_x < thumb.pos {
+                            delta_scroll.x += content_bounds.size.x;
+                        } else if mouse_x > thumb.pos + thumb.size {
+                            delta_scroll.x -= content_bounds.size.x;
+                        }
                     }
                 }
         
                 let enable_vertical = pane_min.y < content_min.y || pane_max.y > content_max.y;
                 // check whether to show vertical scrollbar
                 if vert.show(enable_vertical) {
-                    let mut scroll_button_center_y = 0.0;
-                    let mut scroll_ratio = 1.0;
+                    let scrolled = content_min.y - pane_min.y;
+                    let range = pane_bounds.size.y - content_bounds.size.y;
+                    let mut thumb = Thumb::default();
+                    let mut track_origin = 0.0;
 
                     let scrollbar_result = ui.start("scrollbar_vertical")
+                    .repeat_on_hold(PAGE_REPEAT_DELAY, PAGE_REPEAT_INTERVAL)
                     .children(|ui| {
                         let mut top_rect = Rect::default();
                         let result = ui.start("up")
@@ -252,63 +320,149 @@ impl<'a> ScrollpaneBuilder<'a> {
                             delta_scroll.y -= ui.context().options().line_scroll;
                         }
         
-                        // compute size and position for main scroll button
-                        let start_frac = ((content_min.y - pane_min.y) / pane_bounds.size.y).max(0.0);
-                        let height_frac = content_bounds.size.y / pane_bounds.size.y;
-        
                         // assume top button starts at 0,0 within the parent widget
                         let size_x = top_rect.size.x;
-                        let min_y = top_rect.size.y;
-                        let mut max_y = bot_rect.pos.y - top_rect.pos.y;
-                        let mut size_y = height_frac * (max_y - min_y);
-                        if size_y < size_x {
-                            // don't let scroll button get too small
-                            max_y -= size_x - size_y;
-                            size_y = size_x;
-                        }
-
-                        let pos_x = 0.0;
-                        let pos_y = min_y + start_frac * (max_y - min_y);
-
-                        scroll_button_center_y = pos_y + size_y * 0.5 + ui.cursor().y;
-                        let scrollbar_dist = max_y - min_y - size_y; // total distance the scrollbar may move
-                        let content_dist = pane_bounds.size.y - content_bounds.size.y; // total distance the content may move
-                        scroll_ratio = content_dist / scrollbar_dist;
+                        let track_max = bot_rect.pos.y - top_rect.pos.y;
+                        thumb = Thumb::new(top_rect.size.y, track_max, size_x, content_bounds.size.y, pane_bounds.size.y, scrolled);
 
+                        let mut thumb_rect = Rect::default();
                         let result = ui.start("scroll")
-                        .size(size_x, size_y)
-                        .pos(pos_x, pos_y)
+                        .size(size_x, thumb.size)
+                        .pos(0.0, thumb.pos)
                         .enabled(enable_vertical)
+                        .trigger_layout(&mut thumb_rect)
                         .finish();
+                        track_origin = thumb_rect.pos.y - thumb.pos;
         
-                        if result.pressed && result.moved.y != 0.0 {
-                            delta_scroll.y -= result.moved.y * scroll_ratio;
+                        if result.pressed {
+                            let mouse_y = ui.mouse_pos().y;
+                            let offset = grab_offset.get_or_insert(Point::new(0.0, mouse_y - thumb_rect.pos.y)).y;
+                            delta_scroll.y -= thumb.scrolled_at(mouse_y - offset - track_origin, range) - scrolled;
+                            grabbed = true;
                         }
                     });
 
+                    // page towards the mouse when clicking the track
                     if scrollbar_result.clicked {
-                        delta_scroll.y -= (ui.mouse_pos().y - scroll_button_center_y - scrollpane_pos.y) * scroll_ratio;
+                        let mouse_y = ui.mouse_pos().y - track_origin;
+                        if mouse_y < thumb.pos {
+                            delta_scroll.y += content_bounds.size.y;
+                        } else if mouse_y > thumb.pos + thumb.size {
+                            delta_scroll.y -= content_bounds.size.y;
+                        }
                     }
                 }
         
-                min_scroll = content_max - pane_max;
+                min_scroll = content_max - children_max;
                 max_scroll = content_min - pane_min;
                 delta = delta_scroll;
+
+                if !grabbed {
+                    grab_offset = None;
+                }
             })
         );
 
         delta = delta + pane_result.moved;
 
-        // set the scroll every frame to bound it, in case it was modified externally
-        ui.modify(&content_id, |state| {
+        // set the scroll every frame to bound it, in case it was modified externally or the content changed
+        let mut reached_end = false;
+        let adjusted = ui.modify(&content_id, |state| {
             let min = min_scroll + state.scroll;
             let max = Point::default();
 
-            state.scroll = (state.scroll + delta).max(min).min(max);
+            let prev_scroll = state.scroll;
+            state.scroll = (state.scroll + delta + anchor_delta).max(min).min(max);
+            state.content_size = content_size;
+            state.viewport_size = viewport_size;
+            state.anchor_pos = anchor_pos;
+            state.grab_offset = grab_offset;
+
+            if let Some((threshold, _)) = near_end.as_ref() {
+                let remaining = content_size.y - viewport_size.y + state.scroll.y;
+                let was_near_end = state.near_end && content_size == prev_content_size;
+                state.near_end = remaining <= *threshold;
+                reached_end = state.near_end && !was_near_end;
+            }
+
+            state.scroll != prev_scroll + delta
         });
+
+        // the content was drawn using the old scroll position, so make sure it is drawn again
+        if adjusted {
+            ui.request_redraw();
+        }
+
+        if let (true, Some((_, callback))) = (reached_end, near_end) {
+            callback();
+        }
     }
 }
 
+const PAGE_REPEAT_DELAY: u32 = 400;
+const PAGE_REPEAT_INTERVAL: u32 = 100;
+
+/// The position and size of a scrollbar thumb along its track
+#[derive(Default)]
+struct Thumb {
+    pos: f32,
+    size: f32,
+    track_min: f32,
+    travel: f32,
+}
+
+impl Thumb {
+    /// Computes the thumb for a track between `track_min` and `track_max`, with the thumb sized
+    /// according to the fraction of the `total` content size which is `visible`
+    fn new(track_min: f32, track_max: f32, min_size: f32, visible: f32, total: f32, scrolled: f32) -> Thumb {
+        let track = (track_max - track_min).max(0.0);
+        let frac_visible = if total > 0.0 { (visible / total).min(1.0) } else { 1.0 };
+        let size = (track * frac_visible).max(min_size).min(track);
+        let travel = track - size;
+
+        let range = total - visible;
+        let frac = if range > 0.0 { (scrolled / range).clamp(0.0, 1.0) } else { 0.0 };
+
+        Thumb { pos: track_min + frac * travel, size, track_min, travel }
+    }
+
+    /// Returns the amount the content is scrolled when the thumb is at `pos`, for a total scrollable `range`
+    fn scrolled_at(&self, pos: f32, range: f32) -> f32 {
+        if self.travel <= 0.0 { return 0.0; }
+        ((pos - self.track_min) / self.travel).clamp(0.0, 1.0) * range.max(0.0)
+    }
+}
+
+/// Returns the scroll position covering the same fraction of the scrollable `range` as `scroll` did of `prev_range`
+fn relative_scroll(scroll: f32, prev_range: f32, range: f32) -> f32 {
+    if prev_range <= 0.0 || range <= 0.0 {
+        scroll
+    } else {
+        scroll * range / prev_range
+    }
+}
+
+/**
+Determines how a [`Scrollpane`](struct.ScrollpaneBuilder.html) adjusts its scroll position when the size of its
+content or the scrollpane itself changes, such as when items are added or removed or the window is resized.
+In all cases, the scroll position is clamped so that the pane is never scrolled past the end of its content.
+**/
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum ScrollAnchor {
+    /// Keep the current scroll offset, only clamping it to the content
+    #[default]
+    Clamp,
+
+    /// Keep the same relative position, so that a pane scrolled halfway through its content remains
+    /// halfway through after the content grows or shrinks
+    Relative,
+
+    /// Keep the child widget with the specified id at the same position within the visible area.  This is
+    /// useful for content which grows at the top, such as a chat log with older messages loaded on demand.
+    /// If the child is not present, the scroll offset is only clamped.
+    Child(String),
+}
+
 /// An enum to define when to show a particular UI element.
 #[derive(Debug, Copy, Clone)]
 pub enum ShowElement {
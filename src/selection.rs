@@ -0,0 +1,254 @@
+use std::collections::BTreeSet;
+
+use serde::{Serialize, Deserialize};
+
+use crate::{Frame, InputModifiers, Point, Rect};
+
+/// Whether a [`Selection`](struct.Selection.html) may contain one or many items.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum SelectionMode {
+    /// At most one item may be selected at a time
+    #[default]
+    Single,
+
+    /// Any number of items may be selected.  Ctrl-click toggles individual items, Shift-click
+    /// selects a range of items, and dragging inside the container selects all items in the box.
+    Multi,
+}
+
+/**
+The set of selected items in a list, tree, table, or similar container, identified by index.  Each container's
+selection is stored in the [`PersistentState`](struct.PersistentState.html) of the container's `id`, so it
+persists between frames and is saved along with the rest of the [`Context`](struct.Context.html).
+
+[`selection_list`](struct.Frame.html#method.selection_list) handles selection automatically.  Other widgets
+can obtain the same behavior by calling [`Frame.select`](struct.Frame.html#method.select) when an item is clicked.
+**/
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Selection {
+    selected: BTreeSet<usize>,
+    anchor: Option<usize>,
+
+    #[serde(skip)]
+    box_start: Option<Point>,
+}
+
+impl Selection {
+    /// Returns whether the item at `index` is selected
+    pub fn is_selected(&self, index: usize) -> bool {
+        self.selected.contains(&index)
+    }
+
+    /// Returns an iterator over all selected indices, in ascending order
+    pub fn selected(&self) -> impl Iterator<Item=usize> + '_ {
+        self.selected.iter().copied()
+    }
+
+    /// Returns the lowest selected index, if any.  Useful for [`Single`](enum.SelectionMode.html#variant.Single)
+    /// selections.
+    pub fn first(&self) -> Option<usize> {
+        self.selected.iter().next().copied()
+    }
+
+    /// Returns the number of selected items
+    pub fn len(&self) -> usize { self.selected.len() }
+
+    /// Returns true if no items are selected
+    pub fn is_empty(&self) -> bool { self.selected.is_empty() }
+
+    /// The item that Shift-click ranges extend from; normally the last item clicked without Shift
+    pub fn anchor(&self) -> Option<usize> { self.anchor }
+
+    /// Deselects all items
+    pub fn clear(&mut self) {
+        self.selected.clear();
+        self.anchor = None;
+    }
+
+    /// Adds the item at `index` to the selection
+    pub fn select(&mut self, index: usize) {
+        self.selected.insert(index);
+    }
+
+    /// Removes the item at `index` from the selection
+    pub fn deselect(&mut self, index: usize) {
+        self.selected.remove(&index);
+    }
+
+    /// Removes any selected indices greater than or equal to `count`, such as after items have been removed
+    /// from the underlying list.
+    pub fn truncate(&mut self, count: usize) {
+        self.selected.retain(|index| *index < count);
+        if self.anchor.is_some_and(|anchor| anchor >= count) {
+            self.anchor = None;
+        }
+    }
+
+    /// Updates the selection in response to the user clicking the item at `index`, using the standard
+    /// semantics for the specified `mode` and keyboard `modifiers`.
+    pub fn click(&mut self, index: usize, mode: SelectionMode, modifiers: InputModifiers) {
+        if mode == SelectionMode::Single {
+            self.selected.clear();
+            self.selected.insert(index);
+            self.anchor = Some(index);
+            return;
+        }
+
+        if modifiers.shift {
+            let anchor = *self.anchor.get_or_insert(index);
+            if !modifiers.ctrl {
+                self.selected.clear();
+            }
+            self.selected.extend(anchor.min(index)..=anchor.max(index));
+        } else if modifiers.ctrl {
+            if !self.selected.remove(&index) {
+                self.selected.insert(index);
+            }
+            self.anchor = Some(index);
+        } else {
+            self.selected.clear();
+            self.selected.insert(index);
+            self.anchor = Some(index);
+        }
+    }
+}
+
+impl Frame {
+    /// Returns a copy of the current [`Selection`](struct.Selection.html) for the container with the specified `id`.
+    pub fn selection(&self, id: &str) -> Selection {
+        let context = self.context_internal().borrow();
+        context.state(id).selection.clone()
+    }
+
+    /// Updates the [`Selection`](struct.Selection.html) of the container with the specified `id` in response
+    /// to the user clicking on the item at `index`, taking into account the current
+    /// [`input modifiers`](#method.input_modifiers).  Use this to add selection to custom widgets.
+    ///
+    /// # Example
+    /// ```
+    /// fn files(ui: &mut Frame, files: &[String]) {
+    ///     let selection = ui.selection("files");
+    ///     for (index, file) in files.iter().enumerate() {
+    ///         if ui.toggle_button("file", file, selection.is_selected(index)).clicked {
+    ///             ui.select("files", index, SelectionMode::Multi);
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn select(&mut self, id: &str, index: usize, mode: SelectionMode) {
+        let modifiers = self.input_modifiers();
+        self.modify(id, |state| state.selection.click(index, mode, modifiers));
+    }
+
+    /**
+    A vertical list of `count` selectable items.  Each item is created as an `item` widget, which is
+    [`active`](struct.WidgetBuilder.html#method.active) when selected, with its contents created by the `f`
+    closure, which is passed the index of the item.  Clicking items updates the selection according to `mode`.
+    In [`Multi`](enum.SelectionMode.html#variant.Multi) mode, the user may also drag on the list background to
+    select all items intersecting a box, drawn with the `selection_box` widget.  Holding Ctrl while dragging adds to
+    the existing selection.  The `id` must be unique, and identifies the selection.  Returns the current selection.
+
+    An example YAML theme definition:
+    ```yaml
+    selection_list:
+      layout: Vertical
+      size_from: [Parent, Children]
+      children:
+        item:
+          wants_mouse: true
+          layout: Horizontal
+          size_from: [Parent, Normal]
+          height: 24
+          background: gui/list_item
+          children:
+            label:
+              from: label
+        selection_box:
+          background: gui/selection_box
+    ```
+
+    # Example
+    ```
+    fn inventory(ui: &mut Frame, items: &[String]) {
+        let selection = ui.selection_list("selection_list", "inventory", SelectionMode::Multi, items.len(), |ui, index| {
+            ui.label("label", items[index].clone());
+        });
+
+        for index in selection.selected() {
+            println!("Selected {}", items[index]);
+        }
+    }
+    ```
+    **/
+    pub fn selection_list<F: FnMut(&mut Frame, usize)>(
+        &mut self,
+        theme: &str,
+        id: &str,
+        mode: SelectionMode,
+        count: usize,
+        mut f: F,
+    ) -> Selection {
+        let mut selection = self.selection(id);
+        selection.truncate(count);
+
+        let mouse_pos = self.mouse_pos();
+        let box_rect = selection.box_start.map(|start| Rect::new(start, Point::default()).max(Rect::new(mouse_pos, Point::default())));
+
+        let mut clicked = None;
+        let mut rects = Vec::with_capacity(count);
+
+        let result = self.start(theme)
+        .id(id)
+        .wants_mouse(mode == SelectionMode::Multi)
+        .children(|ui| {
+            for index in 0..count {
+                let mut rect = Rect::default();
+                let item = ui.start("item")
+                .wants_mouse(true)
+                .active(selection.is_selected(index))
+                .trigger_layout(&mut rect)
+                .children(|ui| f(ui, index));
+
+                if item.clicked {
+                    clicked = Some(index);
+                }
+                rects.push(rect);
+            }
+
+            if let Some(rect) = box_rect {
+                ui.start("selection_box")
+                .screen_pos(rect.pos.x, rect.pos.y)
+                .size(rect.size.x, rect.size.y)
+                .finish();
+            }
+        });
+
+        let modifiers = self.input_modifiers();
+        if let Some(index) = clicked {
+            selection.click(index, mode, modifiers);
+        }
+
+        if mode == SelectionMode::Multi {
+            if let Some(box_rect) = box_rect {
+                if !modifiers.ctrl {
+                    selection.selected.clear();
+                }
+
+                for (index, rect) in rects.iter().enumerate() {
+                    if box_rect.intersects(*rect) {
+                        selection.selected.insert(index);
+                    }
+                }
+
+                if !result.pressed {
+                    selection.box_start = None;
+                }
+            } else if result.pressed && clicked.is_none() {
+                selection.box_start = Some(mouse_pos);
+            }
+        }
+
+        self.modify(id, |state| state.selection = selection.clone());
+        selection
+    }
+}
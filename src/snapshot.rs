@@ -0,0 +1,244 @@
+use std::path::{Path, PathBuf};
+
+use crate::conformance::differing_pixels;
+use crate::{BuildOptions, ContextBuilder, DrawDataRenderer, Error, Frame, ManualClock, Point, IO};
+
+/// The environment variable which, when set, causes [`SnapshotTester`](struct.SnapshotTester.html) to
+/// overwrite existing reference images rather than comparing against them.
+pub const UPDATE_SNAPSHOTS_VAR: &str = "THYME_UPDATE_SNAPSHOTS";
+
+type SnapshotSetup = dyn Fn(&mut ContextBuilder) -> Result<(), Error>;
+
+struct SnapshotIO {
+    size: Point,
+    scale: f32,
+}
+
+impl IO for SnapshotIO {
+    fn scale_factor(&self) -> f32 { self.scale }
+
+    fn display_size(&self) -> Point { self.size * self.scale }
+}
+
+/// The outcome of checking a UI snapshot with [`SnapshotTester::check`](struct.SnapshotTester.html#method.check).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SnapshotResult {
+    /// The rendered UI matched the reference image, within the tolerance
+    Matched,
+
+    /// No reference image existed, so the rendered UI was saved as the reference, as
+    /// [`UPDATE_SNAPSHOTS_VAR`](constant.UPDATE_SNAPSHOTS_VAR.html) is set
+    Created,
+
+    /// No reference image existed.  The rendered image is saved next to where the reference would be, with an
+    /// `.actual.png` extension, for inspection.
+    Missing,
+
+    /// The reference image was overwritten with the rendered UI, as [`UPDATE_SNAPSHOTS_VAR`](constant.UPDATE_SNAPSHOTS_VAR.html)
+    /// is set
+    Updated,
+
+    /// The rendered UI did not match the reference image.  The rendered image is saved next to the reference, with
+    /// an `.actual.png` extension, for inspection.
+    Mismatched {
+        /// The number of pixels differing by more than the tolerance, or every pixel if the size differs
+        differing_pixels: usize,
+    },
+}
+
+impl SnapshotResult {
+    /// Returns whether this result is anything other than [`Missing`](#variant.Missing) or
+    /// [`Mismatched`](#variant.Mismatched)
+    pub fn passed(self) -> bool {
+        !matches!(self, SnapshotResult::Missing | SnapshotResult::Mismatched { .. })
+    }
+}
+
+/**
+Renders UI closures in software, at a fixed display size and scale factor, and compares the results against
+reference PNG images stored in a directory, so that theme and layout regressions are caught by tests.  Rendering
+uses a [`DrawDataRenderer`](struct.DrawDataRenderer.html) and [`DrawData::rasterize`](struct.DrawData.html#method.rasterize),
+so no graphics device is needed, and UI time is fixed at zero, so animations are deterministic.
+
+A missing reference image fails the check, so that a snapshot is not silently accepted.  Set the
+`THYME_UPDATE_SNAPSHOTS` environment variable to create missing references, and to overwrite existing references
+after an intended change.  See also
+[`assert_ui_snapshot`](macro.assert_ui_snapshot.html).  Requires the `image` feature.
+
+# Example
+```
+let tester = thyme::SnapshotTester::new("tests/snapshots", |builder| {
+    builder.register_font("Roboto-Medium", std::fs::read("examples/data/fonts/Roboto-Medium.ttf")?);
+    builder.register_theme(serde_yaml::Deserializer::from_str(THEME))?;
+    Ok(())
+}).with_size(Point::new(320.0, 240.0));
+
+thyme::assert_ui_snapshot!(tester, "button", |ui| {
+    ui.button("button", "Click me");
+});
+```
+**/
+pub struct SnapshotTester {
+    dir: PathBuf,
+    setup: Box<SnapshotSetup>,
+    size: Point,
+    scale: f32,
+    frames: usize,
+    tolerance: u8,
+    max_pixels: usize,
+}
+
+impl SnapshotTester {
+    /**
+    Creates a new tester, storing reference images in `dir`.  The `setup` closure is called on a new
+    [`ContextBuilder`](struct.ContextBuilder.html) for each snapshot, and should register the themes, fonts, and
+    images used by the UI.  By default, the display is 800 by 600 logical pixels at a scale factor of 1.0, and
+    any difference beyond a tolerance of 2 in a single pixel fails the comparison.
+    **/
+    pub fn new<P, F>(dir: P, setup: F) -> SnapshotTester
+    where
+        P: Into<PathBuf>,
+        F: Fn(&mut ContextBuilder) -> Result<(), Error> + 'static,
+    {
+        SnapshotTester {
+            dir: dir.into(),
+            setup: Box::new(setup),
+            size: Point::new(800.0, 600.0),
+            scale: 1.0,
+            frames: 2,
+            tolerance: 2,
+            max_pixels: 0,
+        }
+    }
+
+    /// Sets the display `size` that snapshots are rendered at, in logical pixels
+    #[must_use]
+    pub fn with_size(mut self, size: Point) -> SnapshotTester {
+        self.size = size;
+        self
+    }
+
+    /// Sets the `scale` factor that snapshots are rendered at
+    #[must_use]
+    pub fn with_scale(mut self, scale: f32) -> SnapshotTester {
+        self.scale = scale;
+        self
+    }
+
+    /// Sets the number of `frames` that the UI closure is run for before the last frame is compared.  Widgets
+    /// which size themselves based on the previous frame, such as windows and scrollpanes, need more than one.
+    /// Defaults to 2.
+    #[must_use]
+    pub fn with_frames(mut self, frames: usize) -> SnapshotTester {
+        self.frames = frames.max(1);
+        self
+    }
+
+    /// Allows up to `max_pixels` pixels to differ by more than `tolerance` in any channel before a snapshot
+    /// fails.  See [`ConformanceResult::differing_pixels`](struct.ConformanceResult.html#method.differing_pixels).
+    #[must_use]
+    pub fn with_tolerance(mut self, tolerance: u8, max_pixels: usize) -> SnapshotTester {
+        self.tolerance = tolerance;
+        self.max_pixels = max_pixels;
+        self
+    }
+
+    /// Returns the path of the reference image for the snapshot `name`
+    pub fn reference_path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.png", name))
+    }
+
+    /// Renders the UI built by `build` and returns its RGBA pixels, with 8 bits per channel and the top row
+    /// first, together with the size in physical pixels.
+    pub fn render<F: FnMut(&mut Frame)>(&self, mut build: F) -> Result<(Vec<u8>, (u32, u32)), Error> {
+        let mut builder = ContextBuilder::new(BuildOptions { enable_live_reload: false, ..Default::default() });
+        (self.setup)(&mut builder)?;
+
+        let mut renderer = DrawDataRenderer::new();
+        let mut io = SnapshotIO { size: self.size, scale: self.scale };
+        let mut context = builder.build(&mut renderer, &mut io)?;
+        context.set_clock(ManualClock::new());
+
+        for _ in 1..self.frames {
+            let mut frame = context.create_frame();
+            build(&mut frame);
+            renderer.draw_frame(frame);
+        }
+
+        let mut frame = context.create_frame();
+        build(&mut frame);
+        let draw_data = renderer.draw_frame(frame).clone();
+        Ok((draw_data.rasterize(&renderer), draw_data.pixel_size()))
+    }
+
+    /**
+    Renders the UI built by `build` and compares it against the reference image for the snapshot `name`.  If
+    [`UPDATE_SNAPSHOTS_VAR`](constant.UPDATE_SNAPSHOTS_VAR.html) is set, the rendered image is saved as the
+    reference instead.  On a mismatch, or if the reference does not exist, the rendered image is saved as
+    `name.actual.png`.  Returns `Err` if rendering fails or the images cannot be read or written.
+    **/
+    pub fn check<F: FnMut(&mut Frame)>(&self, name: &str, build: F) -> Result<SnapshotResult, Error> {
+        let (actual, size) = self.render(build)?;
+        let path = self.reference_path(name);
+
+        let actual_path = self.dir.join(format!("{}.actual.png", name));
+        let exists = path.is_file();
+        if std::env::var_os(UPDATE_SNAPSHOTS_VAR).is_some() {
+            std::fs::create_dir_all(&self.dir).map_err(Error::IO)?;
+            save_png(&path, &actual, size)?;
+            return Ok(if exists { SnapshotResult::Updated } else { SnapshotResult::Created });
+        }
+
+        if !exists {
+            std::fs::create_dir_all(&self.dir).map_err(Error::IO)?;
+            save_png(&actual_path, &actual, size)?;
+            return Ok(SnapshotResult::Missing);
+        }
+
+        let expected = image::open(&path).map_err(Error::Image)?.into_rgba8();
+        let differing = if expected.dimensions() != size {
+            (size.0 * size.1) as usize
+        } else {
+            differing_pixels(expected.as_raw(), &actual, self.tolerance)
+        };
+
+        if differing <= self.max_pixels {
+            if actual_path.is_file() {
+                std::fs::remove_file(&actual_path).map_err(Error::IO)?;
+            }
+            Ok(SnapshotResult::Matched)
+        } else {
+            save_png(&actual_path, &actual, size)?;
+            Ok(SnapshotResult::Mismatched { differing_pixels: differing })
+        }
+    }
+}
+
+fn save_png(path: &Path, data: &[u8], size: (u32, u32)) -> Result<(), Error> {
+    image::save_buffer(path, data, size.0, size.1, image::ExtendedColorType::Rgba8).map_err(Error::Image)
+}
+
+/**
+Renders the UI built by a closure with a [`SnapshotTester`](struct.SnapshotTester.html), and panics if it does
+not match the stored reference image with the given name.  Pass in the tester, the snapshot name, and a closure
+taking the `ui` [`Frame`](struct.Frame.html).
+
+# Example
+```
+thyme::assert_ui_snapshot!(tester, "main_menu", |ui| {
+    build_main_menu(ui);
+});
+```
+**/
+#[macro_export]
+macro_rules! assert_ui_snapshot {
+    ($tester:expr, $name:expr, $build:expr $(,)?) => {
+        match $tester.check($name, $build) {
+            Ok(result) => assert!(
+                result.passed(),
+                "UI snapshot '{}' does not match {:?}: {:?}", $name, $tester.reference_path($name), result,
+            ),
+            Err(e) => panic!("Unable to check UI snapshot '{}': {}", $name, e),
+        }
+    };
+}
@@ -0,0 +1,320 @@
+use crate::{Frame, KeyEvent, MouseButton, Point, SelectionMode};
+
+/// The type of editor shown when an [`editable_cell`](struct.Frame.html#method.editable_cell)
+/// enters edit mode.
+#[derive(Copy, Clone, Debug)]
+pub enum CellEditor<'a> {
+    /// The cell cannot be edited
+    None,
+
+    /// Free text entry
+    Text,
+
+    /// Text entry restricted to characters that may appear in a number; digits, signs,
+    /// and decimal separators.  Parsing the resulting value is left to the caller.
+    Numeric,
+
+    /// A drop down list of the specified values
+    Combo(&'a [&'a str]),
+}
+
+/// A value committed by the user from an [`editable_cell`](struct.Frame.html#method.editable_cell).
+#[derive(Clone, Debug)]
+pub struct CellEdit {
+    /// The new value for the cell
+    pub value: String,
+
+    /// Whether the user committed the value with the Tab key, and editing should move to the next cell
+    pub next: bool,
+}
+
+/// A value committed by the user in a [`table`](struct.Frame.html#method.table).
+#[derive(Clone, Debug)]
+pub struct TableEdit {
+    /// The row index of the edited cell
+    pub row: usize,
+
+    /// The column index of the edited cell
+    pub column: usize,
+
+    /// The new value for the cell
+    pub value: String,
+}
+
+impl Frame {
+    /**
+    A table cell displaying `value`, which switches into an edit mode when double clicked, using the
+    specified `editor`.  The `id` must be unique.  While editing, pressing Enter or clicking elsewhere commits
+    the edit, Tab commits the edit and requests that the next cell begin editing, and Escape cancels the edit.
+    When an edit is committed, the new value is returned; the caller is responsible for storing it.
+    Editing may also be started in code with [`edit_cell`](#method.edit_cell).
+
+    An example YAML theme definition:
+    ```yaml
+    cell:
+      font: small
+      wants_mouse: true
+      background: gui/button
+      border: { all: 4 }
+      text_align: Left
+      size: [100, 24]
+      children:
+        editor:
+          from: input_field
+          size_from: [Parent, Parent]
+          align: TopLeft
+          pos: [-4, -4]
+        combo_popup:
+          from: combo_box_popup
+        entry:
+          from: button
+          size_from: [Parent, Normal]
+    ```
+
+    # Example
+    ```
+    fn name_cell(ui: &mut Frame, name: &mut String) {
+        if let Some(edit) = ui.editable_cell("cell", "name_cell", name, CellEditor::Text) {
+            *name = edit.value;
+        }
+    }
+    ```
+    **/
+    pub fn editable_cell(&mut self, theme: &str, id: &str, value: &str, editor: CellEditor) -> Option<CellEdit> {
+        self.cell(theme, id, value, editor).0
+    }
+
+    /// An [`editable_cell`](#method.editable_cell), also returning whether the cell was clicked while not editing
+    fn cell(&mut self, theme: &str, id: &str, value: &str, editor: CellEditor) -> (Option<CellEdit>, bool) {
+        let editor_id = format!("{}_editor", id);
+
+        if self.text_for(&editor_id).is_none() {
+            let result = self.start(theme).id(id).text(value).wants_mouse(true).finish();
+
+            if result.clicked && result.mouse_button == Some(MouseButton::Left) && self.check_double_click(id) {
+                self.edit_cell(id, value);
+            }
+
+            return (None, result.clicked);
+        }
+
+        let mut commit = None;
+        let mut cancel = false;
+
+        let numeric = matches!(editor, CellEditor::Numeric);
+        self.modify(&editor_id, |state| {
+            let text = state.text.get_or_insert_with(String::new);
+            for c in state.characters.drain(..) {
+                match c {
+                    '\x08' => { text.pop(); }, // backspace
+                    c if c.is_control() => (),
+                    c if numeric && !(c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | ',')) => (),
+                    c => text.push(c),
+                }
+            }
+
+            for event in state.key_events.drain(..) {
+                match event {
+                    KeyEvent::Return => commit = Some(false),
+                    KeyEvent::Tab => commit = Some(true),
+                    KeyEvent::Escape => cancel = true,
+                    _ => (),
+                }
+            }
+        });
+
+        let mut editor_clicked = false;
+        match editor {
+            CellEditor::None => cancel = true,
+            CellEditor::Text | CellEditor::Numeric => {
+                let mut text_pos = Point::default();
+                self.start(theme).id(id).children(|ui| {
+                    editor_clicked = ui.start("editor")
+                    .id(&editor_id)
+                    .wants_mouse(true)
+                    .trigger_text_layout(&mut text_pos)
+                    .children(|ui| {
+                        ui.start("caret").pos(text_pos.x, text_pos.y).finish();
+                    }).clicked;
+                });
+            },
+            CellEditor::Combo(values) => {
+                let popup_id = format!("{}_popup", editor_id);
+                let current = self.text_for(&editor_id).unwrap_or_default();
+
+                let mut chosen = None;
+                self.start(theme).id(id).text(current).wants_mouse(true).children(|ui| {
+                    ui.start("combo_popup")
+                    .id(&popup_id)
+                    .unclip()
+                    .unparent()
+                    .new_render_group()
+                    .scrollpane(&format!("{}_content", popup_id))
+                    .children(|ui| {
+                        for value in values {
+                            if ui.button("entry", *value).clicked {
+                                chosen = Some(value.to_string());
+                            }
+                        }
+                    });
+                });
+                self.open(&popup_id);
+
+                if let Some(value) = chosen {
+                    self.modify(&editor_id, |state| state.text = Some(value));
+                    commit = Some(false);
+                }
+            }
+        }
+
+        if editor_clicked {
+            self.focus_keyboard(&editor_id);
+        } else if commit.is_none() && !cancel && !self.is_focus_keyboard(&editor_id) {
+            // the user clicked elsewhere
+            commit = Some(false);
+        }
+
+        if cancel {
+            self.stop_cell_edit(&editor_id);
+            return (None, false);
+        }
+
+        let next = match commit {
+            None => return (None, false),
+            Some(next) => next,
+        };
+        let value = self.stop_cell_edit(&editor_id).unwrap_or_default();
+        (Some(CellEdit { value, next }), false)
+    }
+
+    /// Causes the [`editable_cell`](#method.editable_cell) with the specified `id` to begin
+    /// editing, with an initial editor value of `value`.
+    pub fn edit_cell(&mut self, id: &str, value: &str) {
+        let editor_id = format!("{}_editor", id);
+        self.modify(&editor_id, |state| {
+            state.text = Some(value.to_string());
+            state.characters.clear();
+            state.key_events.clear();
+        });
+        self.focus_keyboard(editor_id);
+    }
+
+    /**
+    A table of [`editable cells`](#method.editable_cell), with a header row containing the specified
+    `columns` names and the specified number of `rows`.  The `value` closure is called to obtain the text of the
+    cell at a given row and column, and `editor` to obtain the [`CellEditor`](enum.CellEditor.html) for a given
+    column.  When the user commits an edit, the new value is returned.  If the user presses Tab, editing moves
+    on to the next editable cell in the table.  The `id` must be unique.
+
+    Clicking a cell selects its row according to `mode`, in the [`Selection`](struct.Selection.html) stored for the
+    `id`, which may be obtained with [`selection`](#method.selection).  Selected rows are
+    [`active`](struct.WidgetBuilder.html#method.active).
+
+    An example YAML theme definition:
+    ```yaml
+    table:
+      layout: Vertical
+      size_from: [Parent, Children]
+      children:
+        header:
+          layout: Horizontal
+          size_from: [Parent, Children]
+          children:
+            column:
+              from: label
+              size: [100, 24]
+        row:
+          layout: Horizontal
+          size_from: [Parent, Children]
+          children:
+            cell:
+              from: cell
+    ```
+
+    # Example
+    ```
+    fn item_table(ui: &mut Frame, items: &mut [(String, String)]) {
+        let edit = ui.table("table", "items", &["Name", "Price"], items.len(), SelectionMode::Single,
+            |row, col| if col == 0 { items[row].0.clone() } else { items[row].1.clone() },
+            |col| if col == 0 { CellEditor::Text } else { CellEditor::Numeric },
+        );
+
+        if let Some(edit) = edit {
+            let item = &mut items[edit.row];
+            if edit.column == 0 { item.0 = edit.value } else { item.1 = edit.value }
+        }
+    }
+    ```
+    **/
+    #[allow(clippy::too_many_arguments)]
+    pub fn table<'e, F: FnMut(usize, usize) -> String, E: Fn(usize) -> CellEditor<'e>>(
+        &mut self,
+        theme: &str,
+        id: &str,
+        columns: &[&str],
+        rows: usize,
+        mode: SelectionMode,
+        mut value: F,
+        editor: E,
+    ) -> Option<TableEdit> {
+        let cell_id = |row: usize, column: usize| format!("{}_{}_{}", id, row, column);
+        let selection = self.modify(id, |state| {
+            state.selection.truncate(rows);
+            state.selection.clone()
+        });
+
+        let mut result = None;
+        let mut move_next = false;
+        let mut clicked = None;
+        self.start(theme).id(id).children(|ui| {
+            ui.start("header").children(|ui| {
+                for column in columns {
+                    ui.label("column", *column);
+                }
+            });
+
+            for row in 0..rows {
+                ui.start("row").active(selection.is_selected(row)).children(|ui| {
+                    for column in 0..columns.len() {
+                        let text = value(row, column);
+                        let (edit, cell_clicked) = ui.cell("cell", &cell_id(row, column), &text, editor(column));
+                        if let Some(edit) = edit {
+                            move_next = edit.next;
+                            result = Some(TableEdit { row, column, value: edit.value });
+                        }
+
+                        if cell_clicked {
+                            clicked = Some(row);
+                        }
+                    }
+                });
+            }
+        });
+
+        if let Some(row) = clicked {
+            self.select(id, row, mode);
+        }
+
+        if move_next {
+            let edit = result.as_ref().unwrap();
+            let start = edit.row * columns.len() + edit.column + 1;
+            let next = (start..rows * columns.len())
+                .map(|index| (index / columns.len(), index % columns.len()))
+                .find(|(_, column)| !matches!(editor(*column), CellEditor::None));
+
+            if let Some((row, column)) = next {
+                let text = value(row, column);
+                self.edit_cell(&cell_id(row, column), &text);
+            }
+        }
+
+        result
+    }
+
+    fn stop_cell_edit(&mut self, editor_id: &str) -> Option<String> {
+        if self.is_focus_keyboard(editor_id) {
+            self.clear_focus_keyboard();
+        }
+        self.modify(editor_id, |state| state.text.take())
+    }
+}
@@ -0,0 +1,108 @@
+use crate::Frame;
+use crate::theme_definition::{TemplateKind, TemplateNode};
+
+impl Frame {
+    /**
+    Creates the widgets defined by the template with the specified `id` in the `templates` section of the theme.
+    Since templates are part of the theme, they are reloaded along with the rest of the theme when using live
+    reload, allowing layouts to be changed without recompiling.  Each node in a template creates a widget of the
+    specified `kind` using its `theme`:
+    - `Widget`: a generic widget, as created by [`start`](#method.start), with any `children`.  This is the default.
+    - `Button`: a [`button`](#method.button).
+    - `Label`: a [`label`](#method.label).
+    - `Scrollpane`: a [`scrollpane`](#method.scrollpane) with any `children`, using `id` as the content id.
+    - `Window`: a [`window`](#method.window) with any `children`.
+
+    Nodes may optionally specify an `id` and `text`.  If a node has an `event`, clicking the widget reports that
+    event.  Returns the `event` of the widget that was clicked this frame, if any.  If the template is not found,
+    an error is logged and nothing is created.
+
+    An example theme definition:
+    ```yaml
+    templates:
+      main_menu:
+        - kind: Window
+          theme: main_menu_window
+          children:
+            - kind: Label
+              theme: title
+              text: "My Game"
+            - kind: Button
+              theme: button
+              text: "Play"
+              event: play
+            - kind: Button
+              theme: button
+              text: "Quit"
+              event: quit
+    ```
+
+    # Example
+    ```
+    fn main_menu(ui: &mut Frame, state: &mut GameState) {
+        match ui.build_from_template("main_menu").as_deref() {
+            Some("play") => state.start_game(),
+            Some("quit") => state.exit(),
+            _ => (),
+        }
+    }
+    ```
+    **/
+    pub fn build_from_template(&mut self, id: &str) -> Option<String> {
+        let template = self.context_internal().borrow().themes().template(id);
+        let template = match template {
+            None => {
+                self.log(log::Level::Error, format!("Unable to locate template '{}'", id));
+                return None;
+            }, Some(template) => template,
+        };
+
+        let mut event = None;
+        build_nodes(self, &template, &mut event);
+        event
+    }
+}
+
+fn build_nodes(ui: &mut Frame, nodes: &[TemplateNode], event: &mut Option<String>) {
+    for node in nodes {
+        build_node(ui, node, event);
+    }
+}
+
+fn build_node(ui: &mut Frame, node: &TemplateNode, event: &mut Option<String>) {
+    let clicked = match node.kind {
+        TemplateKind::Widget => {
+            let mut builder = ui.start(&node.theme);
+            if let Some(id) = node.id.as_ref() {
+                builder = builder.id(id);
+            }
+            if let Some(text) = node.text.as_ref() {
+                builder = builder.text(text);
+            }
+            if node.event.is_some() {
+                builder = builder.wants_mouse(true);
+            }
+            builder.children(|ui| build_nodes(ui, &node.children, event)).clicked
+        },
+        TemplateKind::Button => ui.button(&node.theme, node.text.as_deref().unwrap_or_default()).clicked,
+        TemplateKind::Label => {
+            ui.label(&node.theme, node.text.as_deref().unwrap_or_default());
+            false
+        },
+        TemplateKind::Scrollpane => {
+            let content_id = node.id.as_deref().unwrap_or(&node.theme);
+            ui.scrollpane(&node.theme, content_id, |ui| build_nodes(ui, &node.children, event));
+            false
+        },
+        TemplateKind::Window => {
+            ui.window(&node.theme, |ui| build_nodes(ui, &node.children, event));
+            false
+        },
+    };
+
+    if clicked {
+        if let Some(node_event) = node.event.as_ref() {
+            *event = Some(node_event.to_string());
+        }
+    }
+}
@@ -1,6 +1,20 @@
 use pulldown_cmark::{Alignment, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
 
-use crate::{Frame, Rect, Point, Align, WidthRelative, Color};
+use crate::{Frame, Rect, Point, Align, WidthRelative, HeightRelative, Color, SystemCursor, TextCarets, UiEvent};
+use crate::theme::WidgetCursor;
+
+/**
+The links within a [`text_area`](struct.Frame.html#method.text_area) that the user interacted with this frame.
+Links are identified by their Markdown URL, which may be any string, such as the ID of a help page.
+**/
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TextAreaState {
+    /// The URL of the link currently under the mouse, if any
+    pub hovered_link: Option<String>,
+
+    /// The URL of the link clicked this frame, if any
+    pub clicked_link: Option<String>,
+}
 
 impl Frame {
     /**
@@ -10,12 +24,16 @@ impl Frame {
     combination that is not defined, the widget will log an error.
 
     The widget can currently handle a subset of common Markdown, including headings, strong / emphasis text, unordered
-    and ordered lists, atables with column alignments, and images.
+    and ordered lists, atables with column alignments, images, and links.
 
     Several parameters need to be specified for the widget to function properly, including `tab_width`, `column_width`, and
     a `list_bullet` character.  See the example below.  Note that the widget does not perform look-ahead to determine
     appropriate column widths - these are specified with the `column_width` parameter instead.
 
+    Each span of text is placed in a child widget sized to the height of its wrapped text, regardless of the child
+    theme's `height_from`.  A text area with `Children` height therefore grows to enclose all lines of its text,
+    which allows it to be placed in a scrollpane.
+
     ### Variable Substitution
     Using curly braces, i.e. `{my_variable_id}` you can substitute in values that are set dynamically in your code.  See
     [`set_variable`](struct.Frame.html#method.set_variable).  Also available is an `if` statement with optional `else`
@@ -30,6 +48,13 @@ impl Frame {
     be parsed by the [`Color`](struct.Color.html) struct.  Quotes are not used in specifying the value.  For
     example, `<e c=#f00>Red text</e>`.  The extended tag may be nested.
 
+    ### Links
+    Markdown links, such as `[Credits](credits)`, are drawn using the `link` child theme, which should set a distinct
+    font or text color.  The URL is not opened; instead, it is reported in the returned
+    [`TextAreaState`](struct.TextAreaState.html) while the link text is hovered or clicked, and clicks are also
+    sent as a [`UiEvent::LinkClicked`](enum.UiEvent.html#variant.LinkClicked).  While the mouse is over the link text,
+    the `cursor` of the `link` theme is shown, or a [`Pointer`](enum.SystemCursor.html#variant.Pointer) by default.
+
     ### Column Widths
     In addition to specifying a default column width using the `custom` value `column_width`, the width of individual
     columns of tables may be set using the `<e>` tag with `width` attribute, for example `<e width=30.0></e>`.
@@ -67,6 +92,10 @@ impl Frame {
         heading2_normal:
           from: text_area_item
           font: heading2
+        link:
+          from: text_area_item
+          font: small
+          text_color: "#48F"
       text: |
         This is multiline text in *YAML* with some
         basic markdown formatting.
@@ -75,14 +104,22 @@ impl Frame {
     # Example
     ```
     fn create_text_box(ui: &mut Frame) {
-        ui.text_area("text_area");
+        let state = ui.text_area("text_area");
+        if let Some(url) = state.clicked_link {
+            open_help_page(&url);
+        }
+    }
+
+    fn open_help_page(url: &str) {
+        println!("Opening help page '{}'", url);
     }
     ```
     **/
-    pub fn text_area(&mut self, theme: &str) {
+    pub fn text_area(&mut self, theme: &str) -> TextAreaState {
         let scale_factor = self.context().scale_factor();
 
         let builder = self.start(theme);
+        let id = builder.widget().id().to_string();
 
         let column_width = builder.custom_float("column_width", 25.0);
         let mut state = MarkdownState {
@@ -104,6 +141,8 @@ impl Frame {
             size: SizeMode::Paragraph,
             cur_theme: "paragraph_normal".to_string(),
             currently_at_new_line: true,
+            link: None,
+            links: TextAreaState::default(),
         };
 
         // copy the text over, expanding variables and evaluating ifs
@@ -225,6 +264,12 @@ impl Frame {
                 }
             }
         });
+
+        if let Some(url) = state.links.clicked_link.clone() {
+            self.push_event(UiEvent::LinkClicked { id, url });
+        }
+
+        state.links
     }
 }
 
@@ -256,7 +301,10 @@ fn item(
 ) {
     let original_y = state.cursor.y;
 
-    let mut builder = ui.start(state.cur_theme());
+    let theme = if state.link.is_some() { "link" } else { state.cur_theme() };
+
+    // size each item to its wrapped text, so the text area encloses all lines
+    let mut builder = ui.start(theme).height_from(HeightRelative::Text);
 
     if let Some(col) = state.table_column {
         let align = if state.table_header {
@@ -282,19 +330,38 @@ fn item(
     }
 
     let mut size = Rect::default();
+    let mut carets = TextCarets::default();
 
-    builder
+    builder = builder
         .text(text)
         .text_indent(state.text_indent)
         .trigger_layout(&mut size)
-        .trigger_text_layout(&mut state.cursor)
-        .finish();
+        .trigger_text_layout(&mut state.cursor);
+
+    if let Some(url) = state.link.clone() {
+        let cursor = builder.take_cursor().unwrap_or(WidgetCursor::System(SystemCursor::Pointer));
+        let widget_state = builder.wants_mouse(true).trigger_text_carets(&mut carets).finish();
+
+        // only the link text is interactive, not the remainder of the widget's area
+        let mouse_pos = ui.mouse_pos();
+        if widget_state.hovered && carets.is_over_text(mouse_pos) {
+            ui.set_hover_cursor(cursor);
+            if widget_state.clicked {
+                state.links.clicked_link = Some(url.clone());
+            }
+            state.links.hovered_link = Some(url);
+        }
+    } else {
+        builder.finish();
+    }
     
+    // the height of the last line of this item, which the next item continues on
+    let last_line_height = size.size.y - state.cursor.y;
     if state.currently_at_new_line {
         // if this is the first element in a new line, reset the line height
-        state.line_height = size.size.y;
+        state.line_height = last_line_height;
     } else {
-        state.line_height = state.line_height.max(size.size.y);
+        state.line_height = state.line_height.max(last_line_height);
     }
     
     state.cursor.y += original_y;
@@ -334,6 +401,10 @@ struct MarkdownState {
     font: FontMode,
 
     cur_theme: String, // computed based on size and font
+
+    // the URL of the link currently being drawn, if any
+    link: Option<String>,
+    links: TextAreaState,
 }
 
 impl MarkdownState {
@@ -394,7 +465,10 @@ impl MarkdownState {
             Tag::Image{ dest_url, .. } => {
                 image(ui, self, &dest_url);
             },
-            Tag::BlockQuote(_) | Tag::CodeBlock(_) | Tag::FootnoteDefinition(_) | Tag::Strikethrough | Tag::Link{ .. } |
+            Tag::Link{ dest_url, .. } => {
+                self.link = Some(dest_url.to_string());
+            },
+            Tag::BlockQuote(_) | Tag::CodeBlock(_) | Tag::FootnoteDefinition(_) | Tag::Strikethrough |
                 Tag::DefinitionList | Tag::DefinitionListDefinition | Tag::DefinitionListTitle | Tag::HtmlBlock | Tag::MetadataBlock(_) => {
                 ui.log(log::Level::Warn, format!("Tag {:?} is unsupported", tag));
             }
@@ -451,7 +525,8 @@ impl MarkdownState {
                 *col += 1;
             },
             TagEnd::Image => { },
-            TagEnd::BlockQuote(_) | TagEnd::CodeBlock | TagEnd::FootnoteDefinition | TagEnd::Strikethrough | TagEnd::Link | TagEnd::HtmlBlock |
+            TagEnd::Link => self.link = None,
+            TagEnd::BlockQuote(_) | TagEnd::CodeBlock | TagEnd::FootnoteDefinition | TagEnd::Strikethrough | TagEnd::HtmlBlock |
                 TagEnd::MetadataBlock(_) | TagEnd::DefinitionList | TagEnd::DefinitionListDefinition | TagEnd::DefinitionListTitle => {
                 ui.log(log::Level::Warn, format!("Tag {:?} is unsupported", tag));
             }
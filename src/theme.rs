@@ -1,72 +1,117 @@
 use std::collections::VecDeque;
+use std::rc::Rc;
 use indexmap::{IndexMap, map::Entry};
+use rustc_hash::FxHashMap;
 
 use crate::theme_definition::{
-    ThemeDefinition, ImageDefinition, ImageDefinitionKind, WidgetThemeDefinition,
-    CustomData,
+    ThemeDefinition, FontDefinition, ImageDefinition, ImageDefinitionKind, ImageFill, WidgetThemeDefinition,
+    CustomData, CursorDefinition, TemplateNode,
 };
 use crate::font::{Font, FontSummary, FontSource};
 use crate::image::{Image, ImageHandle};
 use crate::render::{TextureData, Renderer, FontHandle};
-use crate::theme_definition::CharacterRange;
-use crate::{Color, Error, Point, Border, Align, Layout, WidthRelative, HeightRelative};
+use crate::resource::{FontUsage, ResourceReport, TextureUsage};
+use crate::theme_definition::{CharacterRange, CharacterRangePreset};
+use crate::{AnimState, Color, Error, Point, Border, Align, Layout, SystemCursor, TextWrap, WidthRelative, HeightRelative};
 
 pub struct ThemeSet {
-    fonts: Vec<Font>,
+    fonts: FxHashMap<FontHandle, Font>,
     font_handles: IndexMap<String, FontSummary>,
 
     images: Vec<Image>,
     image_handles: IndexMap<String, ImageHandle>,
+    backplate: ImageHandle,
 
     theme_handles: IndexMap<String, WidgetThemeHandle>,
     themes: Vec<WidgetTheme>,
+
+    templates: IndexMap<String, Rc<[TemplateNode]>>,
+
+    // the textures registered while building this theme set
+    texture_usage: Vec<TextureUsage>,
+
+    fallbacks: Fallbacks,
+}
+
+/// The placeholders used in place of missing images and fonts, if enabled
+#[derive(Copy, Clone, Default)]
+struct Fallbacks {
+    image: Option<ImageHandle>,
+    font: Option<FontSummary>,
+}
+
+/// Returns the `found` asset, or the `fallback` if it is missing, logging the `error`.  Returns the `error` if
+/// there is no fallback.
+fn or_fallback<T, F: FnOnce() -> String>(found: Option<T>, fallback: Option<T>, error: F) -> Result<T, Error> {
+    if let Some(found) = found {
+        return Ok(found);
+    }
+
+    let error = error();
+    match fallback {
+        None => Err(Error::Theme(error)),
+        Some(fallback) => {
+            log::error!("{}", error);
+            Ok(fallback)
+        }
+    }
 }
 
 impl ThemeSet {
-    pub(crate) fn new<R: Renderer>(
-        // we pass in a mutable reference to allow easier expanding of image aliases with less copying
-        definition: &mut ThemeDefinition,
-        textures: IndexMap<String, TextureData>,
-        font_sources: IndexMap<String, FontSource>,
+    /// Returns the merged character ranges to bake for the font `definition`, including the `extra_font_ranges`
+    pub(crate) fn font_ranges(definition: &FontDefinition, extra_font_ranges: &[CharacterRange]) -> Vec<CharacterRange> {
+        let mut ranges: Vec<CharacterRange> = if definition.characters.is_empty() {
+            CharacterRangePreset::Default.ranges().to_vec()
+        } else {
+            definition.characters.iter().flat_map(|def| def.ranges()).copied().collect()
+        };
+        ranges.extend_from_slice(extra_font_ranges);
+        merge_ranges(ranges)
+    }
+
+    /// Bakes the font `definition` with the character `ranges` from [`font_ranges`](#method.font_ranges),
+    /// registering it with the `renderer` using the specified `handle`
+    pub(crate) fn bake_font<R: Renderer>(
+        handle: FontHandle,
+        definition: &FontDefinition,
+        font_sources: &IndexMap<String, FontSource>,
         renderer: &mut R,
+        ranges: &[CharacterRange],
         display_scale: f32,
-    ) -> Result<ThemeSet, Error> {
-        let default_font_ranges = vec![
-            CharacterRange { lower: 32, upper: 126 },
-            CharacterRange { lower: 161, upper: 255 },
-        ];
-
+    ) -> Result<Font, Error> {
         // TODO need to be able to rebuild fonts when scale factor changes
         // FontSummary size will stay the same for this
-        let mut font_handles = IndexMap::new();
-        let mut font_handle = FontHandle::default();
-        let mut fonts = Vec::new();
-        for (font_id, font) in &definition.fonts {
-            let source = font_sources.get(&font.source).ok_or_else(||
-                Error::Theme(format!("Unable to locate font handle {}", font.source))
-            )?;
-
-            let ranges = if font.characters.is_empty() {
-                &default_font_ranges
-            } else {
-                &font.characters
-            };
-
-            let font = renderer.register_font(
-                font_handle,
-                source,
-                ranges,
-                font.size,
-                display_scale
-            )?;
+        let source = font_sources.get(&definition.source).ok_or_else(||
+            Error::Theme(format!("Unable to locate font handle {}", definition.source))
+        )?;
 
-            font_handle = font_handle.next();
+        renderer.register_font(
+            handle,
+            source,
+            ranges,
+            definition.size,
+            display_scale
+        )
+    }
 
+    /// Creates the theme set from the `definition`, using the uploaded `textures` and the `fonts` baked
+    /// with [`bake_font`](#method.bake_font).  If `placeholders` is set, missing images and fonts
+    /// referenced by widget themes use a placeholder rather than failing.
+    pub(crate) fn new(
+        // we pass in a mutable reference to allow easier expanding of image aliases with less copying
+        definition: &mut ThemeDefinition,
+        textures: IndexMap<String, TextureData>,
+        baked_fonts: Vec<(String, Font)>,
+        display_scale: f32,
+        placeholders: bool,
+    ) -> Result<ThemeSet, Error> {
+        let mut font_handles = IndexMap::new();
+        let mut fonts = FxHashMap::default();
+        for (font_id, font) in baked_fonts {
             let line_height = font.line_height() / display_scale;
             let handle = font.handle();
-            assert!(handle.id() == fonts.len());
-            fonts.push(font);
-            font_handles.insert(font_id.to_string(), FontSummary { handle, line_height });
+            fonts.insert(handle, font);
+            font_handles.insert(font_id, FontSummary { handle, line_height });
         }
 
         let mut images = IndexMap::new();
@@ -213,6 +258,39 @@ impl ThemeSet {
             image_handles.insert(id, handle);
         }
 
+        // a solid image on the internal texture, not referenced by id, for drawing high contrast text backplates
+        // and widget triangles
+        let backplate = ImageHandle { id: images_out.len() };
+        images_out.push(Image::new(
+            crate::resource::INTERNAL_SINGLE_PIX_IMAGE_ID,
+            &ImageDefinition { color: Color::white(), kind: ImageDefinitionKind::Solid { solid: true } },
+            &textures[crate::resource::INTERNAL_SINGLE_PIX_IMAGE_ID],
+            &IndexMap::new(),
+            1.0,
+        )?);
+
+        let mut fallbacks = Fallbacks::default();
+        if placeholders {
+            if let Some(texture) = textures.get(crate::resource::INTERNAL_PLACEHOLDER_IMAGE_ID) {
+                fallbacks.image = Some(ImageHandle { id: images_out.len() });
+                let size = [crate::resource::PLACEHOLDER_IMAGE_SIZE; 2];
+                images_out.push(Image::new(
+                    crate::resource::INTERNAL_PLACEHOLDER_IMAGE_ID,
+                    &ImageDefinition {
+                        color: Color::white(),
+                        kind: ImageDefinitionKind::Simple { position: [0, 0], size, fill: ImageFill::Repeat },
+                    },
+                    texture,
+                    &IndexMap::new(),
+                    1.0,
+                )?);
+            }
+
+            fallbacks.font = font_handles.get(crate::resource::INTERNAL_DEFAULT_FONT_ID)
+                .or_else(|| font_handles.values().next())
+                .copied();
+        }
+
         // build the set of themes
         let mut theme_handles = IndexMap::new();
         let mut themes = Vec::new();
@@ -235,6 +313,7 @@ impl ThemeSet {
                 theme, 
                 &image_handles,
                 &font_handles,
+                &fallbacks,
             )?;
         }
 
@@ -292,11 +371,39 @@ impl ThemeSet {
             fonts,
             image_handles,
             images: images_out,
+            backplate,
             theme_handles,
             themes,
+            templates: definition.templates.iter().map(|(id, nodes)| (id.to_string(), nodes.as_slice().into())).collect(),
+            texture_usage: Vec::new(),
+            fallbacks,
         })
     }
 
+    pub(crate) fn set_texture_usage(&mut self, usage: Vec<TextureUsage>) {
+        self.texture_usage = usage;
+    }
+
+    /// Fills in the portion of the `report` describing the resources in this theme set
+    pub(crate) fn add_to_report(&self, report: &mut ResourceReport) {
+        report.textures = self.texture_usage.clone();
+        report.fonts = self.font_handles.iter().map(|(id, summary)| FontUsage {
+            id: id.to_string(),
+            atlas_size: self.font(summary.handle).texture_size(),
+        }).collect();
+        report.theme_count = self.themes.len();
+        report.image_count = self.images.len();
+
+        report.approximate_heap_bytes += self.fonts.values().map(|font| font.heap_bytes()).sum::<usize>() +
+            self.images.capacity() * std::mem::size_of::<Image>() +
+            self.themes.capacity() * std::mem::size_of::<WidgetTheme>() +
+            self.theme_handles.keys().chain(self.image_handles.keys()).map(|id| id.capacity()).sum::<usize>();
+    }
+
+    pub(crate) fn template(&self, id: &str) -> Option<Rc<[TemplateNode]>> {
+        self.templates.get(id).cloned()
+    }
+
     pub(crate) fn default_theme(&self) -> &WidgetTheme {
         // This is always manually created
         &self.themes[0]
@@ -307,7 +414,17 @@ impl ThemeSet {
     }
 
     pub fn font(&self, handle: FontHandle) -> &Font {
-        &self.fonts[handle.id()]
+        &self.fonts[&handle]
+    }
+
+    /// The font used in place of a missing font, if placeholders are enabled
+    pub(crate) fn fallback_font(&self) -> Option<FontSummary> {
+        self.fallbacks.font
+    }
+
+    /// The checkered image drawn in place of a missing image, if placeholders are enabled
+    pub(crate) fn placeholder_image(&self) -> Option<ImageHandle> {
+        self.fallbacks.image
     }
 
     pub fn find_font(&self, id: Option<&str>) -> Option<FontSummary> {
@@ -321,6 +438,11 @@ impl ThemeSet {
         &self.images[handle.id]
     }
 
+    /// A solid white image, used to draw backplates behind text in high contrast mode and widget triangles
+    pub(crate) fn backplate(&self) -> &Image {
+        &self.images[self.backplate.id]
+    }
+
     pub fn find_image(&self, id: Option<&str>) -> Option<ImageHandle> {
         match id {
             None => None,
@@ -331,6 +453,200 @@ impl ThemeSet {
     pub fn handle(&self, id: &str) -> Option<WidgetThemeHandle> {
         self.theme_handles.get(id).cloned()
     }
+
+    pub(crate) fn widget_theme_ids(&self) -> impl Iterator<Item = &str> {
+        self.theme_handles.keys().map(|id| id.as_str())
+    }
+
+    pub(crate) fn image_ids(&self) -> impl Iterator<Item = &str> {
+        self.image_handles.keys().map(|id| id.as_str())
+    }
+
+    pub(crate) fn font_ids(&self) -> impl Iterator<Item = &str> {
+        self.font_handles.keys().map(|id| id.as_str())
+    }
+
+    fn image_id(&self, handle: Option<ImageHandle>) -> Option<String> {
+        let handle = handle?;
+        self.image_handles.iter().find(|(_, h)| h.id == handle.id).map(|(id, _)| id.to_string())
+    }
+
+    fn font_id(&self, font: Option<FontSummary>) -> Option<String> {
+        let font = font?;
+        self.font_handles.iter().find(|(_, f)| f.handle == font.handle).map(|(id, _)| id.to_string())
+    }
+
+    /// Returns the resolved attributes of the widget theme with the specified full `id`
+    pub(crate) fn widget_theme_info(&self, id: &str) -> Option<WidgetThemeInfo> {
+        let theme = self.theme(id)?;
+
+        let cursor = match theme.cursor {
+            None => None,
+            Some(WidgetCursor::System(cursor)) => Some(format!("{:?}", cursor)),
+            Some(WidgetCursor::Image(image, _)) => self.image_id(Some(image)),
+        };
+
+        Some(WidgetThemeInfo {
+            full_id: theme.full_id.clone(),
+            id: theme.id.clone(),
+            from: theme.from.clone(),
+            parent: theme.parent_handle.map(|handle| self.themes[handle.id as usize].full_id.clone()),
+            children: theme.children.iter().map(|handle| self.themes[handle.id as usize].full_id.clone()).collect(),
+            text: theme.text.clone(),
+            text_color: theme.text_color,
+            font: self.font_id(theme.font),
+            image_color: theme.image_color,
+            background: self.image_id(theme.background),
+            foreground: self.image_id(theme.foreground),
+            tooltip: theme.tooltip.clone(),
+            aria_label: theme.aria_label.clone(),
+            description: theme.description.clone(),
+            cursor,
+            shader: theme.shader.clone(),
+            sound_hover: theme.sound_hover.clone(),
+            sound_click: theme.sound_click.clone(),
+            wants_mouse: theme.wants_mouse,
+            wants_scroll: theme.wants_scroll,
+            hit_test: theme.hit_test,
+            text_align: theme.text_align,
+            text_wrap: theme.text_wrap,
+            text_soft_hyphens: theme.text_soft_hyphens,
+            pos: theme.pos,
+            screen_pos: theme.screen_pos,
+            width: theme.width,
+            height: theme.height,
+            width_from: theme.width_from,
+            height_from: theme.height_from,
+            border: theme.border,
+            align: theme.align,
+            child_align: theme.child_align,
+            layout: theme.layout,
+            layout_spacing: theme.layout_spacing,
+            image_tints: theme.image_tints.clone(),
+            custom: theme.custom.clone(),
+        })
+    }
+}
+
+/**
+The resolved attributes of a widget theme, after `from` references have been merged, as returned by
+[`Context::widget_theme`](struct.Context.html#method.widget_theme).  This allows theme editors and debugging
+tools to present the effective theme rather than the raw theme definition.  Attributes which are not
+specified by the theme are `None`, in which case the widget uses its default value.  Images and fonts are
+referred to by their ids.
+**/
+#[derive(Clone, Debug)]
+pub struct WidgetThemeInfo {
+    /// The full id of this theme, including the ids of its parents, separated by `/`
+    pub full_id: String,
+
+    /// The id of this theme within its parent
+    pub id: String,
+
+    /// The theme that this theme was derived from, if any
+    pub from: Option<String>,
+
+    /// The full id of the parent theme, if any
+    pub parent: Option<String>,
+
+    /// The full ids of the child themes
+    pub children: Vec<String>,
+
+    /// The default text
+    pub text: Option<String>,
+
+    /// The text color
+    pub text_color: Option<Color>,
+
+    /// The font id
+    pub font: Option<String>,
+
+    /// The color multiplied with the background and foreground images
+    pub image_color: Option<Color>,
+
+    /// The background image id
+    pub background: Option<String>,
+
+    /// The foreground image id
+    pub foreground: Option<String>,
+
+    /// The tooltip text
+    pub tooltip: Option<String>,
+
+    /// The label read by screen readers
+    pub aria_label: Option<String>,
+
+    /// The longer description read by screen readers
+    pub description: Option<String>,
+
+    /// The mouse cursor shown on hover, either a system cursor name or an image id
+    pub cursor: Option<String>,
+
+    /// The name of the custom shader used to draw the images
+    pub shader: Option<String>,
+
+    /// The sound played when the widget is hovered
+    pub sound_hover: Option<String>,
+
+    /// The sound played when the widget is clicked
+    pub sound_click: Option<String>,
+
+    /// Whether the widget receives mouse input
+    pub wants_mouse: Option<bool>,
+
+    /// Whether the widget receives mouse scroll input
+    pub wants_scroll: Option<bool>,
+
+    /// Whether the widget and its children take part in mouse hit testing
+    pub hit_test: Option<bool>,
+
+    /// The text alignment
+    pub text_align: Option<Align>,
+
+    /// The text wrapping mode
+    pub text_wrap: Option<TextWrap>,
+
+    /// Whether soft hyphens in the text mark places where a word may be broken across lines
+    pub text_soft_hyphens: Option<bool>,
+
+    /// The position relative to the parent
+    pub pos: Option<Point>,
+
+    /// The absolute position on the screen
+    pub screen_pos: Option<Point>,
+
+    /// The width
+    pub width: Option<f32>,
+
+    /// The height
+    pub height: Option<f32>,
+
+    /// How the width is computed
+    pub width_from: Option<WidthRelative>,
+
+    /// How the height is computed
+    pub height_from: Option<HeightRelative>,
+
+    /// The border
+    pub border: Option<Border>,
+
+    /// The alignment within the parent
+    pub align: Option<Align>,
+
+    /// The default alignment of children
+    pub child_align: Option<Align>,
+
+    /// The layout of children
+    pub layout: Option<Layout>,
+
+    /// The spacing between children
+    pub layout_spacing: Option<Point>,
+
+    /// The colors multiplied with the image color in particular animation states
+    pub image_tints: Vec<(AnimState, Color)>,
+
+    /// The custom data
+    pub custom: IndexMap<String, CustomData>,
 }
 
 fn resolve_from(
@@ -360,6 +676,13 @@ pub struct WidgetThemeHandle {
     id: u64,
 }
 
+/// The mouse cursor shown while a widget is hovered
+#[derive(Copy, Clone)]
+pub(crate) enum WidgetCursor {
+    System(SystemCursor),
+    Image(ImageHandle, Align),
+}
+
 #[derive(Clone)]
 pub struct WidgetTheme {
     from: Option<String>,
@@ -376,12 +699,21 @@ pub struct WidgetTheme {
     pub background: Option<ImageHandle>,
     pub foreground: Option<ImageHandle>,
     pub tooltip: Option<String>,
+    pub aria_label: Option<String>,
+    pub description: Option<String>,
+    pub cursor: Option<WidgetCursor>,
+    pub shader: Option<String>,
+    pub sound_hover: Option<String>,
+    pub sound_click: Option<String>,
 
     // all fields are options instead of using default so
     // we can detect when to override them
     pub wants_mouse: Option<bool>,
     pub wants_scroll: Option<bool>,
+    pub hit_test: Option<bool>,
     pub text_align: Option<Align>,
+    pub text_wrap: Option<TextWrap>,
+    pub text_soft_hyphens: Option<bool>,
     pub pos: Option<Point>,
     pub screen_pos: Option<Point>,
     pub width: Option<f32>,
@@ -394,6 +726,7 @@ pub struct WidgetTheme {
     pub layout: Option<Layout>,
     pub layout_spacing: Option<Point>,
     pub children: Vec<WidgetThemeHandle>,
+    pub image_tints: Vec<(AnimState, Color)>,
 
     pub custom: IndexMap<String, CustomData>,
 }
@@ -408,6 +741,12 @@ impl WidgetTheme {
             handle,
             text: None,
             text_color: None,
+            aria_label: None,
+            description: None,
+            cursor: None,
+            shader: None,
+            sound_hover: None,
+            sound_click: None,
             font: None,
             image_color: None,
             background: None,
@@ -415,7 +754,10 @@ impl WidgetTheme {
             tooltip: None,
             wants_mouse: None,
             wants_scroll: None,
+            hit_test: None,
             text_align: None,
+            text_wrap: None,
+            text_soft_hyphens: None,
             pos: None,
             screen_pos: None,
             width: None,
@@ -428,6 +770,7 @@ impl WidgetTheme {
             layout: None,
             layout_spacing: None,
             children: Vec::new(),
+            image_tints: Vec::new(),
             custom: IndexMap::new(),
         }
     }
@@ -443,6 +786,7 @@ impl WidgetTheme {
         def: &WidgetThemeDefinition,
         images: &IndexMap<String, ImageHandle>,
         fonts: &IndexMap<String, FontSummary>,
+        fallbacks: &Fallbacks,
     ) -> Result<WidgetThemeHandle, Error> {
         if id.contains('/') {
             return Err(
@@ -458,26 +802,36 @@ impl WidgetTheme {
         };
 
         let background = if let Some(bg) = def.background.as_ref() {
-            Some(*images.get(bg).ok_or_else(||
-                Error::Theme(format!("Unable to locate image '{}' as background for widget '{}'", bg, parent_id))
+            Some(or_fallback(images.get(bg).copied(), fallbacks.image, ||
+                format!("Unable to locate image '{}' as background for widget '{}'", bg, parent_id)
             )?)
         } else {
             None
         };
 
         let foreground = if let Some(fg) = def.foreground.as_ref() {
-            Some(*images.get(fg).ok_or_else(||
-                Error::Theme(format!("Unable to locate image '{}' as foreground for widget '{}'", fg, parent_id))
+            Some(or_fallback(images.get(fg).copied(), fallbacks.image, ||
+                format!("Unable to locate image '{}' as foreground for widget '{}'", fg, parent_id)
             )?)
         } else {
             None
         };
 
+        let cursor = match def.cursor.as_ref() {
+            None => None,
+            Some(CursorDefinition::System(cursor)) => Some(WidgetCursor::System(*cursor)),
+            Some(CursorDefinition::Image { image, align }) => {
+                let handle = or_fallback(images.get(image).copied(), fallbacks.image, ||
+                    format!("Unable to locate image '{}' as cursor for widget '{}'", image, parent_id)
+                )?;
+                Some(WidgetCursor::Image(handle, *align))
+            }
+        };
+
         let font = if let Some(font) = def.font.as_ref() {
-            let font_handle = fonts.get(font).ok_or_else(||
-                Error::Theme(format!("Unable to locate font '{}' for widget '{}'", font, parent_id))
-            )?;
-            Some(*font_handle)
+            Some(or_fallback(fonts.get(font).copied(), fallbacks.font, ||
+                format!("Unable to locate font '{}' for widget '{}'", font, parent_id)
+            )?)
         } else {
             None
         };
@@ -511,9 +865,18 @@ impl WidgetTheme {
             background,
             foreground,
             tooltip: def.tooltip.clone(),
+            aria_label: def.aria_label.clone(),
+            description: def.description.clone(),
+            cursor,
+            shader: def.shader.clone(),
+            sound_hover: def.sound_hover.clone(),
+            sound_click: def.sound_click.clone(),
             wants_mouse: def.wants_mouse,
             wants_scroll: def.wants_scroll,
+            hit_test: def.hit_test,
             text_align: def.text_align,
+            text_wrap: def.text_wrap,
+            text_soft_hyphens: def.text_soft_hyphens,
             pos: def.pos,
             screen_pos: def.screen_pos,
             width,
@@ -526,6 +889,7 @@ impl WidgetTheme {
             layout: def.layout,
             layout_spacing: def.layout_spacing,
             children: Vec::new(),
+            image_tints: def.image_tints.iter().map(|(state, color)| (*state, *color)).collect(),
             custom: def.custom.clone(),
         };
 
@@ -542,7 +906,8 @@ impl WidgetTheme {
                 themes,
                 child_def,
                 images,
-                fonts
+                fonts,
+                fallbacks,
             )?;
             children.push(child);
         }
@@ -573,11 +938,14 @@ fn merge_from(
 
     if to.wants_mouse.is_none() { to.wants_mouse = from.wants_mouse; }
     if to.wants_scroll.is_none() { to.wants_scroll = from.wants_scroll; }
+    if to.hit_test.is_none() { to.hit_test = from.hit_test; }
     if to.font.is_none() { to.font = from.font; }
     if to.image_color.is_none() { to.image_color = from.image_color; }
     if to.background.is_none() { to.background = from.background; }
     if to.foreground.is_none() { to.foreground = from.foreground; }
     if to.text_align.is_none() { to.text_align = from.text_align; }
+    if to.text_wrap.is_none() { to.text_wrap = from.text_wrap; }
+    if to.text_soft_hyphens.is_none() { to.text_soft_hyphens = from.text_soft_hyphens; }
     if to.pos.is_none() { to.pos = from.pos; }
     if to.screen_pos.is_none() { to.screen_pos = from.screen_pos; }
     if to.width.is_none() { to.width = from.width; }
@@ -592,6 +960,13 @@ fn merge_from(
     if to.text.is_none() { to.text = from.text.clone(); }
     if to.text_color.is_none() { to.text_color = from.text_color; }
     if to.tooltip.is_none() { to.tooltip = from.tooltip.clone(); }
+    if to.aria_label.is_none() { to.aria_label = from.aria_label.clone(); }
+    if to.description.is_none() { to.description = from.description.clone(); }
+    if to.cursor.is_none() { to.cursor = from.cursor; }
+    if to.shader.is_none() { to.shader = from.shader.clone(); }
+    if to.image_tints.is_empty() { to.image_tints = from.image_tints.clone(); }
+    if to.sound_hover.is_none() { to.sound_hover = from.sound_hover.clone(); }
+    if to.sound_click.is_none() { to.sound_click = from.sound_click.clone(); }
 
     for (id, value) in from.custom.iter() {
         match to.custom.entry(id.to_string()) {
@@ -689,4 +1064,21 @@ fn add_children_recursive(
         }
         add_children_recursive(from_child, handle, themes, handle_index, theme_handles);
     }
-}
\ No newline at end of file
+}
+
+// sorts the ranges and combines any that overlap, so no character is rendered twice
+fn merge_ranges(mut ranges: Vec<CharacterRange>) -> Vec<CharacterRange> {
+    ranges.retain(|range| range.lower <= range.upper);
+    ranges.sort_by_key(|range| range.lower);
+
+    let mut out: Vec<CharacterRange> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match out.last_mut() {
+            Some(last) if range.lower <= last.upper.saturating_add(1) => {
+                last.upper = last.upper.max(range.upper);
+            },
+            _ => out.push(range),
+        }
+    }
+    out
+}
@@ -16,6 +16,9 @@ pub struct ThemeDefinition {
 
     #[serde(default)]
     pub widgets: IndexMap<String, WidgetThemeDefinition>,
+
+    #[serde(default)]
+    pub templates: IndexMap<String, Vec<TemplateNode>>,
 }
 
 impl ThemeDefinition {
@@ -50,9 +53,45 @@ impl ThemeDefinition {
                 }, Vacant(entry) => { entry.insert(widget); }
             }
         }
+
+        for (id, template) in other.templates {
+            match self.templates.entry(id) {
+                Occupied(mut entry) => {
+                    log::warn!("Overwriting template id '{}'", entry.key());
+                    entry.insert(template);
+                }, Vacant(entry) => { entry.insert(template); }
+            }
+        }
     }
 }
 
+/// A widget in a UI template.  See [`build_from_template`](struct.Frame.html#method.build_from_template).
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct TemplateNode {
+    #[serde(default)]
+    pub kind: TemplateKind,
+
+    pub theme: String,
+    pub id: Option<String>,
+    pub text: Option<String>,
+    pub event: Option<String>,
+
+    #[serde(default)]
+    pub children: Vec<TemplateNode>,
+}
+
+/// The kind of widget created by a `TemplateNode`.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum TemplateKind {
+    #[default]
+    Widget,
+    Button,
+    Label,
+    Scrollpane,
+    Window,
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct WidgetThemeDefinition {
@@ -63,6 +102,12 @@ pub struct WidgetThemeDefinition {
     pub background: Option<String>,
     pub foreground: Option<String>,
     pub tooltip: Option<String>,
+    pub aria_label: Option<String>,
+    pub description: Option<String>,
+    pub cursor: Option<CursorDefinition>,
+    pub shader: Option<String>,
+    pub sound_hover: Option<String>,
+    pub sound_click: Option<String>,
 
     // all fields are options instead of using default so
     // we can detect when to override them
@@ -70,7 +115,10 @@ pub struct WidgetThemeDefinition {
     pub text_color: Option<Color>,
     pub wants_mouse: Option<bool>,
     pub wants_scroll: Option<bool>,
+    pub hit_test: Option<bool>,
     pub text_align: Option<Align>,
+    pub text_wrap: Option<TextWrap>,
+    pub text_soft_hyphens: Option<bool>,
     pub pos: Option<Point>,
     pub screen_pos: Option<Point>,
     pub size: Option<Point>,
@@ -85,6 +133,9 @@ pub struct WidgetThemeDefinition {
     pub layout: Option<Layout>,
     pub layout_spacing: Option<Point>,
 
+    #[serde(default)]
+    pub image_tints: IndexMap<AnimState, Color>,
+
     #[serde(default)]
     pub custom: IndexMap<String, CustomData>,
 
@@ -92,22 +143,51 @@ pub struct WidgetThemeDefinition {
     pub children: IndexMap<String, WidgetThemeDefinition>,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+/// A custom value in a widget theme, as read by [`WidgetBuilder::custom_int`](struct.WidgetBuilder.html#method.custom_int)
+/// and related methods.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 #[serde(deny_unknown_fields, untagged)]
 pub enum CustomData {
+    /// An integer value
     Int(i32),
+
+    /// A floating point value
     Float(f32),
+
+    /// A string value
     String(String),
 }
 
+impl From<i32> for CustomData {
+    fn from(value: i32) -> Self { CustomData::Int(value) }
+}
+
+impl From<f32> for CustomData {
+    fn from(value: f32) -> Self { CustomData::Float(value) }
+}
+
+impl From<String> for CustomData {
+    fn from(value: String) -> Self { CustomData::String(value) }
+}
+
+impl From<&str> for CustomData {
+    fn from(value: &str) -> Self { CustomData::String(value.to_string()) }
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct ImageSet {
     pub source: Option<String>,
 
+    // image files packed into a single texture when the theme is built, in place of the source
+    #[serde(default)]
+    pub files: Vec<String>,
+    pub directory: Option<String>,
+
     #[serde(default="f32_one")]
     pub scale: f32,
 
+    #[serde(default)]
     pub images: IndexMap<String, ImageDefinition>,
 }
 
@@ -193,6 +273,9 @@ pub enum ImageDefinitionKind {
     },
     Animated {
         states: IndexMap<AnimState, String>,
+
+        #[serde(default)]
+        tints: IndexMap<AnimState, Color>,
     }
 }
 
@@ -430,6 +513,88 @@ pub enum AnimStateKey {
     Active,
 }
 
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields, untagged)]
+pub enum CursorDefinition {
+    System(SystemCursor),
+    Image {
+        image: String,
+
+        #[serde(default)]
+        align: Align,
+    },
+}
+
+/// A platform mouse cursor, requested by a widget's `cursor` theme attribute or
+/// [`Frame.set_system_cursor`](struct.Frame.html#method.set_system_cursor).  The cursor
+/// for the current frame is obtained via [`Context.system_cursor`](struct.Context.html#method.system_cursor),
+/// and should be applied to the window by the application, or by [`WinitIo`](struct.WinitIo.html#method.update_cursor).
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[serde(deny_unknown_fields)]
+pub enum SystemCursor {
+    /// The default platform cursor, usually an arrow
+    #[default]
+    Default,
+
+    /// A pointing hand, typically used for links
+    Pointer,
+
+    /// A text selection I-beam
+    Text,
+
+    /// A crosshair
+    Crosshair,
+
+    /// Indicates something that can be moved
+    Move,
+
+    /// Indicates the program is busy
+    Wait,
+
+    /// Indicates the action will not be carried out
+    NotAllowed,
+
+    /// Indicates something that can be grabbed
+    Grab,
+
+    /// Indicates something is being grabbed
+    Grabbing,
+
+    /// Horizontal (left-right) resize
+    ResizeHorizontal,
+
+    /// Vertical (up-down) resize
+    ResizeVertical,
+
+    /// Diagonal resize, between top left and bottom right
+    ResizeDiagonal,
+
+    /// Anti-diagonal resize, between top right and bottom left
+    ResizeAntiDiagonal,
+}
+
+/// How text that is too long to fit on a single line within a widget is broken
+/// onto multiple lines.  Explicit newlines always begin a new line.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[serde(deny_unknown_fields)]
+pub enum TextWrap {
+    /// Never wrap text.  Lines that are too long will extend past the widget's area.
+    None,
+
+    /// Wrap text at whitespace.  Words that are too long to fit on a line by themselves
+    /// will extend past the widget's area.
+    Word,
+
+    /// Wrap text at any character.  Useful for content such as long identifiers
+    /// that do not contain whitespace.
+    Character,
+
+    /// Wrap text at whitespace, but break words at any character if they are too long
+    /// to fit on a line by themselves.  This is the default.
+    #[default]
+    WordThenCharacter,
+}
+
 /// The Layout direction for a widget's children.
 ///
 /// This only has effect if the child widget does not manually specify an alignment.
@@ -452,6 +617,11 @@ pub enum Layout {
     /// Optionally specify a maximum width for each row, independant of the
     /// normal widget size
     Grid(Option<i16>),
+
+    /// Layout children in the specified number of equal width columns, placing each child at the
+    /// top of the shortest column so far.  This keeps the column heights even when the children
+    /// have varying heights, such as cards in a gallery.  Children should be no wider than a column.
+    Masonry(u16),
 }
 
 /// Widget or text horizontal and vertical alignment.
@@ -521,16 +691,145 @@ pub struct FontDefinition {
     pub size: f32,
 
     #[serde(default)]
-    pub characters: Vec<CharacterRange>,
+    pub characters: Vec<CharacterRangeDefinition>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum CharacterRangeDefinition {
+    Preset(CharacterRangePreset),
+    Range(CharacterRange),
+}
+
+impl CharacterRangeDefinition {
+    pub fn ranges(&self) -> &[CharacterRange] {
+        match self {
+            CharacterRangeDefinition::Preset(preset) => preset.ranges(),
+            CharacterRangeDefinition::Range(range) => std::slice::from_ref(range),
+        }
+    }
+}
+
+/// An inclusive range of unicode codepoints to be rendered for a font.  See
+/// [`ContextBuilder.add_font_characters`](struct.ContextBuilder.html#method.add_font_characters).
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
 #[serde(deny_unknown_fields)]
 pub struct CharacterRange {
+    /// The first codepoint in the range
     pub lower: u32,
+
+    /// The last codepoint in the range, inclusive
     pub upper: u32,
 }
 
+impl CharacterRange {
+    /// Creates a new range from `lower` to `upper`, inclusive
+    pub const fn new(lower: u32, upper: u32) -> CharacterRange {
+        CharacterRange { lower, upper }
+    }
+}
+
+/// A named set of [`CharacterRanges`](struct.CharacterRange.html) covering the characters
+/// commonly needed for a particular script.  Presets may be specified by name in the
+/// `characters` list of a font in the theme, or added to all fonts with
+/// [`ContextBuilder.add_font_preset`](struct.ContextBuilder.html#method.add_font_preset).
+/// Note that large presets such as `CjkCommon` will result in very large font textures.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CharacterRangePreset {
+    /// The characters used by default when no ranges are specified; printable ASCII and Latin-1
+    Default,
+
+    /// Printable ASCII characters, U+0020 to U+007E
+    BasicLatin,
+
+    /// Printable Latin-1 Supplement characters, U+00A1 to U+00FF
+    Latin1,
+
+    /// Latin Extended-A, Extended-B, and Extended Additional, used by most European
+    /// and Vietnamese languages
+    LatinExtended,
+
+    /// General punctuation such as dashes, quotation marks, and the ellipsis
+    Punctuation,
+
+    /// Greek and Coptic
+    Greek,
+
+    /// Cyrillic
+    Cyrillic,
+
+    /// CJK symbols and punctuation, Hiragana, Katakana, the CJK Unified Ideographs,
+    /// and halfwidth and fullwidth forms
+    CjkCommon,
+
+    /// Hangul Compatibility Jamo and Hangul Syllables
+    Hangul,
+
+    /// Common emoji, pictographs and dingbats
+    Emoji,
+}
+
+impl CharacterRangePreset {
+    /// Returns the [`CharacterRanges`](struct.CharacterRange.html) making up this preset
+    pub fn ranges(self) -> &'static [CharacterRange] {
+        use CharacterRangePreset::*;
+        match self {
+            Default => &[
+                CharacterRange { lower: 0x20, upper: 0x7e },
+                CharacterRange { lower: 0xa1, upper: 0xff },
+            ],
+            BasicLatin => &[CharacterRange { lower: 0x20, upper: 0x7e }],
+            Latin1 => &[CharacterRange { lower: 0xa1, upper: 0xff }],
+            LatinExtended => &[
+                CharacterRange { lower: 0x100, upper: 0x24f },
+                CharacterRange { lower: 0x1e00, upper: 0x1eff },
+            ],
+            Punctuation => &[CharacterRange { lower: 0x2000, upper: 0x206f }],
+            Greek => &[CharacterRange { lower: 0x370, upper: 0x3ff }],
+            Cyrillic => &[CharacterRange { lower: 0x400, upper: 0x4ff }],
+            CjkCommon => &[
+                CharacterRange { lower: 0x3000, upper: 0x30ff },
+                CharacterRange { lower: 0x4e00, upper: 0x9fff },
+                CharacterRange { lower: 0xff00, upper: 0xffef },
+            ],
+            Hangul => &[
+                CharacterRange { lower: 0x3130, upper: 0x318f },
+                CharacterRange { lower: 0xac00, upper: 0xd7a3 },
+            ],
+            Emoji => &[
+                CharacterRange { lower: 0x2600, upper: 0x27bf },
+                CharacterRange { lower: 0x1f300, upper: 0x1f64f },
+                CharacterRange { lower: 0x1f680, upper: 0x1f6ff },
+                CharacterRange { lower: 0x1f900, upper: 0x1f9ff },
+            ],
+        }
+    }
+
+    /// Returns the presets needed to display text in the language of the specified `locale`,
+    /// in addition to the [`Default`](#variant.Default) preset.  The locale should be
+    /// a string such as `ru_RU.UTF-8` or `zh-CN`; only the language code is considered.
+    /// Returns an empty list if no additional presets are needed or the language is unknown.
+    pub fn for_locale(locale: &str) -> Vec<CharacterRangePreset> {
+        use CharacterRangePreset::*;
+
+        let language = locale
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        match language.as_str() {
+            "be" | "bg" | "kk" | "ky" | "mk" | "mn" | "ru" | "sr" | "tg" | "uk" => vec![Cyrillic, Punctuation],
+            "el" => vec![Greek, Punctuation],
+            "ja" | "zh" => vec![CjkCommon, Punctuation],
+            "ko" => vec![Hangul, Punctuation],
+            "az" | "cs" | "cy" | "eo" | "et" | "hr" | "hu" | "lt" | "lv" | "mt" |
+            "pl" | "ro" | "sk" | "sl" | "tr" | "vi" => vec![LatinExtended, Punctuation],
+            _ => Vec::new(),
+        }
+    }
+}
+
 /// What to compute the width of a widget relative to.
 #[derive(Debug, Serialize, Deserialize, Copy, Clone, PartialEq, Eq, Hash, Default)]
 #[serde(deny_unknown_fields)]
@@ -569,6 +868,10 @@ pub enum HeightRelative {
 
     /// Height is equal to the line height of the widget's font plus the `y` field of the widget's `size`.
     FontLine,
+
+    /// Height is equal to the height of all lines of the widget's text, wrapped to the widget's width, plus the
+    /// `y` field of the widget's `size`.
+    Text,
 }
 
 /// A Color with red, green, blue, and alpha components, with each component stored as a `u8`.
@@ -0,0 +1,251 @@
+use indexmap::IndexMap;
+use serde_yaml::{Mapping, Number, Value};
+
+use crate::Error;
+
+/// Widget attributes which may contain expressions
+const WIDGET_NUMERIC_KEYS: [&str; 7] = ["pos", "screen_pos", "size", "width", "height", "layout_spacing", "border"];
+
+/// Font attributes which may contain expressions
+const FONT_NUMERIC_KEYS: [&str; 1] = ["size"];
+
+/**
+Evaluates the expressions in all of the specified theme `docs`, replacing them with their numeric result.
+Named constants are read from the `variables` section of each document, which is then removed.  Variables
+may be referenced by any document, and may themselves be expressions referencing previously defined variables.
+
+Expressions are strings in numeric widget and font attributes, consisting of numbers, variables, the `+`, `-`,
+`*`, and `/` operators, and parentheses.  Within a widget, `parent` refers to the same attribute of the parent widget.
+**/
+pub(crate) fn evaluate_theme(docs: &mut [Value]) -> Result<(), Error> {
+    let mut variables = IndexMap::new();
+    for doc in docs.iter_mut() {
+        let defs = match doc.as_mapping_mut().and_then(|doc| doc.remove(&key("variables"))) {
+            None => continue,
+            Some(Value::Mapping(defs)) => defs,
+            Some(_) => return Err(Error::Theme("The 'variables' section must be a mapping".to_string())),
+        };
+
+        for (id, value) in defs {
+            let id = match id {
+                Value::String(id) => id,
+                _ => return Err(Error::Theme("Variable names must be strings".to_string())),
+            };
+
+            let value = match value {
+                Value::Number(number) => number.as_f64().unwrap_or_default() as f32,
+                Value::String(expr) => Expression::new(&expr, &variables, None).evaluate().map_err(|e|
+                    Error::Theme(format!("Invalid expression for variable '{}': {}", id, e))
+                )?,
+                _ => return Err(Error::Theme(format!("Variable '{}' must be a number or expression", id))),
+            };
+            variables.insert(id, value);
+        }
+    }
+
+    for doc in docs.iter_mut() {
+        let doc = match doc.as_mapping_mut() {
+            None => continue,
+            Some(doc) => doc,
+        };
+
+        if let Some(Value::Mapping(fonts)) = doc.get_mut(&key("fonts")) {
+            for (id, font) in fonts.iter_mut() {
+                if let Value::Mapping(font) = font {
+                    evaluate_attributes(&display(id), font, None, &FONT_NUMERIC_KEYS, &variables)?;
+                }
+            }
+        }
+
+        if let Some(Value::Mapping(widgets)) = doc.get_mut(&key("widgets")) {
+            for (id, widget) in widgets.iter_mut() {
+                evaluate_widget(&display(id), widget, None, &variables)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn evaluate_widget(
+    id: &str,
+    widget: &mut Value,
+    parent: Option<&Mapping>,
+    variables: &IndexMap<String, f32>,
+) -> Result<(), Error> {
+    let widget = match widget {
+        Value::Mapping(widget) => widget,
+        _ => return Ok(()),
+    };
+
+    evaluate_attributes(id, widget, parent, &WIDGET_NUMERIC_KEYS, variables)?;
+
+    // only the evaluated attributes are needed by the children
+    let mut attributes = Mapping::new();
+    for attr in WIDGET_NUMERIC_KEYS.iter() {
+        if let Some(value) = widget.get(&key(attr)) {
+            attributes.insert(key(attr), value.clone());
+        }
+    }
+
+    if let Some(Value::Mapping(children)) = widget.get_mut(&key("children")) {
+        for (child_id, child) in children.iter_mut() {
+            evaluate_widget(&format!("{}/{}", id, display(child_id)), child, Some(&attributes), variables)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn evaluate_attributes(
+    id: &str,
+    def: &mut Mapping,
+    parent: Option<&Mapping>,
+    attrs: &[&str],
+    variables: &IndexMap<String, f32>,
+) -> Result<(), Error> {
+    for attr in attrs {
+        if let Some(value) = def.get_mut(&key(attr)) {
+            let parent = parent.and_then(|parent| parent.get(&key(attr)));
+            evaluate_value(value, parent, variables).map_err(|e|
+                Error::Theme(format!("Invalid expression for '{}' in '{}': {}", attr, id, e))
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Evaluates any expressions in `value`, recursing into sequences such as sizes and mappings such as borders
+fn evaluate_value(value: &mut Value, parent: Option<&Value>, variables: &IndexMap<String, f32>) -> Result<(), String> {
+    match value {
+        Value::String(expr) => {
+            let parent = match parent {
+                Some(Value::Number(number)) => number.as_f64().map(|value| value as f32),
+                _ => None,
+            };
+            let result = Expression::new(expr, variables, parent).evaluate()?;
+            *value = Value::Number(Number::from(result as f64));
+        },
+        Value::Sequence(values) => {
+            for (index, value) in values.iter_mut().enumerate() {
+                let parent = match parent {
+                    Some(Value::Sequence(parent)) => parent.get(index),
+                    _ => None,
+                };
+                evaluate_value(value, parent, variables)?;
+            }
+        },
+        Value::Mapping(values) => {
+            for (id, value) in values.iter_mut() {
+                let parent = match parent {
+                    Some(Value::Mapping(parent)) => parent.get(id),
+                    _ => None,
+                };
+                evaluate_value(value, parent, variables)?;
+            }
+        },
+        _ => (),
+    }
+
+    Ok(())
+}
+
+fn key(id: &str) -> Value {
+    Value::String(id.to_string())
+}
+
+fn display(id: &Value) -> String {
+    match id {
+        Value::String(id) => id.clone(),
+        _ => format!("{:?}", id),
+    }
+}
+
+/// A simple recursive descent parser and evaluator for arithmetic expressions
+struct Expression<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    variables: &'a IndexMap<String, f32>,
+    parent: Option<f32>,
+}
+
+impl<'a> Expression<'a> {
+    fn new(expr: &'a str, variables: &'a IndexMap<String, f32>, parent: Option<f32>) -> Expression<'a> {
+        Expression { chars: expr.chars().peekable(), variables, parent }
+    }
+
+    fn evaluate(mut self) -> Result<f32, String> {
+        let value = self.sum()?;
+        match self.next_token() {
+            None => Ok(value),
+            Some(c) => Err(format!("Unexpected '{}'", c)),
+        }
+    }
+
+    fn next_token(&mut self) -> Option<char> {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+        self.chars.peek().copied()
+    }
+
+    fn sum(&mut self) -> Result<f32, String> {
+        let mut value = self.product()?;
+        loop {
+            match self.next_token() {
+                Some('+') => { self.chars.next(); value += self.product()?; },
+                Some('-') => { self.chars.next(); value -= self.product()?; },
+                _ => return Ok(value),
+            }
+        }
+    }
+
+    fn product(&mut self) -> Result<f32, String> {
+        let mut value = self.unary()?;
+        loop {
+            match self.next_token() {
+                Some('*') => { self.chars.next(); value *= self.unary()?; },
+                Some('/') => {
+                    self.chars.next();
+                    let divisor = self.unary()?;
+                    if divisor == 0.0 { return Err("Division by zero".to_string()); }
+                    value /= divisor;
+                },
+                _ => return Ok(value),
+            }
+        }
+    }
+
+    fn unary(&mut self) -> Result<f32, String> {
+        match self.next_token() {
+            Some('-') => { self.chars.next(); Ok(-self.unary()?) },
+            Some('(') => {
+                self.chars.next();
+                let value = self.sum()?;
+                match self.next_token() {
+                    Some(')') => { self.chars.next(); Ok(value) },
+                    _ => Err("Expected ')'".to_string()),
+                }
+            },
+            Some(c) if c.is_ascii_digit() || c == '.' => {
+                let mut number = String::new();
+                while let Some(c) = self.chars.next_if(|c| c.is_ascii_digit() || *c == '.') {
+                    number.push(c);
+                }
+                number.parse().map_err(|_| format!("Invalid number '{}'", number))
+            },
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                let mut name = String::new();
+                while let Some(c) = self.chars.next_if(|c| c.is_alphanumeric() || *c == '_') {
+                    name.push(c);
+                }
+
+                if name == "parent" {
+                    self.parent.ok_or_else(|| "The parent widget does not define this value".to_string())
+                } else {
+                    self.variables.get(&name).copied().ok_or_else(|| format!("Unknown variable '{}'", name))
+                }
+            },
+            Some(c) => Err(format!("Unexpected '{}'", c)),
+            None => Err("Unexpected end of expression".to_string()),
+        }
+    }
+}
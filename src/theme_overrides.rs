@@ -0,0 +1,117 @@
+use serde_yaml::{Mapping, Value};
+
+use crate::{Error, Point};
+
+/// The properties of the display and platform that theme overrides are matched against
+pub(crate) struct ThemeEnvironment<'a> {
+    /// The logical display size
+    pub display_size: Point,
+
+    /// An application specified platform name, in addition to the operating system and form factor
+    pub platform: Option<&'a str>,
+}
+
+impl<'a> ThemeEnvironment<'a> {
+    fn matches_platform(&self, platform: &str) -> bool {
+        let os = std::env::consts::OS;
+        let form_factor = match os {
+            "android" | "ios" => "mobile",
+            _ => "desktop",
+        };
+
+        platform == os || platform == form_factor || Some(platform) == self.platform
+    }
+}
+
+/**
+Applies the `overrides` section of each of the theme `docs`, which is then removed.  Each override consists of
+a `when` condition and any number of theme sections, such as `variables`, `fonts`, or `widgets`.  If the
+condition matches `env`, the sections are merged into the document, replacing any existing values.  Returns
+whether each override, in order, was applied.
+**/
+pub(crate) fn apply_overrides(docs: &mut [Value], env: &ThemeEnvironment) -> Result<Vec<bool>, Error> {
+    let mut applied = Vec::new();
+
+    for doc in docs.iter_mut() {
+        let doc = match doc.as_mapping_mut() {
+            None => continue,
+            Some(doc) => doc,
+        };
+
+        let overrides = match doc.remove(&key("overrides")) {
+            None => continue,
+            Some(Value::Sequence(overrides)) => overrides,
+            Some(_) => return Err(Error::Theme("The 'overrides' section must be a sequence".to_string())),
+        };
+
+        for over in overrides {
+            let mut over = match over {
+                Value::Mapping(over) => over,
+                _ => return Err(Error::Theme("Each theme override must be a mapping".to_string())),
+            };
+
+            let condition = over.remove(&key("when"));
+            let matches = condition_matches(condition.as_ref(), env)?;
+            applied.push(matches);
+
+            if matches {
+                merge(doc, over);
+            }
+        }
+    }
+
+    Ok(applied)
+}
+
+/// Returns whether each override in the theme `docs` matches `env`, without modifying the docs
+pub(crate) fn matching_overrides(docs: &[Value], env: &ThemeEnvironment) -> Result<Vec<bool>, Error> {
+    let mut matches = Vec::new();
+
+    for doc in docs {
+        if let Some(Value::Sequence(overrides)) = doc.get("overrides") {
+            for over in overrides {
+                matches.push(condition_matches(over.get("when"), env)?);
+            }
+        }
+    }
+
+    Ok(matches)
+}
+
+fn condition_matches(condition: Option<&Value>, env: &ThemeEnvironment) -> Result<bool, Error> {
+    let condition = match condition {
+        None => return Ok(true),
+        Some(Value::Mapping(condition)) => condition,
+        Some(_) => return Err(Error::Theme("The 'when' condition of a theme override must be a mapping".to_string())),
+    };
+
+    let size = env.display_size;
+    for (id, value) in condition {
+        let matches = match (id.as_str(), value) {
+            (Some("min_width"), Value::Number(n)) => size.x >= n.as_f64().unwrap_or_default() as f32,
+            (Some("max_width"), Value::Number(n)) => size.x <= n.as_f64().unwrap_or_default() as f32,
+            (Some("min_height"), Value::Number(n)) => size.y >= n.as_f64().unwrap_or_default() as f32,
+            (Some("max_height"), Value::Number(n)) => size.y <= n.as_f64().unwrap_or_default() as f32,
+            (Some("platform"), Value::String(platform)) => env.matches_platform(platform),
+            _ => return Err(Error::Theme(format!("Invalid theme override condition: {:?}: {:?}", id, value))),
+        };
+
+        if !matches { return Ok(false); }
+    }
+
+    Ok(true)
+}
+
+/// Recursively merges `from` into `into`.  Mappings are merged key by key, while all other values are replaced.
+fn merge(into: &mut Mapping, from: Mapping) {
+    for (id, value) in from {
+        match (into.get_mut(&id), value) {
+            (Some(Value::Mapping(into)), Value::Mapping(from)) => merge(into, from),
+            (_, value) => { into.insert(id, value); },
+        }
+    }
+}
+
+fn key(id: &str) -> Value {
+    Value::String(id.to_string())
+}
@@ -0,0 +1,230 @@
+use crate::{Align, Frame, Rect};
+
+/// An item in a [`toolbar`](struct.Frame.html#method.toolbar).
+#[derive(Copy, Clone, Debug)]
+pub enum ToolbarItem<'a> {
+    /// A button using the specified child `theme`, typically an icon.  The `label` is shown
+    /// as a tooltip, and as the item's text when it is moved into the overflow menu.
+    Button {
+        /// The child theme of the button
+        theme: &'a str,
+
+        /// The label for the button
+        label: &'a str,
+    },
+
+    /// A button which is drawn as [`active`](struct.WidgetBuilder.html#method.active) based on `active`.
+    /// A group of toggles where at most one is active, such as a set of tools, may be created by
+    /// setting `active` based on a single shared value.
+    Toggle {
+        /// The child theme of the button
+        theme: &'a str,
+
+        /// The label for the button
+        label: &'a str,
+
+        /// Whether the button is currently toggled on
+        active: bool,
+    },
+
+    /// A `separator` widget between groups of buttons.  Separators are not shown in the overflow menu.
+    Separator,
+}
+
+impl Frame {
+    /**
+    A horizontal toolbar containing the specified `items`.  Items which do not fit within the toolbar's width
+    are instead placed in a popup menu, shown by clicking the `overflow` button.  Space for the overflow button is
+    reserved according to `overflow_width`, a theme `custom` float defaulting to 24.  The `id` must be unique.
+    Returns the index of the item that was clicked, if any.
+
+    An example theme definition:
+    ```yaml
+    toolbar:
+      layout: Horizontal
+      layout_spacing: [2, 0]
+      size_from: [Parent, Normal]
+      height: 28
+      border: { all: 2 }
+      background: gui/toolbar
+      custom:
+        overflow_width: 24
+      children:
+        save:
+          from: button
+          size: [24, 24]
+          foreground: gui/icon_save
+        brush:
+          from: button
+          size: [24, 24]
+          foreground: gui/icon_brush
+        eraser:
+          from: button
+          size: [24, 24]
+          foreground: gui/icon_eraser
+        separator:
+          size: [2, 24]
+          background: gui/separator
+        overflow:
+          from: button
+          align: Right
+          size: [24, 24]
+          text: "..."
+        overflow_menu:
+          from: combo_box_popup
+          align: TopRight
+          children:
+            entry:
+              from: button
+              size_from: [Parent, Normal]
+    ```
+
+    # Example
+    ```
+    fn tools(ui: &mut Frame, tool: &mut usize) {
+        let items = [
+            ToolbarItem::Button { theme: "save", label: "Save" },
+            ToolbarItem::Separator,
+            ToolbarItem::Toggle { theme: "brush", label: "Brush", active: *tool == 0 },
+            ToolbarItem::Toggle { theme: "eraser", label: "Eraser", active: *tool == 1 },
+        ];
+
+        match ui.toolbar("toolbar", "tools", &items) {
+            Some(0) => println!("Save"),
+            Some(2) => *tool = 0,
+            Some(3) => *tool = 1,
+            _ => (),
+        }
+    }
+    ```
+    **/
+    pub fn toolbar(&mut self, theme: &str, id: &str, items: &[ToolbarItem]) -> Option<usize> {
+        let popup_id = format!("{}_overflow", id);
+
+        let builder = self.start(theme);
+        let overflow_width = builder.custom_float("overflow_width", 24.0);
+
+        let mut result = None;
+        let mut overflow_open = false;
+        builder.id(id).children(|ui| {
+            let bounds = ui.parent_bounds();
+            let limit = bounds.pos.x + bounds.size.x - overflow_width;
+
+            let mut overflow_start = None;
+            for (index, item) in items.iter().enumerate() {
+                let (theme, label, active) = match *item {
+                    ToolbarItem::Button { theme, label } => (theme, label, false),
+                    ToolbarItem::Toggle { theme, label, active } => (theme, label, active),
+                    ToolbarItem::Separator => ("separator", "", false),
+                };
+
+                let mut rect = Rect::default();
+                let builder = ui.start(theme).active(active).trigger_layout(&mut rect);
+
+                // the item is not finished, and so is not added to the frame
+                if rect.pos.x + rect.size.x > limit {
+                    overflow_start = Some(index);
+                    break;
+                }
+
+                let builder = if label.is_empty() {
+                    builder
+                } else {
+                    builder.wants_mouse(true).tooltip_text(label)
+                };
+
+                if builder.finish().clicked {
+                    result = Some(index);
+                }
+            }
+
+            let overflow_start = match overflow_start {
+                None => return,
+                Some(index) => index,
+            };
+
+            overflow_open = ui.child("overflow").clicked;
+
+            ui.start("overflow_menu")
+            .id(&popup_id)
+            .initially_open(false)
+            .unclip()
+            .unparent()
+            .new_render_group()
+            .children(|ui| {
+                for (index, item) in items.iter().enumerate().skip(overflow_start) {
+                    let (label, active) = match *item {
+                        ToolbarItem::Button { label, .. } => (label, false),
+                        ToolbarItem::Toggle { label, active, .. } => (label, active),
+                        ToolbarItem::Separator => continue,
+                    };
+
+                    if ui.toggle_button("entry", label, active).clicked {
+                        result = Some(index);
+                        ui.close(&popup_id);
+                    }
+                }
+            });
+        });
+
+        if overflow_open {
+            self.open_modal(&popup_id);
+            self.close_modal_on_click_outside();
+        }
+
+        result
+    }
+
+    /**
+    A status bar, anchored to the bottom of its parent, normally the window.  The status bar contains
+    `left`, `center`, and `right` sections, aligned to the respective sides of the bar, whose content is
+    created by the `left`, `center`, and `right` closures.
+
+    An example theme definition:
+    ```yaml
+    status_bar:
+      layout: Free
+      size_from: [Parent, Normal]
+      height: 24
+      border: { width: 4 }
+      background: gui/status_bar
+      children:
+        left:
+          layout: Horizontal
+          size_from: [Children, Parent]
+        center:
+          layout: Horizontal
+          size_from: [Children, Parent]
+        right:
+          layout: Horizontal
+          size_from: [Children, Parent]
+        label:
+          from: label
+          size_from: [Text, Parent]
+    ```
+
+    # Example
+    ```
+    fn status(ui: &mut Frame, message: &str, line: usize, column: usize) {
+        ui.status_bar("status_bar", |ui| {
+            ui.label("label", message);
+        }, |_| {}, |ui| {
+            ui.label("label", format!("Ln {}, Col {}", line, column));
+        });
+    }
+    ```
+    **/
+    pub fn status_bar<L: FnOnce(&mut Frame), C: FnOnce(&mut Frame), R: FnOnce(&mut Frame)>(
+        &mut self,
+        theme: &str,
+        left: L,
+        center: C,
+        right: R,
+    ) {
+        self.start(theme).align(Align::BotLeft).children(|ui| {
+            ui.start("left").align(Align::Left).children(left);
+            ui.start("center").align(Align::Center).children(center);
+            ui.start("right").align(Align::Right).children(right);
+        });
+    }
+}
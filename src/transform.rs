@@ -0,0 +1,160 @@
+use std::ops::Mul;
+
+use serde::{Serialize, Deserialize};
+
+use crate::{Point, Rect};
+
+/**
+A 2D transform applied to a widget and all of its children when drawn.  See
+[`WidgetBuilder::transform`](struct.WidgetBuilder.html#method.transform).  The widget is scaled
+and then rotated about its pivot point, and then moved by the offset.  Transforms only affect how widgets are drawn and how the
+mouse interacts with them; they do not affect layout.
+**/
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Transform {
+    /// The clockwise rotation, in degrees
+    pub rotation: f32,
+
+    /// The scale factor along each axis.  A negative scale mirrors the widget.
+    pub scale: Point,
+
+    /// The point the widget is scaled and rotated about, as a fraction of the widget size.  `(0.5, 0.5)`
+    /// is the widget center, while `(0.0, 0.0)` is its top left corner.
+    pub pivot: Point,
+
+    /// An amount to move the widget by after scaling and rotating, in logical pixels
+    pub offset: Point,
+}
+
+impl Default for Transform {
+    fn default() -> Transform {
+        Transform {
+            rotation: 0.0,
+            scale: Point::new(1.0, 1.0),
+            pivot: Point::new(0.5, 0.5),
+            offset: Point::default(),
+        }
+    }
+}
+
+impl Transform {
+    /// Creates a new transform rotating clockwise by the specified number of `degrees` about the widget center.
+    pub fn rotation(degrees: f32) -> Transform {
+        Transform { rotation: degrees, ..Default::default() }
+    }
+
+    /// Creates a new transform scaling by the specified `x` and `y` factors about the widget center.
+    pub fn scale(x: f32, y: f32) -> Transform {
+        Transform { scale: Point::new(x, y), ..Default::default() }
+    }
+
+    /// Creates a new transform moving the widget by the specified `x` and `y` amounts, in logical pixels.
+    pub fn offset(x: f32, y: f32) -> Transform {
+        Transform { offset: Point::new(x, y), ..Default::default() }
+    }
+
+    /// Returns this transform with the pivot set to the specified fraction of the widget size.
+    #[must_use]
+    pub fn with_pivot(mut self, x: f32, y: f32) -> Transform {
+        self.pivot = Point::new(x, y);
+        self
+    }
+
+    /// The affine transform equivalent to this transform, for a widget occupying `rect`
+    pub(crate) fn affine(&self, rect: Rect) -> Affine {
+        let pivot = Point::new(
+            rect.pos.x + rect.size.x * self.pivot.x,
+            rect.pos.y + rect.size.y * self.pivot.y,
+        );
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+
+        let linear = Affine {
+            a: cos * self.scale.x, b: sin * self.scale.x,
+            c: -sin * self.scale.y, d: cos * self.scale.y,
+            tx: 0.0, ty: 0.0,
+        };
+
+        Affine::translation(pivot + self.offset) * linear * Affine::translation(Point::new(-pivot.x, -pivot.y))
+    }
+}
+
+/// An affine transform of points, mapping `(x, y)` to `(a * x + c * y + tx, b * x + d * y + ty)`
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) struct Affine {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub tx: f32,
+    pub ty: f32,
+}
+
+impl Default for Affine {
+    fn default() -> Affine {
+        Affine { a: 1.0, b: 0.0, c: 0.0, d: 1.0, tx: 0.0, ty: 0.0 }
+    }
+}
+
+impl Affine {
+    pub fn translation(offset: Point) -> Affine {
+        Affine { tx: offset.x, ty: offset.y, ..Default::default() }
+    }
+
+    pub fn apply(&self, p: Point) -> Point {
+        Point {
+            x: self.a * p.x + self.c * p.y + self.tx,
+            y: self.b * p.x + self.d * p.y + self.ty,
+        }
+    }
+
+    /// The inverse of this transform, or `None` if it collapses the plane, such as with a zero scale
+    pub fn inverse(&self) -> Option<Affine> {
+        let det = self.a * self.d - self.b * self.c;
+        if det.abs() < f32::EPSILON { return None; }
+
+        Some(Affine {
+            a: self.d / det,
+            b: -self.b / det,
+            c: -self.c / det,
+            d: self.a / det,
+            tx: (self.c * self.ty - self.d * self.tx) / det,
+            ty: (self.b * self.tx - self.a * self.ty) / det,
+        })
+    }
+
+    /// This transform, operating on points which have been multiplied by `scale`, such as physical pixels
+    pub fn scaled(&self, scale: f32) -> Affine {
+        Affine { tx: self.tx * scale, ty: self.ty * scale, ..*self }
+    }
+
+    /// The smallest axis aligned rectangle containing the transformed `rect`
+    pub fn bounds(&self, rect: Rect) -> Rect {
+        let corners = [
+            self.apply(rect.pos),
+            self.apply(Point::new(rect.right(), rect.top())),
+            self.apply(Point::new(rect.left(), rect.bot())),
+            self.apply(rect.pos + rect.size),
+        ];
+
+        let min = corners.iter().fold(corners[0], |min, corner| min.min(*corner));
+        let max = corners.iter().fold(corners[0], |max, corner| max.max(*corner));
+        Rect::new(min, max - min)
+    }
+}
+
+impl Mul<Affine> for Affine {
+    type Output = Affine;
+
+    /// The transform applying `rhs` followed by this transform
+    fn mul(self, rhs: Affine) -> Affine {
+        Affine {
+            a: self.a * rhs.a + self.c * rhs.b,
+            b: self.b * rhs.a + self.d * rhs.b,
+            c: self.a * rhs.c + self.c * rhs.d,
+            d: self.b * rhs.c + self.d * rhs.d,
+            tx: self.a * rhs.tx + self.c * rhs.ty + self.tx,
+            ty: self.b * rhs.tx + self.d * rhs.ty + self.ty,
+        }
+    }
+}
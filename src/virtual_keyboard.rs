@@ -0,0 +1,306 @@
+use crate::{Frame, KeyEvent, WidgetBuilder};
+
+/// A page of character keys for a [`virtual_keyboard`](struct.Frame.html#method.virtual_keyboard), such as
+/// the letters for a particular locale, or symbols.
+#[derive(Copy, Clone, Debug)]
+pub struct KeyboardLayout<'a> {
+    /// The label of the key which switches to this layout, such as "ABC" or "?123"
+    pub name: &'a str,
+
+    /// The characters of each row of keys, from top to bottom, in lowercase.  Uppercase characters
+    /// are entered with the shift key.
+    pub rows: &'a [&'a str],
+}
+
+impl KeyboardLayout<'static> {
+    /// An English QWERTY layout, with a row of digits
+    pub const QWERTY: KeyboardLayout<'static> = KeyboardLayout {
+        name: "ABC",
+        rows: &["1234567890", "qwertyuiop", "asdfghjkl'", "zxcvbnm,.?"],
+    };
+
+    /// A German QWERTZ layout, with a row of digits
+    pub const QWERTZ: KeyboardLayout<'static> = KeyboardLayout {
+        name: "ABC",
+        rows: &["1234567890ß", "qwertzuiopü", "asdfghjklöä", "yxcvbnm,.-"],
+    };
+
+    /// A French AZERTY layout, with a row of digits
+    pub const AZERTY: KeyboardLayout<'static> = KeyboardLayout {
+        name: "ABC",
+        rows: &["1234567890", "azertyuiop", "qsdfghjklm", "wxcvbn,.'é"],
+    };
+
+    /// Common symbols and punctuation
+    pub const SYMBOLS: KeyboardLayout<'static> = KeyboardLayout {
+        name: "?123",
+        rows: &["1234567890", "@#$%&*-+()", "!\"':;/?_=", "[]{}<>\\|~^"],
+    };
+}
+
+/// A navigation input for a [`virtual_keyboard`](struct.Frame.html#method.virtual_keyboard), typically from
+/// a gamepad's d-pad, analog stick, and face buttons.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum KeyboardNav {
+    /// Moves the focused key up one row
+    Up,
+
+    /// Moves the focused key down one row
+    Down,
+
+    /// Moves the focused key left, wrapping around the row
+    Left,
+
+    /// Moves the focused key right, wrapping around the row
+    Right,
+
+    /// Presses the focused key
+    Select,
+}
+
+impl KeyboardNav {
+    /// Converts an analog stick position, with each axis from -1.0 to 1.0 and `y` increasing downwards, into
+    /// the direction of its larger axis.  Returns `None` if the stick is within the `dead_zone` of the center.
+    /// Since this is called each frame, the application should limit how often the result is passed to the
+    /// keyboard, such as by repeating a held direction every few hundred milliseconds.
+    pub fn from_stick(x: f32, y: f32, dead_zone: f32) -> Option<KeyboardNav> {
+        if x.abs().max(y.abs()) <= dead_zone { return None; }
+
+        Some(if x.abs() > y.abs() {
+            if x > 0.0 { KeyboardNav::Right } else { KeyboardNav::Left }
+        } else if y > 0.0 {
+            KeyboardNav::Down
+        } else {
+            KeyboardNav::Up
+        })
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Key {
+    Char(char),
+    Shift,
+    Page,
+    Space,
+    Backspace,
+    Done,
+}
+
+/// The rows of keys for each of the `layouts`, with the special keys in a final row
+fn keyboard_pages(layouts: &[KeyboardLayout]) -> Vec<Vec<Vec<Key>>> {
+    layouts.iter().map(|layout| {
+        let mut rows: Vec<Vec<Key>> = layout.rows.iter()
+            .map(|row| row.chars().map(Key::Char).collect())
+            .filter(|row: &Vec<Key>| !row.is_empty())
+            .collect();
+
+        let mut special = vec![Key::Shift];
+        if layouts.len() > 1 {
+            special.push(Key::Page);
+        }
+        special.extend_from_slice(&[Key::Space, Key::Backspace, Key::Done]);
+        rows.push(special);
+
+        rows
+    }).collect()
+}
+
+fn key_count(page: &[Vec<Key>]) -> usize {
+    page.iter().map(|row| row.len()).sum()
+}
+
+impl Frame {
+    /**
+    An on screen keyboard, for entering text on platforms without a physical keyboard, such as with a gamepad.
+    Pressed keys are sent to the widget with [`keyboard focus`](#method.focus_keyboard), such as an
+    [`input_field`](#method.input_field), as if they were typed.
+
+    Each of the `layouts` is a page of keys, such as letters for a locale or symbols.  Each row of keys is drawn
+    with a `row` child, containing a `key` child for each character.  A final row contains the `shift`,
+    `page`, `space`, `backspace`, and `done` keys, with the `page` key, cycling through the layouts, only
+    shown if there is more than one.  The text of these keys, other than `page`, should be set in the theme.
+
+    One key is focused at a time, and shown as hovered.  The focus is moved, and the focused key is pressed,
+    with the `nav` input, which the application should pass in response to gamepad input.  Keys may also be
+    clicked with the mouse, with the widget that had keyboard focus while the keyboard was shown keeping it.
+    The shift key applies to the next character only.  The `id` must be unique,
+    and the focused key and shift state are stored in its [`PersistentState`](struct.PersistentState.html).
+    Returns true when the `done` key is pressed, at which point the keyboard would typically be closed.
+
+    An example theme definition:
+    ```yaml
+    virtual_keyboard:
+      background: gui/window_bg
+      size_from: [Children, Children]
+      layout: Vertical
+      border: { all: 6 }
+      children:
+        row:
+          size_from: [Children, Children]
+          layout: Horizontal
+          layout_spacing: [4, 4]
+          children:
+            key:
+              from: button
+              size: [36, 36]
+            shift:
+              from: button
+              text: Shift
+              size: [72, 36]
+            page:
+              from: button
+              size: [72, 36]
+            space:
+              from: button
+              text: Space
+              size: [180, 36]
+            backspace:
+              from: button
+              text: Del
+              size: [72, 36]
+            done:
+              from: button
+              text: Done
+              size: [72, 36]
+    ```
+
+    # Example
+    ```
+    fn name_entry(ui: &mut Frame, gamepad: &Gamepad, keyboard_open: &mut bool) {
+        ui.input_field("input_field", "name", None);
+
+        if *keyboard_open {
+            let nav = match gamepad.just_pressed() {
+                Some(Button::DPadUp) => Some(KeyboardNav::Up),
+                Some(Button::DPadDown) => Some(KeyboardNav::Down),
+                Some(Button::DPadLeft) => Some(KeyboardNav::Left),
+                Some(Button::DPadRight) => Some(KeyboardNav::Right),
+                Some(Button::South) => Some(KeyboardNav::Select),
+                _ => None,
+            };
+
+            let layouts = [KeyboardLayout::QWERTY, KeyboardLayout::SYMBOLS];
+            if ui.virtual_keyboard("virtual_keyboard", "keyboard", &layouts, nav) {
+                *keyboard_open = false;
+            }
+        }
+    }
+    ```
+    **/
+    pub fn virtual_keyboard(&mut self, theme: &str, id: &str, layouts: &[KeyboardLayout], nav: Option<KeyboardNav>) -> bool {
+        let pages = keyboard_pages(layouts);
+        if pages.is_empty() { return false; }
+
+        // remember the focused widget, as clicking a key with the mouse clears the keyboard focus
+        let current = self.context_internal().borrow().focus_keyboard_widget().map(str::to_string);
+        let (focused, mut shift, target) = self.modify(id, |state| {
+            if current.is_some() {
+                state.focus_target = current;
+            }
+            (state.selection.first().unwrap_or_default(), state.toggled, state.focus_target.clone())
+        });
+
+        // find the page, row, and column of the focused key, from its index among the keys of all pages
+        let (mut page, mut row, mut column) = (0, 0, 0);
+        let mut remaining = focused;
+        'find: for (page_index, keys) in pages.iter().enumerate() {
+            for (row_index, keys) in keys.iter().enumerate() {
+                if remaining < keys.len() {
+                    (page, row, column) = (page_index, row_index, remaining);
+                    break 'find;
+                }
+                remaining -= keys.len();
+            }
+        }
+
+        let rows = &pages[page];
+        let mut pressed = None;
+        match nav {
+            None => (),
+            Some(KeyboardNav::Left) => column = (column + rows[row].len() - 1) % rows[row].len(),
+            Some(KeyboardNav::Right) => column = (column + 1) % rows[row].len(),
+            Some(KeyboardNav::Up) | Some(KeyboardNav::Down) => {
+                let next = if nav == Some(KeyboardNav::Up) {
+                    (row + rows.len() - 1) % rows.len()
+                } else {
+                    (row + 1) % rows.len()
+                };
+
+                // keep the same relative position along the row, as rows have different lengths
+                let position = (column as f32 + 0.5) / rows[row].len() as f32;
+                column = ((position * rows[next].len() as f32) as usize).min(rows[next].len() - 1);
+                row = next;
+            },
+            Some(KeyboardNav::Select) => pressed = Some(rows[row][column]),
+        }
+
+        let next_layout = layouts[(page + 1) % layouts.len()].name;
+        self.start(theme).id(id).children(|ui| {
+            for (row_index, keys) in rows.iter().enumerate() {
+                ui.start("row").children(|ui| {
+                    for (column_index, key) in keys.iter().enumerate() {
+                        let builder = match key {
+                            Key::Char(c) if shift => ui.start("key").text(c.to_uppercase().to_string()),
+                            Key::Char(c) => ui.start("key").text(c.to_string()),
+                            Key::Shift => ui.start("shift").active(shift),
+                            Key::Page => ui.start("page").text(next_layout),
+                            Key::Space => ui.start("space"),
+                            Key::Backspace => ui.start("backspace"),
+                            Key::Done => ui.start("done"),
+                        };
+
+                        let is_focused = row_index == row && column_index == column;
+                        if keyboard_key(builder, is_focused) {
+                            (row, column) = (row_index, column_index);
+                            pressed = Some(*key);
+                        }
+                    }
+                });
+            }
+        });
+
+        if let (Some(_), Some(target)) = (pressed, target) {
+            self.context_internal().borrow_mut().set_focus_keyboard(target);
+        }
+
+        let mut done = false;
+        match pressed {
+            None => (),
+            Some(Key::Char(c)) => {
+                let mut internal = self.context_internal().borrow_mut();
+                if shift {
+                    c.to_uppercase().for_each(|c| internal.push_focus_character(c));
+                } else {
+                    internal.push_focus_character(c);
+                }
+                shift = false;
+            },
+            Some(Key::Shift) => shift = !shift,
+            Some(Key::Page) => {
+                // move to the page key of the next page, so it may be pressed repeatedly
+                page = (page + 1) % pages.len();
+                let last = pages[page].len() - 1;
+                (row, column) = (last, 1);
+            },
+            Some(Key::Space) => self.context_internal().borrow_mut().push_focus_character(' '),
+            Some(Key::Backspace) => self.context_internal().borrow_mut().push_focus_key_event(KeyEvent::Back),
+            Some(Key::Done) => done = true,
+        }
+
+        let focused = pages[..page].iter().map(|keys| key_count(keys)).sum::<usize>() +
+            pages[page][..row].iter().map(|keys| keys.len()).sum::<usize>() + column;
+
+        self.modify(id, |state| {
+            state.selection.clear();
+            state.selection.select(focused);
+            state.toggled = shift;
+        });
+
+        done
+    }
+}
+
+/// Finishes a key of the keyboard, shown as hovered if it is focused, and returns whether it was clicked
+fn keyboard_key(builder: WidgetBuilder, focused: bool) -> bool {
+    builder.wants_mouse(true).force_hover(focused).finish().clicked
+}
@@ -1,10 +1,18 @@
+use std::borrow::Cow;
+use std::fmt::Display;
+
 use crate::{
-    AnimState, AnimStateKey, Color, Frame, Point, Border, Align, 
-    Layout, WidthRelative, HeightRelative, Rect,
+    AnimState, AnimStateKey, Color, Frame, HapticEvent, Interaction, Point, Border, Align, 
+    Layout, SystemCursor, TextWrap, WidthRelative, HeightRelative, Rect, Transform, UiEvent,
 };
-use crate::font::FontDrawParams;
+use crate::bench::{self, ProfileKind};
+use crate::context::ContextInternal;
+use crate::font::{FontDrawParams, TextCarets, TextLayout};
+use crate::transform::Affine;
 use crate::{frame::{MouseButton, RendGroup, RendGroupOrder}, font::FontSummary, image::ImageHandle};
-use crate::theme::WidgetTheme;
+use crate::render::{Vertex, WidgetTriangles};
+use crate::theme::{WidgetCursor, WidgetTheme};
+use crate::theme_definition::CustomData;
 use crate::window::WindowBuilder;
 use crate::scrollpane::ScrollpaneBuilder;
 
@@ -18,6 +26,7 @@ pub struct Widget {
     // stored in the widget for parent ref purposes
     scroll: Point,
     cursor: Point,
+    masonry_heights: Vec<f32>,
     theme_id: String,
     child_align: Align,
     layout: Layout,
@@ -25,46 +34,79 @@ pub struct Widget {
 
     // stored in the widget for drawing purposes
     clip: Rect,
+    transform: Option<Affine>,
     text: Option<String>,
     text_color: Color,
     text_align: Align,
     text_indent: f32,
+    text_wrap: TextWrap,
+    text_soft_hyphens: bool,
+    text_reveal: Option<usize>,
+    inline_images: Vec<ImageHandle>,
     font: Option<FontSummary>,
     image_color: Color,
     background: Option<ImageHandle>,
     foreground: Option<ImageHandle>,
+    triangles: Option<WidgetTriangles>,
     pos: Point,
     size: Point,
     border: Border,
     anim_state: AnimState,
     visible: bool,
+
+    // stored for keyboard navigation
+    focusable: bool,
+    focus_scope: usize,
+
+    // stored for accessibility
+    aria_label: Option<String>,
+    description: Option<String>,
+
+    // application data attached with the builder
+    custom: Vec<(String, CustomData)>,
+
+    // stored for drawing with a renderer defined shader
+    shader: Option<String>,
 }
 
 impl Widget {
-    pub(crate) fn root(size: Point) -> Widget {
+    pub(crate) fn root(size: Point, insets: Border) -> Widget {
         Widget {
             theme_id: String::new(),
             text: None,
             text_align: Align::default(),
             text_indent: 0.0,
+            text_wrap: TextWrap::default(),
+            text_soft_hyphens: false,
+            text_reveal: None,
+            inline_images: Vec::new(),
             text_color: Color::default(),
             font: None,
             image_color: Color::default(),
             background: None,
             foreground: None,
+            triangles: None,
             layout: Layout::default(),
             layout_spacing: Point::default(),
             child_align: Align::default(),
             pos: Point::default(),
             scroll: Point::default(),
             cursor: Point::default(),
-            border: Border::default(),
+            masonry_heights: Vec::new(),
+            border: insets,
             size,
             id: String::new(),
             rend_group: RendGroup::default(),
             anim_state: AnimState::normal(),
             visible: true,
+            focusable: false,
+            focus_scope: 0,
             clip: Rect { pos: Point::default(), size },
+            transform: None,
+            aria_label: None,
+            description: None,
+            custom: Vec::new(),
+            shader: None,
         }
     }
 
@@ -110,7 +152,23 @@ impl Widget {
             recalc_pos_size,
             next_render_group: None,
             unparent: false,
+            focusable: false,
+            focus_scope: false,
+            input_passthrough: !theme.hit_test.unwrap_or(true),
+            explicit_id: false,
+            raw_mouse_drag: false,
+            lock_cursor_on_drag: false,
             tooltip: theme.tooltip.clone(),
+            cursor: theme.cursor,
+            sound_hover: theme.sound_hover.clone(),
+            sound_click: theme.sound_click.clone(),
+            image_tints: theme.image_tints.clone(),
+            repeat_on_hold: None,
+            tooltip_delay: None,
+            tooltip_duration: None,
+            transform: None,
+            effect: None,
+            transition: None,
         };
 
         let widget = Widget {
@@ -122,31 +180,90 @@ impl Widget {
             text_color: theme.text_color.unwrap_or_default(),
             text_align: theme.text_align.unwrap_or_default(),
             text_indent: 0.0,
+            text_wrap: theme.text_wrap.unwrap_or_default(),
+            text_soft_hyphens: theme.text_soft_hyphens.unwrap_or_default(),
+            text_reveal: None,
+            inline_images: Vec::new(),
             font,
             image_color: theme.image_color.unwrap_or_default(),
             background: theme.background,
             foreground: theme.foreground,
+            triangles: None,
             pos,
             scroll: Point::default(),
             cursor: Point::default(),
+            masonry_heights: Vec::new(),
             border,
             size: raw_size,
             id,
             rend_group: RendGroup::default(),
             anim_state: AnimState::normal(),
             visible: true,
+            focusable: false,
+            focus_scope: parent.focus_scope,
             clip: parent.clip,
+            transform: parent.transform,
+            aria_label: theme.aria_label.clone(),
+            description: theme.description.clone(),
+            custom: Vec::new(),
+            shader: theme.shader.clone(),
         };
 
         (data, widget)
     }
 
-    /// The current clip rectangle for this widget
+    /// The current clip rectangle for this widget.  For widgets with a
+    /// [`transform`](struct.WidgetBuilder.html#method.transform), this is in the untransformed space of the widget.
     pub fn clip(&self) -> Rect { self.clip }
 
+    /// The combined transform of this widget and all of its parents, mapping from the widget's
+    /// layout position to the screen, in logical pixels
+    pub(crate) fn transform(&self) -> Option<Affine> { self.transform }
+
+    /// Converts the specified `screen` position to the untransformed space of this widget.  Returns
+    /// `None` if the widget's transform has collapsed it to a line or point.
+    pub(crate) fn to_local(&self, screen: Point) -> Option<Point> {
+        match self.transform {
+            None => Some(screen),
+            Some(transform) => transform.inverse().map(|inverse| inverse.apply(screen)),
+        }
+    }
+
+    /// The bounding box of the specified `rect` in this widget's space, once transformed to the screen
+    pub(crate) fn screen_bounds(&self, rect: Rect) -> Rect {
+        match self.transform {
+            None => rect,
+            Some(transform) => transform.bounds(rect),
+        }
+    }
+
+    /// Applies the `local` transform of this widget, relative to its parent, on top of the parent's transform
+    fn apply_local_transform(&mut self, local: Affine) {
+        // the clip is converted to the widget's space, conservatively using the bounds of the transformed clip
+        self.clip = match local.inverse() {
+            None => Rect::default(),
+            Some(inverse) => inverse.bounds(self.clip),
+        };
+        self.transform = Some(self.transform.unwrap_or_default() * local);
+    }
+
     /// Whether this widget is visible
     pub fn visible(&self) -> bool { self.visible }
 
+    /// Whether this widget may receive keyboard focus via Tab navigation
+    pub(crate) fn focusable(&self) -> bool { self.focusable }
+
+    /// The index of the widget at the root of this widget's focus scope
+    pub(crate) fn focus_scope(&self) -> usize { self.focus_scope }
+
+    /// Whether this widget lies entirely outside of its [`clip`](#method.clip) rectangle.
+    /// Widgets that are clipped out are not drawn, although their children may still be.
+    pub fn clipped_out(&self) -> bool {
+        if self.clip.size.x <= 0.0 || self.clip.size.y <= 0.0 { return true; }
+
+        !self.clip.intersects(Rect::new(self.pos, self.size))
+    }
+
     /// The text color for this widget
     pub fn text_color(&self) -> Color { self.text_color }
 
@@ -156,9 +273,54 @@ impl Widget {
     /// The indent of the first line of text, in logical pixels
     pub fn text_indent(&self) -> f32 { self.text_indent }
 
+    /// How text is wrapped onto multiple lines for this widget
+    pub fn text_wrap(&self) -> TextWrap { self.text_wrap }
+
+    /// Whether soft hyphens in this widget's text are used as potential line breaks
+    pub fn text_soft_hyphens(&self) -> bool { self.text_soft_hyphens }
+
+    /// The number of characters of this widget's text which are drawn, if limited
+    pub fn text_reveal(&self) -> Option<usize> { self.text_reveal }
+
+    /// The images shown inline in this widget's text, in order
+    pub(crate) fn inline_images(&self) -> &[ImageHandle] { &self.inline_images }
+
+    /// Sets the `text` of this widget, after it has been resolved, replacing any inline image markup
+    fn set_resolved_text(&mut self, text: String, context: &ContextInternal) {
+        match context.inline_images(&text) {
+            None => {
+                self.text = Some(text);
+                self.inline_images.clear();
+            },
+            Some((text, images)) => {
+                self.text = Some(text);
+                self.inline_images = images;
+            }
+        }
+    }
+
     /// The text for this widget, if any
     pub fn text(&self) -> Option<&str> { self.text.as_deref() }
 
+    /// The screen reader label for this widget, if any
+    pub fn aria_label(&self) -> Option<&str> { self.aria_label.as_deref() }
+
+    /// The screen reader description for this widget, if any
+    pub fn description(&self) -> Option<&str> { self.description.as_deref() }
+
+    /// The custom data attached to this widget with the specified `key`, if any.  See
+    /// [`WidgetBuilder::custom`](struct.WidgetBuilder.html#method.custom).
+    pub fn custom(&self, key: &str) -> Option<&CustomData> {
+        self.custom.iter().find(|(id, _)| id == key).map(|(_, value)| value)
+    }
+
+    /// All custom data attached to this widget, as key value pairs in the order they were attached
+    pub fn custom_data(&self) -> &[(String, CustomData)] { &self.custom }
+
+    /// The name of the custom shader used to draw this widget's images, if any.  See
+    /// [`WidgetBuilder::shader`](struct.WidgetBuilder.html#method.shader).
+    pub fn shader(&self) -> Option<&str> { self.shader.as_deref() }
+
     /// The font to render text for this widget, if any
     pub fn font(&self) -> Option<FontSummary> { self.font }
 
@@ -171,6 +333,9 @@ impl Widget {
     /// The current background image for this widget.
     pub fn background(&self) -> Option<ImageHandle> { self.background }
 
+    /// The solid colored triangles drawn by this widget, if any.
+    pub(crate) fn triangles(&self) -> Option<&WidgetTriangles> { self.triangles.as_ref() }
+
     /// The border area for this widget
     pub fn border(&self) -> Border { self.border }
 
@@ -210,9 +375,12 @@ impl Widget {
             Layout::Vertical => self.cursor.y += gap,
             Layout::Free => (),
             Layout::Grid(_) => self.cursor.x += gap,
+            Layout::Masonry(_) => self.cursor.y += gap,
         }
     }
 
+    pub(crate) fn layout_spacing(&self) -> Point { self.layout_spacing }
+
     pub(crate) fn rend_group(&self) -> RendGroup { self.rend_group }
 
     pub(crate) fn set_rend_group(&mut self, group: RendGroup) {
@@ -261,6 +429,16 @@ pub struct WidgetState {
 
     /// If the mouse was pressed or clicked, which mouse button was used.
     pub mouse_button: Option<MouseButton>,
+
+    /// Whether the mouse has been held down on this widget for the
+    /// [`long_press_time`](struct.BuildOptions.html#structfield.long_press_time).  This field will
+    /// only be `true` once per press, on the frame the time is reached.
+    pub long_pressed: bool,
+
+    /// Whether this widget was drawn this frame but not on the previous frame.  This is only tracked for widgets with an
+    /// explicit [`id`](struct.WidgetBuilder.html#method.id), and is useful for starting entrance animations.  Widgets
+    /// which are no longer drawn are reported with [`UiEvent::Disappeared`](enum.UiEvent.html#variant.Disappeared).
+    pub appeared: bool,
 }
 
 impl WidgetState {
@@ -272,6 +450,8 @@ impl WidgetState {
             clicked: false,
             moved: Point::default(),
             mouse_button: None,
+            long_pressed: false,
+            appeared: false,
         }
     }
 
@@ -291,10 +471,46 @@ impl WidgetState {
             clicked,
             moved,
             mouse_button,
+            long_pressed: false,
+            appeared: false,
         }
     }
 }
 
+/// The number of repeats that have occurred after the mouse has been held for `held` milliseconds
+fn repeat_count(held: u32, delay: u32, interval: u32) -> u32 {
+    if held < delay { return 0; }
+
+    1 + (held - delay) / interval.max(1)
+}
+
+/// The index of the first column with the smallest height
+fn shortest_column(heights: &[f32]) -> usize {
+    let mut shortest = 0;
+    for (index, height) in heights.iter().enumerate() {
+        if *height < heights[shortest] {
+            shortest = index;
+        }
+    }
+    shortest
+}
+
+/// The id for a widget with the specified `theme`, before any index or counter is appended
+fn id_base(parent_id: &str, theme: &str) -> String {
+    if parent_id.is_empty() {
+        theme.to_string()
+    } else {
+        format!("{}/{}", parent_id, theme)
+    }
+}
+
+fn id_with_key(mut id: String, key: &dyn Display) -> String {
+    use std::fmt::Write;
+    // writing to a String cannot fail
+    let _ = write!(id, "#{}", key);
+    id
+}
+
 fn pos(parent: &Widget, pos: Point, self_size: Point, align: Align) -> Point {
     let size = parent.size;
     let border = parent.border;
@@ -359,8 +575,38 @@ pub(crate) struct WidgetData {
     recalc_pos_size: bool,
     next_render_group: Option<RendGroupOrder>,
     unparent: bool,
+    focusable: bool,
+    focus_scope: bool,
+    input_passthrough: bool,
+    explicit_id: bool,
+    raw_mouse_drag: bool,
+    lock_cursor_on_drag: bool,
 
     tooltip: Option<String>,
+    cursor: Option<WidgetCursor>,
+    sound_hover: Option<String>,
+    sound_click: Option<String>,
+    image_tints: Vec<(AnimState, Color)>,
+    repeat_on_hold: Option<(u32, u32)>,
+    tooltip_delay: Option<u32>,
+    tooltip_duration: Option<u32>,
+    transform: Option<Transform>,
+    effect: Option<Transform>,
+    transition: Option<Transform>,
+}
+
+impl WidgetData {
+    /// The transform of the widget relative to its parent when occupying `rect`, combining any
+    /// modal transition and attention effect with the specified transform
+    fn local_transform(&self, rect: Rect) -> Option<Affine> {
+        match (self.transform, self.effect, self.transition) {
+            (None, None, None) => None,
+            (transform, effect, transition) => {
+                let affine = |transform: Option<Transform>| transform.map_or(Affine::default(), |t| t.affine(rect));
+                Some(affine(transition) * affine(effect) * affine(transform))
+            }
+        }
+    }
 }
 
 /// A `WidgetBuilder` is used to customize widgets within your UI tree, following a builder pattern.
@@ -383,7 +629,13 @@ pub struct WidgetBuilder<'a> {
 
 impl<'a> WidgetBuilder<'a> {
     #[must_use]
-    pub(crate) fn new(frame: &'a mut Frame, parent: usize, theme_id: String, base_theme: &str) -> WidgetBuilder<'a> {
+    pub(crate) fn new(
+        frame: &'a mut Frame,
+        parent: usize,
+        theme_id: String,
+        base_theme: &str,
+        key: Option<&dyn Display>,
+    ) -> WidgetBuilder<'a> {
         let (data, widget) = {
             let context = std::rc::Rc::clone(frame.context_internal());
             let mut context = context.borrow_mut();
@@ -398,19 +650,24 @@ impl<'a> WidgetBuilder<'a> {
                 }, Some(theme) => theme,
             };
 
-            let id = {
-                let parent_widget = frame.widget(parent);
-                if parent_widget.id.is_empty() {
-                    theme.id.to_string()
-                } else {
-                    format!("{}/{}", parent_widget.id, theme.id)
-                }
-            };
+            let id = id_base(&frame.widget(parent).id, &theme.id);
 
-            let id = frame.generate_id(id);
+            let id = match key {
+                None => frame.generate_id(id),
+                Some(key) => id_with_key(id, key),
+            };
             let parent_widget = frame.widget(parent);
 
-            let (data, widget) = Widget::create(parent_widget, theme, id);
+            let (data, mut widget) = Widget::create(parent_widget, theme, id);
+
+            if let Some(text) = widget.text.take() {
+                // avoid a copy when the resolver returns the text unchanged
+                let resolved = match context.resolve_text(&text) {
+                    Cow::Borrowed(resolved) if resolved == text => None,
+                    resolved => Some(resolved.into_owned()),
+                };
+                widget.set_resolved_text(resolved.unwrap_or(text), &context);
+            }
 
             (data, widget)
         };
@@ -424,6 +681,7 @@ impl<'a> WidgetBuilder<'a> {
     }
 
     fn recalculate_pos_size(&mut self, state_moved: Point, state_resize: Point) {
+        let profile = bench::profile_start();
         let parent = self.frame.widget(self.parent);
         let widget = &self.widget;
 
@@ -439,6 +697,10 @@ impl<'a> WidgetBuilder<'a> {
             HeightRelative::Normal => raw.y,
             HeightRelative::Parent => raw.y + parent.size.y - parent.border.vertical(),
             HeightRelative::FontLine => raw.y + widget.font.map_or(0.0, |sum| sum.line_height) + widget.border.vertical(),
+            HeightRelative::Text => {
+                let width = x + state_resize.x - widget.border.horizontal();
+                raw.y + self.calculate_wrapped_text_height(width) + widget.border.vertical()
+            },
         };
         let self_size = Point { x, y } + state_resize;
 
@@ -447,6 +709,20 @@ impl<'a> WidgetBuilder<'a> {
         self.widget.pos = pos + state_moved;
         self.widget.size = self_size;
         self.data.recalc_pos_size = false;
+        bench::profile_end(profile, &self.widget.theme_id, ProfileKind::Layout);
+    }
+
+    /// The bounds of the widget from its layout, ignoring any move in its persistent state, but including any resize
+    pub(crate) fn layout_bounds(&mut self) -> Rect {
+        if self.data.recalc_pos_size {
+            let resize = self.frame.context_internal().borrow().state(&self.widget.id).resize;
+            self.recalculate_pos_size(Point::default(), resize);
+
+            // the move is applied once the widget is finished
+            self.data.recalc_pos_size = true;
+        }
+
+        Rect::new(self.widget.pos, self.widget.size)
     }
 
     /// Returns the parent widget for this WidgetBuilder, which can be used to directly
@@ -502,6 +778,55 @@ impl<'a> WidgetBuilder<'a> {
         self
     }
 
+    /// Sets whether this widget and all of its children pass mouse input through to the widgets beneath them.  Such widgets
+    /// never take hover, clicks, or scrolling, even when [`wants_mouse`](#method.wants_mouse) is set.  If this widget starts a
+    /// [`new render group`](#method.new_render_group), that render group is also ignored when determining which render group
+    /// the mouse is over.  This is useful for decorative overlays, such as vignettes and frames, drawn on top of other widgets.
+    /// This may also be specified in the widget's [`theme`](index.html) by setting `hit_test: false`.
+    #[must_use]
+    pub fn input_passthrough(mut self, passthrough: bool) -> WidgetBuilder<'a> {
+        self.data.input_passthrough = passthrough;
+        self
+    }
+
+    /// Sets whether the movement reported in [`WidgetState::moved`](struct.WidgetState.html#structfield.moved) while this
+    /// widget is dragged uses the raw mouse movement, rather than the change in cursor position.  This allows drag to adjust
+    /// widgets, such as sliders and orbit controls, to keep working when the cursor reaches the edge of the screen.  This
+    /// falls back to the cursor position if the [`IO`](trait.IO.html) backend does not provide
+    /// [`raw mouse movement`](struct.Context.html#method.add_raw_mouse_delta).
+    #[must_use]
+    pub fn raw_mouse_drag(mut self, raw: bool) -> WidgetBuilder<'a> {
+        self.data.raw_mouse_drag = raw;
+        self
+    }
+
+    /// Sets whether the OS cursor is hidden and held in place while this widget is dragged, returning to where the drag
+    /// started once it ends.  This also enables [`raw_mouse_drag`](#method.raw_mouse_drag), as the cursor position no
+    /// longer changes.  See [`Context::cursor_locked`](struct.Context.html#method.cursor_locked).
+    #[must_use]
+    pub fn lock_cursor_on_drag(mut self, lock: bool) -> WidgetBuilder<'a> {
+        self.data.lock_cursor_on_drag = lock;
+        self
+    }
+
+    /// Sets whether this widget may receive keyboard focus by pressing Tab.  Tab moves the keyboard focus to the next
+    /// focusable widget within the same [`focus scope`](#method.focus_scope), in the order the widgets are created, while
+    /// Shift + Tab moves it to the previous one.  Widgets which are not [`enabled`](#method.enabled) are skipped.
+    #[must_use]
+    pub fn focusable(mut self, focusable: bool) -> WidgetBuilder<'a> {
+        self.data.focusable = focusable;
+        self
+    }
+
+    /// Specifies that this widget starts a new focus scope.  Tab navigation between its [`focusable`](#method.focusable)
+    /// children cycles within the scope, and does not move to widgets outside of it.  Modals and any widget starting a
+    /// [`new render group`](#method.new_render_group), including [`Windows`](struct.WindowBuilder.html), are always focus scopes.
+    #[must_use]
+    pub fn focus_scope(mut self) -> WidgetBuilder<'a> {
+        self.data.focus_scope = true;
+        self
+    }
+
     /// Sets whether this widget will interact with the mouse.  By default, widgets will not interact with the mouse, so this is set to `true`
     /// for buttons and similar.
     /// This may also be specified in the widget's [`theme`](index.html).
@@ -527,6 +852,27 @@ impl<'a> WidgetBuilder<'a> {
     pub fn id<T: Into<String>>(mut self, id: T) -> WidgetBuilder<'a> {
         self.widget.id = id.into();
         self.data.recalc_pos_size = true;
+        self.data.explicit_id = true;
+        self
+    }
+
+    /// Sets the `id` for this widget based on the specified number, which must be unique among widgets with the same
+    /// parent and theme.  This is an alternative to [`Frame::start_indexed`](struct.Frame.html#method.start_indexed),
+    /// useful when the number is not known until after the widget is started.
+    #[must_use]
+    pub fn id_num(self, num: u64) -> WidgetBuilder<'a> {
+        self.key(num)
+    }
+
+    /// Sets the `id` for this widget based on the specified `key`, which must be unique among widgets with the same
+    /// parent and theme.  See [`Frame::start_keyed`](struct.Frame.html#method.start_keyed).
+    #[must_use]
+    pub fn key<K: Display>(mut self, key: K) -> WidgetBuilder<'a> {
+        let theme = self.widget.theme_id.rsplit('/').next().unwrap_or_default();
+        let base = id_base(&self.frame.widget(self.parent).id, theme);
+        self.frame.release_id(&base);
+        self.widget.id = id_with_key(base, &key);
+        self.data.recalc_pos_size = true;
         self
     }
 
@@ -569,15 +915,125 @@ impl<'a> WidgetBuilder<'a> {
         self
     }
 
+    /// Specify how the widget's text is [`wrapped`](enum.TextWrap.html) when it is too long to
+    /// fit on a single line within the widget's inner area.
+    /// This may also be specified in the widget's [`theme`](index.html).
+    #[must_use]
+    pub fn text_wrap(mut self, wrap: TextWrap) -> WidgetBuilder<'a> {
+        self.widget.text_wrap = wrap;
+        self
+    }
+
+    /// Specify whether soft hyphens (U+00AD) in the widget's text mark places where a word
+    /// may be broken across lines.  When a word is broken at a soft hyphen, a hyphen is drawn
+    /// at the end of the line.  Soft hyphens are otherwise never drawn.
+    /// This may also be specified in the widget's [`theme`](index.html).
+    #[must_use]
+    pub fn text_soft_hyphens(mut self, soft_hyphens: bool) -> WidgetBuilder<'a> {
+        self.widget.text_soft_hyphens = soft_hyphens;
+        self
+    }
+
+    /// Multiplies the alpha of this widget's image and text colors by `alpha`, between 0.0 and 1.0
+    #[must_use]
+    pub(crate) fn fade(mut self, alpha: f32) -> WidgetBuilder<'a> {
+        let fade = Color { r: 255, g: 255, b: 255, a: (alpha.clamp(0.0, 1.0) * 255.0).round() as u8 };
+        self.widget.image_color = self.widget.image_color * fade;
+        self.widget.text_color = self.widget.text_color * fade;
+        self
+    }
+
+    /// Specify that only the first `chars` characters of the widget's text are drawn.  The text is
+    /// still laid out in full, so words do not jump between lines as more characters are revealed, such
+    /// as for a typewriter effect.  Characters which would not be drawn anyway, such as whitespace,
+    /// are still counted.
+    #[must_use]
+    pub fn text_reveal(mut self, chars: usize) -> WidgetBuilder<'a> {
+        self.widget.text_reveal = Some(chars);
+        self
+    }
+
     /// Specify `text` to display for this widget.  The widget must have a [`font`](#method.font)
-    /// specified to render text.
+    /// specified to render text.  Images may be shown inline with the text, such as a controller button in
+    /// "Press [img:gamepad/a] to continue", by writing `[img:{image_set_id}/{image_id}]`.  Inline images are drawn
+    /// at their base size, sitting on the text baseline, and wrap along with the surrounding words.  Each inline
+    /// image counts as a single character, for example in [`TextCarets`](struct.TextCarets.html).
     /// This may also be specified in the widget's [`theme`](index.html).
     #[must_use]
     pub fn text<T: Into<String>>(mut self, text: T) -> WidgetBuilder<'a> {
-        self.widget.text = Some(text.into());
+        let text = text.into();
+        let context = self.frame.context_internal().borrow();
+        let resolved = match context.resolve_text(&text) {
+            Cow::Borrowed(resolved) if resolved == text => None,
+            resolved => Some(resolved.into_owned()),
+        };
+        self.widget.set_resolved_text(resolved.unwrap_or(text), &context);
+        drop(context);
+        self
+    }
+
+    /// Specify a short, human readable `label` for this widget, exposed to screen readers via
+    /// [`Context.accessible_widgets`](struct.Context.html#method.accessible_widgets).  This is especially
+    /// useful for icon only buttons.  This may also be specified in the widget's [`theme`](index.html).
+    #[must_use]
+    pub fn aria_label<T: Into<String>>(mut self, label: T) -> WidgetBuilder<'a> {
+        self.widget.aria_label = Some(label.into());
         self
     }
 
+    /// Specify a longer `description` of this widget's purpose, exposed to screen readers via
+    /// [`Context.accessible_widgets`](struct.Context.html#method.accessible_widgets).
+    /// This may also be specified in the widget's [`theme`](index.html).
+    #[must_use]
+    pub fn description<T: Into<String>>(mut self, description: T) -> WidgetBuilder<'a> {
+        self.widget.description = Some(description.into());
+        self
+    }
+
+    /**
+    Attaches an arbitrary `value` to this widget with the specified `key`, replacing any value previously attached
+    with the same key.  This data is not used by Thyme, but is kept for use by the application, renderers, and tools,
+    such as marking a widget as anchored to a world position, or tagging it with an analytics ID.  The data for widgets
+    in the most recently drawn frame is available from
+    [`Context::widget_custom_data`](struct.Context.html#method.widget_custom_data).  Unlike the theme `custom`
+    values, read with [`custom_float`](#method.custom_float) and similar, this is set in code per widget.
+    **/
+    #[must_use]
+    pub fn custom<K: Into<String>, V: Into<CustomData>>(mut self, key: K, value: V) -> WidgetBuilder<'a> {
+        let key = key.into();
+        let value = value.into();
+        match self.widget.custom.iter_mut().find(|(id, _)| *id == key) {
+            None => self.widget.custom.push((key, value)),
+            Some((_, existing)) => *existing = value,
+        }
+        self
+    }
+
+    /// Specify the name of a custom fragment shader, registered with the renderer, used to draw this widget's
+    /// background and foreground images, such as with
+    /// [`GLRenderer::register_shader`](struct.GLRenderer.html#method.register_shader).  Renderers without the
+    /// named shader draw the images normally.  Custom shaders are currently only supported by the
+    /// [`GLRenderer`](struct.GLRenderer.html); the Glium renderer logs a warning and draws the images normally, and
+    /// the draw data renderer ignores them.  This may also be specified in the widget's [`theme`](index.html).
+    #[must_use]
+    pub fn shader<T: Into<String>>(mut self, shader: T) -> WidgetBuilder<'a> {
+        self.widget.shader = Some(shader.into());
+        self
+    }
+
+    /// Specify the platform mouse `cursor` shown while this widget is hovered with the mouse.
+    /// This may also be specified in the widget's [`theme`](index.html), where an image may be used instead.
+    #[must_use]
+    pub fn cursor(mut self, cursor: SystemCursor) -> WidgetBuilder<'a> {
+        self.data.cursor = Some(WidgetCursor::System(cursor));
+        self
+    }
+
+    /// Removes the mouse cursor from this widget, so it is not shown while the widget is hovered, and returns it
+    pub(crate) fn take_cursor(&mut self) -> Option<WidgetCursor> {
+        self.data.cursor.take()
+    }
+
     /// Specify `tooltip` to display as a simple tooltip if this widget is hovered with the mouse.
     /// The tooltip will use the "tooltip" theme which must be present in the theme.
     /// This may also be specified in the widget's [`theme`](index.html).
@@ -587,6 +1043,26 @@ impl<'a> WidgetBuilder<'a> {
         self
     }
 
+    /// Specify the time in milliseconds that the mouse must hover this widget before its
+    /// [`tooltip`](#method.tooltip_text) is shown, overriding the
+    /// [`tooltip_time`](struct.BuildOptions.html#structfield.tooltip_time).  If this widget is itself
+    /// a tooltip, this must be called before [`render_as_tooltip`](#method.render_as_tooltip).
+    #[must_use]
+    pub fn tooltip_delay(mut self, delay: u32) -> WidgetBuilder<'a> {
+        self.data.tooltip_delay = Some(delay);
+        self
+    }
+
+    /// Specify the time in milliseconds that this widget's [`tooltip`](#method.tooltip_text) is shown
+    /// before being hidden again, while the mouse continues to hover the widget.  By default, tooltips are
+    /// shown for as long as the widget is hovered.  If this widget is itself a tooltip, this must be called
+    /// before [`render_as_tooltip`](#method.render_as_tooltip).
+    #[must_use]
+    pub fn tooltip_duration(mut self, duration: u32) -> WidgetBuilder<'a> {
+        self.data.tooltip_duration = Some(duration);
+        self
+    }
+
     /// Specify a `font` for any text rendered by this widget.  A widget must have a font
     /// specified to render text.  The `font` must be registered in the theme's font definitions.
     /// This may also be specified in the widget's [`theme`](index.html).
@@ -632,6 +1108,28 @@ impl<'a> WidgetBuilder<'a> {
         self
     }
 
+    /// Specify solid colored triangles to draw for this widget, such as for charts, radial fills, or links
+    /// between nodes.  Each set of three `indices` into the `vertices` forms a triangle, and triangles with an
+    /// out of range index are skipped.  Vertex positions are relative to the widget's inner area, inside its
+    /// border, and the triangles are clipped to the widget.  Triangles are drawn above the foreground image
+    /// and below text.
+    #[must_use]
+    pub fn triangles(mut self, vertices: Vec<Vertex>, indices: Vec<u32>) -> WidgetBuilder<'a> {
+        self.widget.triangles = Some(WidgetTriangles { vertices, indices });
+        self
+    }
+
+    /// Specify a [`Transform`](struct.Transform.html), scaling and rotating this widget and all of its children
+    /// about a pivot point, such as for card flips, dial needles, or buttons that shrink when pressed.  The
+    /// transform is combined with any transforms of the parent widgets, and the mouse is tested against the
+    /// transformed widget.  Transforms do not affect layout, and clipping of rotated widgets is conservative,
+    /// using the bounding box of the rotated clip rectangle.
+    #[must_use]
+    pub fn transform(mut self, transform: Transform) -> WidgetBuilder<'a> {
+        self.data.transform = Some(transform);
+        self
+    }
+
     /// Specifies the default alignment of children added to this widget.  See [`Align`](enum.Align.html).
     /// This may be overridden by the child, either in the theme or by calling [`align`](#method.align).
     /// This may also be specified in the widget's [`theme`](index.html).
@@ -690,7 +1188,7 @@ impl<'a> WidgetBuilder<'a> {
 	/// cursor position.  See [`new_render_group`](#method.new_render_group).
 	#[must_use]
 	pub fn render_as_tooltip(mut self) -> WidgetBuilder<'a> {
-        let tooltip_pos = match self.frame.tooltip_ready() {
+        let tooltip_pos = match self.frame.tooltip_ready_with(self.data.tooltip_delay, self.data.tooltip_duration) {
             None => return self.visible(false),
             Some(pos) => pos,
         };
@@ -878,6 +1376,26 @@ impl<'a> WidgetBuilder<'a> {
         self
     }
 
+    /// Causes this widget to be [`clicked`](struct.WidgetState.html#structfield.clicked) when the mouse is
+    /// first pressed on it, and then repeatedly while the mouse is held down.  The first repeat occurs after
+    /// `initial_delay` milliseconds, and subsequent repeats every `interval` milliseconds, or at most once per
+    /// frame.  Releasing the mouse does not produce an additional click.  This is useful for spinner and
+    /// scrollbar buttons.
+    ///
+    /// # Example
+    /// ```
+    /// fn counter(ui: &mut Frame, value: &mut i32) {
+    ///     if ui.start("increase").wants_mouse(true).repeat_on_hold(400, 50).finish().clicked {
+    ///         *value += 1;
+    ///     }
+    /// }
+    /// ```
+    #[must_use]
+    pub fn repeat_on_hold(mut self, initial_delay: u32, interval: u32) -> WidgetBuilder<'a> {
+        self.data.repeat_on_hold = Some((initial_delay, interval));
+        self
+    }
+
     /// Sets whether the widget's [`AnimState`](struct.AnimState.html) will
     /// include the `active` [`AnimStateKey`](enum.AnimStateKey.html).
     #[must_use]
@@ -974,8 +1492,82 @@ impl<'a> WidgetBuilder<'a> {
             self.widget.text = Some(text);
         }
 
-        if let Some(result) = self.calculate_font_layout_cursor(*cursor) {
-            *cursor = result;
+        if let Some(result) = self.calculate_font_layout() {
+            *cursor = result.cursor;
+        }
+
+        self
+    }
+
+    /// Causes this widget to layout its current text, wrapping it to the widget's inner width, and writes the
+    /// total height of all lines of the text into `height`, in logical pixels.  This allows a parent to be sized
+    /// to fit multiple lines of text before the widget is finished.  If this widget does not have a font or has no
+    /// text, nothing is written into `height`.  See also [`HeightRelative::Text`](enum.HeightRelative.html#variant.Text).
+    #[must_use]
+    pub fn trigger_text_height(mut self, height: &mut f32) -> WidgetBuilder<'a> {
+        let (text, state_moved, state_resize) = {
+            let internal = self.frame.context_internal().borrow();
+            let state = internal.state(&self.widget.id);
+            (state.text.clone(), state.moved, state.resize)
+        };
+
+        if self.data.recalc_pos_size {
+            self.recalculate_pos_size(state_moved, state_resize);
+        }
+
+        if let Some(text) = text {
+            self.widget.text = Some(text);
+        }
+
+        if let Some(result) = self.calculate_font_layout() {
+            *height = result.height;
+        }
+
+        self
+    }
+
+    /// Causes this widget to layout its current text, and writes the position of the caret at each character into
+    /// `carets`, for placing a caret or selection at any point in the text, or finding the character under the mouse.
+    /// If this widget does not have a font, nothing is written into `carets`.  See [`TextCarets`](struct.TextCarets.html).
+    #[must_use]
+    pub fn trigger_text_carets(mut self, carets: &mut TextCarets) -> WidgetBuilder<'a> {
+        let (text, state_moved, state_resize) = {
+            let internal = self.frame.context_internal().borrow();
+            let state = internal.state(&self.widget.id);
+            (state.text.clone(), state.moved, state.resize)
+        };
+        if self.data.recalc_pos_size {
+            self.recalculate_pos_size(state_moved, state_resize);
+        }
+
+        if let Some(text) = text {
+            self.widget.text = Some(text);
+        }
+
+        if let Some(result) = self.calculate_text_carets() {
+            *carets = result;
+        }
+
+        self
+    }
+
+    /// Causes this widget to layout its current text, splitting it into pages which each consist of the
+    /// lines of text fitting within the widget's inner area.  The index, in characters, of the first
+    /// character drawn on each page is written into `pages`, with the first page always starting at zero.
+    /// If this widget does not have a font or has no text, nothing is written into `pages`.
+    #[must_use]
+    pub fn trigger_text_pages(mut self, pages: &mut Vec<usize>) -> WidgetBuilder<'a> {
+        let (state_moved, state_resize) = {
+            let internal = self.frame.context_internal().borrow();
+            let state = internal.state(&self.widget.id);
+            (state.moved, state.resize)
+        };
+        if self.data.recalc_pos_size {
+            self.recalculate_pos_size(state_moved, state_resize);
+        }
+
+        if let Some(result) = self.calculate_text_pages() {
+            *pages = result;
         }
 
         self
@@ -987,54 +1579,146 @@ impl<'a> WidgetBuilder<'a> {
             _ => return 0.0,
         };
 
-        let internal = self.frame.context_internal().borrow();
-        let font = internal.themes().font(font_def.handle);
+        let profile = bench::profile_start();
+        let mut internal = self.frame.context_internal().borrow_mut();
 
-        let mut cursor = Point::default();
         let params = FontDrawParams {
             area_size: Point::new(f32::MAX, f32::MAX),
             pos: Point::default(),
             indent: self.widget.text_indent(),
             align: Align::TopLeft,
+            wrap: self.widget.text_wrap(),
+            soft_hyphens: self.widget.text_soft_hyphens(),
             color: Color::white(),
             scale_factor: internal.scale_factor(),
+            visible_chars: None,
         };
 
-        font.layout(params, text, &mut cursor);
+        let cursor = internal.text_layout(&font_def, params, text).cursor;
+        bench::profile_end(profile, &self.widget.theme_id, ProfileKind::Text);
 
         cursor.x / internal.scale_factor()
     }
 
-    fn calculate_font_layout_cursor(&self, cursor: Point) -> Option<Point> {
+    fn calculate_wrapped_text_height(&self, width: f32) -> f32 {
+        let (text, font_def) = match (&self.widget.text, self.widget.font) {
+            (Some(text), Some(font)) => (text, font),
+            _ => return 0.0,
+        };
+
+        let profile = bench::profile_start();
+        let mut internal = self.frame.context_internal().borrow_mut();
+        let scale = internal.scale_factor();
+
+        let params = FontDrawParams {
+            area_size: Point::new(width * scale, f32::MAX),
+            pos: Point::default(),
+            indent: self.widget.text_indent(),
+            align: Align::TopLeft,
+            wrap: self.widget.text_wrap(),
+            soft_hyphens: self.widget.text_soft_hyphens(),
+            color: Color::white(),
+            scale_factor: scale,
+            visible_chars: None,
+        };
+
+        let height = internal.text_layout(&font_def, params, text).height;
+        bench::profile_end(profile, &self.widget.theme_id, ProfileKind::Text);
+
+        height / scale
+    }
+
+    fn calculate_font_layout(&self) -> Option<TextLayout> {
         let (text, font_def) = match (&self.widget.text, self.widget.font) {
             (Some(text), Some(font)) => (text, font),
             _ => return None,
         };
 
+        let profile = bench::profile_start();
         let widget = &self.widget;
         let fg_pos = Point::default();
         let fg_size = widget.inner_size();
         let align = widget.text_align();
 
-        let internal = self.frame.context_internal().borrow();
+        let mut internal = self.frame.context_internal().borrow_mut();
         let scale = internal.scale_factor();
-        let font = internal.themes().font(font_def.handle);
         let indent = widget.text_indent();
 
-        let mut scaled_cursor = cursor * scale;
-
         let params = FontDrawParams {
             area_size: fg_size * scale,
             pos: fg_pos * scale,
             indent,
             align,
+            wrap: widget.text_wrap(),
+            soft_hyphens: widget.text_soft_hyphens(),
             color: Color::white(),
             scale_factor: internal.scale_factor(),
+            visible_chars: None,
         };
 
-        font.layout(params, text, &mut scaled_cursor);
+        let layout = internal.text_layout(&font_def, params, text);
+        bench::profile_end(profile, &widget.theme_id, ProfileKind::Text);
 
-        Some(scaled_cursor / scale)
+        Some(TextLayout { cursor: layout.cursor / scale, height: layout.height / scale })
+    }
+
+    fn calculate_text_carets(&self) -> Option<TextCarets> {
+        let font_def = self.widget.font?;
+        let text = self.widget.text.as_deref().unwrap_or_default();
+
+        let profile = bench::profile_start();
+        let widget = &self.widget;
+        let internal = self.frame.context_internal().borrow();
+        let scale = internal.scale_factor();
+
+        let params = FontDrawParams {
+            area_size: widget.inner_size() * scale,
+            pos: Point::default(),
+            indent: widget.text_indent(),
+            align: widget.text_align(),
+            wrap: widget.text_wrap(),
+            soft_hyphens: widget.text_soft_hyphens(),
+            color: Color::white(),
+            scale_factor: scale,
+            visible_chars: None,
+        };
+
+        let rects = internal.text_carets(&font_def, params, text).into_iter()
+            .map(|rect| Rect::new(rect.pos / scale, rect.size / scale))
+            .collect();
+        bench::profile_end(profile, &widget.theme_id, ProfileKind::Text);
+
+        let origin = widget.pos + Point::new(widget.border.left, widget.border.top);
+        Some(TextCarets::new(origin, rects))
+    }
+
+    fn calculate_text_pages(&self) -> Option<Vec<usize>> {
+        let (text, font_def) = match (&self.widget.text, self.widget.font) {
+            (Some(text), Some(font)) => (text, font),
+            _ => return None,
+        };
+
+        let profile = bench::profile_start();
+        let widget = &self.widget;
+        let internal = self.frame.context_internal().borrow();
+        let scale = internal.scale_factor();
+
+        let params = FontDrawParams {
+            area_size: widget.inner_size() * scale,
+            pos: Point::default(),
+            indent: widget.text_indent(),
+            align: Align::TopLeft,
+            wrap: widget.text_wrap(),
+            soft_hyphens: widget.text_soft_hyphens(),
+            color: Color::white(),
+            scale_factor: scale,
+            visible_chars: None,
+        };
+
+        let pages = internal.text_pages(&font_def, params, text);
+        bench::profile_end(profile, &widget.theme_id, ProfileKind::Text);
+
+        Some(pages)
     }
 
     /// Turns this builder into a WindowBuilder.  You should use all `WidgetBuilder` methods
@@ -1103,17 +1787,34 @@ impl<'a> WidgetBuilder<'a> {
     pub(crate) fn finish_with<F: FnOnce(&mut Frame)>(mut self, f: Option<F>) -> (&'a mut Frame, WidgetState) {
         if !self.widget.visible { return (self.frame, WidgetState::hidden()); }
 
-        let (state, text, in_modal_tree) = {
+        if !self.frame.within_widget_limits(&self.widget.id, self.data.next_render_group.is_some()) {
+            self.widget.visible = false;
+            return (self.frame, WidgetState::hidden());
+        }
+
+        let (state, text, in_modal_tree, transition, elapsed) = {
             let internal = self.frame.context_internal().borrow();
             let state = internal.state(&self.widget.id);
 
             let text = state.text.as_ref().map(|t| t.to_string());
 
             let in_modal_tree = Some(self.widget.id()) == internal.modal_id();
+            let transition = internal.modal_transition(&self.widget.id);
 
-            (state.copy_data(), text, in_modal_tree)
+            (state.copy_data(), text, in_modal_tree, transition, internal.time_millis())
         };
 
+        let mut effect_opacity = 1.0;
+        if let Some((effect, start)) = state.effect {
+            let elapsed = elapsed.saturating_sub(start);
+            if elapsed < effect.duration_millis() {
+                let (transform, opacity) = effect.at(elapsed);
+                self.data.effect = transform;
+                effect_opacity = opacity;
+                self.frame.request_redraw();
+            }
+        }
+
         if let Some(text) = text {
             self.widget.text = Some(text);
         }
@@ -1125,6 +1826,11 @@ impl<'a> WidgetBuilder<'a> {
             return (self.frame, WidgetState::hidden());
         }
 
+        let appeared = self.data.explicit_id &&
+            self.frame.context_internal().borrow_mut().mark_present(&self.widget.id);
+
+        let profile = bench::profile_start();
+
         if self.data.recalc_pos_size {
             self.recalculate_pos_size(state.moved, state.resize);
         }
@@ -1132,14 +1838,32 @@ impl<'a> WidgetBuilder<'a> {
         let self_pos = self.widget.pos;
         let self_size = self.widget.size;
         let mut self_bounds = Rect::new(self_pos, self_size);
+
+        let mut transition_opacity = 1.0;
+        if let Some((transition, shown)) = transition {
+            let (transform, opacity) = transition.at(shown, self_size);
+            self.data.transition = transform;
+            transition_opacity = opacity;
+            if shown < 1.0 {
+                self.frame.request_redraw();
+            }
+        }
+
+        let parent_transform = self.widget.transform.unwrap_or_default();
+        let local_transform = self.data.local_transform(self_bounds);
+        if let Some(local) = local_transform {
+            self.widget.apply_local_transform(local);
+        }
+
         let old_max_child_bounds = self.frame.max_child_bounds();
+        let old_children_bounds = self.frame.children_bounds();
 
         // set modal tree value only if a match is found
         if in_modal_tree {
             {
                 let mut internal = self.frame.context_internal().borrow_mut();
                 internal.mut_modal(|modal| {
-                    modal.bounds = self_bounds;
+                    modal.bounds = self.widget.screen_bounds(self_bounds);
                 });
             }
             self.frame.in_modal_tree = true;
@@ -1147,11 +1871,21 @@ impl<'a> WidgetBuilder<'a> {
 
         let prev_rend_group = self.frame.cur_render_group();
 
+        let prev_passthrough_tree = self.frame.in_passthrough_tree;
+        if self.data.input_passthrough {
+            self.frame.in_passthrough_tree = true;
+        }
+
         if let Some(order) = self.data.next_render_group {
-            self.frame.next_render_group(self_bounds, self.widget.id.to_string(), order);
+            let bounds = self.widget.screen_bounds(self_bounds);
+            self.frame.next_render_group(bounds, self.widget.id.to_string(), order, self.data.input_passthrough);
         }
 
         let widget_index = self.frame.num_widgets();
+        if self.data.focus_scope || in_modal_tree || self.data.next_render_group.is_some() {
+            self.widget.focus_scope = widget_index;
+        }
+        self.widget.focusable = self.data.focusable && self.data.enabled;
         self.frame.push_widget(self.widget);
 
         let mut rebound_rend_group = false;
@@ -1160,15 +1894,20 @@ impl<'a> WidgetBuilder<'a> {
         if let Some(f) = f {
             // push the max_child pos and parent index
             self.frame.set_max_child_bounds(self_bounds);
+            self.frame.set_children_bounds(None);
             let old_parent_index = self.frame.parent_index();
             self.frame.set_parent_index(widget_index);
 
             // build all children
+            self.frame.enter_children();
             (f)(self.frame);
+            self.frame.exit_children();
 
             self.frame.set_parent_index(old_parent_index);
             let this_children_max_bounds = self.frame.max_child_bounds();
             self.frame.set_parent_max_child_bounds(this_children_max_bounds);
+            let this_children_bounds = self.frame.children_bounds();
+            self.frame.set_parent_children_bounds(this_children_bounds);
 
             // adjust widget size if needed for Child relative size
             if self.data.height_from == HeightRelative::Children {
@@ -1203,21 +1942,57 @@ impl<'a> WidgetBuilder<'a> {
             }
         }
 
+        // the pivot point moves along with the widget if it was resized or moved above
+        if let Some(old_local) = local_transform {
+            let widget = self.frame.widget(widget_index);
+            let local = self.data.local_transform(Rect::new(widget.pos, widget.size)).unwrap_or_default();
+            if let (true, Some(old_inverse)) = (local != old_local, (parent_transform * old_local).inverse()) {
+                let adjust = parent_transform * local * old_inverse;
+                for index in widget_index..self.frame.num_widgets() {
+                    let widget = self.frame.widget_mut(index);
+                    widget.transform = widget.transform.map(|transform| adjust * transform);
+                }
+            }
+        }
+
+        // the modal transition fades the modal along with all of its children
+        if transition_opacity < 1.0 {
+            let fade = Color { r: 255, g: 255, b: 255, a: (transition_opacity * 255.0).round() as u8 };
+            for index in widget_index..self.frame.num_widgets() {
+                let widget = self.frame.widget_mut(index);
+                widget.image_color = widget.image_color * fade;
+                widget.text_color = widget.text_color * fade;
+            }
+        }
+
         if rebound_rend_group {
             // if we just created the render group, rebound it
             if self.data.next_render_group.is_some() {
-                self.frame.rebound_cur_render_group(self_bounds);
+                let bounds = self.frame.widget(widget_index).screen_bounds(self_bounds);
+                self.frame.rebound_cur_render_group(bounds);
             }
         }
 
         if !self.data.unparent {
             self.frame.set_max_child_bounds(old_max_child_bounds.max(self_bounds));
+            self.frame.set_children_bounds(Some(old_children_bounds.map_or(self_bounds, |bounds| bounds.max(self_bounds))));
         } else {
             self.frame.set_max_child_bounds(old_max_child_bounds);
+            self.frame.set_children_bounds(old_children_bounds);
         }
 
         let (clicked, mut anim_state, mut dragged, button) = if self.data.enabled && self.data.wants_mouse {
-            let mouse_state = self.frame.check_mouse_state(widget_index);
+            let mut mouse_state = self.frame.check_mouse_state(widget_index);
+            if mouse_state.anim.contains(AnimStateKey::Pressed) && (self.data.raw_mouse_drag || self.data.lock_cursor_on_drag) {
+                self.frame.use_raw_mouse_drag();
+                if let Some(delta) = self.frame.context_internal().borrow().raw_mouse_delta() {
+                    mouse_state.dragged = delta;
+                }
+
+                if self.data.lock_cursor_on_drag {
+                    self.frame.lock_cursor();
+                }
+            }
             (mouse_state.clicked, mouse_state.anim, mouse_state.dragged, mouse_state.button)
         } else {
             (false, AnimState::disabled(), Point::default(), None)
@@ -1230,11 +2005,58 @@ impl<'a> WidgetBuilder<'a> {
             }
         }
 
-        let state = WidgetState::new(anim_state, clicked, dragged, button);
+        let mut state = WidgetState::new(anim_state, clicked, dragged, button);
+        state.appeared = appeared;
+
+        match (state.pressed, state.mouse_button) {
+            (true, Some(button)) => {
+                let (held_prev, held) = self.frame.context_internal().borrow().mouse_held_millis(button);
+                let long_press_time = self.frame.context().options().long_press_time;
+
+                state.long_pressed = held >= long_press_time && !matches!(held_prev, Some(prev) if prev >= long_press_time);
+
+                if let Some((delay, interval)) = self.data.repeat_on_hold {
+                    state.clicked = match held_prev {
+                        None => true,
+                        Some(prev) => repeat_count(prev, delay, interval) != repeat_count(held, delay, interval),
+                    };
+                }
+            },
+            _ => {
+                // the click was already delivered when the mouse was pressed
+                if self.data.repeat_on_hold.is_some() {
+                    state.clicked = false;
+                }
+            }
+        }
+
+        if state.clicked {
+            if let Some(sound) = self.data.sound_click.as_ref() {
+                self.frame.play_sound(sound);
+            }
+            self.frame.haptic(HapticEvent::Confirm);
+
+            let id = self.frame.widget(widget_index).id().to_string();
+            let button = state.mouse_button.unwrap_or(MouseButton::Left);
+            self.frame.context_internal().borrow_mut().record_interaction(&id, Interaction::Clicked);
+            self.frame.push_event(UiEvent::Clicked { id, button });
+        }
 
         if state.hovered {
+            if let Some(cursor) = self.data.cursor {
+                self.frame.set_hover_cursor(cursor);
+            }
+
+            if let Some(sound) = self.data.sound_hover.as_ref() {
+                let id = self.frame.widget(widget_index).id();
+                self.frame.context_internal().borrow_mut().play_hover_sound(id, sound);
+            }
+
             if let Some(tooltip) = self.data.tooltip.take() {
-                self.frame.tooltip_label("tooltip", tooltip);
+                let mut builder = self.frame.start("tooltip").text(tooltip);
+                builder.data.tooltip_delay = self.data.tooltip_delay;
+                builder.data.tooltip_duration = self.data.tooltip_duration;
+                builder.render_as_tooltip().finish();
             }
         }
 
@@ -1247,6 +2069,8 @@ impl<'a> WidgetBuilder<'a> {
             self.frame.in_modal_tree = false;
         }
 
+        self.frame.in_passthrough_tree = prev_passthrough_tree;
+
         if self.data.active {
             anim_state.add(AnimStateKey::Active);
         }
@@ -1259,7 +2083,17 @@ impl<'a> WidgetBuilder<'a> {
             anim_state.set(AnimStateKey::Pressed, pressed);
         }
 
-        self.frame.widget_mut(widget_index).anim_state = anim_state;
+        let widget = self.frame.widget_mut(widget_index);
+        widget.anim_state = anim_state;
+        if let Some((_, tint)) = self.data.image_tints.iter().find(|(state, _)| *state == anim_state) {
+            widget.image_color = widget.image_color * *tint;
+        }
+
+        if effect_opacity < 1.0 {
+            let fade = Color { r: 255, g: 255, b: 255, a: (effect_opacity * 255.0).round() as u8 };
+            widget.image_color = widget.image_color * fade;
+            widget.text_color = widget.text_color * fade;
+        }
 
         
         let size = self.frame.widget(widget_index).size;
@@ -1292,8 +2126,25 @@ impl<'a> WidgetBuilder<'a> {
                         parent.cursor.y += y + parent.layout_spacing.y;
                     }
                 }
+                Masonry(columns) => {
+                    let columns = columns.max(1) as usize;
+                    if parent.masonry_heights.len() != columns {
+                        parent.masonry_heights = vec![0.0; columns];
+                    }
+
+                    // the child was placed in the shortest column, so extend it and move to the new shortest
+                    let spacing = parent.layout_spacing;
+                    let column_width = (parent.inner_size().x - spacing.x * (columns - 1) as f32) / columns as f32;
+                    let placed = shortest_column(&parent.masonry_heights);
+                    parent.masonry_heights[placed] = parent.cursor.y + size.y + spacing.y;
+
+                    let next = shortest_column(&parent.masonry_heights);
+                    parent.cursor = Point::new(next as f32 * (column_width + spacing.x), parent.masonry_heights[next]);
+                }
             }
         }
+
+        bench::profile_end(profile, self.frame.widget(widget_index).theme_id(), ProfileKind::Build);
         
         (self.frame, state)
     }
@@ -1,4 +1,35 @@
-use crate::{Frame, widget::WidgetBuilder, WidgetState, Point};
+use crate::{Frame, widget::WidgetBuilder, WidgetState, Point, HeightRelative, MouseButton};
+
+/// The offset between successive windows placed with [`WindowPlacement::Cascade`](enum.WindowPlacement.html#variant.Cascade)
+const CASCADE_STEP: Point = Point { x: 30.0, y: 30.0 };
+
+/**
+A policy for positioning a window the first time it is opened, set with
+[`WindowBuilder::placement`](struct.WindowBuilder.html#method.placement).  The policy is applied once per window,
+recorded in the [`placed`](struct.PersistentState.html#structfield.placed) persistent state, so the window keeps
+any position the user moves it to.  Windows restored from a [`SavedContext`](struct.SavedContext.html) keep their
+saved position.  The placed window is kept within the display.
+**/
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum WindowPlacement {
+    /// The window is positioned by its theme and builder, as with any other widget
+    #[default]
+    Theme,
+
+    /// The window is centered on the display
+    CenterScreen,
+
+    /// The window is centered on its parent widget
+    CenterParent,
+
+    /// The window's top left corner is placed at the mouse cursor
+    AtCursor,
+
+    /// The window is placed down and to the right of the most recent cascaded window, starting over from the top
+    /// left of the display when it would no longer fit
+    Cascade,
+}
+
 
 /**
 A [`WidgetBuilder`](struct.WidgetBuilder.html) specifically for creating windows.
@@ -92,6 +123,25 @@ impl<'a> WindowBuilder<'a> {
         self
     }
 
+    /// Specifies that this window is always drawn above other windows and render groups, other than those
+    /// also always on top.  Useful for overlays such as FPS counters.
+    /// See [`WidgetBuilder::always_top`](struct.WidgetBuilder.html#method.always_top)
+    #[must_use]
+    pub fn always_on_top(mut self) -> WindowBuilder<'a> {
+        self.builder = self.builder.always_top();
+        self
+    }
+
+    /// Specifies that this window and all of its children ignore the mouse, passing all mouse input through to
+    /// the widgets beneath, while still being drawn.  The titlebar, close button, and resize handle of a
+    /// click through window cannot be used, so these are usually disabled.  Useful for overlays layered over
+    /// interactive UI.  See [`WidgetBuilder::input_passthrough`](struct.WidgetBuilder.html#method.input_passthrough)
+    #[must_use]
+    pub fn click_through(mut self) -> WindowBuilder<'a> {
+        self.builder = self.builder.input_passthrough(true);
+        self
+    }
+
     /// Specifies whether the created window should show a titlebar.
     #[must_use]
     pub fn with_titlebar(mut self, with_titlebar: bool) -> WindowBuilder<'a> {
@@ -131,17 +181,112 @@ impl<'a> WindowBuilder<'a> {
         self
     }
 
+    /// Specifies whether the user can shade the window by double clicking its titlebar, collapsing the window to
+    /// just its titlebar and border.  Double clicking again restores the window.  The shaded state is stored in the
+    /// window's [`PersistentState`](struct.PersistentState.html), and may also be set with
+    /// [`Frame::set_shaded`](struct.Frame.html#method.set_shaded).  Defaults to false.
+    #[must_use]
+    pub fn shadeable(mut self, shadeable: bool) -> WindowBuilder<'a> {
+        self.state.shadeable = shadeable;
+        self
+    }
+
+    /// Specifies how the window is positioned the first time it is opened.  See
+    /// [`WindowPlacement`](enum.WindowPlacement.html).  Defaults to using the position from the theme.
+    #[must_use]
+    pub fn placement(mut self, placement: WindowPlacement) -> WindowBuilder<'a> {
+        self.state.placement = placement;
+        self
+    }
+
+    /**
+    Makes this window a child of the window with the specified `parent` id.  Child windows are closed when
+    their parent is closed or is not open, and are always drawn above their parent.
+    If `docked`, the child also moves along with the parent when the parent is dragged.  This is useful for
+    tool palettes and property popouts.
+    **/
+    #[must_use]
+    pub fn parent_window(mut self, parent: &str, docked: bool) -> WindowBuilder<'a> {
+        self.state.parent = Some((parent.to_string(), docked));
+        self
+    }
+
+    /// Moves the window to its placement, if it has not already been placed
+    fn place(builder: &mut WidgetBuilder, placement: WindowPlacement) {
+        let id = builder.widget.id().to_string();
+        if !builder.frame.is_open(&id) || builder.frame.context_internal().borrow().state(&id).placed {
+            return;
+        }
+
+        let bounds = builder.layout_bounds();
+        let size = bounds.size;
+        let display = builder.frame.display_size();
+
+        let pos = match placement {
+            WindowPlacement::Theme => bounds.pos,
+            WindowPlacement::CenterScreen => (display - size) * 0.5,
+            WindowPlacement::CenterParent => {
+                let parent = builder.parent();
+                parent.pos() + (parent.size() - size) * 0.5
+            },
+            WindowPlacement::AtCursor => builder.frame.mouse_pos(),
+            WindowPlacement::Cascade => {
+                let mut internal = builder.frame.context_internal().borrow_mut();
+                let pos = match internal.last_cascade_pos() {
+                    None => CASCADE_STEP,
+                    Some(last) => last + CASCADE_STEP,
+                };
+                let fits = pos.x + size.x <= display.x && pos.y + size.y <= display.y;
+                let pos = if fits { pos } else { CASCADE_STEP };
+                internal.set_last_cascade_pos(pos);
+                pos
+            },
+        };
+
+        // keep the window within the display, favoring its top left corner
+        let pos = Point::new(
+            pos.x.min(display.x - size.x).max(0.0),
+            pos.y.min(display.y - size.y).max(0.0),
+        );
+
+        builder.frame.modify(id, |state| {
+            state.moved = pos - bounds.pos;
+            state.placed = true;
+        });
+    }
+
     /// Consumes the builder and adds a widget to the current frame.  The
     /// returned data includes information about the animation state and
     /// mouse interactions of the created element.
     /// The provided closure is called to enable adding children to this window.
     pub fn children<F: FnOnce(&mut Frame)>(self, children: F) -> WidgetState {
-        let builder = self.builder;
+        let mut builder = self.builder;
         let state = self.state;
         let id = builder.widget.id().to_string();
 
+        if let Some((parent, docked)) = state.parent.as_ref() {
+            builder.frame.context_internal().borrow_mut().set_window_parent(&id, parent, *docked);
+            if !builder.frame.is_open(parent) {
+                builder.frame.close(&id);
+            }
+        }
+
+        if state.placement != WindowPlacement::Theme {
+            WindowBuilder::place(&mut builder, state.placement);
+        }
+
+        let shaded = state.with_titlebar && state.shadeable && builder.frame.is_shaded(&id);
+        if shaded {
+            // collapse the window to its top and bottom border, where the titlebar is drawn
+            let resize = builder.frame.context_internal().borrow().state(&id).resize;
+            let height = builder.widget().border().vertical() - resize.y;
+            builder = builder.height_from(HeightRelative::Normal).height(height);
+        }
+
         builder.children(|ui| {
-            (children)(ui);
+            if !shaded {
+                (children)(ui);
+            }
 
             let drag_move = if state.with_titlebar {
                 let result = ui.start("titlebar")
@@ -161,6 +306,11 @@ impl<'a> WindowBuilder<'a> {
                     }
                 });
 
+                let left_click = result.clicked && result.mouse_button == Some(MouseButton::Left);
+                if state.shadeable && left_click && ui.check_double_click(&id) {
+                    ui.set_shaded(&id, !shaded);
+                }
+
                 if state.moveable && result.pressed {
                     result.moved
                 } else {
@@ -174,9 +324,16 @@ impl<'a> WindowBuilder<'a> {
                 ui.modify(&id, |state| {
                     state.moved = state.moved + drag_move;
                 });
+
+                let docked = ui.context_internal().borrow().child_windows(&id, true);
+                for child in docked {
+                    ui.modify(child, |state| {
+                        state.moved = state.moved + drag_move;
+                    });
+                }
             }
 
-            if state.resizable {
+            if state.resizable && !shaded {
                 let result = ui.button("handle", "");
                 if result.pressed {
                     ui.modify(&id, |state| {
@@ -193,7 +350,10 @@ struct WindowState {
     with_close_button: bool,
     moveable: bool,
     resizable: bool,
+    shadeable: bool,
     title: Option<String>,
+    placement: WindowPlacement,
+    parent: Option<(String, bool)>,
 }
 
 impl Default for WindowState {
@@ -203,7 +363,10 @@ impl Default for WindowState {
             with_close_button: true,
             moveable: true,
             resizable: true,
+            shadeable: false,
             title: None,
+            placement: WindowPlacement::Theme,
+            parent: None,
         }
     }
 }
\ No newline at end of file
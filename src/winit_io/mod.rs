@@ -1,13 +1,14 @@
 use std::error::Error;
 
-use winit::event::{ElementState, MouseButton, MouseScrollDelta, WindowEvent};
+use winit::dpi::PhysicalPosition;
+use winit::event::{DeviceEvent, ElementState, MouseButton, MouseScrollDelta, WindowEvent};
 use winit::keyboard::{Key, NamedKey, ModifiersKeyState};
-use winit::window::Window;
+use winit::window::{CursorGrabMode, CursorIcon, Window};
 
 use crate::point::Point;
 use crate::context::{InputModifiers, Context};
 use crate::render::IO;
-use crate::KeyEvent;
+use crate::{KeyEvent, SystemCursor};
 
 /**
 A Thyme Input/Output adapter for [`winit`](https://github.com/rust-windowing/winit).
@@ -39,6 +40,14 @@ fn main_loop(event_loop: winit::EventLoop<()>, thyme: thyme::Context) {
 pub struct WinitIo {
     scale_factor: f32,
     display_size: Point,
+    cursor: SystemCursor,
+
+    // whether the scale factor was explicitly specified, rather than following the window's monitor
+    fixed_scale: bool,
+
+    // the last cursor position in the window, and where the cursor is held while locked
+    cursor_pos: PhysicalPosition<f64>,
+    locked_pos: Option<PhysicalPosition<f64>>,
 }
 
 impl IO for WinitIo {
@@ -49,17 +58,88 @@ impl IO for WinitIo {
 
 impl WinitIo {
     /// Creates a new adapter from the given `EventLoop`, with the specified initial display size,
-    /// in logical pixels.  This may change over time.
+    /// in logical pixels.  This may change over time.  The scale factor is taken from the primary
+    /// monitor, or if no primary monitor is reported, such as under some Wayland compositors, from the
+    /// window's current monitor or the window itself.  The scale factor is updated when the window
+    /// moves to a monitor with a different scale factor.
     pub fn new(
         window: &Window,
         logical_display_size: Point,
     ) -> Result<WinitIo, WinitError> {
-        let monitor = window.primary_monitor().ok_or(WinitError::PrimaryMonitorNotFound)?;
-        let scale_factor = monitor.scale_factor() as f32;
-        Ok(WinitIo {
+        let monitor = window.primary_monitor().or_else(|| window.current_monitor());
+        let scale_factor = match monitor {
+            Some(monitor) => monitor.scale_factor(),
+            None => {
+                log::info!("No monitor found, using the window scale factor");
+                window.scale_factor()
+            }
+        };
+
+        let mut io = WinitIo::with_scale_factor(logical_display_size, scale_factor as f32);
+        io.fixed_scale = false;
+        Ok(io)
+    }
+
+    /// Creates a new adapter with the specified initial display size, in logical pixels, and an explicit
+    /// `scale_factor`, which is kept even if the window moves to a monitor with a different scale factor.
+    /// This is useful in headless environments, or to let users override the scale of the UI.
+    pub fn with_scale_factor(logical_display_size: Point, scale_factor: f32) -> WinitIo {
+        WinitIo {
             scale_factor,
             display_size: logical_display_size * scale_factor,
-        })
+            cursor: SystemCursor::Default,
+            fixed_scale: true,
+            cursor_pos: PhysicalPosition::default(),
+            locked_pos: None,
+        }
+    }
+
+    /// Applies the [`system cursor`](struct.Context.html#method.system_cursor) requested by the most
+    /// recently drawn frame to the `window`.  This should be called after drawing each frame.  The window
+    /// cursor is only changed when the requested cursor changes.  This also hides and holds the cursor in place
+    /// while it is [`locked`](struct.Context.html#method.cursor_locked).
+    pub fn update_cursor(&mut self, context: &Context, window: &Window) {
+        self.update_cursor_lock(context.cursor_locked(), window);
+
+        let cursor = context.system_cursor();
+        if cursor == self.cursor { return; }
+
+        self.cursor = cursor;
+        window.set_cursor(cursor_icon(cursor));
+    }
+
+    fn update_cursor_lock(&mut self, locked: bool, window: &Window) {
+        match (locked, self.locked_pos) {
+            (true, None) => {
+                // not all platforms support locking the cursor, so confine it and move it back each frame instead
+                if window.set_cursor_grab(CursorGrabMode::Locked).is_err() {
+                    let _ = window.set_cursor_grab(CursorGrabMode::Confined);
+                }
+                window.set_cursor_visible(false);
+                self.locked_pos = Some(self.cursor_pos);
+            },
+            (true, Some(pos)) => {
+                if pos != self.cursor_pos {
+                    let _ = window.set_cursor_position(pos);
+                }
+            },
+            (false, Some(pos)) => {
+                let _ = window.set_cursor_grab(CursorGrabMode::None);
+                let _ = window.set_cursor_position(pos);
+                window.set_cursor_visible(true);
+                self.locked_pos = None;
+            },
+            (false, None) => (),
+        }
+    }
+
+    /// Handles a winit `DeviceEvent`, passing raw mouse movement to the Thyme [`Context`](struct.Context.html).
+    /// This is needed for widgets using [`raw_mouse_drag`](struct.WidgetBuilder.html#method.raw_mouse_drag) or
+    /// [`lock_cursor_on_drag`](struct.WidgetBuilder.html#method.lock_cursor_on_drag).
+    pub fn handle_device_event(&mut self, context: &mut Context, event: &DeviceEvent) {
+        if let DeviceEvent::MouseMotion { delta: (x, y) } = event {
+            context.add_raw_mouse_delta(Point::new(*x as f32, *y as f32));
+        }
     }
 
     /// Handles a winit `Event` and passes it to the Thyme [`Context`](struct.Context.html).
@@ -79,7 +159,7 @@ impl WinitIo {
                 let alt = m.lalt_state() == Pressed || m.ralt_state() == Pressed;
                 context.set_input_modifiers(InputModifiers { shift, ctrl, alt });
             },
-            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } if !self.fixed_scale => {
                 let scale = *scale_factor as f32;
                 self.scale_factor = scale;
                 context.set_scale_factor(scale);
@@ -104,7 +184,6 @@ impl WinitIo {
             MouseWheel { delta, .. } => {
                 match delta {
                     MouseScrollDelta::LineDelta(x, y) => {
-                        // TODO configure line delta
                         context.add_mouse_wheel(Point::new(*x, *y), true);
                     }, MouseScrollDelta::PixelDelta(pos) => {
                         let x = pos.x as f32;
@@ -114,7 +193,10 @@ impl WinitIo {
                 }
             },
             CursorMoved { position, .. } => {
-                context.set_mouse_pos((position.x as f32 / self.scale_factor, position.y as f32 / self.scale_factor).into());
+                self.cursor_pos = *position;
+                let pos = Point::new(position.x as f32, position.y as f32) / self.scale_factor;
+                let window_size = self.display_size / self.scale_factor;
+                context.set_mouse_pos(context.display_rotation().window_to_ui(pos, window_size));
             },
             KeyboardInput { event, .. } => {
                 if let Some(str) = event.text.as_ref() {
@@ -144,7 +226,8 @@ impl WinitIo {
 /// An error of several types originating from winit Windowing functions
 #[derive(Debug)]
 pub enum WinitError {
-    /// No primary monitor is found
+    /// No primary monitor is found.  This is no longer returned by [`WinitIo::new`](struct.WinitIo.html#method.new),
+    /// which falls back to the window's scale factor instead.
     PrimaryMonitorNotFound,
 
     /// Internal OS error forwarded to winit
@@ -181,6 +264,25 @@ impl Error for WinitError {
     }
 }
 
+fn cursor_icon(cursor: SystemCursor) -> CursorIcon {
+    use SystemCursor::*;
+    match cursor {
+        Default => CursorIcon::Default,
+        Pointer => CursorIcon::Pointer,
+        Text => CursorIcon::Text,
+        Crosshair => CursorIcon::Crosshair,
+        Move => CursorIcon::Move,
+        Wait => CursorIcon::Wait,
+        NotAllowed => CursorIcon::NotAllowed,
+        Grab => CursorIcon::Grab,
+        Grabbing => CursorIcon::Grabbing,
+        ResizeHorizontal => CursorIcon::EwResize,
+        ResizeVertical => CursorIcon::NsResize,
+        ResizeDiagonal => CursorIcon::NwseResize,
+        ResizeAntiDiagonal => CursorIcon::NeswResize,
+    }
+}
+
 fn key_event(input: NamedKey) -> Option<KeyEvent> {
     use NamedKey::*;
     Some(match input {